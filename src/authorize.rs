@@ -1,6 +1,6 @@
-use cosmwasm_std::{HumanAddr, StdError, StdResult};
+use cosmwasm_std::{StdError, StdResult};
 
-pub fn authorize(expected: HumanAddr, received: HumanAddr) -> StdResult<()> {
+pub fn authorize<T: PartialEq>(expected: T, received: T) -> StdResult<()> {
     if expected != received {
         return Err(StdError::Unauthorized { backtrace: None });
     }
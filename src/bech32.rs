@@ -0,0 +1,82 @@
+// Just enough of BIP-173 to tell whether a string is a bech32 address and read its data
+const CHARSET: &[u8] = b"qpzry9x8gf2tvdw0s3jn54khce6mua7l";
+const CHECKSUM_LENGTH: usize = 6;
+const GENERATOR: [u32; 5] = [
+    0x3b6a_57b2,
+    0x2650_8e6d,
+    0x1ea1_19fa,
+    0x3d42_33dd,
+    0x2a14_62b3,
+];
+
+// A lowercase bech32 string with a valid checksum whose data is a 20 or 32 byte address, whatever
+// its human readable part
+pub fn is_bech32_address(string: &str) -> bool {
+    match decode(string) {
+        Some((_, data)) => data.len() == 20 || data.len() == 32,
+        None => false,
+    }
+}
+
+// The human readable part and the data of a lowercase bech32 string with a valid checksum
+pub fn decode(string: &str) -> Option<(String, Vec<u8>)> {
+    let separator: usize = string.rfind('1')?;
+    let (hrp, data) = (&string[..separator], &string[separator + 1..]);
+    if hrp.is_empty()
+        || !hrp.bytes().all(|byte| (33..=126).contains(&byte))
+        || data.len() <= CHECKSUM_LENGTH
+    {
+        return None;
+    }
+    let values: Vec<u8> = data
+        .bytes()
+        .map(|byte| CHARSET.iter().position(|c| *c == byte).map(|i| i as u8))
+        .collect::<Option<Vec<u8>>>()?;
+    let hrp_expanded = hrp
+        .bytes()
+        .map(|byte| byte >> 5)
+        .chain(Some(0))
+        .chain(hrp.bytes().map(|byte| byte & 31));
+    if polymod(hrp_expanded.chain(values.iter().copied())) != 1 {
+        return None;
+    }
+
+    // The 5 bit groups are padded with at most 4 zero bits to a whole number of bytes
+    let payload: &[u8] = &values[..values.len() - CHECKSUM_LENGTH];
+    let bits: usize = payload.len() * 5;
+    let padding_bits: usize = bits % 8;
+    let padding_is_zero: bool = payload
+        .last()
+        .map_or(true, |last| last & ((1 << padding_bits) - 1) == 0);
+    if padding_bits >= 5 || !padding_is_zero {
+        return None;
+    }
+    let mut bytes: Vec<u8> = Vec::with_capacity(bits / 8);
+    let mut accumulator: u32 = 0;
+    let mut accumulated_bits: usize = 0;
+    for value in payload {
+        // Never more than 12 bits are held, as a byte is taken out once there are 8
+        accumulator = ((accumulator << 5) | u32::from(*value)) & 0xfff;
+        accumulated_bits += 5;
+        if accumulated_bits >= 8 {
+            accumulated_bits -= 8;
+            bytes.push((accumulator >> accumulated_bits) as u8);
+        }
+    }
+
+    Some((hrp.to_string(), bytes))
+}
+
+fn polymod(values: impl Iterator<Item = u8>) -> u32 {
+    let mut checksum: u32 = 1;
+    for value in values {
+        let top: u32 = checksum >> 25;
+        checksum = ((checksum & 0x1ff_ffff) << 5) ^ u32::from(value);
+        for (i, generator) in GENERATOR.iter().enumerate() {
+            if (top >> i) & 1 == 1 {
+                checksum ^= generator;
+            }
+        }
+    }
+    checksum
+}
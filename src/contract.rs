@@ -1,50 +1,206 @@
 use crate::authorize::authorize;
+use crate::bech32::{decode, is_bech32_address};
 use crate::msg::ResponseStatus::Success;
 use crate::msg::{
-    AliasAttributes, HandleAnswer, HandleMsg, InitMsg, QueryAnswer, QueryMsg, ReceiveAnswer,
-    ReceiveMsg, SearchResponse,
+    AliasAttributes, AliasMetadata, AliasStatus, ChangedAlias, DidDocument, DidService,
+    DidVerificationMethod, ExpiringAlias, Fees, HandleAnswer, HandleMsg, InitMsg, QueryAnswer,
+    QueryError, QueryErrorCode, QueryErrorDetails, QueryMsg, QueuedAction, ReceiveAnswer,
+    ReceiveMsg, ReceiverHandleMsg, ResolveResult, ResolvedAddress, ResolvedCanonicalAddress,
+    SearchResponse, Sponsorship,
+};
+use crate::payments::{
+    active_promotion, buttcoin_payment, discounted_receive_payment, fee_amount, forward_payment,
+    native_payment, promotional_fee, receive_payment, send_asset_msg, Payment,
+};
+use crate::signatures::{
+    address_bytes, adr36_sign_doc, is_valid_public_key, recover_ethereum_address, verify_signature,
 };
 use crate::state::{
-    AddressesAliasesReadonlyStorage, AddressesAliasesStorage, Alias, AliasesReadonlyStorage,
-    AliasesStorage, Config,
+    AcceptedPayment, AdminGrant, AdminRole, AirdropClaimsReadonlyStorage, AirdropClaimsStorage,
+    Alias, AliasVersion, AliasVersionsReadonlyStorage, AliasVersionsStorage, AliasViewingKey,
+    AliasViewingKeysReadonlyStorage, AliasViewingKeysStorage, Asset, Attestation,
+    AttestationsReadonlyStorage, AttestationsStorage, BatchLimits, CaseFolding, Change,
+    ChangeAction, ChangesReadonlyStorage, ChangesStorage, CoOwnership, CoOwnershipsReadonlyStorage,
+    CoOwnershipsStorage, Config, CrossChainClaimsReadonlyStorage, CrossChainClaimsStorage,
+    CrossChainSnapshot, DailyStats, DailyStatsReadonlyStorage, DailyStatsStorage, Discount,
+    DiscountClaimsReadonlyStorage, DiscountClaimsStorage, Drop, DropsReadonlyStorage, DropsStorage,
+    EncryptionKey, EncryptionKeyAlgorithm, EncryptionKeysReadonlyStorage, EncryptionKeysStorage,
+    EndorsementsReadonlyStorage, EndorsementsStorage, Escrow, EscrowsReadonlyStorage,
+    EscrowsStorage, FeeRecipient, FeeSplit, Follows, FollowsReadonlyStorage, FollowsStorage,
+    FrozenAlias, FrozenAliasesReadonlyStorage, FrozenAliasesStorage, Gift, GiftsReadonlyStorage,
+    GiftsStorage, Governance, Image, ImageMetadata, ImagesReadonlyStorage, ImagesStorage,
+    KnownContractsReadonlyStorage, KnownContractsStorage, Launch, LaunchStage, Lease, LeaseTerms,
+    LeasesReadonlyStorage, LeasesStorage, LinkedAddress, LinkedAddressesReadonlyStorage,
+    LinkedAddressesStorage, LocksReadonlyStorage, LocksStorage, LotteryApplication,
+    LotteryApplicationsReadonlyStorage, LotteryApplicationsStorage, Member, MembersReadonlyStorage,
+    MembersStorage, Metrics, MintPhase, NoncesReadonlyStorage, NoncesStorage, Note,
+    NotesReadonlyStorage, NotesStorage, Offer, OffersReadonlyStorage, OffersStorage,
+    PaymentPreference, PaymentPreferencesReadonlyStorage, PaymentPreferencesStorage, PendingAction,
+    PendingActionsReadonlyStorage, PendingActionsStorage, PreviousProfile,
+    PreviousProfilesReadonlyStorage, PreviousProfilesStorage, PriceOracle, Promotion, Reclaim,
+    ReclaimTerms, ReclaimsReadonlyStorage, ReclaimsStorage, Record, RecordsReadonlyStorage,
+    RecordsStorage, RegistryReadonlyStorage, RegistryStorage, ReleasesReadonlyStorage,
+    ReleasesStorage, RenouncedAliasesReadonlyStorage, RenouncedAliasesStorage,
+    ReservedAliasesReadonlyStorage, ReservedAliasesStorage, Role, SecretContract,
+    SigningKeysReadonlyStorage, SigningKeysStorage, StoredAdminGrant, StoredAliasVersion,
+    StoredApprovalAction, StoredAttestation, StoredChange, StoredEscrow, StoredGovernance,
+    StoredMember, StoredOffer, StoredPaymentPreference, StoredPendingAction, StoredQueuedAction,
+    StoredReclaim, Suspension, SuspensionsReadonlyStorage, SuspensionsStorage, Tip, TipBalance,
+    TipJar, TipJarsReadonlyStorage, TipJarsStorage, Tombstone, TombstonesReadonlyStorage,
+    TombstonesStorage, TransferCallback, ViewingKeysReadonlyStorage, ViewingKeysStorage,
 };
+use crate::viewing_key::ViewingKey;
 use cosmwasm_std::{
-    from_binary, to_binary, Api, Binary, Env, Extern, HandleResponse, HumanAddr, InitResponse,
-    Querier, QueryResult, StdError, StdResult, Storage, Uint128,
+    from_binary, to_binary, to_vec, Api, Binary, BlockInfo, CanonicalAddr, Coin, CosmosMsg, Env,
+    Extern, HandleResponse, HumanAddr, InitResponse, Querier, QueryResult, StdError, StdResult,
+    Storage, Uint128,
 };
+use secret_toolkit::crypto::sha_256;
 use secret_toolkit::snip20;
 use secret_toolkit::storage::{TypedStore, TypedStoreMut};
+use secret_toolkit::utils::{pad_handle_result, pad_query_result, HandleCallback};
 
+pub const ALIAS_COUNT_KEY: &[u8] = b"alias_count";
 pub const AMOUNT_FOR_TRANSACTION: u128 = 1_000_000;
 pub const BLOCK_SIZE: usize = 1;
+pub const BUTTCOIN_DECIMALS: u32 = 6;
+pub const CHANGED_SINCE_PAGE_SIZE: u64 = 50;
+pub const CHANGE_LOG_START_KEY: &[u8] = b"change_log_start";
+pub const CHANGE_SEQ_KEY: &[u8] = b"change_seq";
+pub const CHECKSUM_KEY: &[u8] = b"checksum";
 pub const CONFIG_KEY: &[u8] = b"config";
+pub const DEFAULT_ALIAS_VERSIONS_KEPT: u16 = 10;
+pub const DID_CONTEXT: &str = "https://www.w3.org/ns/did/v1";
+pub const DID_METHOD_PREFIX: &str = "did:scrt:";
+pub const DEFAULT_MAX_BATCH_SIZE: u16 = 50;
+pub const DEFAULT_RESPONSE_BLOCK_SIZE: u16 = 256;
+pub const EXPIRING_WITHIN_PAGE_SIZE: usize = 50;
+pub const EXPORT_CHUNK_SIZE: u64 = 50;
+pub const FOLLOWS_PAGE_SIZE: usize = 50;
+pub const MAX_ADMIN_GRANTS: usize = 20;
+pub const MAX_ALIAS_TARGET_DEPTH: u8 = 5;
+pub const MAX_ALIAS_VERSIONS_KEPT: u16 = 100;
+pub const MAX_AVATAR_DOMAINS: usize = 20;
+pub const MAX_CHANGES_PER_QUERY: u32 = 100;
+pub const MAX_CHANGES_TO_PRUNE: u64 = 10;
+pub const MAX_CHANGES_TO_SCAN: u64 = 500;
+pub const MAX_CO_OWNERS_PER_ALIAS: usize = 10;
+pub const MAX_DISCOUNTS: usize = 10;
+pub const MAX_FEE_SPLITS: usize = 10;
+pub const MAX_DAYS_PER_QUERY: u64 = 366;
+pub const MAX_ENDORSEMENTS_PER_ALIAS: usize = 1_000;
+pub const MAX_EXPIRED_TO_PURGE: u32 = 50;
+pub const MAX_FALLBACK_VARIANTS: u32 = 20;
+pub const MAX_FOLLOWERS: usize = 1_000;
+pub const MAX_FOLLOWING: usize = 500;
+pub const MAX_IMAGES_PER_ALIAS: usize = 10;
+pub const MAX_LABEL_LENGTH: usize = 64;
+pub const MAX_LINKED_ADDRESSES: usize = 10;
+// About a year of blocks
+pub const MAX_LOCK_BLOCKS: u64 = 5_256_000;
+pub const MAX_LOTTERY_APPLICATIONS: usize = 100;
+pub const MAX_MEMBERS_PER_ALIAS: usize = 20;
+pub const MAX_MERKLE_PROOF_LENGTH: usize = 32;
+pub const MAX_MIME_TYPE_LENGTH: usize = 64;
+pub const MAX_MINT_PHASES: usize = 10;
+pub const MAX_NOTE_LENGTH: usize = 1024;
+pub const MAX_NOTE_VIEWERS: usize = 20;
+pub const MAX_OFFERS_PER_ALIAS: usize = 20;
+pub const MAX_PROMOTIONS: usize = 10;
+pub const MAX_RECENT_ALIASES: u32 = 50;
+pub const MAX_RECORDS_PER_ALIAS: usize = 20;
+pub const MAX_RECORD_KEY_LENGTH: usize = 64;
+pub const MAX_RECORD_VALUE_LENGTH: usize = 512;
+pub const MAX_TIPS_PER_JAR: usize = 20;
+pub const MAX_QUEUED_ACTIONS: usize = 50;
+// About a week of blocks
+pub const MAX_TIMELOCK_BLOCKS: u64 = 100_800;
+pub const MAX_TIP_MESSAGE_LENGTH: usize = 280;
+pub const MAX_URL_LENGTH: usize = 512;
+pub const MAX_VIEWING_KEYS_PER_ALIAS: usize = 10;
+pub const METRICS_KEY: &[u8] = b"metrics";
+pub const OFFER_DURATION: u64 = 30 * SECONDS_PER_DAY;
+pub const PRNG_SEED_KEY: &[u8] = b"prng_seed";
+pub const QUEUED_ACTIONS_KEY: &[u8] = b"queued_actions";
+pub const QUEUED_ACTION_SEQ_KEY: &[u8] = b"queued_action_seq";
+pub const RESERVATION_CHUNK_SIZE: usize = 500;
+pub const RESERVED_ALIAS_COUNT_KEY: &[u8] = b"reserved_alias_count";
+pub const SECONDS_PER_DAY: u64 = 86_400;
+// About a day of blocks
+pub const TOMBSTONE_BLOCKS: u64 = 14_400;
+pub const X25519_PUBLIC_KEY_LENGTH: usize = 32;
 
 pub fn init<S: Storage, A: Api, Q: Querier>(
     deps: &mut Extern<S, A, Q>,
     env: Env,
     msg: InitMsg,
 ) -> StdResult<InitResponse> {
-    let mut config_store = TypedStoreMut::attach(&mut deps.storage);
+    let response_block_size: u16 = msg
+        .response_block_size
+        .unwrap_or(DEFAULT_RESPONSE_BLOCK_SIZE);
+    if response_block_size == 0 {
+        return Err(StdError::generic_err(
+            "Response block size must be greater than 0",
+        ));
+    }
+    let batch_limits: BatchLimits = msg.batch_limits.unwrap_or(BatchLimits {
+        resolve_addresses: DEFAULT_MAX_BATCH_SIZE,
+        resolve_canonical_addresses: DEFAULT_MAX_BATCH_SIZE,
+    });
+    validate_batch_limits(&batch_limits)?;
+    validate_change_log_size(msg.change_log_size)?;
+    validate_launch(&msg.launch)?;
+    let alias_versions_kept: u16 = msg
+        .alias_versions_kept
+        .unwrap_or(DEFAULT_ALIAS_VERSIONS_KEPT);
+    validate_alias_versions_kept(alias_versions_kept)?;
     let config: Config = Config {
+        accepted_payments: vec![],
+        admin: deps.api.canonical_address(&env.message.sender)?,
+        admin_grants: vec![],
+        airdrop_merkle_root: None,
+        alias_versions_kept,
+        avatar_domains: vec![],
+        batch_limits,
+        bootstrap_ends_at: msg
+            .bootstrap_blocks
+            .map(|bootstrap_blocks| env.block.height.saturating_add(bootstrap_blocks)),
         buttcoin: msg.buttcoin.clone(),
         butt_lode: msg.butt_lode,
+        case_folding: msg.case_folding.unwrap_or(CaseFolding::Unicode),
+        chain_id: env.block.chain_id.clone(),
+        change_log_size: msg.change_log_size,
+        cross_chain_snapshot: None,
+        discounts: vec![],
+        fee_splits: vec![],
+        governance: None,
+        launch: msg.launch,
+        lease_terms: None,
+        lottery_ends_at: None,
+        mint_phases: vec![],
+        oracles: vec![],
+        price_oracle: msg.price_oracle,
+        promotions: vec![],
+        reclaim_terms: None,
+        require_avatar: msg.require_avatar.unwrap_or(false),
+        response_block_size,
+        routable_tokens: vec![],
+        timelock_blocks: 0,
     };
-    config_store.store(CONFIG_KEY, &config)?;
+    TypedStoreMut::attach(&mut deps.storage).store(CONFIG_KEY, &config)?;
+    // Mix the supplied seed with data from the init transaction so the deployer alone can't predict it
+    let prng_seed: [u8; 32] = sha_256(
+        &[
+            msg.prng_seed.as_slice(),
+            &env.block.height.to_be_bytes(),
+            &env.block.time.to_be_bytes(),
+            env.message.sender.0.as_bytes(),
+        ]
+        .concat(),
+    );
+    TypedStoreMut::attach(&mut deps.storage).store(PRNG_SEED_KEY, &prng_seed)?;
     for alias_attributes in msg.aliases {
-        let mut alias_storage = AliasesStorage::from_storage(&mut deps.storage);
-        let new_alias: Alias = Alias {
-            avatar_url: alias_attributes.avatar_url,
-            human_address: alias_attributes.address.clone(),
-        };
-        let alias_string_byte_slice: &[u8] = alias_attributes.alias.as_bytes();
-        alias_storage.set_alias(alias_string_byte_slice, new_alias);
-        // Check that the user doesn't already have an alias
-        let mut addresses_aliases_storage =
-            AddressesAliasesStorage::from_storage(&mut deps.storage);
-        addresses_aliases_storage.set_alias(
-            alias_attributes.address.0.as_bytes(),
-            &alias_attributes.alias,
-        )
+        import_alias(&mut deps.storage, &deps.api, &env, alias_attributes)?;
     }
 
     Ok(InitResponse {
@@ -63,12 +219,373 @@ pub fn handle<S: Storage, A: Api, Q: Querier>(
     deps: &mut Extern<S, A, Q>,
     env: Env,
     msg: HandleMsg,
+) -> StdResult<HandleResponse> {
+    // Only fees and tips are paid in native coins, so refuse them elsewhere rather than letting
+    // them get stuck
+    let takes_coins: bool = matches!(
+        msg,
+        HandleMsg::Create { .. }
+            | HandleMsg::CreateGift { .. }
+            | HandleMsg::FundEscrow { .. }
+            | HandleMsg::MakeOffer { .. }
+            | HandleMsg::Rename { .. }
+            | HandleMsg::Tip { .. }
+    );
+    if !takes_coins && !env.message.sent_funds.is_empty() {
+        return Err(StdError::generic_err(
+            "This contract does not accept native coins",
+        ));
+    }
+    let config: Config = TypedStore::attach(&deps.storage).load(CONFIG_KEY)?;
+
+    let response = match timelocked_role(&msg) {
+        Some(role) if config.timelock_blocks > 0 => try_queue_action(deps, env, config, role, msg),
+        _ => dispatch(deps, env, msg),
+    };
+    pad_handle_result(response, response_block_size(&deps.storage)?)
+}
+
+fn dispatch<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    env: Env,
+    msg: HandleMsg,
 ) -> StdResult<HandleResponse> {
     match msg {
-        HandleMsg::Destroy { alias } => try_destroy(deps, env, alias),
+        HandleMsg::AcceptOffer { alias, bidder, .. } => try_accept_offer(deps, env, alias, bidder),
+        HandleMsg::AddImage {
+            alias,
+            url,
+            metadata,
+            ..
+        } => try_add_image(deps, env, alias, url, metadata),
+        HandleMsg::AddKnownContract { label, address, .. } => {
+            try_add_known_contract(deps, env, label, address)
+        }
+        HandleMsg::AddMember {
+            alias,
+            address,
+            role,
+            ..
+        } => try_add_member(deps, env, alias, address, role),
+        HandleMsg::AddNoteViewer { alias, address, .. } => {
+            try_add_note_viewer(deps, env, alias, address)
+        }
+        HandleMsg::AddOracle { address, .. } => try_add_oracle(deps, env, address),
+        HandleMsg::AddRoutableToken { token, .. } => try_add_routable_token(deps, env, token),
+        HandleMsg::Approve { alias, .. } => try_approve(deps, env, alias),
+        HandleMsg::Attest {
+            alias,
+            platform,
+            handle,
+            ..
+        } => try_attest(deps, env, alias, platform, handle),
+        HandleMsg::ClaimAirdrop {
+            alias,
+            proof,
+            avatar_url,
+            ..
+        } => try_claim_airdrop(deps, env, alias, proof, avatar_url),
+        HandleMsg::ClaimCrossChain {
+            alias,
+            signature,
+            proof,
+            avatar_url,
+            ..
+        } => try_claim_cross_chain(deps, env, alias, signature, proof, avatar_url),
+        HandleMsg::ClaimGift {
+            alias,
+            code,
+            avatar_url,
+            ..
+        } => try_claim_gift(deps, env, alias, code, avatar_url),
+        HandleMsg::CancelDrop { alias, .. } => try_cancel_drop(deps, env, alias),
+        HandleMsg::CancelEscrow { alias, .. } => try_cancel_escrow(deps, env, alias),
+        HandleMsg::CancelQueuedAction { id, .. } => try_cancel_queued_action(deps, env, id),
+        HandleMsg::Create {
+            alias,
+            avatar_url,
+            avatar_metadata,
+            records,
+            fallback,
+            ..
+        } => {
+            let config: Config = TypedStore::attach(&deps.storage).load(CONFIG_KEY)?;
+            ensure_registration_open(&config, env.block.height)?;
+            ensure_no_lottery(&config, env.block.height)?;
+            ensure_no_mint_phase(&config, env.block.height)?;
+            let payment: Payment = native_payment(&config, &env)?;
+            let contract_address: HumanAddr = env.contract.address.clone();
+            let from: HumanAddr = env.message.sender.clone();
+            let records: Vec<Record> = records.unwrap_or_default();
+            let response: StdResult<HandleResponse> = if fallback.unwrap_or(false) {
+                try_create_with_fallback(
+                    deps,
+                    env,
+                    from,
+                    alias,
+                    avatar_url,
+                    avatar_metadata,
+                    records,
+                )
+            } else {
+                try_create(deps, env, from, alias, avatar_url, avatar_metadata, records)
+            };
+            response
+                .and_then(|response| forward_payment(response, config, contract_address, payment))
+        }
+        HandleMsg::CreateGift {
+            alias, code_hash, ..
+        } => {
+            let config: Config = TypedStore::attach(&deps.storage).load(CONFIG_KEY)?;
+            ensure_registration_open(&config, env.block.height)?;
+            ensure_no_lottery(&config, env.block.height)?;
+            ensure_no_mint_phase(&config, env.block.height)?;
+            let payment: Payment = native_payment(&config, &env)?;
+            let contract_address: HumanAddr = env.contract.address.clone();
+            let from: HumanAddr = env.message.sender.clone();
+            try_create_gift(deps, env, from, alias, code_hash)
+                .and_then(|response| forward_payment(response, config, contract_address, payment))
+        }
+        HandleMsg::CreateAliasViewingKey {
+            alias,
+            label,
+            entropy,
+            ..
+        } => try_create_alias_viewing_key(deps, env, alias, label, entropy),
+        HandleMsg::CreateViewingKey { entropy, .. } => try_create_viewing_key(deps, env, entropy),
+        HandleMsg::Destroy { alias, .. } => try_destroy(deps, env, alias),
+        HandleMsg::EndBootstrap { .. } => try_end_bootstrap(deps, env),
+        HandleMsg::ExecuteQueuedAction { id, .. } => try_execute_queued_action(deps, env, id),
+        HandleMsg::Endorse { alias, .. } => try_endorse(deps, env, alias),
+        HandleMsg::FinalizeLottery { alias, .. } => try_finalize_lottery(deps, env, alias),
+        HandleMsg::Follow { alias, .. } => try_follow(deps, env, alias),
+        HandleMsg::Freeze {
+            alias,
+            allow_transfers,
+            ..
+        } => try_freeze(deps, env, alias, allow_transfers),
+        HandleMsg::FundEscrow { alias, .. } => {
+            let payment: Payment = single_coin_payment(&env)?;
+            let buyer: HumanAddr = env.message.sender.clone();
+            try_fund_escrow(deps, env, buyer, alias, payment).and_then(|mut response| {
+                response.data = Some(to_binary(&HandleAnswer::FundEscrow { status: Success })?);
+                Ok(response)
+            })
+        }
+        HandleMsg::GrantRole { address, role, .. } => try_grant_role(deps, env, address, role),
+        HandleMsg::ImportChunk {
+            aliases, checksum, ..
+        } => try_import_chunk(deps, env, aliases, checksum),
+        HandleMsg::LinkAddress {
+            alias,
+            address,
+            public_key,
+            signature,
+            ..
+        } => try_link_address(deps, env, alias, address, public_key, signature),
+        HandleMsg::Lock {
+            alias,
+            until_height,
+            ..
+        } => try_lock(deps, env, alias, until_height),
+        HandleMsg::MakeOffer { alias, .. } => {
+            let payment: Payment = single_coin_payment(&env)?;
+            let bidder: HumanAddr = env.message.sender.clone();
+            try_make_offer(deps, env, bidder, alias, payment).and_then(|mut response| {
+                response.data = Some(to_binary(&HandleAnswer::MakeOffer { status: Success })?);
+                Ok(response)
+            })
+        }
+        HandleMsg::OpenEscrow {
+            alias,
+            buyer,
+            asset,
+            amount,
+            ..
+        } => try_open_escrow(deps, env, alias, buyer, asset, amount),
+        HandleMsg::OpenLottery { blocks, .. } => try_open_lottery(deps, env, blocks),
+        HandleMsg::PurgeExpired { limit, .. } => try_purge_expired(deps, env, limit),
         HandleMsg::Receive {
             from, amount, msg, ..
         } => receive(deps, env, from, amount, msg),
+        HandleMsg::RemoveImage { alias, index, .. } => try_remove_image(deps, env, alias, index),
+        HandleMsg::RemoveKnownContract { label, .. } => try_remove_known_contract(deps, env, label),
+        HandleMsg::RemoveMember { alias, address, .. } => {
+            try_remove_member(deps, env, alias, address)
+        }
+        HandleMsg::RemoveNoteViewer { alias, address, .. } => {
+            try_remove_note_viewer(deps, env, alias, address)
+        }
+        HandleMsg::RemoveOracle { address, .. } => try_remove_oracle(deps, env, address),
+        HandleMsg::Reject { alias, .. } => try_reject(deps, env, alias),
+        HandleMsg::Renounce { alias, .. } => try_renounce(deps, env, alias),
+        HandleMsg::Rename { from, to, .. } => {
+            let config: Config = TypedStore::attach(&deps.storage).load(CONFIG_KEY)?;
+            let payment: Payment = native_payment(&config, &env)?;
+            let contract_address: HumanAddr = env.contract.address.clone();
+            let sender: HumanAddr = env.message.sender.clone();
+            try_rename(deps, env, sender, from, to)
+                .and_then(|response| forward_payment(response, config, contract_address, payment))
+        }
+        HandleMsg::ReserveAliases { aliases, .. } => try_reserve_aliases(deps, env, aliases),
+        HandleMsg::Revert { alias, .. } => try_revert(deps, env, alias),
+        HandleMsg::RevokeAliasViewingKey { alias, label, .. } => {
+            try_revoke_alias_viewing_key(deps, env, alias, label)
+        }
+        HandleMsg::RevokeAttestation {
+            alias, platform, ..
+        } => try_revoke_attestation(deps, env, alias, platform),
+        HandleMsg::RevokeEndorsement { alias, .. } => try_revoke_endorsement(deps, env, alias),
+        HandleMsg::RevokeRole { address, role, .. } => try_revoke_role(deps, env, address, role),
+        HandleMsg::ScheduleDrop { alias, drop, .. } => try_schedule_drop(deps, env, alias, drop),
+        HandleMsg::SetAcceptedPayments {
+            accepted_payments, ..
+        } => try_set_accepted_payments(deps, env, accepted_payments),
+        HandleMsg::SetAirdropMerkleRoot { merkle_root, .. } => {
+            try_set_airdrop_merkle_root(deps, env, merkle_root)
+        }
+        HandleMsg::SetAliasVersionsKept { count, .. } => {
+            try_set_alias_versions_kept(deps, env, count)
+        }
+        HandleMsg::SetAvatarDomains { domains, .. } => try_set_avatar_domains(deps, env, domains),
+        HandleMsg::SetBatchLimits { batch_limits, .. } => {
+            try_set_batch_limits(deps, env, batch_limits)
+        }
+        HandleMsg::SetChangeLogSize { size, .. } => try_set_change_log_size(deps, env, size),
+        HandleMsg::SetRequireAvatar { required, .. } => try_set_require_avatar(deps, env, required),
+        HandleMsg::SetSigningKey {
+            alias, public_key, ..
+        } => try_set_signing_key(deps, env, alias, public_key),
+        HandleMsg::SetCoOwners {
+            alias,
+            co_owners,
+            threshold,
+            ..
+        } => try_set_co_owners(deps, env, alias, co_owners, threshold),
+        HandleMsg::SetCrossChainSnapshot {
+            cross_chain_snapshot,
+            ..
+        } => try_set_cross_chain_snapshot(deps, env, cross_chain_snapshot),
+        HandleMsg::SetDiscounts { discounts, .. } => try_set_discounts(deps, env, discounts),
+        HandleMsg::SetDisplayName {
+            alias,
+            display_name,
+            ..
+        } => try_set_display_name(deps, env, alias, display_name),
+        HandleMsg::SetEncryptionKey {
+            alias,
+            encryption_key,
+            ..
+        } => try_set_encryption_key(deps, env, alias, encryption_key),
+        HandleMsg::SetFeeSplits { fee_splits, .. } => try_set_fee_splits(deps, env, fee_splits),
+        HandleMsg::SetFollowsPrivate { private, .. } => try_set_follows_private(deps, env, private),
+        HandleMsg::SetGovernance { governance, .. } => try_set_governance(deps, env, governance),
+        HandleMsg::SetLaunch { launch, .. } => try_set_launch(deps, env, launch),
+        HandleMsg::SetLeaseTerms { lease_terms, .. } => try_set_lease_terms(deps, env, lease_terms),
+        HandleMsg::SetMintPhases { phases, .. } => try_set_mint_phases(deps, env, phases),
+        HandleMsg::SetNote { alias, note, .. } => try_set_note(deps, env, alias, note),
+        HandleMsg::SetPaymentPreference {
+            alias,
+            payment_preference,
+            ..
+        } => try_set_payment_preference(deps, env, alias, payment_preference),
+        HandleMsg::SetPriceOracle { price_oracle, .. } => {
+            try_set_price_oracle(deps, env, price_oracle)
+        }
+        HandleMsg::SetPrimaryImage { alias, index, .. } => {
+            try_set_primary_image(deps, env, alias, index)
+        }
+        HandleMsg::SetPrivateReverseLookup { alias, enabled, .. } => {
+            try_set_private_reverse_lookup(deps, env, alias, enabled)
+        }
+        HandleMsg::SetPromotions { promotions, .. } => try_set_promotions(deps, env, promotions),
+        HandleMsg::SetReclaimTerms { reclaim_terms, .. } => {
+            try_set_reclaim_terms(deps, env, reclaim_terms)
+        }
+        HandleMsg::SetTarget { alias, target, .. } => try_set_target(deps, env, alias, target),
+        HandleMsg::SetTimelock { blocks, .. } => try_set_timelock(deps, env, blocks),
+        HandleMsg::SetViewingKey { key, .. } => try_set_viewing_key(deps, env, key),
+        HandleMsg::SetWildcard {
+            alias, wildcard, ..
+        } => try_set_wildcard(deps, env, alias, wildcard),
+        HandleMsg::SettleReclaim { alias, .. } => try_settle_reclaim(deps, env, alias),
+        HandleMsg::SponsoredTransfer {
+            alias,
+            recipient,
+            sponsorship,
+            ..
+        } => try_sponsored_transfer(deps, env, alias, recipient, sponsorship),
+        HandleMsg::StartReclaim { alias, .. } => try_start_reclaim(deps, env, alias),
+        HandleMsg::Suspend {
+            alias,
+            hide_resolution,
+            ..
+        } => try_suspend(deps, env, alias, hide_resolution),
+        HandleMsg::Tip { alias, message, .. } => try_tip(deps, env, alias, message),
+        HandleMsg::Transfer {
+            alias,
+            recipient,
+            recipient_code_hash,
+            msg,
+            ..
+        } => try_transfer(deps, env, alias, recipient, recipient_code_hash, msg),
+        HandleMsg::UpdateRecords {
+            alias, set, remove, ..
+        } => try_update_records(deps, env, alias, set, remove),
+        HandleMsg::Unfollow { alias, .. } => try_unfollow(deps, env, alias),
+        HandleMsg::UnlinkAddress { alias, address, .. } => {
+            try_unlink_address(deps, env, alias, address)
+        }
+        HandleMsg::UnreserveAliases { aliases, .. } => try_unreserve_aliases(deps, env, aliases),
+        HandleMsg::Unsuspend { alias, .. } => try_unsuspend(deps, env, alias),
+        HandleMsg::UpdateProfile {
+            alias,
+            banner_url,
+            accent_color,
+            ..
+        } => try_update_profile(deps, env, alias, banner_url, accent_color),
+        HandleMsg::WithdrawOffer { alias, .. } => try_withdraw_offer(deps, env, alias),
+        HandleMsg::WithdrawTips { alias, .. } => try_withdraw_tips(deps, env, alias),
+    }
+}
+
+// The role a timelocked handle needs. Fee changes, bans and config updates are timelocked so users
+// can react before they apply. CancelQueuedAction stops a queued handle, ImportChunk and
+// EndBootstrap only work before launch, and Suspend and Unsuspend are for disputes that can't
+// wait, so those apply straight away.
+fn timelocked_role(msg: &HandleMsg) -> Option<AdminRole> {
+    match msg {
+        HandleMsg::AddRoutableToken { .. }
+        | HandleMsg::CancelDrop { .. }
+        | HandleMsg::ScheduleDrop { .. }
+        | HandleMsg::SetAcceptedPayments { .. }
+        | HandleMsg::SetDiscounts { .. }
+        | HandleMsg::SetFeeSplits { .. }
+        | HandleMsg::SetLeaseTerms { .. }
+        | HandleMsg::SetMintPhases { .. }
+        | HandleMsg::SetPriceOracle { .. }
+        | HandleMsg::SetPromotions { .. } => Some(AdminRole::Treasurer),
+        HandleMsg::ReserveAliases { .. }
+        | HandleMsg::SetAvatarDomains { .. }
+        | HandleMsg::SetReclaimTerms { .. }
+        | HandleMsg::SetRequireAvatar { .. }
+        | HandleMsg::UnreserveAliases { .. } => Some(AdminRole::Moderator),
+        HandleMsg::AddKnownContract { .. }
+        | HandleMsg::AddOracle { .. }
+        | HandleMsg::GrantRole { .. }
+        | HandleMsg::OpenLottery { .. }
+        | HandleMsg::RemoveKnownContract { .. }
+        | HandleMsg::RemoveOracle { .. }
+        | HandleMsg::RevokeRole { .. }
+        | HandleMsg::SetAirdropMerkleRoot { .. }
+        | HandleMsg::SetAliasVersionsKept { .. }
+        | HandleMsg::SetBatchLimits { .. }
+        | HandleMsg::SetChangeLogSize { .. }
+        | HandleMsg::SetCrossChainSnapshot { .. }
+        | HandleMsg::SetGovernance { .. }
+        | HandleMsg::SetLaunch { .. }
+        | HandleMsg::SetTimelock { .. } => Some(AdminRole::SuperAdmin),
+        _ => None,
     }
 }
 
@@ -80,274 +597,16648 @@ fn receive<S: Storage, A: Api, Q: Querier>(
     msg: Binary,
 ) -> StdResult<HandleResponse> {
     let config: Config = TypedStore::attach(&deps.storage).load(CONFIG_KEY)?;
-    // Ensure that the sent tokens are Buttcoins
-    authorize(config.buttcoin.address, env.message.sender)?;
+    let msg: ReceiveMsg = from_binary(&msg)?;
+    match msg {
+        ReceiveMsg::ApplyForAlias { alias } => {
+            let payment: Payment = receive_payment(&deps.querier, &config, &env, amount)?;
+            try_apply_for_alias(deps, env, config, from, alias, payment)
+        }
+        ReceiveMsg::BidReclaim { alias } => try_bid_reclaim(deps, env, config, from, amount, alias),
+        ReceiveMsg::BuyDrop { alias } => {
+            ensure_auctions_open(&config, env.block.height)?;
+            try_buy_drop(deps, env, config, from, amount, alias)
+        }
+        ReceiveMsg::Create {
+            alias,
+            avatar_url,
+            avatar_metadata,
+            records,
+            fallback,
+        } => {
+            ensure_registration_open(&config, env.block.height)?;
+            ensure_no_lottery(&config, env.block.height)?;
+            ensure_no_mint_phase(&config, env.block.height)?;
+            let payment: Payment = receive_payment(&deps.querier, &config, &env, amount)?;
+            let contract_address: HumanAddr = env.contract.address.clone();
+            let records: Vec<Record> = records.unwrap_or_default();
+            let response: HandleResponse = if fallback.unwrap_or(false) {
+                try_create_with_fallback(
+                    deps,
+                    env,
+                    from,
+                    alias,
+                    avatar_url,
+                    avatar_metadata,
+                    records,
+                )?
+            } else {
+                try_create(deps, env, from, alias, avatar_url, avatar_metadata, records)?
+            };
+            forward_payment(response, config, contract_address, payment)
+        }
+        ReceiveMsg::CreateGift { alias, code_hash } => {
+            ensure_registration_open(&config, env.block.height)?;
+            ensure_no_lottery(&config, env.block.height)?;
+            ensure_no_mint_phase(&config, env.block.height)?;
+            let payment: Payment = receive_payment(&deps.querier, &config, &env, amount)?;
+            let contract_address: HumanAddr = env.contract.address.clone();
+            let response: HandleResponse = try_create_gift(deps, env, from, alias, code_hash)?;
+            forward_payment(response, config, contract_address, payment)
+        }
+        ReceiveMsg::CreateWithDiscount {
+            alias,
+            avatar_url,
+            avatar_metadata,
+            records,
+            discount,
+            proof,
+        } => {
+            ensure_registration_open(&config, env.block.height)?;
+            ensure_no_lottery(&config, env.block.height)?;
+            ensure_no_mint_phase(&config, env.block.height)?;
+            let discount: Discount = config
+                .discounts
+                .get(usize::from(discount))
+                .cloned()
+                .ok_or_else(|| StdError::not_found("Discount"))?;
+            let leaf: [u8; 32] = verify_discount_proof(&deps.storage, &discount, &from, &proof)?;
+            let payment: Payment = discounted_receive_payment(
+                &deps.querier,
+                &config,
+                &env,
+                amount,
+                discount.percent_off,
+            )?;
+            let contract_address: HumanAddr = env.contract.address.clone();
+            let response: HandleResponse = try_create(
+                deps,
+                env,
+                from,
+                alias,
+                avatar_url,
+                avatar_metadata,
+                records.unwrap_or_default(),
+            )?;
+            DiscountClaimsStorage::from_storage(&mut deps.storage)
+                .set_claimed(discount.merkle_root.as_slice(), &leaf);
+            forward_payment(response, config, contract_address, payment)
+        }
+        ReceiveMsg::CreateWithProof {
+            alias,
+            avatar_url,
+            avatar_metadata,
+            records,
+            proof,
+        } => {
+            ensure_registration_open(&config, env.block.height)?;
+            ensure_no_lottery(&config, env.block.height)?;
+            let fee: Uint128 =
+                allowlisted_mint_phase_fee(&config, env.block.height, &from, &proof)?;
+            let payment: Payment = buttcoin_payment(&config, &env, amount, fee)?;
+            let contract_address: HumanAddr = env.contract.address.clone();
+            let response: HandleResponse = try_create(
+                deps,
+                env,
+                from,
+                alias,
+                avatar_url,
+                avatar_metadata,
+                records.unwrap_or_default(),
+            )?;
+            forward_payment(response, config, contract_address, payment)
+        }
+        ReceiveMsg::DefendReclaim { alias } => {
+            try_defend_reclaim(deps, env, config, from, amount, alias)
+        }
+        ReceiveMsg::Rename {
+            from: alias,
+            to: new_alias,
+        } => {
+            ensure_registration_open(&config, env.block.height)?;
+            ensure_no_lottery(&config, env.block.height)?;
+            ensure_no_mint_phase(&config, env.block.height)?;
+            let payment: Payment = receive_payment(&deps.querier, &config, &env, amount)?;
+            let contract_address: HumanAddr = env.contract.address.clone();
+            let response: HandleResponse = try_rename(deps, env, from, alias, new_alias)?;
+            forward_payment(response, config, contract_address, payment)
+        }
+        ReceiveMsg::FundEscrow { alias } => {
+            let payment: Payment = Payment {
+                asset: Asset::Snip20 {
+                    token: routable_token(config, &env.message.sender)?,
+                },
+                amount,
+            };
+            let mut response: HandleResponse = try_fund_escrow(deps, env, from, alias, payment)?;
+            response.data = Some(to_binary(&ReceiveAnswer::FundEscrow { status: Success })?);
+            Ok(response)
+        }
+        ReceiveMsg::Lease { alias, periods } => {
+            ensure_registration_open(&config, env.block.height)?;
+            ensure_no_lottery(&config, env.block.height)?;
+            ensure_no_mint_phase(&config, env.block.height)?;
+            try_lease(deps, env, config, from, amount, alias, periods)
+        }
+        ReceiveMsg::MakeOffer { alias } => {
+            let payment: Payment = Payment {
+                asset: Asset::Snip20 {
+                    token: routable_token(config, &env.message.sender)?,
+                },
+                amount,
+            };
+            let mut response: HandleResponse = try_make_offer(deps, env, from, alias, payment)?;
+            response.data = Some(to_binary(&ReceiveAnswer::MakeOffer { status: Success })?);
+            Ok(response)
+        }
+        ReceiveMsg::RenewLease { alias, periods } => {
+            try_renew_lease(deps, env, config, amount, alias, periods)
+        }
+        ReceiveMsg::SendToAlias { alias } => try_send_to_alias(deps, env, config, amount, alias),
+        ReceiveMsg::SponsoredCreate { alias, sponsorship } => {
+            ensure_registration_open(&config, env.block.height)?;
+            ensure_no_lottery(&config, env.block.height)?;
+            ensure_no_mint_phase(&config, env.block.height)?;
+            let payment: Payment = receive_payment(&deps.querier, &config, &env, amount)?;
+            let contract_address: HumanAddr = env.contract.address.clone();
+            let response: HandleResponse = try_sponsored_create(deps, env, alias, sponsorship)?;
+            forward_payment(response, config, contract_address, payment)
+        }
+        ReceiveMsg::Tip { alias, message } => {
+            try_tip_token(deps, env, config, amount, alias, message)
+        }
+    }
+}
 
-    // Ensure that amount sent in is 1 Buttcoin
-    if amount != Uint128(AMOUNT_FOR_TRANSACTION) {
+// Register an alias given at init or in an ImportChunk, held to the same rules as Create. An alias
+// already registered to the same address is left as it is, so overlapping chunks can be imported.
+// Any other conflict fails the whole chunk.
+fn import_alias<S: Storage, A: Api>(
+    storage: &mut S,
+    api: &A,
+    env: &Env,
+    alias_attributes: AliasAttributes,
+) -> StdResult<()> {
+    validate_avatar_metadata(
+        &alias_attributes.avatar_url,
+        &alias_attributes.avatar_metadata,
+    )?;
+    let config: Config = TypedStore::attach(&*storage).load(CONFIG_KEY)?;
+    let alias_string: String = normalize_alias(&*storage, &alias_attributes.alias)?;
+    validate_alias_string(&alias_string)?;
+    let display_name: String = alias_attributes
+        .display_name
+        .unwrap_or_else(|| alias_attributes.alias.trim().to_string());
+    validate_display_name(config.case_folding, &display_name, &alias_string)?;
+    let owner: CanonicalAddr = api.canonical_address(&alias_attributes.address)?;
+    if let Some(alias_object) =
+        RegistryReadonlyStorage::from_storage(&*storage, api).get_alias(alias_string.as_bytes())
+    {
+        if alias_object.owner == owner {
+            return Ok(());
+        }
         return Err(StdError::generic_err(format!(
-            "Amount sent in: {}. Amount required {}.",
-            amount,
-            Uint128(AMOUNT_FOR_TRANSACTION)
+            "{} is already registered to another address",
+            alias_string
         )));
     }
-
-    let msg: ReceiveMsg = from_binary(&msg)?;
-    match msg {
-        ReceiveMsg::Create { alias, avatar_url } => try_create(deps, from, alias, avatar_url),
+    ensure_alias_available(&*storage, api, &alias_string)?;
+    ensure_not_tombstoned(&*storage, &alias_string, &owner, env.block.height)?;
+    let mut alias_storage = RegistryStorage::from_storage(storage, api);
+    if alias_storage.get_alias_for_address(&owner).is_some() {
+        return Err(StdError::generic_err(format!(
+            "{} already has an alias",
+            alias_attributes.address
+        )));
     }
+    let alias_string_byte_slice: &[u8] = alias_string.as_bytes();
+    let new_alias: Alias = Alias {
+        alias: alias_string.clone(),
+        display_name,
+        avatar_url: alias_attributes.avatar_url,
+        avatar_metadata: alias_attributes.avatar_metadata,
+        owner: owner.clone(),
+        created_at: env.block.height,
+        updated_at: env.block.height,
+        target: None,
+        wildcard: false,
+        private_reverse_lookup: false,
+        banner_url: None,
+        accent_color: None,
+    };
+    alias_storage.set_alias(alias_string_byte_slice, new_alias);
+    record_change(
+        storage,
+        api,
+        &env.block,
+        ChangeAction::Create,
+        alias_string,
+        owner,
+    )
 }
 
-fn try_create<S: Storage, A: Api, Q: Querier>(
-    deps: &mut Extern<S, A, Q>,
-    from: HumanAddr,
-    alias_string: String,
-    avatar_url: Option<String>,
-) -> StdResult<HandleResponse> {
-    let config: Config = TypedStore::attach(&deps.storage).load(CONFIG_KEY)?;
-    let alias_string = alias_string.trim();
-    let alias_string_formatted = alias_string.to_lowercase().to_string();
-    let alias_string_byte_slice: &[u8] = alias_string_formatted.as_bytes();
-    // Check alias size
-    if alias_string_byte_slice.len() > u8::MAX.into() {
-        return Err(StdError::generic_err("Alias is too long"));
-    }
-    // Check that Alias doesn't already exist
-    let mut alias_storage = AliasesStorage::from_storage(&mut deps.storage);
-    let alias_object: Option<Alias> = alias_storage.get_alias(alias_string_byte_slice);
-    if alias_object.is_none() {
-        let new_alias = Alias {
-            avatar_url: avatar_url,
-            human_address: from.clone(),
-        };
-        alias_storage.set_alias(alias_string_byte_slice, new_alias);
-        // Check that the user doesn't already have an alias
-        let mut addresses_aliases_storage =
-            AddressesAliasesStorage::from_storage(&mut deps.storage);
-        let alias_key: Option<Vec<u8>> = addresses_aliases_storage.get_alias(&from.to_string());
-        if alias_key.is_none() {
-            addresses_aliases_storage.set_alias(from.0.as_bytes(), &alias_string_formatted)
-        } else {
-            return Err(StdError::generic_err("Address already has an alias"));
+fn validate_avatar_url(config: &Config, avatar_url: &Option<String>) -> StdResult<()> {
+    let avatar_url: &String = match avatar_url {
+        Some(avatar_url) => avatar_url,
+        None if config.require_avatar => {
+            return Err(StdError::generic_err("Avatar url is required"))
         }
-    } else {
-        return Err(StdError::generic_err("Alias has already been taken"));
+        None => return Ok(()),
+    };
+
+    validate_image_url(config, "Avatar", avatar_url)
+}
+
+// Avatars, banners and gallery images are http(s) urls of at most MAX_URL_LENGTH, on one of the avatar
+// domains when the admin has set any
+fn validate_image_url(config: &Config, kind: &str, url: &str) -> StdResult<()> {
+    let host: String = url_host(url)
+        .filter(|_| url.len() <= MAX_URL_LENGTH)
+        .ok_or_else(|| StdError::generic_err(format!("{} url is invalid", kind)))?;
+    if !config.avatar_domains.is_empty()
+        && !config
+            .avatar_domains
+            .iter()
+            .any(|domain| host == *domain || host.ends_with(&format!(".{}", domain)))
+    {
+        return Err(StdError::generic_err(format!(
+            "{}s can't be hosted on {}",
+            kind, host
+        )));
     }
 
-    Ok(HandleResponse {
-        messages: vec![snip20::transfer_msg(
-            config.butt_lode.address,
-            Uint128(AMOUNT_FOR_TRANSACTION),
-            None,
-            BLOCK_SIZE,
-            config.buttcoin.contract_hash,
-            config.buttcoin.address,
-        )?],
-        log: vec![],
-        data: Some(to_binary(&ReceiveAnswer::Create { status: Success })?),
-    })
+    Ok(())
 }
 
-fn try_destroy<S: Storage, A: Api, Q: Querier>(
-    deps: &mut Extern<S, A, Q>,
-    env: Env,
-    alias_string: String,
-) -> StdResult<HandleResponse> {
-    let alias_string = alias_string.trim().to_lowercase();
-    let alias_string_byte_slice: &[u8] = alias_string.as_bytes();
-    let mut alias_storage = AliasesStorage::from_storage(&mut deps.storage);
-    let alias_object: Option<Alias> = alias_storage.get_alias(alias_string_byte_slice);
-    if alias_object.is_none() {
-        return Err(StdError::not_found("Alias"));
+// The lowercased host of an http(s) url, without any credentials or port
+fn url_host(url: &str) -> Option<String> {
+    let url: String = url.trim().to_lowercase();
+    let rest: &str = url
+        .strip_prefix("https://")
+        .or_else(|| url.strip_prefix("http://"))?;
+    let authority: &str = rest.split(|c| c == '/' || c == '?' || c == '#').next()?;
+    let host: &str = authority.rsplit('@').next()?.split(':').next()?;
+    if host.is_empty() {
+        return None;
     }
-    let alias_object: Alias = alias_object.unwrap();
-    authorize(env.message.sender.clone(), alias_object.human_address)?;
 
-    alias_storage.remove_alias(alias_string_byte_slice);
-    let mut addresses_aliases_storage = AddressesAliasesStorage::from_storage(&mut deps.storage);
-    addresses_aliases_storage.remove_alias(env.message.sender.0.as_bytes());
+    Some(host.to_string())
+}
 
-    Ok(HandleResponse {
-        messages: vec![],
-        log: vec![],
-        data: Some(to_binary(&HandleAnswer::Destroy { status: Success })?),
-    })
+fn validate_avatar_metadata(
+    avatar_url: &Option<String>,
+    avatar_metadata: &Option<ImageMetadata>,
+) -> StdResult<()> {
+    let avatar_metadata: &ImageMetadata = match avatar_metadata {
+        Some(avatar_metadata) => avatar_metadata,
+        None => return Ok(()),
+    };
+    if avatar_url.is_none() {
+        return Err(StdError::generic_err(
+            "Avatar metadata requires an avatar url",
+        ));
+    }
+
+    validate_image_metadata("Avatar", avatar_metadata)
 }
 
-pub fn query<S: Storage, A: Api, Q: Querier>(deps: &Extern<S, A, Q>, msg: QueryMsg) -> QueryResult {
-    match msg {
-        QueryMsg::Config {} => query_config(deps),
-        QueryMsg::Search {
-            search_type,
-            mut search_value,
-        } => {
-            let alias_object: Option<Alias>;
-            let alias_attributes: AliasAttributes;
-            if search_type == "address" {
-                let addresses_aliases_storage =
-                    AddressesAliasesReadonlyStorage::from_storage(&deps.storage);
-                let alias_key = addresses_aliases_storage.get_alias(&search_value);
-                if alias_key.is_none() {
-                    return Err(StdError::not_found("Alias"));
-                }
-                search_value =
-                    String::from_utf8(alias_key.clone().unwrap()).expect("Found invalid UTF-8");
-            } else if search_type == "alias" {
-                search_value = search_value.trim().to_lowercase();
-            } else {
-                return Err(StdError::parse_err(
-                    "search_type",
-                    "must be address or alias.",
-                ));
-            }
+fn validate_image_metadata(kind: &str, image_metadata: &ImageMetadata) -> StdResult<()> {
+    if let Some(mime_type) = &image_metadata.mime_type {
+        if !mime_type.starts_with("image/") || mime_type.len() > MAX_MIME_TYPE_LENGTH {
+            return Err(StdError::generic_err(format!(
+                "{} mime type is invalid",
+                kind
+            )));
+        }
+    }
+    if image_metadata.width == Some(0) || image_metadata.height == Some(0) {
+        return Err(StdError::generic_err(format!(
+            "{} dimensions must be greater than 0",
+            kind
+        )));
+    }
 
-            let alias_storage = AliasesReadonlyStorage::from_storage(&deps.storage);
-            alias_object = alias_storage.get_alias(search_value.as_bytes());
-            if alias_object.is_none() {
-                return Err(StdError::not_found("Alias"));
-            }
-            alias_attributes = AliasAttributes {
-                alias: search_value,
-                avatar_url: alias_object.clone().unwrap().avatar_url,
-                address: alias_object.unwrap().human_address,
-            };
+    Ok(())
+}
 
-            return Ok(to_binary(&SearchResponse {
-                r#type: "aliases".to_string(),
-                attributes: alias_attributes,
-            })?);
+// Keys are trimmed and lowercased so each key is only held once
+fn validate_records(records: Vec<Record>) -> StdResult<Vec<Record>> {
+    if records.len() > MAX_RECORDS_PER_ALIAS {
+        return Err(StdError::generic_err(format!(
+            "An alias can have at most {} records",
+            MAX_RECORDS_PER_ALIAS
+        )));
+    }
+    let mut validated_records: Vec<Record> = Vec::with_capacity(records.len());
+    for record in records {
+        let key: String = record.key.trim().to_lowercase();
+        if key.is_empty() || key.len() > MAX_RECORD_KEY_LENGTH {
+            return Err(StdError::generic_err("Record key is invalid"));
+        }
+        if record.value.trim().is_empty() || record.value.len() > MAX_RECORD_VALUE_LENGTH {
+            return Err(StdError::generic_err(format!(
+                "Record value for {} is invalid",
+                key
+            )));
         }
+        if validated_records
+            .iter()
+            .any(|validated| validated.key == key)
+        {
+            return Err(StdError::generic_err(format!(
+                "Record {} is set more than once",
+                key
+            )));
+        }
+        validated_records.push(Record {
+            key,
+            value: record.value,
+        });
     }
+
+    Ok(validated_records)
 }
 
-fn query_config<S: Storage, A: Api, Q: Querier>(deps: &Extern<S, A, Q>) -> QueryResult {
-    let config: Config = TypedStore::attach(&deps.storage).load(CONFIG_KEY)?;
+fn validate_encryption_key(encryption_key: &EncryptionKey) -> StdResult<()> {
+    let public_key: &[u8] = encryption_key.public_key.as_slice();
+    let valid: bool = match encryption_key.algorithm {
+        EncryptionKeyAlgorithm::Secp256k1 => is_valid_public_key(public_key),
+        // Every 32 byte string is an X25519 key, but all zeros gives a shared secret of zero
+        EncryptionKeyAlgorithm::X25519 => {
+            public_key.len() == X25519_PUBLIC_KEY_LENGTH && public_key.iter().any(|byte| *byte != 0)
+        }
+    };
+    if !valid {
+        return Err(StdError::generic_err("Encryption key is invalid"));
+    }
 
-    to_binary(&QueryAnswer::Config {
-        buttcoin: config.buttcoin,
-        butt_lode: config.butt_lode,
+    Ok(())
+}
+
+fn validate_payment_preference<A: Api>(
+    api: &A,
+    payment_preference: PaymentPreference,
+) -> StdResult<StoredPaymentPreference> {
+    if let Some(Asset::Native { denom }) = &payment_preference.asset {
+        if denom.trim().is_empty() {
+            return Err(StdError::generic_err("Denom is invalid"));
+        }
+    }
+
+    Ok(StoredPaymentPreference {
+        asset: payment_preference.asset,
+        memo_required: payment_preference.memo_required,
+        receiving_address: payment_preference
+            .receiving_address
+            .map(|address| api.canonical_address(&address))
+            .transpose()?,
     })
 }
 
-// === TESTS ===
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::state::SecretContract;
-    use cosmwasm_std::from_binary;
-    use cosmwasm_std::testing::*;
-    use cosmwasm_std::HumanAddr;
-    use std::any::Any;
+fn validate_alias_versions_kept(alias_versions_kept: u16) -> StdResult<()> {
+    if alias_versions_kept == 0 || alias_versions_kept > MAX_ALIAS_VERSIONS_KEPT {
+        return Err(StdError::generic_err(format!(
+            "Alias versions kept must be between 1 and {}",
+            MAX_ALIAS_VERSIONS_KEPT
+        )));
+    }
 
-    fn extract_error_msg<T: Any>(error: StdResult<T>) -> String {
-        match error {
-            Ok(_response) => {
-                panic!("Unexpected query answer")
-            }
-            Err(err) => match err {
-                StdError::GenericErr { msg, .. } => msg,
-                StdError::NotFound { kind, .. } => format!("{} not found", kind),
-                StdError::Unauthorized { .. } => "Unauthorized".to_string(),
-                _ => panic!("Unexpected result from init"),
-            },
-        }
+    Ok(())
+}
+
+fn validate_batch_limits(batch_limits: &BatchLimits) -> StdResult<()> {
+    if batch_limits.resolve_addresses == 0 || batch_limits.resolve_canonical_addresses == 0 {
+        return Err(StdError::generic_err("Batch limits must be greater than 0"));
     }
 
-    //=== HELPER FUNCTIONS ===
-    fn init_helper() -> (
-        StdResult<InitResponse>,
-        Extern<MockStorage, MockApi, MockQuerier>,
-    ) {
-        let mut deps = mock_dependencies(20, &[]);
-        let env = mock_env(mock_user_address(), &[]);
+    Ok(())
+}
 
-        let init_msg = InitMsg {
-            aliases: vec![AliasAttributes {
-                alias: "epstein didn't kill himself".to_string(),
-                address: HumanAddr::from("frump"),
-                avatar_url: None,
-            }],
-            buttcoin: mock_buttcoin(),
-            butt_lode: mock_butt_lode(),
-        };
+fn validate_discounts(discounts: &[Discount]) -> StdResult<()> {
+    if discounts.len() > MAX_DISCOUNTS {
+        return Err(StdError::generic_err(format!(
+            "There can be at most {} discounts",
+            MAX_DISCOUNTS
+        )));
+    }
+    for discount in discounts {
+        if discount.merkle_root.len() != 32 {
+            return Err(StdError::generic_err("Merkle root must be a sha256 hash"));
+        }
+        if discount.percent_off == 0 || discount.percent_off > 100 {
+            return Err(StdError::generic_err(
+                "Discount must be between 1 and 100 percent",
+            ));
+        }
+    }
 
-        (init(&mut deps, env, init_msg), deps)
+    Ok(())
+}
+
+fn validate_mint_phases(phases: &[MintPhase]) -> StdResult<()> {
+    if phases.len() > MAX_MINT_PHASES {
+        return Err(StdError::generic_err(format!(
+            "There can be at most {} mint phases",
+            MAX_MINT_PHASES
+        )));
+    }
+    for phase in phases {
+        if phase.merkle_root.len() != 32 {
+            return Err(StdError::generic_err("Merkle root must be a sha256 hash"));
+        }
+        if phase.end_height <= phase.start_height {
+            return Err(StdError::generic_err(
+                "A mint phase must end after it starts",
+            ));
+        }
+    }
+    if phases
+        .windows(2)
+        .any(|pair| pair[1].start_height < pair[0].end_height)
+    {
+        return Err(StdError::generic_err(
+            "Mint phases must be in order and not overlap",
+        ));
     }
 
-    fn mock_buttcoin() -> SecretContract {
-        SecretContract {
-            address: HumanAddr("buttcoin-address".to_string()),
-            contract_hash: "buttcoin-contract-hash".to_string(),
+    Ok(())
+}
+
+fn validate_promotions(promotions: &[Promotion]) -> StdResult<()> {
+    if promotions.len() > MAX_PROMOTIONS {
+        return Err(StdError::generic_err(format!(
+            "There can be at most {} promotions",
+            MAX_PROMOTIONS
+        )));
+    }
+    for promotion in promotions {
+        if promotion.end_height <= promotion.start_height {
+            return Err(StdError::generic_err(
+                "A promotion must end after it starts",
+            ));
+        }
+        if promotion.percent_off == 0 || promotion.percent_off > 100 {
+            return Err(StdError::generic_err(
+                "Promotion must be between 1 and 100 percent off",
+            ));
         }
     }
+    if promotions
+        .windows(2)
+        .any(|pair| pair[1].start_height < pair[0].end_height)
+    {
+        return Err(StdError::generic_err(
+            "Promotions must be in order and not overlap",
+        ));
+    }
+
+    Ok(())
+}
 
-    fn mock_butt_lode() -> SecretContract {
-        SecretContract {
-            address: HumanAddr("profit-sharing-contract-address".to_string()),
-            contract_hash: "profit-sharing-contract-hash".to_string(),
+fn validate_reclaim_terms(reclaim_terms: &Option<ReclaimTerms>) -> StdResult<()> {
+    if let Some(reclaim_terms) = reclaim_terms {
+        if reclaim_terms.idle_blocks == 0
+            || reclaim_terms.bidding_blocks == 0
+            || reclaim_terms.defense_blocks == 0
+        {
+            return Err(StdError::generic_err(
+                "Reclaim terms must be greater than 0 blocks",
+            ));
         }
     }
 
-    fn mock_user_address() -> HumanAddr {
-        HumanAddr::from("some-geezer")
+    Ok(())
+}
+
+fn validate_launch(launch: &Option<Launch>) -> StdResult<()> {
+    if matches!(launch, Some(launch) if launch.registration_opens_at < launch.auctions_open_at) {
+        return Err(StdError::generic_err(
+            "Registration can't open before auctions",
+        ));
     }
 
-    // === TESTS ===
-    #[test]
-    fn test_try_destroy() {
-        let alias: &str = "nailbiter";
+    Ok(())
+}
 
-        // Initialize
-        let (_init_result, mut deps) = init_helper();
+fn validate_change_log_size(change_log_size: Option<u64>) -> StdResult<()> {
+    if change_log_size == Some(0) {
+        return Err(StdError::generic_err(
+            "Change log size must be greater than 0",
+        ));
+    }
 
-        // when alias exists
-        let create_alias_message = ReceiveMsg::Create {
-            alias: alias.to_string(),
-            avatar_url: None,
-        };
-        let receive_msg = HandleMsg::Receive {
-            sender: mock_user_address(),
-            from: mock_user_address(),
+    Ok(())
+}
+
+// Reserving and unreserving take aliases in bounded chunks, each short enough to be an alias
+fn validate_reservation_chunk(aliases: &[String]) -> StdResult<()> {
+    if aliases.len() > RESERVATION_CHUNK_SIZE {
+        return Err(StdError::generic_err(format!(
+            "A chunk can have at most {} aliases",
+            RESERVATION_CHUNK_SIZE
+        )));
+    }
+    if aliases
+        .iter()
+        .any(|alias_string| alias_string.trim().len() > u8::MAX.into())
+    {
+        return Err(StdError::generic_err("Alias is too long"));
+    }
+
+    Ok(())
+}
+
+// A display name can only change the capitalization of the alias
+fn validate_display_name(
+    case_folding: CaseFolding,
+    display_name: &str,
+    alias: &str,
+) -> StdResult<()> {
+    if display_name.trim() != display_name
+        || case_folding.fold(display_name) != case_folding.fold(alias)
+    {
+        return Err(StdError::generic_err("Display name must match the alias"));
+    }
+
+    Ok(())
+}
+
+fn try_accept_offer<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    env: Env,
+    alias_string: String,
+    bidder: HumanAddr,
+) -> StdResult<HandleResponse> {
+    let alias_string = normalize_alias(&deps.storage, &alias_string)?;
+    let alias_object: Alias = load_owned_alias(&deps.storage, &deps.api, &env, &alias_string)?;
+    ensure_not_locked(&deps.storage, &alias_string, env.block.height)?;
+    // Co-owners approve transfers, which accepting an offer would get around
+    if CoOwnershipsReadonlyStorage::from_storage(&deps.storage)
+        .get_co_ownership(alias_string.as_bytes())
+        .is_some()
+    {
+        return Err(StdError::generic_err(
+            "Co-owned aliases can't accept offers",
+        ));
+    }
+    let bidder: CanonicalAddr = deps.api.canonical_address(&bidder)?;
+    let mut offers_storage = OffersStorage::from_storage(&mut deps.storage);
+    let mut offers: Vec<StoredOffer> = offers_storage.get_offers(alias_string.as_bytes());
+    let index: Option<usize> = offers.iter().position(|offer| offer.bidder == bidder);
+    if index.is_none() {
+        return Err(StdError::not_found("Offer"));
+    }
+    let offer: StoredOffer = offers.remove(index.unwrap());
+    if offer.expires_at <= env.block.time {
+        return Err(StdError::generic_err("Offer has expired"));
+    }
+
+    if offers.is_empty() {
+        offers_storage.remove_offers(alias_string.as_bytes());
+    } else {
+        offers_storage.set_offers(alias_string.as_bytes(), &offers);
+    }
+    let mut messages: Vec<CosmosMsg> = withdraw_tips(
+        &mut deps.storage,
+        &alias_string,
+        env.contract.address.clone(),
+        env.message.sender.clone(),
+    )?;
+    messages.push(send_asset_msg(
+        env.contract.address.clone(),
+        env.message.sender.clone(),
+        offer.asset,
+        offer.amount,
+    )?);
+    transfer_alias(
+        &mut deps.storage,
+        &deps.api,
+        &env,
+        alias_string,
+        alias_object,
+        offer.bidder,
+    )?;
+
+    Ok(HandleResponse {
+        messages,
+        log: vec![],
+        data: Some(to_binary(&HandleAnswer::AcceptOffer { status: Success })?),
+    })
+}
+
+fn try_add_image<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    env: Env,
+    alias_string: String,
+    url: String,
+    metadata: Option<ImageMetadata>,
+) -> StdResult<HandleResponse> {
+    let config: Config = TypedStore::attach(&deps.storage).load(CONFIG_KEY)?;
+    let alias_string = normalize_alias(&deps.storage, &alias_string)?;
+    let alias_object: Alias = load_editable_alias(&deps.storage, &deps.api, &env, &alias_string)?;
+    ensure_not_frozen(&deps.storage, &alias_string)?;
+    validate_image_url(&config, "Image", &url)?;
+    if let Some(metadata) = &metadata {
+        validate_image_metadata("Image", metadata)?;
+    }
+
+    let mut images_storage = ImagesStorage::from_storage(&mut deps.storage);
+    let mut images: Vec<Image> = images_storage.get_images(alias_string.as_bytes());
+    if images.len() >= MAX_IMAGES_PER_ALIAS {
+        return Err(StdError::generic_err(format!(
+            "Too many images. Maximum is {}.",
+            MAX_IMAGES_PER_ALIAS
+        )));
+    }
+    // The first image becomes the primary one
+    images.push(Image {
+        url,
+        metadata,
+        primary: images.is_empty(),
+    });
+    images_storage.set_images(alias_string.as_bytes(), &images);
+    save_updated_alias(
+        &mut deps.storage,
+        &deps.api,
+        &env,
+        alias_string,
+        alias_object,
+    )?;
+
+    Ok(HandleResponse {
+        messages: vec![],
+        log: vec![],
+        data: Some(to_binary(&HandleAnswer::AddImage { status: Success })?),
+    })
+}
+
+// Aliases that are already registered under the label are left alone
+fn try_add_known_contract<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    env: Env,
+    label: String,
+    address: HumanAddr,
+) -> StdResult<HandleResponse> {
+    let config: Config = TypedStore::attach(&deps.storage).load(CONFIG_KEY)?;
+    authorize_admin_action(
+        &mut deps.storage,
+        &deps.api,
+        &config,
+        &env.message.sender,
+        AdminRole::SuperAdmin,
+    )?;
+    let label = normalize_alias(&deps.storage, &label)?;
+    if label.is_empty() || label.len() > u8::MAX.into() {
+        return Err(StdError::generic_err("Label is invalid"));
+    }
+    deps.api.canonical_address(&address)?;
+
+    KnownContractsStorage::from_storage(&mut deps.storage)
+        .set_known_contract(label.as_bytes(), &address);
+
+    Ok(HandleResponse {
+        messages: vec![],
+        log: vec![],
+        data: Some(to_binary(&HandleAnswer::AddKnownContract {
+            status: Success,
+        })?),
+    })
+}
+
+fn try_add_member<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    env: Env,
+    alias_string: String,
+    address: HumanAddr,
+    role: Role,
+) -> StdResult<HandleResponse> {
+    let alias_string = normalize_alias(&deps.storage, &alias_string)?;
+    let alias_object: Alias = load_editable_alias(&deps.storage, &deps.api, &env, &alias_string)?;
+    let sender: CanonicalAddr = deps.api.canonical_address(&env.message.sender)?;
+    let address: CanonicalAddr = deps.api.canonical_address(&address)?;
+    if address == alias_object.owner {
+        return Err(StdError::generic_err("Owner can not be a member"));
+    }
+
+    let mut members_storage = MembersStorage::from_storage(&mut deps.storage);
+    let mut members: Vec<StoredMember> = members_storage.get_members(alias_string.as_bytes());
+    authorize_member_manager(&alias_object.owner, &members, &sender, &address, Some(role))?;
+    match members.iter_mut().find(|member| member.address == address) {
+        Some(member) => member.role = role,
+        None => {
+            if members.len() >= MAX_MEMBERS_PER_ALIAS {
+                return Err(StdError::generic_err(format!(
+                    "Too many members. Maximum is {}.",
+                    MAX_MEMBERS_PER_ALIAS
+                )));
+            }
+            members.push(StoredMember { address, role });
+        }
+    }
+    members_storage.set_members(alias_string.as_bytes(), &members);
+    save_updated_alias(
+        &mut deps.storage,
+        &deps.api,
+        &env,
+        alias_string,
+        alias_object,
+    )?;
+
+    Ok(HandleResponse {
+        messages: vec![],
+        log: vec![],
+        data: Some(to_binary(&HandleAnswer::AddMember { status: Success })?),
+    })
+}
+
+fn try_add_note_viewer<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    env: Env,
+    alias_string: String,
+    address: HumanAddr,
+) -> StdResult<HandleResponse> {
+    let alias_string = normalize_alias(&deps.storage, &alias_string)?;
+    load_owned_alias(&deps.storage, &deps.api, &env, &alias_string)?;
+    let address: CanonicalAddr = deps.api.canonical_address(&address)?;
+
+    let mut notes_storage = NotesStorage::from_storage(&mut deps.storage);
+    let note: Option<Note> = notes_storage.get_note(alias_string.as_bytes());
+    if note.is_none() {
+        return Err(StdError::not_found("Note"));
+    }
+    let mut note: Note = note.unwrap();
+    if !note.viewers.contains(&address) {
+        if note.viewers.len() >= MAX_NOTE_VIEWERS {
+            return Err(StdError::generic_err(format!(
+                "Too many viewers. Maximum is {}.",
+                MAX_NOTE_VIEWERS
+            )));
+        }
+        note.viewers.push(address);
+        notes_storage.set_note(alias_string.as_bytes(), &note);
+    }
+
+    Ok(HandleResponse {
+        messages: vec![],
+        log: vec![],
+        data: Some(to_binary(&HandleAnswer::AddNoteViewer { status: Success })?),
+    })
+}
+
+fn try_add_oracle<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    env: Env,
+    address: HumanAddr,
+) -> StdResult<HandleResponse> {
+    let mut config: Config = TypedStore::attach(&deps.storage).load(CONFIG_KEY)?;
+    authorize_admin_action(
+        &mut deps.storage,
+        &deps.api,
+        &config,
+        &env.message.sender,
+        AdminRole::SuperAdmin,
+    )?;
+
+    let address: CanonicalAddr = deps.api.canonical_address(&address)?;
+    if !config.oracles.contains(&address) {
+        config.oracles.push(address);
+        TypedStoreMut::attach(&mut deps.storage).store(CONFIG_KEY, &config)?;
+    }
+
+    Ok(HandleResponse {
+        messages: vec![],
+        log: vec![],
+        data: Some(to_binary(&HandleAnswer::AddOracle { status: Success })?),
+    })
+}
+
+fn try_add_routable_token<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    env: Env,
+    token: SecretContract,
+) -> StdResult<HandleResponse> {
+    let mut config: Config = TypedStore::attach(&deps.storage).load(CONFIG_KEY)?;
+    authorize_admin_action(
+        &mut deps.storage,
+        &deps.api,
+        &config,
+        &env.message.sender,
+        AdminRole::Treasurer,
+    )?;
+
+    let mut messages: Vec<CosmosMsg> = vec![];
+    if !config
+        .routable_tokens
+        .iter()
+        .any(|routable_token| routable_token.address == token.address)
+    {
+        messages.push(snip20::register_receive_msg(
+            env.contract_code_hash,
+            None,
+            BLOCK_SIZE,
+            token.contract_hash.clone(),
+            token.address.clone(),
+        )?);
+        config.routable_tokens.push(token);
+        ensure_fees_burnable(&config)?;
+        TypedStoreMut::attach(&mut deps.storage).store(CONFIG_KEY, &config)?;
+    }
+
+    Ok(HandleResponse {
+        messages,
+        log: vec![],
+        data: Some(to_binary(&HandleAnswer::AddRoutableToken {
+            status: Success,
+        })?),
+    })
+}
+
+fn try_approve<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    env: Env,
+    alias_string: String,
+) -> StdResult<HandleResponse> {
+    let alias_string = normalize_alias(&deps.storage, &alias_string)?;
+    let alias_object: Alias = load_co_owned_alias(&deps.storage, &deps.api, &env, &alias_string)?;
+    let sender: CanonicalAddr = deps.api.canonical_address(&env.message.sender)?;
+
+    let pending_action: Option<StoredPendingAction> =
+        PendingActionsReadonlyStorage::from_storage(&deps.storage)
+            .get_pending_action(alias_string.as_bytes());
+    if pending_action.is_none() {
+        return Err(StdError::not_found("Pending action"));
+    }
+    let mut pending_action: StoredPendingAction = pending_action.unwrap();
+    if pending_action.approvals.contains(&sender) {
+        return Err(StdError::generic_err("Pending action is already approved"));
+    }
+    pending_action.approvals.push(sender);
+    let messages: Vec<CosmosMsg> = approve_action(
+        &mut deps.storage,
+        &deps.api,
+        &env,
+        alias_string,
+        alias_object,
+        pending_action,
+    )?;
+
+    Ok(HandleResponse {
+        messages,
+        log: vec![],
+        data: Some(to_binary(&HandleAnswer::Approve { status: Success })?),
+    })
+}
+
+fn try_attest<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    env: Env,
+    alias_string: String,
+    platform: String,
+    handle: String,
+) -> StdResult<HandleResponse> {
+    let config: Config = TypedStore::attach(&deps.storage).load(CONFIG_KEY)?;
+    let sender: CanonicalAddr = deps.api.canonical_address(&env.message.sender)?;
+    if !config.oracles.contains(&sender) {
+        return Err(StdError::Unauthorized { backtrace: None });
+    }
+
+    let alias_string = normalize_alias(&deps.storage, &alias_string)?;
+    let alias_string_byte_slice: &[u8] = alias_string.as_bytes();
+    let alias_object: Alias = load_current_alias(&deps.storage, &deps.api, &env, &alias_string)?;
+    ensure_not_frozen(&deps.storage, &alias_string)?;
+    let platform = platform.trim().to_lowercase();
+    let handle = handle.trim().to_string();
+    if platform.is_empty() || handle.is_empty() {
+        return Err(StdError::generic_err("Platform and handle are required"));
+    }
+
+    // An alias holds at most one attestation per platform
+    let mut attestations_storage = AttestationsStorage::from_storage(&mut deps.storage);
+    let mut attestations: Vec<StoredAttestation> =
+        attestations_storage.get_attestations(alias_string_byte_slice);
+    attestations.retain(|attestation| attestation.platform != platform);
+    attestations.push(StoredAttestation {
+        platform,
+        handle,
+        oracle: sender,
+        block_height: env.block.height,
+    });
+    attestations_storage.set_attestations(alias_string_byte_slice, &attestations);
+    save_updated_alias(
+        &mut deps.storage,
+        &deps.api,
+        &env,
+        alias_string,
+        alias_object,
+    )?;
+
+    Ok(HandleResponse {
+        messages: vec![],
+        log: vec![],
+        data: Some(to_binary(&HandleAnswer::Attest { status: Success })?),
+    })
+}
+
+fn try_apply_for_alias<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    env: Env,
+    config: Config,
+    applicant: HumanAddr,
+    alias_string: String,
+    payment: Payment,
+) -> StdResult<HandleResponse> {
+    match config.lottery_ends_at {
+        Some(lottery_ends_at) if env.block.height < lottery_ends_at => {}
+        _ => return Err(StdError::generic_err("There is no lottery running")),
+    }
+    // The winner is registered without an avatar
+    validate_avatar_url(&config, &None)?;
+    let alias_string: String = normalize_alias(&deps.storage, &alias_string)?;
+    validate_alias_string(&alias_string)?;
+    let applicant: CanonicalAddr = deps.api.canonical_address(&applicant)?;
+    if RegistryReadonlyStorage::from_storage(&deps.storage, &deps.api)
+        .get_alias_for_address(&applicant)
+        .is_some()
+    {
+        return Err(StdError::generic_err("Address already has an alias"));
+    }
+    let mut applications: Vec<LotteryApplication> =
+        LotteryApplicationsReadonlyStorage::from_storage(&deps.storage)
+            .get_applications(alias_string.as_bytes());
+    // Pending applications make the alias unavailable, so only the first applicant is checked
+    if applications.is_empty() {
+        ensure_alias_available(&deps.storage, &deps.api, &alias_string)?;
+    }
+    ensure_not_tombstoned(&deps.storage, &alias_string, &applicant, env.block.height)?;
+    if applications
+        .iter()
+        .any(|application| application.applicant == applicant)
+    {
+        return Err(StdError::generic_err(
+            "You have already applied for this alias",
+        ));
+    }
+    if applications.len() >= MAX_LOTTERY_APPLICATIONS {
+        return Err(StdError::generic_err(format!(
+            "An alias can have at most {} applications",
+            MAX_LOTTERY_APPLICATIONS
+        )));
+    }
+
+    applications.push(LotteryApplication {
+        applicant,
+        asset: payment.asset,
+        amount: payment.amount,
+        applied_at: env.block.height,
+    });
+    LotteryApplicationsStorage::from_storage(&mut deps.storage)
+        .set_applications(alias_string.as_bytes(), &applications);
+
+    Ok(HandleResponse {
+        messages: vec![],
+        log: vec![],
+        data: Some(to_binary(&ReceiveAnswer::ApplyForAlias {
+            status: Success,
+        })?),
+    })
+}
+
+fn try_bid_reclaim<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    env: Env,
+    config: Config,
+    bidder: HumanAddr,
+    amount: Uint128,
+    alias_string: String,
+) -> StdResult<HandleResponse> {
+    if env.message.sender != config.buttcoin.address {
+        return Err(StdError::generic_err("Reclaim bids are paid in Buttcoin"));
+    }
+    let alias_string = normalize_alias(&deps.storage, &alias_string)?;
+    let mut reclaim: StoredReclaim = ReclaimsReadonlyStorage::from_storage(&deps.storage)
+        .get_reclaim(alias_string.as_bytes())
+        .ok_or_else(|| StdError::not_found("Reclaim"))?;
+    if env.block.height >= reclaim.bidding_ends_at {
+        return Err(StdError::generic_err("Bidding has ended"));
+    }
+    if amount <= reclaim.bid {
+        return Err(StdError::generic_err(format!(
+            "Bid must be more than {}",
+            reclaim.bid
+        )));
+    }
+    // This also keeps the owner from bidding on their own alias
+    let bidder: CanonicalAddr = deps.api.canonical_address(&bidder)?;
+    if RegistryReadonlyStorage::from_storage(&deps.storage, &deps.api)
+        .get_alias_for_address(&bidder)
+        .is_some()
+    {
+        return Err(StdError::generic_err("Bidder already has an alias"));
+    }
+
+    let mut messages: Vec<CosmosMsg> = vec![];
+    if let Some(previous_bidder) = reclaim.bidder {
+        messages.push(send_asset_msg(
+            env.contract.address,
+            deps.api.human_address(&previous_bidder)?,
+            Asset::Snip20 {
+                token: config.buttcoin,
+            },
+            reclaim.bid,
+        )?);
+    }
+    reclaim.bidder = Some(bidder);
+    reclaim.bid = amount;
+    ReclaimsStorage::from_storage(&mut deps.storage).set_reclaim(alias_string.as_bytes(), &reclaim);
+
+    Ok(HandleResponse {
+        messages,
+        log: vec![],
+        data: Some(to_binary(&ReceiveAnswer::BidReclaim { status: Success })?),
+    })
+}
+
+fn try_buy_drop<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    env: Env,
+    config: Config,
+    buyer: HumanAddr,
+    amount: Uint128,
+    alias_string: String,
+) -> StdResult<HandleResponse> {
+    if env.message.sender != config.buttcoin.address {
+        return Err(StdError::generic_err("Drops are paid for in Buttcoin"));
+    }
+    let alias_string_formatted: String = config.case_folding.fold(alias_string.trim());
+    let drop: Drop = DropsReadonlyStorage::from_storage(&deps.storage)
+        .get_drop(alias_string_formatted.as_bytes())
+        .ok_or_else(|| StdError::not_found("Drop"))?;
+    if env.block.height < drop.start_height {
+        return Err(StdError::generic_err(format!(
+            "Drop starts at block {}",
+            drop.start_height
+        )));
+    }
+    let price: Uint128 = drop_price(&drop, env.block.height);
+    if amount < price {
+        return Err(StdError::generic_err(format!(
+            "Amount sent in: {}. Amount required {}.",
+            amount, price
+        )));
+    }
+
+    DropsStorage::from_storage(&mut deps.storage).remove_drop(alias_string_formatted.as_bytes());
+    let mut reserved_aliases_storage = ReservedAliasesStorage::from_storage(&mut deps.storage);
+    if reserved_aliases_storage.is_reserved(alias_string_formatted.as_bytes()) {
+        reserved_aliases_storage.remove_reserved(alias_string_formatted.as_bytes());
+        let mut count_store = TypedStoreMut::attach(&mut deps.storage);
+        let reserved_count: u64 = count_store
+            .may_load(RESERVED_ALIAS_COUNT_KEY)?
+            .unwrap_or(0)
+            .saturating_sub(1);
+        count_store.store(RESERVED_ALIAS_COUNT_KEY, &reserved_count)?;
+    }
+    let contract_address: HumanAddr = env.contract.address.clone();
+    let buttcoin: Asset = Asset::Snip20 {
+        token: config.buttcoin.clone(),
+    };
+    let mut response: HandleResponse =
+        try_create(deps, env, buyer.clone(), alias_string, None, None, vec![])?;
+    response.data = Some(to_binary(&ReceiveAnswer::BuyDrop { status: Success })?);
+    if amount > price {
+        response.messages.push(send_asset_msg(
+            contract_address.clone(),
+            buyer,
+            buttcoin.clone(),
+            Uint128(amount.u128() - price.u128()),
+        )?);
+    }
+    forward_payment(
+        response,
+        config,
+        contract_address,
+        Payment {
+            asset: buttcoin,
+            amount: price,
+        },
+    )
+}
+
+fn try_cancel_drop<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    env: Env,
+    alias_string: String,
+) -> StdResult<HandleResponse> {
+    let config: Config = TypedStore::attach(&deps.storage).load(CONFIG_KEY)?;
+    authorize_admin_action(
+        &mut deps.storage,
+        &deps.api,
+        &config,
+        &env.message.sender,
+        AdminRole::Treasurer,
+    )?;
+    let alias_string: String = config.case_folding.fold(alias_string.trim());
+    let mut drops_storage = DropsStorage::from_storage(&mut deps.storage);
+    if drops_storage.get_drop(alias_string.as_bytes()).is_none() {
+        return Err(StdError::not_found("Drop"));
+    }
+    drops_storage.remove_drop(alias_string.as_bytes());
+
+    Ok(HandleResponse {
+        messages: vec![],
+        log: vec![],
+        data: Some(to_binary(&HandleAnswer::CancelDrop { status: Success })?),
+    })
+}
+
+fn try_cancel_queued_action<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    env: Env,
+    id: u64,
+) -> StdResult<HandleResponse> {
+    let config: Config = TypedStore::attach(&deps.storage).load(CONFIG_KEY)?;
+    authorize_admin_action(
+        &mut deps.storage,
+        &deps.api,
+        &config,
+        &env.message.sender,
+        AdminRole::SuperAdmin,
+    )?;
+    let mut queued_actions: Vec<StoredQueuedAction> = TypedStore::attach(&deps.storage)
+        .may_load(QUEUED_ACTIONS_KEY)?
+        .unwrap_or_default();
+    let index: usize = queued_actions
+        .iter()
+        .position(|queued_action| queued_action.id == id)
+        .ok_or_else(|| StdError::not_found("Queued action"))?;
+
+    queued_actions.remove(index);
+    TypedStoreMut::attach(&mut deps.storage).store(QUEUED_ACTIONS_KEY, &queued_actions)?;
+
+    Ok(HandleResponse {
+        messages: vec![],
+        log: vec![],
+        data: Some(to_binary(&HandleAnswer::CancelQueuedAction {
+            status: Success,
+        })?),
+    })
+}
+
+fn try_cancel_escrow<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    env: Env,
+    alias_string: String,
+) -> StdResult<HandleResponse> {
+    let alias_string = normalize_alias(&deps.storage, &alias_string)?;
+    let alias_object: Option<Alias> =
+        RegistryReadonlyStorage::from_storage(&deps.storage, &deps.api)
+            .get_alias(alias_string.as_bytes());
+    if alias_object.is_none() {
+        return Err(StdError::not_found("Alias"));
+    }
+    let alias_object: Alias = alias_object.unwrap();
+    let mut escrows_storage = EscrowsStorage::from_storage(&mut deps.storage);
+    let escrow: Option<StoredEscrow> = escrows_storage.get_escrow(alias_string.as_bytes());
+    if escrow.is_none() {
+        return Err(StdError::not_found("Escrow"));
+    }
+    let sender: CanonicalAddr = deps.api.canonical_address(&env.message.sender)?;
+    if sender != alias_object.owner && sender != escrow.unwrap().buyer {
+        return Err(StdError::Unauthorized { backtrace: None });
+    }
+
+    escrows_storage.remove_escrow(alias_string.as_bytes());
+
+    Ok(HandleResponse {
+        messages: vec![],
+        log: vec![],
+        data: Some(to_binary(&HandleAnswer::CancelEscrow { status: Success })?),
+    })
+}
+
+fn try_claim_airdrop<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    env: Env,
+    alias_string: String,
+    proof: Vec<Binary>,
+    avatar_url: Option<String>,
+) -> StdResult<HandleResponse> {
+    let config: Config = TypedStore::attach(&deps.storage).load(CONFIG_KEY)?;
+    let merkle_root: Binary = match config.airdrop_merkle_root {
+        Some(merkle_root) => merkle_root,
+        None => return Err(StdError::generic_err("There is no airdrop")),
+    };
+    if proof.len() > MAX_MERKLE_PROOF_LENGTH {
+        return Err(StdError::generic_err("Proof is too long"));
+    }
+    let leaf: [u8; 32] = sha_256(
+        &[
+            normalize_alias(&deps.storage, &alias_string)?.as_bytes(),
+            &[0],
+            env.message.sender.0.as_bytes(),
+        ]
+        .concat(),
+    );
+    if !verify_merkle_proof(merkle_root.as_slice(), leaf, &proof) {
+        return Err(StdError::generic_err("Proof is invalid"));
+    }
+    if AirdropClaimsReadonlyStorage::from_storage(&deps.storage).is_claimed(&leaf) {
+        return Err(StdError::generic_err("Airdrop has already been claimed"));
+    }
+
+    let claimant: HumanAddr = env.message.sender.clone();
+    let mut response: HandleResponse =
+        try_create(deps, env, claimant, alias_string, avatar_url, None, vec![])?;
+    AirdropClaimsStorage::from_storage(&mut deps.storage).set_claimed(&leaf);
+    response.data = Some(to_binary(&HandleAnswer::ClaimAirdrop { status: Success })?);
+    Ok(response)
+}
+
+fn try_claim_cross_chain<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    env: Env,
+    alias_string: String,
+    signature: Binary,
+    proof: Vec<Binary>,
+    avatar_url: Option<String>,
+) -> StdResult<HandleResponse> {
+    let config: Config = TypedStore::attach(&deps.storage).load(CONFIG_KEY)?;
+    let cross_chain_snapshot: CrossChainSnapshot = match config.cross_chain_snapshot {
+        Some(cross_chain_snapshot) => cross_chain_snapshot,
+        None => return Err(StdError::generic_err("There are no cross-chain claims")),
+    };
+    if env.block.height >= cross_chain_snapshot.ends_at {
+        return Err(StdError::generic_err("Cross-chain claims have ended"));
+    }
+    if proof.len() > MAX_MERKLE_PROOF_LENGTH {
+        return Err(StdError::generic_err("Proof is too long"));
+    }
+    let alias_string = normalize_alias(&deps.storage, &alias_string)?;
+    // The sender and contract are signed for so a signature can't be used by anyone else or
+    // replayed against another deployment
+    let message: String = format!(
+        "Claim {} for {} on {}",
+        alias_string, env.message.sender, env.contract.address
+    );
+    let owner: String = match recover_ethereum_address(message.as_bytes(), signature.as_slice()) {
+        Some(owner) => owner,
+        None => return Err(StdError::generic_err("Signature is invalid")),
+    };
+    let leaf: [u8; 32] = sha_256(&[alias_string.as_bytes(), &[0], owner.as_bytes()].concat());
+    if !verify_merkle_proof(cross_chain_snapshot.merkle_root.as_slice(), leaf, &proof) {
+        return Err(StdError::generic_err("Proof is invalid"));
+    }
+    if CrossChainClaimsReadonlyStorage::from_storage(&deps.storage).is_claimed(&leaf) {
+        return Err(StdError::generic_err("Alias has already been claimed"));
+    }
+
+    let claimant: HumanAddr = env.message.sender.clone();
+    let mut response: HandleResponse =
+        try_create(deps, env, claimant, alias_string, avatar_url, None, vec![])?;
+    CrossChainClaimsStorage::from_storage(&mut deps.storage).set_claimed(&leaf);
+    response.data = Some(to_binary(&HandleAnswer::ClaimCrossChain {
+        status: Success,
+    })?);
+    Ok(response)
+}
+
+fn try_claim_gift<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    env: Env,
+    alias_string: String,
+    code: String,
+    avatar_url: Option<String>,
+) -> StdResult<HandleResponse> {
+    let alias_string = normalize_alias(&deps.storage, &alias_string)?;
+    // Checked before the gift is used up, although Create checks it again
+    if RegistryReadonlyStorage::from_storage(&deps.storage, &deps.api)
+        .get_alias_for_address(&deps.api.canonical_address(&env.message.sender)?)
+        .is_some()
+    {
+        return Err(StdError::generic_err("Address already has an alias"));
+    }
+    let mut gifts_storage = GiftsStorage::from_storage(&mut deps.storage);
+    let gift: Option<Gift> = gifts_storage.get_gift(alias_string.as_bytes());
+    // Answer not found for a wrong code too so codes can't be checked against gifts
+    if gift.is_none() {
+        return Err(StdError::not_found("Gift"));
+    }
+    let gift: Gift = gift.unwrap();
+    if sha_256(code.as_bytes()).to_vec() != gift.code_hash.0 {
+        return Err(StdError::not_found("Gift"));
+    }
+
+    gifts_storage.remove_gift(alias_string.as_bytes());
+    let claimant: HumanAddr = env.message.sender.clone();
+    let mut response: HandleResponse = try_create(
+        deps,
+        env,
+        claimant,
+        gift.display_name,
+        avatar_url,
+        None,
+        vec![],
+    )?;
+    response.data = Some(to_binary(&HandleAnswer::ClaimGift { status: Success })?);
+    Ok(response)
+}
+
+fn try_create<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    env: Env,
+    from: HumanAddr,
+    alias_string: String,
+    avatar_url: Option<String>,
+    avatar_metadata: Option<ImageMetadata>,
+    records: Vec<Record>,
+) -> StdResult<HandleResponse> {
+    let config: Config = TypedStore::attach(&deps.storage).load(CONFIG_KEY)?;
+    validate_avatar_url(&config, &avatar_url)?;
+    validate_avatar_metadata(&avatar_url, &avatar_metadata)?;
+    let records: Vec<Record> = validate_records(records)?;
+    let from: CanonicalAddr = deps.api.canonical_address(&from)?;
+    let alias_string = alias_string.trim();
+    let alias_string_formatted = config.case_folding.fold(alias_string);
+    let alias_string_byte_slice: &[u8] = alias_string_formatted.as_bytes();
+    validate_alias_string(&alias_string_formatted)?;
+    let messages: Vec<CosmosMsg> = release_expired_lease(
+        &mut deps.storage,
+        &deps.api,
+        &env,
+        &config,
+        &alias_string_formatted,
+    )?;
+    // Check that Alias doesn't already exist and that the user doesn't already have an alias
+    ensure_alias_available(&deps.storage, &deps.api, &alias_string_formatted)?;
+    ensure_not_tombstoned(
+        &deps.storage,
+        &alias_string_formatted,
+        &from,
+        env.block.height,
+    )?;
+    let mut alias_storage = RegistryStorage::from_storage(&mut deps.storage, &deps.api);
+    if alias_storage.get_alias_for_address(&from).is_some() {
+        return Err(StdError::generic_err("Address already has an alias"));
+    }
+    alias_storage.set_alias(
+        alias_string_byte_slice,
+        Alias {
+            alias: alias_string_formatted.clone(),
+            // Keep the capitalization the alias was registered with
+            display_name: alias_string.to_string(),
+            avatar_url: avatar_url,
+            avatar_metadata,
+            owner: from.clone(),
+            created_at: env.block.height,
+            updated_at: env.block.height,
+            target: None,
+            wildcard: false,
+            private_reverse_lookup: false,
+            banner_url: None,
+            accent_color: None,
+        },
+    );
+    if !records.is_empty() {
+        RecordsStorage::from_storage(&mut deps.storage)
+            .set_records(alias_string_byte_slice, &records);
+    }
+    record_change(
+        &mut deps.storage,
+        &deps.api,
+        &env.block,
+        ChangeAction::Create,
+        alias_string_formatted,
+        from,
+    )?;
+
+    Ok(HandleResponse {
+        messages,
+        log: vec![],
+        data: Some(to_binary(&ReceiveAnswer::Create { status: Success })?),
+    })
+}
+
+// Registers the alias or, when it's taken, its first available numbered variant
+fn try_create_with_fallback<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    env: Env,
+    from: HumanAddr,
+    alias_string: String,
+    avatar_url: Option<String>,
+    avatar_metadata: Option<ImageMetadata>,
+    records: Vec<Record>,
+) -> StdResult<HandleResponse> {
+    let config: Config = TypedStore::attach(&deps.storage).load(CONFIG_KEY)?;
+    let registrant: CanonicalAddr = deps.api.canonical_address(&from)?;
+    let alias_string: &str = alias_string.trim();
+    // A variant can't fix an alias that breaks the rules, so that is reported as it would be
+    // without the fallback
+    validate_alias_string(&config.case_folding.fold(alias_string))?;
+    let is_available = |candidate: &str| -> bool {
+        let candidate: String = config.case_folding.fold(candidate);
+        validate_alias_string(&candidate).is_ok()
+            && ensure_alias_available(&deps.storage, &deps.api, &candidate).is_ok()
+            && ensure_not_tombstoned(&deps.storage, &candidate, &registrant, env.block.height)
+                .is_ok()
+    };
+    let registered_alias: String = (1..=MAX_FALLBACK_VARIANTS)
+        .map(|n| {
+            if n == 1 {
+                alias_string.to_string()
+            } else {
+                format!("{}{}", alias_string, n)
+            }
+        })
+        .find(|candidate| is_available(candidate))
+        .ok_or_else(|| StdError::generic_err("No variant of the alias is available"))?;
+
+    let mut response: HandleResponse = try_create(
+        deps,
+        env,
+        from,
+        registered_alias.clone(),
+        avatar_url,
+        avatar_metadata,
+        records,
+    )?;
+    response.data = Some(to_binary(&ReceiveAnswer::CreateWithFallback {
+        alias: config.case_folding.fold(&registered_alias),
+    })?);
+
+    Ok(response)
+}
+
+// The alias is reserved until claimed, so nobody else can register it in the meantime
+fn try_create_gift<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    env: Env,
+    from: HumanAddr,
+    alias_string: String,
+    code_hash: Binary,
+) -> StdResult<HandleResponse> {
+    let giver: CanonicalAddr = deps.api.canonical_address(&from)?;
+    let display_name: String = alias_string.trim().to_string();
+    let alias_string = normalize_alias(&deps.storage, &display_name)?;
+    validate_alias_string(&alias_string)?;
+    if code_hash.len() != 32 {
+        return Err(StdError::generic_err("Code hash must be a sha256 hash"));
+    }
+    ensure_alias_available(&deps.storage, &deps.api, &alias_string)?;
+    ensure_not_tombstoned(&deps.storage, &alias_string, &giver, env.block.height)?;
+
+    // The previous owner is handing the alias on, so the claimant isn't held to the tombstone
+    TombstonesStorage::from_storage(&mut deps.storage).remove_tombstone(alias_string.as_bytes());
+    GiftsStorage::from_storage(&mut deps.storage).set_gift(
+        alias_string.as_bytes(),
+        &Gift {
+            giver,
+            code_hash,
+            display_name,
+            created_at: env.block.height,
+        },
+    );
+
+    Ok(HandleResponse {
+        messages: vec![],
+        log: vec![],
+        data: Some(to_binary(&ReceiveAnswer::CreateGift { status: Success })?),
+    })
+}
+
+// Mint a viewing key that only unlocks this alias's private records
+fn try_create_alias_viewing_key<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    env: Env,
+    alias_string: String,
+    label: String,
+    entropy: String,
+) -> StdResult<HandleResponse> {
+    let alias_string = normalize_alias(&deps.storage, &alias_string)?;
+    load_owned_alias(&deps.storage, &deps.api, &env, &alias_string)?;
+    let label = label.trim().to_string();
+    if label.is_empty() || label.len() > MAX_LABEL_LENGTH {
+        return Err(StdError::generic_err("Label is invalid"));
+    }
+
+    let prng_seed: [u8; 32] = TypedStore::attach(&deps.storage).load(PRNG_SEED_KEY)?;
+    let key = ViewingKey::new(&env, &prng_seed, entropy.as_bytes());
+    let mut alias_viewing_keys_storage = AliasViewingKeysStorage::from_storage(&mut deps.storage);
+    let mut alias_viewing_keys: Vec<AliasViewingKey> =
+        alias_viewing_keys_storage.get_alias_viewing_keys(alias_string.as_bytes());
+    if alias_viewing_keys
+        .iter()
+        .any(|alias_viewing_key| alias_viewing_key.label == label)
+    {
+        return Err(StdError::generic_err("Label has already been taken"));
+    }
+    if alias_viewing_keys.len() >= MAX_VIEWING_KEYS_PER_ALIAS {
+        return Err(StdError::generic_err(format!(
+            "Too many viewing keys. Maximum is {}.",
+            MAX_VIEWING_KEYS_PER_ALIAS
+        )));
+    }
+    alias_viewing_keys.push(AliasViewingKey {
+        label,
+        hashed_key: key.to_hashed().to_vec(),
+    });
+    alias_viewing_keys_storage.set_alias_viewing_keys(alias_string.as_bytes(), &alias_viewing_keys);
+
+    Ok(HandleResponse {
+        messages: vec![],
+        log: vec![],
+        data: Some(to_binary(&HandleAnswer::CreateAliasViewingKey { key })?),
+    })
+}
+
+fn try_create_viewing_key<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    env: Env,
+    entropy: String,
+) -> StdResult<HandleResponse> {
+    let prng_seed: [u8; 32] = TypedStore::attach(&deps.storage).load(PRNG_SEED_KEY)?;
+    let key = ViewingKey::new(&env, &prng_seed, entropy.as_bytes());
+    let sender: CanonicalAddr = deps.api.canonical_address(&env.message.sender)?;
+    ViewingKeysStorage::from_storage(&mut deps.storage)
+        .set_viewing_key(sender.as_slice(), &key.to_hashed());
+
+    Ok(HandleResponse {
+        messages: vec![],
+        log: vec![],
+        data: Some(to_binary(&HandleAnswer::CreateViewingKey { key })?),
+    })
+}
+
+fn try_defend_reclaim<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    env: Env,
+    config: Config,
+    from: HumanAddr,
+    amount: Uint128,
+    alias_string: String,
+) -> StdResult<HandleResponse> {
+    let alias_string = normalize_alias(&deps.storage, &alias_string)?;
+    let alias_object: Alias = RegistryReadonlyStorage::from_storage(&deps.storage, &deps.api)
+        .get_alias(alias_string.as_bytes())
+        .ok_or_else(|| StdError::not_found("Alias"))?;
+    if deps.api.canonical_address(&from)? != alias_object.owner {
+        return Err(StdError::Unauthorized { backtrace: None });
+    }
+    let reclaim: StoredReclaim = ReclaimsReadonlyStorage::from_storage(&deps.storage)
+        .get_reclaim(alias_string.as_bytes())
+        .ok_or_else(|| StdError::not_found("Reclaim"))?;
+    if env.block.height < reclaim.bidding_ends_at || env.block.height >= reclaim.defense_ends_at {
+        return Err(StdError::generic_err(format!(
+            "A reclaim can only be defended from block {} until block {}",
+            reclaim.bidding_ends_at, reclaim.defense_ends_at
+        )));
+    }
+    let bidder: CanonicalAddr = reclaim
+        .bidder
+        .ok_or_else(|| StdError::generic_err("There is no bid to match"))?;
+    let payment: Payment = buttcoin_payment(&config, &env, amount, reclaim.bid)?;
+
+    ReclaimsStorage::from_storage(&mut deps.storage).remove_reclaim(alias_string.as_bytes());
+    // Defending counts as activity, so the alias can't be put up again straight away
+    save_updated_alias(
+        &mut deps.storage,
+        &deps.api,
+        &env,
+        alias_string,
+        alias_object,
+    )?;
+    let contract_address: HumanAddr = env.contract.address.clone();
+    let response: HandleResponse = HandleResponse {
+        messages: vec![send_asset_msg(
+            env.contract.address,
+            deps.api.human_address(&bidder)?,
+            payment.asset.clone(),
+            reclaim.bid,
+        )?],
+        log: vec![],
+        data: Some(to_binary(&ReceiveAnswer::DefendReclaim {
+            status: Success,
+        })?),
+    };
+    forward_payment(response, config, contract_address, payment)
+}
+
+fn try_destroy<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    env: Env,
+    alias_string: String,
+) -> StdResult<HandleResponse> {
+    let alias_string = normalize_alias(&deps.storage, &alias_string)?;
+    let alias_object: Alias = load_co_owned_alias(&deps.storage, &deps.api, &env, &alias_string)?;
+
+    propose_action(
+        &mut deps.storage,
+        &deps.api,
+        &env,
+        alias_string,
+        alias_object,
+        StoredApprovalAction::Destroy,
+    )?;
+
+    Ok(HandleResponse {
+        messages: vec![],
+        log: vec![],
+        data: Some(to_binary(&HandleAnswer::Destroy { status: Success })?),
+    })
+}
+
+// The action is removed before it runs. If it fails, the whole handle is reverted and it stays
+// queued.
+fn try_execute_queued_action<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    mut env: Env,
+    id: u64,
+) -> StdResult<HandleResponse> {
+    let mut queued_actions: Vec<StoredQueuedAction> = TypedStore::attach(&deps.storage)
+        .may_load(QUEUED_ACTIONS_KEY)?
+        .unwrap_or_default();
+    let index: usize = queued_actions
+        .iter()
+        .position(|queued_action| queued_action.id == id)
+        .ok_or_else(|| StdError::not_found("Queued action"))?;
+    if env.block.height < queued_actions[index].executable_at {
+        return Err(StdError::generic_err(format!(
+            "Queued action can't be executed until block {}",
+            queued_actions[index].executable_at
+        )));
+    }
+
+    let queued_action: StoredQueuedAction = queued_actions.remove(index);
+    TypedStoreMut::attach(&mut deps.storage).store(QUEUED_ACTIONS_KEY, &queued_actions)?;
+    env.message.sender = deps.api.human_address(&queued_action.queued_by)?;
+    dispatch(deps, env, from_binary(&queued_action.msg)?)
+}
+
+fn try_end_bootstrap<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    env: Env,
+) -> StdResult<HandleResponse> {
+    let mut config: Config = TypedStore::attach(&deps.storage).load(CONFIG_KEY)?;
+    authorize_admin_action(
+        &mut deps.storage,
+        &deps.api,
+        &config,
+        &env.message.sender,
+        AdminRole::SuperAdmin,
+    )?;
+    ensure_bootstrap_phase(&config, env.block.height)?;
+
+    config.bootstrap_ends_at = None;
+    TypedStoreMut::attach(&mut deps.storage).store(CONFIG_KEY, &config)?;
+
+    Ok(HandleResponse {
+        messages: vec![],
+        log: vec![],
+        data: Some(to_binary(&HandleAnswer::EndBootstrap { status: Success })?),
+    })
+}
+
+fn try_endorse<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    env: Env,
+    alias_string: String,
+) -> StdResult<HandleResponse> {
+    let alias_string = normalize_alias(&deps.storage, &alias_string)?;
+    let alias_object: Option<Alias> =
+        RegistryReadonlyStorage::from_storage(&deps.storage, &deps.api)
+            .get_alias(alias_string.as_bytes());
+    if alias_object.is_none() {
+        return Err(StdError::not_found("Alias"));
+    }
+    let endorser: CanonicalAddr = deps.api.canonical_address(&env.message.sender)?;
+    if endorser == alias_object.unwrap().owner {
+        return Err(StdError::generic_err("You can't endorse your own alias"));
+    }
+    let mut endorsements_storage = EndorsementsStorage::from_storage(&mut deps.storage);
+    let mut endorsers: Vec<CanonicalAddr> =
+        endorsements_storage.get_endorsers(alias_string.as_bytes());
+    if endorsers.contains(&endorser) {
+        return Err(StdError::generic_err("You already endorse this alias"));
+    }
+    if endorsers.len() >= MAX_ENDORSEMENTS_PER_ALIAS {
+        return Err(StdError::generic_err(format!(
+            "An alias can have at most {} endorsements",
+            MAX_ENDORSEMENTS_PER_ALIAS
+        )));
+    }
+
+    endorsers.push(endorser);
+    endorsements_storage.set_endorsers(alias_string.as_bytes(), &endorsers);
+
+    Ok(HandleResponse {
+        messages: vec![],
+        log: vec![],
+        data: Some(to_binary(&HandleAnswer::Endorse { status: Success })?),
+    })
+}
+
+fn try_finalize_lottery<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    env: Env,
+    alias_string: String,
+) -> StdResult<HandleResponse> {
+    let config: Config = TypedStore::attach(&deps.storage).load(CONFIG_KEY)?;
+    if matches!(config.lottery_ends_at, Some(lottery_ends_at) if env.block.height < lottery_ends_at)
+    {
+        return Err(StdError::generic_err("The lottery is still running"));
+    }
+    let alias_string: String = normalize_alias(&deps.storage, &alias_string)?;
+    let applications: Vec<LotteryApplication> =
+        LotteryApplicationsReadonlyStorage::from_storage(&deps.storage)
+            .get_applications(alias_string.as_bytes());
+    if applications.is_empty() {
+        return Err(StdError::not_found("Lottery application"));
+    }
+
+    // Applicants who have registered another alias since applying can't win
+    let registry = RegistryReadonlyStorage::from_storage(&deps.storage, &deps.api);
+    let eligible: Vec<usize> = (0..applications.len())
+        .filter(|i| {
+            registry
+                .get_alias_for_address(&applications[*i].applicant)
+                .is_none()
+        })
+        .collect();
+    // The seed is kept in private storage, so applicants can't work out the draw in advance
+    let prng_seed: [u8; 32] = TypedStore::attach(&deps.storage).load(PRNG_SEED_KEY)?;
+    let draw: [u8; 32] = sha_256(
+        &[
+            &prng_seed[..],
+            alias_string.as_bytes(),
+            &env.block.height.to_be_bytes(),
+            &env.block.time.to_be_bytes(),
+        ]
+        .concat(),
+    );
+    let winner_index: Option<usize> = if eligible.is_empty() {
+        None
+    } else {
+        let mut draw_bytes: [u8; 8] = [0; 8];
+        draw_bytes.copy_from_slice(&draw[..8]);
+        Some(eligible[(u64::from_be_bytes(draw_bytes) % eligible.len() as u64) as usize])
+    };
+    LotteryApplicationsStorage::from_storage(&mut deps.storage)
+        .remove_applications(alias_string.as_bytes());
+
+    // If the winner can't be registered any more, e.g. the alias has been reserved since or avatars
+    // have become required, nobody gets the alias and every applicant is refunded. Failing instead
+    // would keep the applications and their fees stuck. The checks are the ones try_create makes,
+    // run first so that nothing is registered for a winner who doesn't pass them.
+    let can_register = |winner: &CanonicalAddr| -> bool {
+        validate_avatar_url(&config, &None).is_ok()
+            && validate_alias_string(&alias_string).is_ok()
+            && ensure_alias_available(&deps.storage, &deps.api, &alias_string).is_ok()
+            && ensure_not_tombstoned(&deps.storage, &alias_string, winner, env.block.height).is_ok()
+            && RegistryReadonlyStorage::from_storage(&deps.storage, &deps.api)
+                .get_alias_for_address(winner)
+                .is_none()
+    };
+    let winner_index: Option<usize> =
+        winner_index.filter(|winner_index| can_register(&applications[*winner_index].applicant));
+    let contract_address: HumanAddr = env.contract.address.clone();
+    let created: Option<(usize, HumanAddr, HandleResponse)> = match winner_index {
+        Some(winner_index) => {
+            let winner: HumanAddr = deps
+                .api
+                .human_address(&applications[winner_index].applicant)?;
+            let response: HandleResponse =
+                try_create(deps, env, winner.clone(), alias_string, None, None, vec![])?;
+            Some((winner_index, winner, response))
+        }
+        None => None,
+    };
+    let mut refunds: Vec<CosmosMsg> = vec![];
+    for (i, application) in applications.iter().enumerate() {
+        if !matches!(created, Some((winner_index, _, _)) if winner_index == i) {
+            refunds.push(send_asset_msg(
+                contract_address.clone(),
+                deps.api.human_address(&application.applicant)?,
+                application.asset.clone(),
+                application.amount,
+            )?);
+        }
+    }
+    let mut response: HandleResponse = match created {
+        Some((winner_index, winner, mut response)) => {
+            let winning_application: LotteryApplication = applications[winner_index].clone();
+            response.data = Some(to_binary(&HandleAnswer::FinalizeLottery {
+                winner: Some(winner),
+            })?);
+            forward_payment(
+                response,
+                config,
+                contract_address,
+                Payment {
+                    asset: winning_application.asset,
+                    amount: winning_application.amount,
+                },
+            )?
+        }
+        None => HandleResponse {
+            messages: vec![],
+            log: vec![],
+            data: Some(to_binary(&HandleAnswer::FinalizeLottery { winner: None })?),
+        },
+    };
+    response.messages.extend(refunds);
+
+    Ok(response)
+}
+
+fn try_follow<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    env: Env,
+    alias_string: String,
+) -> StdResult<HandleResponse> {
+    let alias_string = normalize_alias(&deps.storage, &alias_string)?;
+    let follower: CanonicalAddr = deps.api.canonical_address(&env.message.sender)?;
+    let alias_storage = RegistryReadonlyStorage::from_storage(&deps.storage, &deps.api);
+    if alias_storage.get_alias_for_address(&follower).is_none() {
+        return Err(StdError::generic_err(
+            "You need an alias to follow other aliases",
+        ));
+    }
+    let followed: CanonicalAddr = match alias_storage.get_alias(alias_string.as_bytes()) {
+        Some(alias_object) => alias_object.owner,
+        None => return Err(StdError::not_found("Alias")),
+    };
+    if followed == follower {
+        return Err(StdError::generic_err("You can't follow your own alias"));
+    }
+    let mut follows_storage = FollowsStorage::from_storage(&mut deps.storage);
+    let mut follower_follows: Follows = follows_storage.get_follows(&follower);
+    if follower_follows.following.contains(&followed) {
+        return Err(StdError::generic_err("You already follow this alias"));
+    }
+    if follower_follows.following.len() >= MAX_FOLLOWING {
+        return Err(StdError::generic_err(format!(
+            "You can follow at most {} aliases",
+            MAX_FOLLOWING
+        )));
+    }
+    let mut followed_follows: Follows = follows_storage.get_follows(&followed);
+    if followed_follows.followers.len() >= MAX_FOLLOWERS {
+        return Err(StdError::generic_err(format!(
+            "An alias can have at most {} followers",
+            MAX_FOLLOWERS
+        )));
+    }
+
+    follower_follows.following.push(followed.clone());
+    followed_follows.followers.push(follower.clone());
+    follows_storage.set_follows(&follower, &follower_follows);
+    follows_storage.set_follows(&followed, &followed_follows);
+
+    Ok(HandleResponse {
+        messages: vec![],
+        log: vec![],
+        data: Some(to_binary(&HandleAnswer::Follow { status: Success })?),
+    })
+}
+
+fn try_freeze<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    env: Env,
+    alias_string: String,
+    allow_transfers: bool,
+) -> StdResult<HandleResponse> {
+    let alias_string = normalize_alias(&deps.storage, &alias_string)?;
+    let alias_object: Alias = load_co_owned_alias(&deps.storage, &deps.api, &env, &alias_string)?;
+    ensure_not_frozen(&deps.storage, &alias_string)?;
+
+    propose_action(
+        &mut deps.storage,
+        &deps.api,
+        &env,
+        alias_string,
+        alias_object,
+        StoredApprovalAction::Freeze { allow_transfers },
+    )?;
+
+    Ok(HandleResponse {
+        messages: vec![],
+        log: vec![],
+        data: Some(to_binary(&HandleAnswer::Freeze { status: Success })?),
+    })
+}
+
+fn try_renounce<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    env: Env,
+    alias_string: String,
+) -> StdResult<HandleResponse> {
+    let alias_string = normalize_alias(&deps.storage, &alias_string)?;
+    let alias_object: Alias = load_co_owned_alias(&deps.storage, &deps.api, &env, &alias_string)?;
+
+    propose_action(
+        &mut deps.storage,
+        &deps.api,
+        &env,
+        alias_string,
+        alias_object,
+        StoredApprovalAction::Renounce,
+    )?;
+
+    Ok(HandleResponse {
+        messages: vec![],
+        log: vec![],
+        data: Some(to_binary(&HandleAnswer::Renounce { status: Success })?),
+    })
+}
+
+// Settles the escrow: the buyer gets the alias and the seller gets the price along with any tips
+// the alias received while it was in escrow
+fn try_fund_escrow<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    env: Env,
+    buyer: HumanAddr,
+    alias_string: String,
+    payment: Payment,
+) -> StdResult<HandleResponse> {
+    let alias_string = normalize_alias(&deps.storage, &alias_string)?;
+    let alias_object: Option<Alias> =
+        RegistryReadonlyStorage::from_storage(&deps.storage, &deps.api)
+            .get_alias(alias_string.as_bytes());
+    if alias_object.is_none() {
+        return Err(StdError::not_found("Alias"));
+    }
+    let alias_object: Alias = alias_object.unwrap();
+    // A leased alias goes to the buyer with its lease, so the lease has to still be running
+    ensure_lease_current(&deps.storage, &alias_string, env.block.height)?;
+    let mut escrows_storage = EscrowsStorage::from_storage(&mut deps.storage);
+    let escrow: Option<StoredEscrow> = escrows_storage.get_escrow(alias_string.as_bytes());
+    if escrow.is_none() {
+        return Err(StdError::not_found("Escrow"));
+    }
+    let escrow: StoredEscrow = escrow.unwrap();
+    authorize(deps.api.canonical_address(&buyer)?, escrow.buyer.clone())?;
+    if payment.asset != escrow.asset || payment.amount != escrow.amount {
+        return Err(StdError::generic_err(format!(
+            "The price is {} of {}",
+            escrow.amount,
+            asset_name(&escrow.asset)
+        )));
+    }
+
+    escrows_storage.remove_escrow(alias_string.as_bytes());
+    let seller: HumanAddr = deps.api.human_address(&alias_object.owner)?;
+    let mut messages: Vec<CosmosMsg> = withdraw_tips(
+        &mut deps.storage,
+        &alias_string,
+        env.contract.address.clone(),
+        seller.clone(),
+    )?;
+    messages.push(send_asset_msg(
+        env.contract.address.clone(),
+        seller,
+        payment.asset,
+        payment.amount,
+    )?);
+    transfer_alias(
+        &mut deps.storage,
+        &deps.api,
+        &env,
+        alias_string,
+        alias_object,
+        escrow.buyer,
+    )?;
+
+    Ok(HandleResponse {
+        messages,
+        log: vec![],
+        data: None,
+    })
+}
+
+fn try_grant_role<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    env: Env,
+    address: HumanAddr,
+    role: AdminRole,
+) -> StdResult<HandleResponse> {
+    let mut config: Config = TypedStore::attach(&deps.storage).load(CONFIG_KEY)?;
+    authorize_admin_action(
+        &mut deps.storage,
+        &deps.api,
+        &config,
+        &env.message.sender,
+        AdminRole::SuperAdmin,
+    )?;
+    let admin_grant = StoredAdminGrant {
+        address: deps.api.canonical_address(&address)?,
+        role,
+    };
+    if admin_grant.address == config.admin || config.admin_grants.contains(&admin_grant) {
+        return Err(StdError::generic_err("Address already has the role"));
+    }
+    if config.admin_grants.len() >= MAX_ADMIN_GRANTS {
+        return Err(StdError::generic_err(format!(
+            "There can't be more than {} role grants",
+            MAX_ADMIN_GRANTS
+        )));
+    }
+
+    config.admin_grants.push(admin_grant);
+    TypedStoreMut::attach(&mut deps.storage).store(CONFIG_KEY, &config)?;
+
+    Ok(HandleResponse {
+        messages: vec![],
+        log: vec![],
+        data: Some(to_binary(&HandleAnswer::GrantRole { status: Success })?),
+    })
+}
+
+fn try_import_chunk<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    env: Env,
+    aliases: Vec<AliasAttributes>,
+    checksum: Binary,
+) -> StdResult<HandleResponse> {
+    let config: Config = TypedStore::attach(&deps.storage).load(CONFIG_KEY)?;
+    authorize_admin_action(
+        &mut deps.storage,
+        &deps.api,
+        &config,
+        &env.message.sender,
+        AdminRole::SuperAdmin,
+    )?;
+    ensure_bootstrap_phase(&config, env.block.height)?;
+    if aliases.len() > EXPORT_CHUNK_SIZE as usize {
+        return Err(StdError::generic_err(format!(
+            "A chunk can have at most {} aliases",
+            EXPORT_CHUNK_SIZE
+        )));
+    }
+    if chunk_checksum(&aliases)[..] != checksum.as_slice()[..] {
+        return Err(StdError::generic_err(
+            "Chunk checksum does not match its aliases",
+        ));
+    }
+
+    for alias_attributes in aliases {
+        import_alias(&mut deps.storage, &deps.api, &env, alias_attributes)?;
+    }
+    let registry_checksum: [u8; 32] = TypedStore::attach(&deps.storage)
+        .may_load(CHECKSUM_KEY)?
+        .unwrap_or([0; 32]);
+    let alias_count: u64 = TypedStore::attach(&deps.storage)
+        .may_load(ALIAS_COUNT_KEY)?
+        .unwrap_or(0);
+
+    Ok(HandleResponse {
+        messages: vec![],
+        log: vec![],
+        data: Some(to_binary(&HandleAnswer::ImportChunk {
+            alias_count,
+            checksum: Binary::from(registry_checksum.to_vec()),
+        })?),
+    })
+}
+
+fn try_lease<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    env: Env,
+    config: Config,
+    from: HumanAddr,
+    amount: Uint128,
+    alias_string: String,
+    periods: u16,
+) -> StdResult<HandleResponse> {
+    let lease_terms: LeaseTerms = config
+        .lease_terms
+        .clone()
+        .ok_or_else(|| StdError::generic_err("Aliases can't be leased"))?;
+    let (fee, blocks): (Uint128, u64) =
+        lease_cost(lease_terms.fee, lease_terms.period_blocks, periods)?;
+    let payment: Payment = buttcoin_payment(&config, &env, amount, fee)?;
+    let lease: Lease = Lease {
+        period_blocks: lease_terms.period_blocks,
+        fee: lease_terms.fee,
+        paid_until: env.block.height.saturating_add(blocks),
+    };
+    let alias_string_formatted: String = config.case_folding.fold(alias_string.trim());
+
+    let contract_address: HumanAddr = env.contract.address.clone();
+    let mut response: HandleResponse =
+        try_create(deps, env, from, alias_string, None, None, vec![])?;
+    LeasesStorage::from_storage(&mut deps.storage)
+        .set_lease(alias_string_formatted.as_bytes(), &lease);
+    response.data = Some(to_binary(&ReceiveAnswer::Lease { status: Success })?);
+    forward_payment(response, config, contract_address, payment)
+}
+
+fn try_link_address<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    env: Env,
+    alias_string: String,
+    address: HumanAddr,
+    public_key: Binary,
+    signature: Binary,
+) -> StdResult<HandleResponse> {
+    let alias_string = normalize_alias(&deps.storage, &alias_string)?;
+    let alias_object: Alias = load_owned_alias(&deps.storage, &deps.api, &env, &alias_string)?;
+    ensure_not_frozen(&deps.storage, &alias_string)?;
+
+    // The public key has to be the one the address was derived from, whatever the chain
+    let address_data: Vec<u8> = match decode(address.as_str()) {
+        Some((_, data)) => data,
+        None => return Err(StdError::generic_err("Address is invalid")),
+    };
+    if !is_valid_public_key(public_key.as_slice())
+        || address_data != address_bytes(public_key.as_slice())
+    {
+        return Err(StdError::generic_err(
+            "Public key doesn't belong to the address",
+        ));
+    }
+    let message: String = link_message(&alias_string, &env.contract.address);
+    if !verify_signature(
+        public_key.as_slice(),
+        &adr36_sign_doc(address.as_str(), message.as_bytes()),
+        signature.as_slice(),
+    ) {
+        return Err(StdError::generic_err("Signature is invalid"));
+    }
+
+    let mut linked_addresses_storage = LinkedAddressesStorage::from_storage(&mut deps.storage);
+    let mut linked_addresses: Vec<LinkedAddress> =
+        linked_addresses_storage.get_linked_addresses(alias_string.as_bytes());
+    if linked_addresses
+        .iter()
+        .any(|linked_address| linked_address.address == address)
+    {
+        return Err(StdError::generic_err("Address is already linked"));
+    }
+    if linked_addresses.len() >= MAX_LINKED_ADDRESSES {
+        return Err(StdError::generic_err(format!(
+            "An alias can't have more than {} linked addresses",
+            MAX_LINKED_ADDRESSES
+        )));
+    }
+    linked_addresses.push(LinkedAddress {
+        address,
+        linked_at: env.block.height,
+    });
+    linked_addresses_storage.set_linked_addresses(alias_string.as_bytes(), &linked_addresses);
+    save_updated_alias(
+        &mut deps.storage,
+        &deps.api,
+        &env,
+        alias_string,
+        alias_object,
+    )?;
+
+    Ok(HandleResponse {
+        messages: vec![],
+        log: vec![],
+        data: Some(to_binary(&HandleAnswer::LinkAddress { status: Success })?),
+    })
+}
+
+fn try_lock<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    env: Env,
+    alias_string: String,
+    until_height: u64,
+) -> StdResult<HandleResponse> {
+    let alias_string = normalize_alias(&deps.storage, &alias_string)?;
+    load_owned_alias(&deps.storage, &deps.api, &env, &alias_string)?;
+    // Locking would stop a reclaim or an escrow from being settled
+    ensure_no_reclaim(&deps.storage, &alias_string)?;
+    ensure_not_in_escrow(&deps.storage, &alias_string)?;
+    if until_height <= env.block.height {
+        return Err(StdError::generic_err(
+            "A lock must end after the current block",
+        ));
+    }
+    if until_height - env.block.height > MAX_LOCK_BLOCKS {
+        return Err(StdError::generic_err(format!(
+            "An alias can be locked for at most {} blocks",
+            MAX_LOCK_BLOCKS
+        )));
+    }
+    if matches!(
+        LocksReadonlyStorage::from_storage(&deps.storage).get_locked_until(alias_string.as_bytes()),
+        Some(locked_until) if until_height < locked_until
+    ) {
+        return Err(StdError::generic_err("A lock can't be shortened"));
+    }
+
+    LocksStorage::from_storage(&mut deps.storage)
+        .set_locked_until(alias_string.as_bytes(), until_height);
+
+    Ok(HandleResponse {
+        messages: vec![],
+        log: vec![],
+        data: Some(to_binary(&HandleAnswer::Lock { status: Success })?),
+    })
+}
+
+// What the holder of a linked address signs. Naming the contract stops a signature being
+// replayed against another deployment.
+fn link_message(alias_string: &str, contract_address: &HumanAddr) -> String {
+    format!("Link to {} on {}", alias_string, contract_address)
+}
+
+// Offers are held until accepted or withdrawn. Expired offers are refunded to make room for new
+// ones.
+fn try_make_offer<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    env: Env,
+    bidder: HumanAddr,
+    alias_string: String,
+    payment: Payment,
+) -> StdResult<HandleResponse> {
+    let config: Config = TypedStore::attach(&deps.storage).load(CONFIG_KEY)?;
+    ensure_auctions_open(&config, env.block.height)?;
+    let alias_string = normalize_alias(&deps.storage, &alias_string)?;
+    let alias_object: Option<Alias> =
+        RegistryReadonlyStorage::from_storage(&deps.storage, &deps.api)
+            .get_alias(alias_string.as_bytes());
+    if alias_object.is_none() {
+        return Err(StdError::not_found("Alias"));
+    }
+    if payment.amount.u128() == 0 {
+        return Err(StdError::generic_err("Offer must be greater than 0"));
+    }
+    let bidder: CanonicalAddr = deps.api.canonical_address(&bidder)?;
+    if bidder == alias_object.unwrap().owner {
+        return Err(StdError::generic_err(
+            "You can't make an offer on your own alias",
+        ));
+    }
+    let mut offers_storage = OffersStorage::from_storage(&mut deps.storage);
+    let offers: Vec<StoredOffer> = offers_storage.get_offers(alias_string.as_bytes());
+    let (expired_offers, mut offers): (Vec<StoredOffer>, Vec<StoredOffer>) = offers
+        .into_iter()
+        .partition(|offer| offer.expires_at <= env.block.time);
+    // A bidder whose offer has expired can make a new one, and the old one is refunded
+    if offers.iter().any(|offer| offer.bidder == bidder) {
+        return Err(StdError::generic_err(
+            "You already have an offer on this alias",
+        ));
+    }
+    if offers.len() >= MAX_OFFERS_PER_ALIAS {
+        return Err(StdError::generic_err(format!(
+            "An alias can have at most {} offers",
+            MAX_OFFERS_PER_ALIAS
+        )));
+    }
+    let mut messages: Vec<CosmosMsg> = vec![];
+    for offer in expired_offers {
+        messages.push(send_asset_msg(
+            env.contract.address.clone(),
+            deps.api.human_address(&offer.bidder)?,
+            offer.asset,
+            offer.amount,
+        )?);
+    }
+    offers.push(StoredOffer {
+        bidder,
+        asset: payment.asset,
+        amount: payment.amount,
+        made_at: env.block.height,
+        expires_at: env.block.time + OFFER_DURATION,
+    });
+    offers_storage.set_offers(alias_string.as_bytes(), &offers);
+
+    Ok(HandleResponse {
+        messages,
+        log: vec![],
+        data: None,
+    })
+}
+
+fn try_open_escrow<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    env: Env,
+    alias_string: String,
+    buyer: HumanAddr,
+    asset: Asset,
+    amount: Uint128,
+) -> StdResult<HandleResponse> {
+    let alias_string = normalize_alias(&deps.storage, &alias_string)?;
+    let alias_object: Alias = load_owned_alias(&deps.storage, &deps.api, &env, &alias_string)?;
+    // Co-owners approve transfers, which an escrow would get around
+    if CoOwnershipsReadonlyStorage::from_storage(&deps.storage)
+        .get_co_ownership(alias_string.as_bytes())
+        .is_some()
+    {
+        return Err(StdError::generic_err(
+            "Co-owned aliases can't be sold through escrow",
+        ));
+    }
+    ensure_not_in_escrow(&deps.storage, &alias_string)?;
+    ensure_no_reclaim(&deps.storage, &alias_string)?;
+    ensure_no_tips(&deps.storage, &alias_string)?;
+    ensure_transferable(&deps.storage, &alias_string)?;
+    ensure_not_locked(&deps.storage, &alias_string, env.block.height)?;
+    let buyer: CanonicalAddr = deps.api.canonical_address(&buyer)?;
+    if buyer == alias_object.owner {
+        return Err(StdError::generic_err("The buyer can't be the seller"));
+    }
+    if amount.u128() == 0 {
+        return Err(StdError::generic_err("Price must be greater than 0"));
+    }
+    // The stored token's code hash is used to pay the seller
+    let asset: Asset = match asset {
+        Asset::Native { denom } => {
+            if denom.is_empty() {
+                return Err(StdError::generic_err("Denom is required"));
+            }
+            Asset::Native { denom }
+        }
+        Asset::Snip20 { token } => {
+            let config: Config = TypedStore::attach(&deps.storage).load(CONFIG_KEY)?;
+            Asset::Snip20 {
+                token: routable_token(config, &token.address)?,
+            }
+        }
+    };
+
+    EscrowsStorage::from_storage(&mut deps.storage).set_escrow(
+        alias_string.as_bytes(),
+        &StoredEscrow {
+            buyer,
+            asset,
+            amount,
+            opened_at: env.block.height,
+        },
+    );
+
+    Ok(HandleResponse {
+        messages: vec![],
+        log: vec![],
+        data: Some(to_binary(&HandleAnswer::OpenEscrow { status: Success })?),
+    })
+}
+
+fn try_remove_image<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    env: Env,
+    alias_string: String,
+    index: u32,
+) -> StdResult<HandleResponse> {
+    let alias_string = normalize_alias(&deps.storage, &alias_string)?;
+    let alias_object: Alias = load_editable_alias(&deps.storage, &deps.api, &env, &alias_string)?;
+    ensure_not_frozen(&deps.storage, &alias_string)?;
+
+    let mut images_storage = ImagesStorage::from_storage(&mut deps.storage);
+    let mut images: Vec<Image> = images_storage.get_images(alias_string.as_bytes());
+    let index = index as usize;
+    if index >= images.len() {
+        return Err(StdError::not_found("Image"));
+    }
+    let removed_image: Image = images.remove(index);
+    if images.is_empty() {
+        images_storage.remove_images(alias_string.as_bytes());
+    } else {
+        // Promote the next image so there is always a primary one
+        if removed_image.primary {
+            images[0].primary = true;
+        }
+        images_storage.set_images(alias_string.as_bytes(), &images);
+    }
+    save_updated_alias(
+        &mut deps.storage,
+        &deps.api,
+        &env,
+        alias_string,
+        alias_object,
+    )?;
+
+    Ok(HandleResponse {
+        messages: vec![],
+        log: vec![],
+        data: Some(to_binary(&HandleAnswer::RemoveImage { status: Success })?),
+    })
+}
+
+fn try_remove_known_contract<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    env: Env,
+    label: String,
+) -> StdResult<HandleResponse> {
+    let config: Config = TypedStore::attach(&deps.storage).load(CONFIG_KEY)?;
+    authorize_admin_action(
+        &mut deps.storage,
+        &deps.api,
+        &config,
+        &env.message.sender,
+        AdminRole::SuperAdmin,
+    )?;
+
+    let label = normalize_alias(&deps.storage, &label)?;
+    KnownContractsStorage::from_storage(&mut deps.storage).remove_known_contract(label.as_bytes());
+
+    Ok(HandleResponse {
+        messages: vec![],
+        log: vec![],
+        data: Some(to_binary(&HandleAnswer::RemoveKnownContract {
+            status: Success,
+        })?),
+    })
+}
+
+fn try_remove_member<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    env: Env,
+    alias_string: String,
+    address: HumanAddr,
+) -> StdResult<HandleResponse> {
+    let alias_string = normalize_alias(&deps.storage, &alias_string)?;
+    let alias_object: Alias = load_editable_alias(&deps.storage, &deps.api, &env, &alias_string)?;
+    let sender: CanonicalAddr = deps.api.canonical_address(&env.message.sender)?;
+    let address: CanonicalAddr = deps.api.canonical_address(&address)?;
+
+    let mut members_storage = MembersStorage::from_storage(&mut deps.storage);
+    let mut members: Vec<StoredMember> = members_storage.get_members(alias_string.as_bytes());
+    authorize_member_manager(&alias_object.owner, &members, &sender, &address, None)?;
+    let members_count: usize = members.len();
+    members.retain(|member| member.address != address);
+    if members.len() == members_count {
+        return Err(StdError::not_found("Member"));
+    }
+    if members.is_empty() {
+        members_storage.remove_members(alias_string.as_bytes());
+    } else {
+        members_storage.set_members(alias_string.as_bytes(), &members);
+    }
+    save_updated_alias(
+        &mut deps.storage,
+        &deps.api,
+        &env,
+        alias_string,
+        alias_object,
+    )?;
+
+    Ok(HandleResponse {
+        messages: vec![],
+        log: vec![],
+        data: Some(to_binary(&HandleAnswer::RemoveMember { status: Success })?),
+    })
+}
+
+fn try_remove_note_viewer<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    env: Env,
+    alias_string: String,
+    address: HumanAddr,
+) -> StdResult<HandleResponse> {
+    let alias_string = normalize_alias(&deps.storage, &alias_string)?;
+    load_owned_alias(&deps.storage, &deps.api, &env, &alias_string)?;
+    let address: CanonicalAddr = deps.api.canonical_address(&address)?;
+
+    let mut notes_storage = NotesStorage::from_storage(&mut deps.storage);
+    let note: Option<Note> = notes_storage.get_note(alias_string.as_bytes());
+    if note.is_none() {
+        return Err(StdError::not_found("Note"));
+    }
+    let mut note: Note = note.unwrap();
+    let viewers_count: usize = note.viewers.len();
+    note.viewers.retain(|viewer| viewer != &address);
+    if note.viewers.len() == viewers_count {
+        return Err(StdError::not_found("Viewer"));
+    }
+    notes_storage.set_note(alias_string.as_bytes(), &note);
+
+    Ok(HandleResponse {
+        messages: vec![],
+        log: vec![],
+        data: Some(to_binary(&HandleAnswer::RemoveNoteViewer {
+            status: Success,
+        })?),
+    })
+}
+
+fn try_open_lottery<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    env: Env,
+    blocks: u64,
+) -> StdResult<HandleResponse> {
+    let mut config: Config = TypedStore::attach(&deps.storage).load(CONFIG_KEY)?;
+    authorize_admin_action(
+        &mut deps.storage,
+        &deps.api,
+        &config,
+        &env.message.sender,
+        AdminRole::SuperAdmin,
+    )?;
+    if blocks == 0 {
+        return Err(StdError::generic_err(
+            "A lottery must last at least one block",
+        ));
+    }
+    if matches!(config.lottery_ends_at, Some(lottery_ends_at) if env.block.height < lottery_ends_at)
+    {
+        return Err(StdError::generic_err("A lottery is already running"));
+    }
+
+    config.lottery_ends_at = Some(env.block.height.saturating_add(blocks));
+    TypedStoreMut::attach(&mut deps.storage).store(CONFIG_KEY, &config)?;
+
+    Ok(HandleResponse {
+        messages: vec![],
+        log: vec![],
+        data: Some(to_binary(&HandleAnswer::OpenLottery { status: Success })?),
+    })
+}
+
+// Fees are passed on as they're paid, so there's no treasury here to pay the caller a bounty from.
+// Suspended aliases can't be destroyed, so they're left for the moderators.
+fn try_purge_expired<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    env: Env,
+    limit: u32,
+) -> StdResult<HandleResponse> {
+    let config: Config = TypedStore::attach(&deps.storage).load(CONFIG_KEY)?;
+    let limit: usize = limit.min(MAX_EXPIRED_TO_PURGE) as usize;
+    let leases_storage = LeasesReadonlyStorage::from_storage(&deps.storage);
+    let expired_aliases: Vec<String> = leases_storage
+        .get_leased_aliases()
+        .into_iter()
+        .filter(|alias| {
+            matches!(
+                leases_storage.get_lease(alias.as_bytes()),
+                Some(lease) if lease.paid_until <= env.block.height
+            ) && ensure_not_suspended(&deps.storage, alias).is_ok()
+        })
+        .take(limit)
+        .collect();
+
+    let mut messages: Vec<CosmosMsg> = vec![];
+    for alias in &expired_aliases {
+        messages.extend(release_expired_lease(
+            &mut deps.storage,
+            &deps.api,
+            &env,
+            &config,
+            alias,
+        )?);
+    }
+
+    Ok(HandleResponse {
+        messages,
+        log: vec![],
+        data: Some(to_binary(&HandleAnswer::PurgeExpired {
+            purged: expired_aliases.len() as u32,
+        })?),
+    })
+}
+
+fn try_renew_lease<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    env: Env,
+    config: Config,
+    amount: Uint128,
+    alias_string: String,
+    periods: u16,
+) -> StdResult<HandleResponse> {
+    let alias_string: String = config.case_folding.fold(alias_string.trim());
+    let mut lease: Lease = LeasesReadonlyStorage::from_storage(&deps.storage)
+        .get_lease(alias_string.as_bytes())
+        .ok_or_else(|| StdError::not_found("Lease"))?;
+    if lease.paid_until <= env.block.height {
+        return Err(StdError::generic_err("Lease has expired"));
+    }
+    let (fee, blocks): (Uint128, u64) = lease_cost(lease.fee, lease.period_blocks, periods)?;
+    let payment: Payment = buttcoin_payment(&config, &env, amount, fee)?;
+
+    lease.paid_until = lease.paid_until.saturating_add(blocks);
+    LeasesStorage::from_storage(&mut deps.storage).set_lease(alias_string.as_bytes(), &lease);
+    update_daily_stats(&mut deps.storage, &env.block, |daily_stats| {
+        daily_stats.renewals += 1
+    });
+
+    let response: HandleResponse = HandleResponse {
+        messages: vec![],
+        log: vec![],
+        data: Some(to_binary(&ReceiveAnswer::RenewLease { status: Success })?),
+    };
+    forward_payment(response, config, env.contract.address, payment)
+}
+
+fn try_reject<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    env: Env,
+    alias_string: String,
+) -> StdResult<HandleResponse> {
+    let alias_string = normalize_alias(&deps.storage, &alias_string)?;
+    load_co_owned_alias(&deps.storage, &deps.api, &env, &alias_string)?;
+
+    let mut pending_actions_storage = PendingActionsStorage::from_storage(&mut deps.storage);
+    if pending_actions_storage
+        .get_pending_action(alias_string.as_bytes())
+        .is_none()
+    {
+        return Err(StdError::not_found("Pending action"));
+    }
+    pending_actions_storage.remove_pending_action(alias_string.as_bytes());
+
+    Ok(HandleResponse {
+        messages: vec![],
+        log: vec![],
+        data: Some(to_binary(&HandleAnswer::Reject { status: Success })?),
+    })
+}
+
+fn try_remove_oracle<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    env: Env,
+    address: HumanAddr,
+) -> StdResult<HandleResponse> {
+    let mut config: Config = TypedStore::attach(&deps.storage).load(CONFIG_KEY)?;
+    authorize_admin_action(
+        &mut deps.storage,
+        &deps.api,
+        &config,
+        &env.message.sender,
+        AdminRole::SuperAdmin,
+    )?;
+
+    let address: CanonicalAddr = deps.api.canonical_address(&address)?;
+    config.oracles.retain(|oracle| oracle != &address);
+    TypedStoreMut::attach(&mut deps.storage).store(CONFIG_KEY, &config)?;
+
+    Ok(HandleResponse {
+        messages: vec![],
+        log: vec![],
+        data: Some(to_binary(&HandleAnswer::RemoveOracle { status: Success })?),
+    })
+}
+
+// Move an alias and everything attached to it to a new, available name
+fn try_rename<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    env: Env,
+    from: HumanAddr,
+    alias_string: String,
+    new_alias_string: String,
+) -> StdResult<HandleResponse> {
+    let from: CanonicalAddr = deps.api.canonical_address(&from)?;
+    let alias_string = normalize_alias(&deps.storage, &alias_string)?;
+    let alias_string_byte_slice: &[u8] = alias_string.as_bytes();
+    let display_name: String = new_alias_string.trim().to_string();
+    let new_alias_string = normalize_alias(&deps.storage, &display_name)?;
+    let new_alias_string_byte_slice: &[u8] = new_alias_string.as_bytes();
+    validate_alias_string(&new_alias_string)?;
+    ensure_not_in_escrow(&deps.storage, &alias_string)?;
+    ensure_no_reclaim(&deps.storage, &alias_string)?;
+    ensure_not_frozen(&deps.storage, &alias_string)?;
+    ensure_not_locked(&deps.storage, &alias_string, env.block.height)?;
+    ensure_not_suspended(&deps.storage, &alias_string)?;
+    ensure_lease_current(&deps.storage, &alias_string, env.block.height)?;
+    ensure_alias_available(&deps.storage, &deps.api, &new_alias_string)?;
+    ensure_not_tombstoned(&deps.storage, &new_alias_string, &from, env.block.height)?;
+    let mut alias_storage = RegistryStorage::from_storage(&mut deps.storage, &deps.api);
+    let alias_object: Option<Alias> = alias_storage.get_alias(alias_string_byte_slice);
+    if alias_object.is_none() {
+        return Err(StdError::not_found("Alias"));
+    }
+    let mut alias_object: Alias = alias_object.unwrap();
+    authorize(from.clone(), alias_object.owner.clone())?;
+
+    alias_object.alias = new_alias_string.clone();
+    alias_object.display_name = display_name;
+    alias_object.updated_at = env.block.height;
+    alias_storage.remove_alias(alias_string_byte_slice);
+    alias_storage.set_alias(new_alias_string_byte_slice, alias_object);
+    ReleasesStorage::from_storage(&mut deps.storage)
+        .set_released_at(alias_string_byte_slice, env.block.height);
+    let mut attestations_storage = AttestationsStorage::from_storage(&mut deps.storage);
+    let attestations: Vec<StoredAttestation> =
+        attestations_storage.get_attestations(alias_string_byte_slice);
+    attestations_storage.remove_attestations(alias_string_byte_slice);
+    if !attestations.is_empty() {
+        attestations_storage.set_attestations(new_alias_string_byte_slice, &attestations);
+    }
+    let mut endorsements_storage = EndorsementsStorage::from_storage(&mut deps.storage);
+    let endorsers: Vec<CanonicalAddr> = endorsements_storage.get_endorsers(alias_string_byte_slice);
+    endorsements_storage.remove_endorsers(alias_string_byte_slice);
+    if !endorsers.is_empty() {
+        endorsements_storage.set_endorsers(new_alias_string_byte_slice, &endorsers);
+    }
+    let mut images_storage = ImagesStorage::from_storage(&mut deps.storage);
+    let images: Vec<Image> = images_storage.get_images(alias_string_byte_slice);
+    images_storage.remove_images(alias_string_byte_slice);
+    if !images.is_empty() {
+        images_storage.set_images(new_alias_string_byte_slice, &images);
+    }
+    let mut records_storage = RecordsStorage::from_storage(&mut deps.storage);
+    let records: Vec<Record> = records_storage.get_records(alias_string_byte_slice);
+    records_storage.remove_records(alias_string_byte_slice);
+    if !records.is_empty() {
+        records_storage.set_records(new_alias_string_byte_slice, &records);
+    }
+    let mut tip_jars_storage = TipJarsStorage::from_storage(&mut deps.storage);
+    let tip_jar: TipJar = tip_jars_storage.get_tip_jar(alias_string_byte_slice);
+    tip_jars_storage.remove_tip_jar(alias_string_byte_slice);
+    if tip_jar != TipJar::default() {
+        tip_jars_storage.set_tip_jar(new_alias_string_byte_slice, &tip_jar);
+    }
+    let mut alias_viewing_keys_storage = AliasViewingKeysStorage::from_storage(&mut deps.storage);
+    let alias_viewing_keys: Vec<AliasViewingKey> =
+        alias_viewing_keys_storage.get_alias_viewing_keys(alias_string_byte_slice);
+    alias_viewing_keys_storage.remove_alias_viewing_keys(alias_string_byte_slice);
+    if !alias_viewing_keys.is_empty() {
+        alias_viewing_keys_storage
+            .set_alias_viewing_keys(new_alias_string_byte_slice, &alias_viewing_keys);
+    }
+    let mut notes_storage = NotesStorage::from_storage(&mut deps.storage);
+    if let Some(note) = notes_storage.get_note(alias_string_byte_slice) {
+        notes_storage.remove_note(alias_string_byte_slice);
+        notes_storage.set_note(new_alias_string_byte_slice, &note);
+    }
+    let mut members_storage = MembersStorage::from_storage(&mut deps.storage);
+    let members: Vec<StoredMember> = members_storage.get_members(alias_string_byte_slice);
+    members_storage.remove_members(alias_string_byte_slice);
+    if !members.is_empty() {
+        members_storage.set_members(new_alias_string_byte_slice, &members);
+    }
+    let mut co_ownerships_storage = CoOwnershipsStorage::from_storage(&mut deps.storage);
+    if let Some(co_ownership) = co_ownerships_storage.get_co_ownership(alias_string_byte_slice) {
+        co_ownerships_storage.remove_co_ownership(alias_string_byte_slice);
+        co_ownerships_storage.set_co_ownership(new_alias_string_byte_slice, &co_ownership);
+    }
+    let mut pending_actions_storage = PendingActionsStorage::from_storage(&mut deps.storage);
+    if let Some(pending_action) =
+        pending_actions_storage.get_pending_action(alias_string_byte_slice)
+    {
+        pending_actions_storage.remove_pending_action(alias_string_byte_slice);
+        pending_actions_storage.set_pending_action(new_alias_string_byte_slice, &pending_action);
+    }
+    let mut previous_profiles_storage = PreviousProfilesStorage::from_storage(&mut deps.storage);
+    if let Some(previous_profile) =
+        previous_profiles_storage.get_previous_profile(alias_string_byte_slice)
+    {
+        previous_profiles_storage.remove_previous_profile(alias_string_byte_slice);
+        previous_profiles_storage
+            .set_previous_profile(new_alias_string_byte_slice, &previous_profile);
+    }
+    let mut signing_keys_storage = SigningKeysStorage::from_storage(&mut deps.storage);
+    if let Some(signing_key) = signing_keys_storage.get_signing_key(alias_string_byte_slice) {
+        signing_keys_storage.remove_signing_key(alias_string_byte_slice);
+        signing_keys_storage.set_signing_key(new_alias_string_byte_slice, &signing_key);
+    }
+    let mut encryption_keys_storage = EncryptionKeysStorage::from_storage(&mut deps.storage);
+    if let Some(encryption_key) =
+        encryption_keys_storage.get_encryption_key(alias_string_byte_slice)
+    {
+        encryption_keys_storage.remove_encryption_key(alias_string_byte_slice);
+        encryption_keys_storage.set_encryption_key(new_alias_string_byte_slice, &encryption_key);
+    }
+    let mut payment_preferences_storage =
+        PaymentPreferencesStorage::from_storage(&mut deps.storage);
+    if let Some(payment_preference) =
+        payment_preferences_storage.get_payment_preference(alias_string_byte_slice)
+    {
+        payment_preferences_storage.remove_payment_preference(alias_string_byte_slice);
+        payment_preferences_storage
+            .set_payment_preference(new_alias_string_byte_slice, &payment_preference);
+    }
+    let mut linked_addresses_storage = LinkedAddressesStorage::from_storage(&mut deps.storage);
+    let linked_addresses: Vec<LinkedAddress> =
+        linked_addresses_storage.get_linked_addresses(alias_string_byte_slice);
+    if !linked_addresses.is_empty() {
+        linked_addresses_storage.remove_linked_addresses(alias_string_byte_slice);
+        linked_addresses_storage
+            .set_linked_addresses(new_alias_string_byte_slice, &linked_addresses);
+    }
+    let mut leases_storage = LeasesStorage::from_storage(&mut deps.storage);
+    if let Some(lease) = leases_storage.get_lease(alias_string_byte_slice) {
+        leases_storage.remove_lease(alias_string_byte_slice);
+        leases_storage.set_lease(new_alias_string_byte_slice, &lease);
+    }
+    // The history goes with the alias, so the old alias starts afresh if it's registered again.
+    // It replaces any history left under the new alias by an earlier holder.
+    let mut alias_versions_storage = AliasVersionsStorage::from_storage(&mut deps.storage);
+    let versions: Vec<StoredAliasVersion> =
+        alias_versions_storage.get_versions(alias_string_byte_slice);
+    if !versions.is_empty() {
+        alias_versions_storage.remove_versions(alias_string_byte_slice);
+        alias_versions_storage.set_versions(new_alias_string_byte_slice, &versions);
+    }
+    // Renaming is refused while locked, so only a lock that has passed is left to drop
+    LocksStorage::from_storage(&mut deps.storage).remove_lock(alias_string_byte_slice);
+    record_change(
+        &mut deps.storage,
+        &deps.api,
+        &env.block,
+        ChangeAction::RenameFrom,
+        alias_string,
+        from.clone(),
+    )?;
+    record_change(
+        &mut deps.storage,
+        &deps.api,
+        &env.block,
+        ChangeAction::RenameTo,
+        new_alias_string,
+        from,
+    )?;
+
+    Ok(HandleResponse {
+        messages: vec![],
+        log: vec![],
+        data: Some(to_binary(&ReceiveAnswer::Rename { status: Success })?),
+    })
+}
+
+// Aliases that are already reserved are skipped, so an interrupted upload can be resent from its
+// last chunk
+fn try_reserve_aliases<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    env: Env,
+    aliases: Vec<String>,
+) -> StdResult<HandleResponse> {
+    let config: Config = TypedStore::attach(&deps.storage).load(CONFIG_KEY)?;
+    authorize_admin_action(
+        &mut deps.storage,
+        &deps.api,
+        &config,
+        &env.message.sender,
+        AdminRole::Moderator,
+    )?;
+    validate_reservation_chunk(&aliases)?;
+
+    let mut added: u32 = 0;
+    let mut reserved_aliases_storage = ReservedAliasesStorage::from_storage(&mut deps.storage);
+    for alias_string in aliases {
+        let alias_string = config.case_folding.fold(alias_string.trim());
+        if !reserved_aliases_storage.is_reserved(alias_string.as_bytes()) {
+            reserved_aliases_storage.set_reserved(alias_string.as_bytes());
+            added += 1;
+        }
+    }
+    let mut count_store = TypedStoreMut::attach(&mut deps.storage);
+    let reserved_count: u64 =
+        count_store.may_load(RESERVED_ALIAS_COUNT_KEY)?.unwrap_or(0) + u64::from(added);
+    count_store.store(RESERVED_ALIAS_COUNT_KEY, &reserved_count)?;
+    update_metrics(&mut deps.storage, |metrics| {
+        metrics.reservations += u64::from(added)
+    })?;
+
+    Ok(HandleResponse {
+        messages: vec![],
+        log: vec![],
+        data: Some(to_binary(&HandleAnswer::ReserveAliases {
+            added,
+            reserved_count,
+        })?),
+    })
+}
+
+fn try_revert<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    env: Env,
+    alias_string: String,
+) -> StdResult<HandleResponse> {
+    let alias_string = normalize_alias(&deps.storage, &alias_string)?;
+    let mut alias_object: Alias = load_owned_alias(&deps.storage, &deps.api, &env, &alias_string)?;
+    ensure_not_frozen(&deps.storage, &alias_string)?;
+    let alias_string_byte_slice: &[u8] = alias_string.as_bytes();
+    let previous_profile: PreviousProfile =
+        PreviousProfilesReadonlyStorage::from_storage(&deps.storage)
+            .get_previous_profile(alias_string_byte_slice)
+            .ok_or_else(|| StdError::not_found("Previous profile"))?;
+    // The avatar rules may have been tightened since the previous profile was saved
+    let config: Config = TypedStore::attach(&deps.storage).load(CONFIG_KEY)?;
+    validate_avatar_url(&config, &previous_profile.avatar_url)?;
+    validate_avatar_metadata(
+        &previous_profile.avatar_url,
+        &previous_profile.avatar_metadata,
+    )?;
+
+    // Removed rather than swapped so a second revert can't redo the change
+    PreviousProfilesStorage::from_storage(&mut deps.storage)
+        .remove_previous_profile(alias_string_byte_slice);
+    let mut records_storage = RecordsStorage::from_storage(&mut deps.storage);
+    if previous_profile.records.is_empty() {
+        records_storage.remove_records(alias_string_byte_slice);
+    } else {
+        records_storage.set_records(alias_string_byte_slice, &previous_profile.records);
+    }
+    alias_object.display_name = previous_profile.display_name;
+    alias_object.avatar_url = previous_profile.avatar_url;
+    alias_object.avatar_metadata = previous_profile.avatar_metadata;
+    save_updated_alias(
+        &mut deps.storage,
+        &deps.api,
+        &env,
+        alias_string,
+        alias_object,
+    )?;
+
+    Ok(HandleResponse {
+        messages: vec![],
+        log: vec![],
+        data: Some(to_binary(&HandleAnswer::Revert { status: Success })?),
+    })
+}
+
+fn try_revoke_alias_viewing_key<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    env: Env,
+    alias_string: String,
+    label: String,
+) -> StdResult<HandleResponse> {
+    let alias_string = normalize_alias(&deps.storage, &alias_string)?;
+    load_owned_alias(&deps.storage, &deps.api, &env, &alias_string)?;
+    let label = label.trim().to_string();
+
+    let mut alias_viewing_keys_storage = AliasViewingKeysStorage::from_storage(&mut deps.storage);
+    let mut alias_viewing_keys: Vec<AliasViewingKey> =
+        alias_viewing_keys_storage.get_alias_viewing_keys(alias_string.as_bytes());
+    let alias_viewing_keys_count: usize = alias_viewing_keys.len();
+    alias_viewing_keys.retain(|alias_viewing_key| alias_viewing_key.label != label);
+    if alias_viewing_keys.len() == alias_viewing_keys_count {
+        return Err(StdError::not_found("Viewing key"));
+    }
+    if alias_viewing_keys.is_empty() {
+        alias_viewing_keys_storage.remove_alias_viewing_keys(alias_string.as_bytes());
+    } else {
+        alias_viewing_keys_storage
+            .set_alias_viewing_keys(alias_string.as_bytes(), &alias_viewing_keys);
+    }
+
+    Ok(HandleResponse {
+        messages: vec![],
+        log: vec![],
+        data: Some(to_binary(&HandleAnswer::RevokeAliasViewingKey {
+            status: Success,
+        })?),
+    })
+}
+
+// Either an approved oracle or the owner of the alias can revoke an attestation
+fn try_revoke_attestation<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    env: Env,
+    alias_string: String,
+    platform: String,
+) -> StdResult<HandleResponse> {
+    let config: Config = TypedStore::attach(&deps.storage).load(CONFIG_KEY)?;
+    let alias_string = normalize_alias(&deps.storage, &alias_string)?;
+    let alias_string_byte_slice: &[u8] = alias_string.as_bytes();
+    let alias_object: Alias = load_current_alias(&deps.storage, &deps.api, &env, &alias_string)?;
+    let sender: CanonicalAddr = deps.api.canonical_address(&env.message.sender)?;
+    if !config.oracles.contains(&sender) {
+        authorize(sender, alias_object.owner.clone())?;
+    }
+
+    let platform = platform.trim().to_lowercase();
+    let mut attestations_storage = AttestationsStorage::from_storage(&mut deps.storage);
+    let mut attestations: Vec<StoredAttestation> =
+        attestations_storage.get_attestations(alias_string_byte_slice);
+    let attestations_count: usize = attestations.len();
+    attestations.retain(|attestation| attestation.platform != platform);
+    if attestations.len() == attestations_count {
+        return Err(StdError::not_found("Attestation"));
+    }
+    attestations_storage.set_attestations(alias_string_byte_slice, &attestations);
+    save_updated_alias(
+        &mut deps.storage,
+        &deps.api,
+        &env,
+        alias_string,
+        alias_object,
+    )?;
+
+    Ok(HandleResponse {
+        messages: vec![],
+        log: vec![],
+        data: Some(to_binary(&HandleAnswer::RevokeAttestation {
+            status: Success,
+        })?),
+    })
+}
+
+fn try_send_to_alias<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    env: Env,
+    config: Config,
+    amount: Uint128,
+    alias_string: String,
+) -> StdResult<HandleResponse> {
+    let alias_string = config.case_folding.fold(alias_string.trim());
+    let token: SecretContract = routable_token(config, &env.message.sender)?;
+    let recipient: HumanAddr = deps.api.human_address(&resolve_alias_address(
+        &deps.storage,
+        &deps.api,
+        &alias_string,
+    )?)?;
+
+    Ok(HandleResponse {
+        messages: vec![snip20::transfer_msg(
+            recipient,
+            amount,
+            None,
+            BLOCK_SIZE,
+            token.contract_hash,
+            token.address,
+        )?],
+        log: vec![],
+        data: Some(to_binary(&ReceiveAnswer::SendToAlias { status: Success })?),
+    })
+}
+
+// Only tokens this contract knows the code hash of can be forwarded or held
+fn routable_token(config: Config, address: &HumanAddr) -> StdResult<SecretContract> {
+    if *address == config.buttcoin.address {
+        return Ok(config.buttcoin);
+    }
+
+    config
+        .routable_tokens
+        .into_iter()
+        .find(|routable_token| routable_token.address == *address)
+        .ok_or(StdError::Unauthorized { backtrace: None })
+}
+
+fn try_revoke_role<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    env: Env,
+    address: HumanAddr,
+    role: AdminRole,
+) -> StdResult<HandleResponse> {
+    let mut config: Config = TypedStore::attach(&deps.storage).load(CONFIG_KEY)?;
+    authorize_admin_action(
+        &mut deps.storage,
+        &deps.api,
+        &config,
+        &env.message.sender,
+        AdminRole::SuperAdmin,
+    )?;
+    let admin_grant = StoredAdminGrant {
+        address: deps.api.canonical_address(&address)?,
+        role,
+    };
+    let index: usize = config
+        .admin_grants
+        .iter()
+        .position(|granted| *granted == admin_grant)
+        .ok_or_else(|| StdError::not_found("Role"))?;
+
+    config.admin_grants.remove(index);
+    TypedStoreMut::attach(&mut deps.storage).store(CONFIG_KEY, &config)?;
+
+    Ok(HandleResponse {
+        messages: vec![],
+        log: vec![],
+        data: Some(to_binary(&HandleAnswer::RevokeRole { status: Success })?),
+    })
+}
+
+fn try_revoke_endorsement<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    env: Env,
+    alias_string: String,
+) -> StdResult<HandleResponse> {
+    let alias_string = normalize_alias(&deps.storage, &alias_string)?;
+    let endorser: CanonicalAddr = deps.api.canonical_address(&env.message.sender)?;
+    let mut endorsements_storage = EndorsementsStorage::from_storage(&mut deps.storage);
+    let mut endorsers: Vec<CanonicalAddr> =
+        endorsements_storage.get_endorsers(alias_string.as_bytes());
+    let index: usize = endorsers
+        .iter()
+        .position(|address| *address == endorser)
+        .ok_or_else(|| StdError::not_found("Endorsement"))?;
+
+    endorsers.remove(index);
+    if endorsers.is_empty() {
+        endorsements_storage.remove_endorsers(alias_string.as_bytes());
+    } else {
+        endorsements_storage.set_endorsers(alias_string.as_bytes(), &endorsers);
+    }
+
+    Ok(HandleResponse {
+        messages: vec![],
+        log: vec![],
+        data: Some(to_binary(&HandleAnswer::RevokeEndorsement {
+            status: Success,
+        })?),
+    })
+}
+
+fn try_schedule_drop<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    env: Env,
+    alias_string: String,
+    drop: Drop,
+) -> StdResult<HandleResponse> {
+    let config: Config = TypedStore::attach(&deps.storage).load(CONFIG_KEY)?;
+    authorize_admin_action(
+        &mut deps.storage,
+        &deps.api,
+        &config,
+        &env.message.sender,
+        AdminRole::Treasurer,
+    )?;
+    let alias_string: String = config.case_folding.fold(alias_string.trim());
+    if !ReservedAliasesReadonlyStorage::from_storage(&deps.storage)
+        .is_reserved(alias_string.as_bytes())
+    {
+        return Err(StdError::generic_err(
+            "Only reserved aliases can be dropped",
+        ));
+    }
+    if drop.end_price > drop.start_price {
+        return Err(StdError::generic_err(
+            "A drop's end price can't be above its start price",
+        ));
+    }
+
+    DropsStorage::from_storage(&mut deps.storage).set_drop(alias_string.as_bytes(), &drop);
+
+    Ok(HandleResponse {
+        messages: vec![],
+        log: vec![],
+        data: Some(to_binary(&HandleAnswer::ScheduleDrop { status: Success })?),
+    })
+}
+
+// Replaces the assets other than Buttcoin that fees can be paid in
+fn try_set_accepted_payments<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    env: Env,
+    accepted_payments: Vec<AcceptedPayment>,
+) -> StdResult<HandleResponse> {
+    let mut config: Config = TypedStore::attach(&deps.storage).load(CONFIG_KEY)?;
+    authorize_admin_action(
+        &mut deps.storage,
+        &deps.api,
+        &config,
+        &env.message.sender,
+        AdminRole::Treasurer,
+    )?;
+
+    let mut messages: Vec<CosmosMsg> = vec![];
+    for (index, accepted_payment) in accepted_payments.iter().enumerate() {
+        if accepted_payment.fee.u128() == 0 {
+            return Err(StdError::generic_err("Fee must be greater than 0"));
+        }
+        if accepted_payments[..index]
+            .iter()
+            .any(|previous| previous.asset == accepted_payment.asset)
+        {
+            return Err(StdError::generic_err("Asset is accepted more than once"));
+        }
+        match &accepted_payment.asset {
+            Asset::Native { denom } => {
+                if denom.is_empty() {
+                    return Err(StdError::generic_err("Denom is required"));
+                }
+            }
+            Asset::Snip20 { token } => {
+                if token.address == config.buttcoin.address {
+                    return Err(StdError::generic_err("Buttcoin is always accepted"));
+                }
+                messages.push(snip20::register_receive_msg(
+                    env.contract_code_hash.clone(),
+                    None,
+                    BLOCK_SIZE,
+                    token.contract_hash.clone(),
+                    token.address.clone(),
+                )?);
+            }
+        }
+    }
+    config.accepted_payments = accepted_payments;
+    ensure_fees_burnable(&config)?;
+    TypedStoreMut::attach(&mut deps.storage).store(CONFIG_KEY, &config)?;
+
+    Ok(HandleResponse {
+        messages,
+        log: vec![],
+        data: Some(to_binary(&HandleAnswer::SetAcceptedPayments {
+            status: Success,
+        })?),
+    })
+}
+
+fn try_set_airdrop_merkle_root<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    env: Env,
+    merkle_root: Option<Binary>,
+) -> StdResult<HandleResponse> {
+    let mut config: Config = TypedStore::attach(&deps.storage).load(CONFIG_KEY)?;
+    authorize_admin_action(
+        &mut deps.storage,
+        &deps.api,
+        &config,
+        &env.message.sender,
+        AdminRole::SuperAdmin,
+    )?;
+    if let Some(merkle_root) = &merkle_root {
+        if merkle_root.len() != 32 {
+            return Err(StdError::generic_err("Merkle root must be a sha256 hash"));
+        }
+    }
+
+    config.airdrop_merkle_root = merkle_root;
+    TypedStoreMut::attach(&mut deps.storage).store(CONFIG_KEY, &config)?;
+
+    Ok(HandleResponse {
+        messages: vec![],
+        log: vec![],
+        data: Some(to_binary(&HandleAnswer::SetAirdropMerkleRoot {
+            status: Success,
+        })?),
+    })
+}
+
+fn try_set_alias_versions_kept<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    env: Env,
+    count: u16,
+) -> StdResult<HandleResponse> {
+    let mut config: Config = TypedStore::attach(&deps.storage).load(CONFIG_KEY)?;
+    authorize_admin_action(
+        &mut deps.storage,
+        &deps.api,
+        &config,
+        &env.message.sender,
+        AdminRole::SuperAdmin,
+    )?;
+    validate_alias_versions_kept(count)?;
+
+    config.alias_versions_kept = count;
+    TypedStoreMut::attach(&mut deps.storage).store(CONFIG_KEY, &config)?;
+
+    Ok(HandleResponse {
+        messages: vec![],
+        log: vec![],
+        data: Some(to_binary(&HandleAnswer::SetAliasVersionsKept {
+            status: Success,
+        })?),
+    })
+}
+
+fn try_set_avatar_domains<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    env: Env,
+    domains: Vec<String>,
+) -> StdResult<HandleResponse> {
+    let mut config: Config = TypedStore::attach(&deps.storage).load(CONFIG_KEY)?;
+    authorize_admin_action(
+        &mut deps.storage,
+        &deps.api,
+        &config,
+        &env.message.sender,
+        AdminRole::Moderator,
+    )?;
+    if domains.len() > MAX_AVATAR_DOMAINS {
+        return Err(StdError::generic_err(format!(
+            "There can be at most {} avatar domains",
+            MAX_AVATAR_DOMAINS
+        )));
+    }
+    let mut avatar_domains: Vec<String> = vec![];
+    for domain in domains {
+        let domain: String = domain.trim().to_lowercase();
+        if domain.is_empty()
+            || !domain
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '.' || c == '-')
+        {
+            return Err(StdError::generic_err(format!(
+                "{} is not a valid domain",
+                domain
+            )));
+        }
+        if !avatar_domains.contains(&domain) {
+            avatar_domains.push(domain);
+        }
+    }
+
+    config.avatar_domains = avatar_domains;
+    TypedStoreMut::attach(&mut deps.storage).store(CONFIG_KEY, &config)?;
+
+    Ok(HandleResponse {
+        messages: vec![],
+        log: vec![],
+        data: Some(to_binary(&HandleAnswer::SetAvatarDomains {
+            status: Success,
+        })?),
+    })
+}
+
+fn try_set_batch_limits<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    env: Env,
+    batch_limits: BatchLimits,
+) -> StdResult<HandleResponse> {
+    let mut config: Config = TypedStore::attach(&deps.storage).load(CONFIG_KEY)?;
+    authorize_admin_action(
+        &mut deps.storage,
+        &deps.api,
+        &config,
+        &env.message.sender,
+        AdminRole::SuperAdmin,
+    )?;
+    validate_batch_limits(&batch_limits)?;
+
+    config.batch_limits = batch_limits;
+    TypedStoreMut::attach(&mut deps.storage).store(CONFIG_KEY, &config)?;
+
+    Ok(HandleResponse {
+        messages: vec![],
+        log: vec![],
+        data: Some(to_binary(&HandleAnswer::SetBatchLimits {
+            status: Success,
+        })?),
+    })
+}
+
+// Shrinking the log doesn't prune straight away. The oldest changes are pruned a few at a time
+// as new ones are recorded.
+fn try_set_change_log_size<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    env: Env,
+    size: Option<u64>,
+) -> StdResult<HandleResponse> {
+    let mut config: Config = TypedStore::attach(&deps.storage).load(CONFIG_KEY)?;
+    authorize_admin_action(
+        &mut deps.storage,
+        &deps.api,
+        &config,
+        &env.message.sender,
+        AdminRole::SuperAdmin,
+    )?;
+    validate_change_log_size(size)?;
+
+    config.change_log_size = size;
+    TypedStoreMut::attach(&mut deps.storage).store(CONFIG_KEY, &config)?;
+
+    Ok(HandleResponse {
+        messages: vec![],
+        log: vec![],
+        data: Some(to_binary(&HandleAnswer::SetChangeLogSize {
+            status: Success,
+        })?),
+    })
+}
+
+fn try_set_require_avatar<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    env: Env,
+    required: bool,
+) -> StdResult<HandleResponse> {
+    let mut config: Config = TypedStore::attach(&deps.storage).load(CONFIG_KEY)?;
+    authorize_admin_action(
+        &mut deps.storage,
+        &deps.api,
+        &config,
+        &env.message.sender,
+        AdminRole::Moderator,
+    )?;
+
+    config.require_avatar = required;
+    TypedStoreMut::attach(&mut deps.storage).store(CONFIG_KEY, &config)?;
+
+    Ok(HandleResponse {
+        messages: vec![],
+        log: vec![],
+        data: Some(to_binary(&HandleAnswer::SetRequireAvatar {
+            status: Success,
+        })?),
+    })
+}
+
+fn try_set_co_owners<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    env: Env,
+    alias_string: String,
+    co_owners: Vec<HumanAddr>,
+    threshold: u8,
+) -> StdResult<HandleResponse> {
+    let alias_string = normalize_alias(&deps.storage, &alias_string)?;
+    let alias_object: Alias = load_co_owned_alias(&deps.storage, &deps.api, &env, &alias_string)?;
+    ensure_not_in_escrow(&deps.storage, &alias_string)?;
+    if co_owners.len() > MAX_CO_OWNERS_PER_ALIAS {
+        return Err(StdError::generic_err(format!(
+            "Too many co-owners. Maximum is {}.",
+            MAX_CO_OWNERS_PER_ALIAS
+        )));
+    }
+    let mut co_owner_addresses: Vec<CanonicalAddr> = vec![];
+    for co_owner in co_owners {
+        let co_owner: CanonicalAddr = deps.api.canonical_address(&co_owner)?;
+        if co_owner == alias_object.owner || co_owner_addresses.contains(&co_owner) {
+            return Err(StdError::generic_err(
+                "Co-owners must be unique and can not include the owner",
+            ));
+        }
+        co_owner_addresses.push(co_owner);
+    }
+    // The owner counts towards the threshold alongside the co-owners
+    if threshold == 0 || usize::from(threshold) > co_owner_addresses.len() + 1 {
+        return Err(StdError::generic_err(
+            "Threshold must be between 1 and the number of owners",
+        ));
+    }
+
+    propose_action(
+        &mut deps.storage,
+        &deps.api,
+        &env,
+        alias_string,
+        alias_object,
+        StoredApprovalAction::SetCoOwners {
+            co_owners: co_owner_addresses,
+            threshold,
+        },
+    )?;
+
+    Ok(HandleResponse {
+        messages: vec![],
+        log: vec![],
+        data: Some(to_binary(&HandleAnswer::SetCoOwners { status: Success })?),
+    })
+}
+
+fn try_set_cross_chain_snapshot<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    env: Env,
+    cross_chain_snapshot: Option<CrossChainSnapshot>,
+) -> StdResult<HandleResponse> {
+    let mut config: Config = TypedStore::attach(&deps.storage).load(CONFIG_KEY)?;
+    authorize_admin_action(
+        &mut deps.storage,
+        &deps.api,
+        &config,
+        &env.message.sender,
+        AdminRole::SuperAdmin,
+    )?;
+    if let Some(cross_chain_snapshot) = &cross_chain_snapshot {
+        if cross_chain_snapshot.merkle_root.len() != 32 {
+            return Err(StdError::generic_err("Merkle root must be a sha256 hash"));
+        }
+    }
+
+    config.cross_chain_snapshot = cross_chain_snapshot;
+    TypedStoreMut::attach(&mut deps.storage).store(CONFIG_KEY, &config)?;
+
+    Ok(HandleResponse {
+        messages: vec![],
+        log: vec![],
+        data: Some(to_binary(&HandleAnswer::SetCrossChainSnapshot {
+            status: Success,
+        })?),
+    })
+}
+
+fn try_set_discounts<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    env: Env,
+    discounts: Vec<Discount>,
+) -> StdResult<HandleResponse> {
+    let mut config: Config = TypedStore::attach(&deps.storage).load(CONFIG_KEY)?;
+    authorize_admin_action(
+        &mut deps.storage,
+        &deps.api,
+        &config,
+        &env.message.sender,
+        AdminRole::Treasurer,
+    )?;
+    validate_discounts(&discounts)?;
+
+    config.discounts = discounts;
+    TypedStoreMut::attach(&mut deps.storage).store(CONFIG_KEY, &config)?;
+
+    Ok(HandleResponse {
+        messages: vec![],
+        log: vec![],
+        data: Some(to_binary(&HandleAnswer::SetDiscounts { status: Success })?),
+    })
+}
+
+fn try_set_display_name<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    env: Env,
+    alias_string: String,
+    display_name: String,
+) -> StdResult<HandleResponse> {
+    let alias_string = normalize_alias(&deps.storage, &alias_string)?;
+    let mut alias_object: Alias =
+        load_editable_alias(&deps.storage, &deps.api, &env, &alias_string)?;
+    ensure_not_frozen(&deps.storage, &alias_string)?;
+    let config: Config = TypedStore::attach(&deps.storage).load(CONFIG_KEY)?;
+    validate_display_name(config.case_folding, &display_name, &alias_string)?;
+
+    keep_previous_profile(&mut deps.storage, &alias_string, &alias_object);
+    alias_object.display_name = display_name;
+    save_updated_alias(
+        &mut deps.storage,
+        &deps.api,
+        &env,
+        alias_string,
+        alias_object,
+    )?;
+
+    Ok(HandleResponse {
+        messages: vec![],
+        log: vec![],
+        data: Some(to_binary(&HandleAnswer::SetDisplayName {
+            status: Success,
+        })?),
+    })
+}
+
+fn try_set_encryption_key<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    env: Env,
+    alias_string: String,
+    encryption_key: Option<EncryptionKey>,
+) -> StdResult<HandleResponse> {
+    let alias_string = normalize_alias(&deps.storage, &alias_string)?;
+    let alias_object: Alias = load_owned_alias(&deps.storage, &deps.api, &env, &alias_string)?;
+    ensure_not_frozen(&deps.storage, &alias_string)?;
+
+    let mut encryption_keys_storage = EncryptionKeysStorage::from_storage(&mut deps.storage);
+    match encryption_key {
+        Some(encryption_key) => {
+            validate_encryption_key(&encryption_key)?;
+            encryption_keys_storage.set_encryption_key(alias_string.as_bytes(), &encryption_key);
+        }
+        None => encryption_keys_storage.remove_encryption_key(alias_string.as_bytes()),
+    }
+    save_updated_alias(
+        &mut deps.storage,
+        &deps.api,
+        &env,
+        alias_string,
+        alias_object,
+    )?;
+
+    Ok(HandleResponse {
+        messages: vec![],
+        log: vec![],
+        data: Some(to_binary(&HandleAnswer::SetEncryptionKey {
+            status: Success,
+        })?),
+    })
+}
+
+fn try_set_fee_splits<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    env: Env,
+    fee_splits: Vec<FeeSplit>,
+) -> StdResult<HandleResponse> {
+    let mut config: Config = TypedStore::attach(&deps.storage).load(CONFIG_KEY)?;
+    authorize_admin_action(
+        &mut deps.storage,
+        &deps.api,
+        &config,
+        &env.message.sender,
+        AdminRole::Treasurer,
+    )?;
+    if fee_splits.len() > MAX_FEE_SPLITS {
+        return Err(StdError::generic_err(format!(
+            "There can't be more than {} fee splits",
+            MAX_FEE_SPLITS
+        )));
+    }
+    for fee_split in &fee_splits {
+        if fee_split.weight == 0 {
+            return Err(StdError::generic_err("Fee split weight must be above 0"));
+        }
+        if let FeeRecipient::Address { address } = &fee_split.recipient {
+            deps.api.canonical_address(address)?;
+        }
+    }
+
+    config.fee_splits = fee_splits;
+    ensure_fees_burnable(&config)?;
+    TypedStoreMut::attach(&mut deps.storage).store(CONFIG_KEY, &config)?;
+
+    Ok(HandleResponse {
+        messages: vec![],
+        log: vec![],
+        data: Some(to_binary(&HandleAnswer::SetFeeSplits { status: Success })?),
+    })
+}
+
+fn try_set_follows_private<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    env: Env,
+    private: bool,
+) -> StdResult<HandleResponse> {
+    let address: CanonicalAddr = deps.api.canonical_address(&env.message.sender)?;
+    let mut follows_storage = FollowsStorage::from_storage(&mut deps.storage);
+    let mut follows: Follows = follows_storage.get_follows(&address);
+
+    follows.private = private;
+    follows_storage.set_follows(&address, &follows);
+
+    Ok(HandleResponse {
+        messages: vec![],
+        log: vec![],
+        data: Some(to_binary(&HandleAnswer::SetFollowsPrivate {
+            status: Success,
+        })?),
+    })
+}
+
+fn try_set_governance<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    env: Env,
+    governance: Option<Governance>,
+) -> StdResult<HandleResponse> {
+    let mut config: Config = TypedStore::attach(&deps.storage).load(CONFIG_KEY)?;
+    authorize_admin_action(
+        &mut deps.storage,
+        &deps.api,
+        &config,
+        &env.message.sender,
+        AdminRole::SuperAdmin,
+    )?;
+
+    config.governance = governance
+        .map(|governance| -> StdResult<StoredGovernance> {
+            Ok(StoredGovernance {
+                contract: deps.api.canonical_address(&governance.contract)?,
+                exclusive: governance.exclusive,
+            })
+        })
+        .transpose()?;
+    TypedStoreMut::attach(&mut deps.storage).store(CONFIG_KEY, &config)?;
+
+    Ok(HandleResponse {
+        messages: vec![],
+        log: vec![],
+        data: Some(to_binary(&HandleAnswer::SetGovernance { status: Success })?),
+    })
+}
+
+fn try_set_launch<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    env: Env,
+    launch: Option<Launch>,
+) -> StdResult<HandleResponse> {
+    let mut config: Config = TypedStore::attach(&deps.storage).load(CONFIG_KEY)?;
+    authorize_admin_action(
+        &mut deps.storage,
+        &deps.api,
+        &config,
+        &env.message.sender,
+        AdminRole::SuperAdmin,
+    )?;
+    validate_launch(&launch)?;
+
+    config.launch = launch;
+    TypedStoreMut::attach(&mut deps.storage).store(CONFIG_KEY, &config)?;
+
+    Ok(HandleResponse {
+        messages: vec![],
+        log: vec![],
+        data: Some(to_binary(&HandleAnswer::SetLaunch { status: Success })?),
+    })
+}
+
+fn try_set_lease_terms<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    env: Env,
+    lease_terms: Option<LeaseTerms>,
+) -> StdResult<HandleResponse> {
+    let mut config: Config = TypedStore::attach(&deps.storage).load(CONFIG_KEY)?;
+    authorize_admin_action(
+        &mut deps.storage,
+        &deps.api,
+        &config,
+        &env.message.sender,
+        AdminRole::Treasurer,
+    )?;
+    if let Some(lease_terms) = &lease_terms {
+        if lease_terms.period_blocks == 0 {
+            return Err(StdError::generic_err(
+                "A lease period must be at least one block",
+            ));
+        }
+    }
+
+    config.lease_terms = lease_terms;
+    TypedStoreMut::attach(&mut deps.storage).store(CONFIG_KEY, &config)?;
+
+    Ok(HandleResponse {
+        messages: vec![],
+        log: vec![],
+        data: Some(to_binary(&HandleAnswer::SetLeaseTerms { status: Success })?),
+    })
+}
+
+fn try_set_mint_phases<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    env: Env,
+    phases: Vec<MintPhase>,
+) -> StdResult<HandleResponse> {
+    let mut config: Config = TypedStore::attach(&deps.storage).load(CONFIG_KEY)?;
+    authorize_admin_action(
+        &mut deps.storage,
+        &deps.api,
+        &config,
+        &env.message.sender,
+        AdminRole::Treasurer,
+    )?;
+    validate_mint_phases(&phases)?;
+
+    config.mint_phases = phases;
+    TypedStoreMut::attach(&mut deps.storage).store(CONFIG_KEY, &config)?;
+
+    Ok(HandleResponse {
+        messages: vec![],
+        log: vec![],
+        data: Some(to_binary(&HandleAnswer::SetMintPhases { status: Success })?),
+    })
+}
+
+fn try_set_note<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    env: Env,
+    alias_string: String,
+    text: Option<String>,
+) -> StdResult<HandleResponse> {
+    let alias_string = normalize_alias(&deps.storage, &alias_string)?;
+    load_owned_alias(&deps.storage, &deps.api, &env, &alias_string)?;
+
+    let mut notes_storage = NotesStorage::from_storage(&mut deps.storage);
+    match text {
+        Some(text) => {
+            if text.len() > MAX_NOTE_LENGTH {
+                return Err(StdError::generic_err(format!(
+                    "Note is too long. Maximum is {} bytes.",
+                    MAX_NOTE_LENGTH
+                )));
+            }
+            let viewers: Vec<CanonicalAddr> = notes_storage
+                .get_note(alias_string.as_bytes())
+                .map(|note| note.viewers)
+                .unwrap_or_default();
+            notes_storage.set_note(alias_string.as_bytes(), &Note { text, viewers });
+        }
+        None => notes_storage.remove_note(alias_string.as_bytes()),
+    }
+
+    Ok(HandleResponse {
+        messages: vec![],
+        log: vec![],
+        data: Some(to_binary(&HandleAnswer::SetNote { status: Success })?),
+    })
+}
+
+fn try_set_price_oracle<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    env: Env,
+    price_oracle: Option<PriceOracle>,
+) -> StdResult<HandleResponse> {
+    let mut config: Config = TypedStore::attach(&deps.storage).load(CONFIG_KEY)?;
+    authorize_admin_action(
+        &mut deps.storage,
+        &deps.api,
+        &config,
+        &env.message.sender,
+        AdminRole::Treasurer,
+    )?;
+
+    config.price_oracle = price_oracle;
+    TypedStoreMut::attach(&mut deps.storage).store(CONFIG_KEY, &config)?;
+
+    Ok(HandleResponse {
+        messages: vec![],
+        log: vec![],
+        data: Some(to_binary(&HandleAnswer::SetPriceOracle {
+            status: Success,
+        })?),
+    })
+}
+
+fn try_set_primary_image<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    env: Env,
+    alias_string: String,
+    index: u32,
+) -> StdResult<HandleResponse> {
+    let alias_string = normalize_alias(&deps.storage, &alias_string)?;
+    let alias_object: Alias = load_editable_alias(&deps.storage, &deps.api, &env, &alias_string)?;
+    ensure_not_frozen(&deps.storage, &alias_string)?;
+
+    let mut images_storage = ImagesStorage::from_storage(&mut deps.storage);
+    let mut images: Vec<Image> = images_storage.get_images(alias_string.as_bytes());
+    let index = index as usize;
+    if index >= images.len() {
+        return Err(StdError::not_found("Image"));
+    }
+    for (image_index, image) in images.iter_mut().enumerate() {
+        image.primary = image_index == index;
+    }
+    images_storage.set_images(alias_string.as_bytes(), &images);
+    save_updated_alias(
+        &mut deps.storage,
+        &deps.api,
+        &env,
+        alias_string,
+        alias_object,
+    )?;
+
+    Ok(HandleResponse {
+        messages: vec![],
+        log: vec![],
+        data: Some(to_binary(&HandleAnswer::SetPrimaryImage {
+            status: Success,
+        })?),
+    })
+}
+
+fn try_set_payment_preference<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    env: Env,
+    alias_string: String,
+    payment_preference: Option<PaymentPreference>,
+) -> StdResult<HandleResponse> {
+    let alias_string = normalize_alias(&deps.storage, &alias_string)?;
+    let alias_object: Alias = load_owned_alias(&deps.storage, &deps.api, &env, &alias_string)?;
+    ensure_not_frozen(&deps.storage, &alias_string)?;
+    let payment_preference: Option<StoredPaymentPreference> = payment_preference
+        .map(|payment_preference| validate_payment_preference(&deps.api, payment_preference))
+        .transpose()?;
+
+    let mut payment_preferences_storage =
+        PaymentPreferencesStorage::from_storage(&mut deps.storage);
+    match payment_preference {
+        Some(payment_preference) => payment_preferences_storage
+            .set_payment_preference(alias_string.as_bytes(), &payment_preference),
+        None => payment_preferences_storage.remove_payment_preference(alias_string.as_bytes()),
+    }
+    save_updated_alias(
+        &mut deps.storage,
+        &deps.api,
+        &env,
+        alias_string,
+        alias_object,
+    )?;
+
+    Ok(HandleResponse {
+        messages: vec![],
+        log: vec![],
+        data: Some(to_binary(&HandleAnswer::SetPaymentPreference {
+            status: Success,
+        })?),
+    })
+}
+
+fn try_set_private_reverse_lookup<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    env: Env,
+    alias_string: String,
+    enabled: bool,
+) -> StdResult<HandleResponse> {
+    let alias_string = normalize_alias(&deps.storage, &alias_string)?;
+    let mut alias_object: Alias = load_owned_alias(&deps.storage, &deps.api, &env, &alias_string)?;
+
+    alias_object.private_reverse_lookup = enabled;
+    save_updated_alias(
+        &mut deps.storage,
+        &deps.api,
+        &env,
+        alias_string,
+        alias_object,
+    )?;
+
+    Ok(HandleResponse {
+        messages: vec![],
+        log: vec![],
+        data: Some(to_binary(&HandleAnswer::SetPrivateReverseLookup {
+            status: Success,
+        })?),
+    })
+}
+
+fn try_set_promotions<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    env: Env,
+    promotions: Vec<Promotion>,
+) -> StdResult<HandleResponse> {
+    let mut config: Config = TypedStore::attach(&deps.storage).load(CONFIG_KEY)?;
+    authorize_admin_action(
+        &mut deps.storage,
+        &deps.api,
+        &config,
+        &env.message.sender,
+        AdminRole::Treasurer,
+    )?;
+    validate_promotions(&promotions)?;
+
+    config.promotions = promotions;
+    TypedStoreMut::attach(&mut deps.storage).store(CONFIG_KEY, &config)?;
+
+    Ok(HandleResponse {
+        messages: vec![],
+        log: vec![],
+        data: Some(to_binary(&HandleAnswer::SetPromotions { status: Success })?),
+    })
+}
+
+fn try_set_reclaim_terms<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    env: Env,
+    reclaim_terms: Option<ReclaimTerms>,
+) -> StdResult<HandleResponse> {
+    let mut config: Config = TypedStore::attach(&deps.storage).load(CONFIG_KEY)?;
+    authorize_admin_action(
+        &mut deps.storage,
+        &deps.api,
+        &config,
+        &env.message.sender,
+        AdminRole::Moderator,
+    )?;
+    validate_reclaim_terms(&reclaim_terms)?;
+
+    config.reclaim_terms = reclaim_terms;
+    TypedStoreMut::attach(&mut deps.storage).store(CONFIG_KEY, &config)?;
+
+    Ok(HandleResponse {
+        messages: vec![],
+        log: vec![],
+        data: Some(to_binary(&HandleAnswer::SetReclaimTerms {
+            status: Success,
+        })?),
+    })
+}
+
+fn try_set_signing_key<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    env: Env,
+    alias_string: String,
+    public_key: Option<Binary>,
+) -> StdResult<HandleResponse> {
+    let alias_string = normalize_alias(&deps.storage, &alias_string)?;
+    let alias_object: Alias = load_owned_alias(&deps.storage, &deps.api, &env, &alias_string)?;
+    ensure_not_frozen(&deps.storage, &alias_string)?;
+
+    let mut signing_keys_storage = SigningKeysStorage::from_storage(&mut deps.storage);
+    match public_key {
+        Some(public_key) => {
+            if !is_valid_public_key(public_key.as_slice()) {
+                return Err(StdError::generic_err(
+                    "Signing key must be a compressed secp256k1 public key",
+                ));
+            }
+            signing_keys_storage.set_signing_key(alias_string.as_bytes(), &public_key);
+        }
+        None => signing_keys_storage.remove_signing_key(alias_string.as_bytes()),
+    }
+    save_updated_alias(
+        &mut deps.storage,
+        &deps.api,
+        &env,
+        alias_string,
+        alias_object,
+    )?;
+
+    Ok(HandleResponse {
+        messages: vec![],
+        log: vec![],
+        data: Some(to_binary(&HandleAnswer::SetSigningKey { status: Success })?),
+    })
+}
+
+fn try_set_target<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    env: Env,
+    alias_string: String,
+    target: Option<String>,
+) -> StdResult<HandleResponse> {
+    let alias_string = normalize_alias(&deps.storage, &alias_string)?;
+    let mut alias_object: Alias =
+        load_editable_alias(&deps.storage, &deps.api, &env, &alias_string)?;
+    ensure_not_frozen(&deps.storage, &alias_string)?;
+
+    // Walk the chain from the new target to make sure it exists, is short enough and doesn't loop
+    let alias_storage = RegistryReadonlyStorage::from_storage(&deps.storage, &deps.api);
+    let target: Option<String> = target
+        .map(|target| normalize_alias(&deps.storage, &target))
+        .transpose()?;
+    let mut next_target: Option<String> = target.clone();
+    let mut depth: u8 = 0;
+    while let Some(target_string) = next_target {
+        if target_string == alias_string {
+            return Err(StdError::generic_err("Alias target can not loop back"));
+        }
+        depth += 1;
+        if depth > MAX_ALIAS_TARGET_DEPTH {
+            return Err(StdError::generic_err("Alias target chain is too long"));
+        }
+        let target_object: Option<Alias> = alias_storage.get_alias(target_string.as_bytes());
+        if target_object.is_none() {
+            return Err(StdError::not_found("Alias target"));
+        }
+        next_target = target_object.unwrap().target;
+    }
+
+    alias_object.target = target;
+    save_updated_alias(
+        &mut deps.storage,
+        &deps.api,
+        &env,
+        alias_string,
+        alias_object,
+    )?;
+
+    Ok(HandleResponse {
+        messages: vec![],
+        log: vec![],
+        data: Some(to_binary(&HandleAnswer::SetTarget { status: Success })?),
+    })
+}
+
+fn try_set_timelock<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    env: Env,
+    blocks: u64,
+) -> StdResult<HandleResponse> {
+    let mut config: Config = TypedStore::attach(&deps.storage).load(CONFIG_KEY)?;
+    authorize_admin_action(
+        &mut deps.storage,
+        &deps.api,
+        &config,
+        &env.message.sender,
+        AdminRole::SuperAdmin,
+    )?;
+    if blocks > MAX_TIMELOCK_BLOCKS {
+        return Err(StdError::generic_err(format!(
+            "Timelock can't be longer than {} blocks",
+            MAX_TIMELOCK_BLOCKS
+        )));
+    }
+
+    config.timelock_blocks = blocks;
+    TypedStoreMut::attach(&mut deps.storage).store(CONFIG_KEY, &config)?;
+
+    Ok(HandleResponse {
+        messages: vec![],
+        log: vec![],
+        data: Some(to_binary(&HandleAnswer::SetTimelock { status: Success })?),
+    })
+}
+
+fn try_set_viewing_key<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    env: Env,
+    key: String,
+) -> StdResult<HandleResponse> {
+    let key = ViewingKey(key);
+    ViewingKeysStorage::from_storage(&mut deps.storage).set_viewing_key(
+        deps.api.canonical_address(&env.message.sender)?.as_slice(),
+        &key.to_hashed(),
+    );
+
+    Ok(HandleResponse {
+        messages: vec![],
+        log: vec![],
+        data: Some(to_binary(&HandleAnswer::SetViewingKey { status: Success })?),
+    })
+}
+
+fn try_set_wildcard<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    env: Env,
+    alias_string: String,
+    wildcard: bool,
+) -> StdResult<HandleResponse> {
+    let alias_string = normalize_alias(&deps.storage, &alias_string)?;
+    let mut alias_object: Alias =
+        load_editable_alias(&deps.storage, &deps.api, &env, &alias_string)?;
+    ensure_not_frozen(&deps.storage, &alias_string)?;
+
+    alias_object.wildcard = wildcard;
+    save_updated_alias(
+        &mut deps.storage,
+        &deps.api,
+        &env,
+        alias_string,
+        alias_object,
+    )?;
+
+    Ok(HandleResponse {
+        messages: vec![],
+        log: vec![],
+        data: Some(to_binary(&HandleAnswer::SetWildcard { status: Success })?),
+    })
+}
+
+fn try_settle_reclaim<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    env: Env,
+    alias_string: String,
+) -> StdResult<HandleResponse> {
+    let config: Config = TypedStore::attach(&deps.storage).load(CONFIG_KEY)?;
+    let alias_string = normalize_alias(&deps.storage, &alias_string)?;
+    let reclaim: StoredReclaim = ReclaimsReadonlyStorage::from_storage(&deps.storage)
+        .get_reclaim(alias_string.as_bytes())
+        .ok_or_else(|| StdError::not_found("Reclaim"))?;
+    // Without a bid there is nothing for the owner to match
+    let settles_at: u64 = if reclaim.bidder.is_some() {
+        reclaim.defense_ends_at
+    } else {
+        reclaim.bidding_ends_at
+    };
+    if env.block.height < settles_at {
+        return Err(StdError::generic_err(format!(
+            "Reclaim can't be settled until block {}",
+            settles_at
+        )));
+    }
+
+    ReclaimsStorage::from_storage(&mut deps.storage).remove_reclaim(alias_string.as_bytes());
+    let mut response: HandleResponse = HandleResponse {
+        messages: vec![],
+        log: vec![],
+        data: Some(to_binary(&HandleAnswer::SettleReclaim { status: Success })?),
+    };
+    let bidder: CanonicalAddr = match reclaim.bidder {
+        Some(bidder) => bidder,
+        None => return Ok(response),
+    };
+    let alias_object: Alias = RegistryReadonlyStorage::from_storage(&deps.storage, &deps.api)
+        .get_alias(alias_string.as_bytes())
+        .ok_or_else(|| StdError::not_found("Alias"))?;
+    let payment: Payment = Payment {
+        asset: Asset::Snip20 {
+            token: config.buttcoin.clone(),
+        },
+        amount: reclaim.bid,
+    };
+    // The bidder may have taken another alias since bidding, or the owner frozen this one
+    if RegistryReadonlyStorage::from_storage(&deps.storage, &deps.api)
+        .get_alias_for_address(&bidder)
+        .is_some()
+        || ensure_transferable(&deps.storage, &alias_string).is_err()
+    {
+        response.messages.push(send_asset_msg(
+            env.contract.address,
+            deps.api.human_address(&bidder)?,
+            payment.asset,
+            payment.amount,
+        )?);
+        return Ok(response);
+    }
+
+    response.messages = withdraw_tips(
+        &mut deps.storage,
+        &alias_string,
+        env.contract.address.clone(),
+        deps.api.human_address(&alias_object.owner)?,
+    )?;
+    transfer_alias(
+        &mut deps.storage,
+        &deps.api,
+        &env,
+        alias_string,
+        alias_object,
+        bidder,
+    )?;
+    let contract_address: HumanAddr = env.contract.address;
+    forward_payment(response, config, contract_address, payment)
+}
+
+// The relayer pays, the signer gets the alias
+fn try_sponsored_create<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    env: Env,
+    alias_string: String,
+    sponsorship: Sponsorship,
+) -> StdResult<HandleResponse> {
+    let alias_string = normalize_alias(&deps.storage, &alias_string)?;
+    let message: String = format!(
+        "Create {} with nonce {} on {}",
+        alias_string, sponsorship.nonce, env.contract.address
+    );
+    use_sponsored_nonce(&mut deps.storage, &deps.api, &sponsorship, &message)?;
+
+    let mut response: HandleResponse = try_create(
+        deps,
+        env,
+        sponsorship.signer,
+        alias_string,
+        None,
+        None,
+        vec![],
+    )?;
+    response.data = Some(to_binary(&ReceiveAnswer::SponsoredCreate {
+        status: Success,
+    })?);
+    Ok(response)
+}
+
+// Goes through Transfer as the signer, so co-owned aliases still need approval
+fn try_sponsored_transfer<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    mut env: Env,
+    alias_string: String,
+    recipient: HumanAddr,
+    sponsorship: Sponsorship,
+) -> StdResult<HandleResponse> {
+    let alias_string = normalize_alias(&deps.storage, &alias_string)?;
+    let message: String = format!(
+        "Transfer {} to {} with nonce {} on {}",
+        alias_string, recipient, sponsorship.nonce, env.contract.address
+    );
+    use_sponsored_nonce(&mut deps.storage, &deps.api, &sponsorship, &message)?;
+
+    env.message.sender = sponsorship.signer;
+    let mut response: HandleResponse =
+        try_transfer(deps, env, alias_string, recipient, None, None)?;
+    response.data = Some(to_binary(&HandleAnswer::SponsoredTransfer {
+        status: Success,
+    })?);
+    Ok(response)
+}
+
+fn try_start_reclaim<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    env: Env,
+    alias_string: String,
+) -> StdResult<HandleResponse> {
+    let config: Config = TypedStore::attach(&deps.storage).load(CONFIG_KEY)?;
+    let reclaim_terms: ReclaimTerms = config
+        .reclaim_terms
+        .ok_or_else(|| StdError::generic_err("Reclaims are turned off"))?;
+    let alias_string = normalize_alias(&deps.storage, &alias_string)?;
+    let alias_string_byte_slice: &[u8] = alias_string.as_bytes();
+    let alias_object: Alias = RegistryReadonlyStorage::from_storage(&deps.storage, &deps.api)
+        .get_alias(alias_string_byte_slice)
+        .ok_or_else(|| StdError::not_found("Alias"))?;
+    ensure_no_reclaim(&deps.storage, &alias_string)?;
+    ensure_not_locked(&deps.storage, &alias_string, env.block.height)?;
+    let idle: bool = alias_object.avatar_url.is_none()
+        && RecordsReadonlyStorage::from_storage(&deps.storage)
+            .get_records(alias_string_byte_slice)
+            .is_empty()
+        && env.block.height.saturating_sub(alias_object.updated_at) >= reclaim_terms.idle_blocks;
+    if !idle {
+        return Err(StdError::generic_err(format!(
+            "Aliases can be reclaimed after {} blocks without records, an avatar or updates",
+            reclaim_terms.idle_blocks
+        )));
+    }
+    ensure_not_in_escrow(&deps.storage, &alias_string)?;
+    ensure_transferable(&deps.storage, &alias_string)?;
+
+    let bidding_ends_at: u64 = env
+        .block
+        .height
+        .saturating_add(reclaim_terms.bidding_blocks);
+    ReclaimsStorage::from_storage(&mut deps.storage).set_reclaim(
+        alias_string_byte_slice,
+        &StoredReclaim {
+            started_by: deps.api.canonical_address(&env.message.sender)?,
+            started_at: env.block.height,
+            bidding_ends_at,
+            defense_ends_at: bidding_ends_at.saturating_add(reclaim_terms.defense_blocks),
+            bidder: None,
+            bid: Uint128(0),
+        },
+    );
+
+    Ok(HandleResponse {
+        messages: vec![],
+        log: vec![],
+        data: Some(to_binary(&HandleAnswer::StartReclaim { status: Success })?),
+    })
+}
+
+fn try_suspend<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    env: Env,
+    alias_string: String,
+    hide_resolution: bool,
+) -> StdResult<HandleResponse> {
+    let config: Config = TypedStore::attach(&deps.storage).load(CONFIG_KEY)?;
+    authorize_admin_action(
+        &mut deps.storage,
+        &deps.api,
+        &config,
+        &env.message.sender,
+        AdminRole::Moderator,
+    )?;
+    let alias_string = normalize_alias(&deps.storage, &alias_string)?;
+    if RegistryReadonlyStorage::from_storage(&deps.storage, &deps.api)
+        .get_alias(alias_string.as_bytes())
+        .is_none()
+    {
+        return Err(StdError::not_found("Alias"));
+    }
+
+    // Suspending again only changes whether the alias still resolves
+    SuspensionsStorage::from_storage(&mut deps.storage).set_suspension(
+        alias_string.as_bytes(),
+        &Suspension {
+            hide_resolution,
+            suspended_at: env.block.height,
+        },
+    );
+
+    Ok(HandleResponse {
+        messages: vec![],
+        log: vec![],
+        data: Some(to_binary(&HandleAnswer::Suspend { status: Success })?),
+    })
+}
+
+fn try_tip<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    env: Env,
+    alias_string: String,
+    message: Option<String>,
+) -> StdResult<HandleResponse> {
+    let alias_string = normalize_alias(&deps.storage, &alias_string)?;
+    validate_tip(&deps.storage, &deps.api, &alias_string, &message)?;
+    let coins: Vec<Coin> = env
+        .message
+        .sent_funds
+        .into_iter()
+        .filter(|coin| coin.amount.u128() > 0)
+        .collect();
+    if coins.is_empty() {
+        return Err(StdError::generic_err("Send the tip in native coins"));
+    }
+
+    for coin in coins {
+        add_tip(
+            &mut deps.storage,
+            &alias_string,
+            Tip {
+                asset: Asset::Native { denom: coin.denom },
+                amount: coin.amount,
+                message: message.clone(),
+                block_height: env.block.height,
+            },
+        )?;
+    }
+
+    Ok(HandleResponse {
+        messages: vec![],
+        log: vec![],
+        data: Some(to_binary(&HandleAnswer::Tip { status: Success })?),
+    })
+}
+
+// A tip in Buttcoin or a routable token sent with a SNIP-20 Receive
+fn try_tip_token<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    env: Env,
+    config: Config,
+    amount: Uint128,
+    alias_string: String,
+    message: Option<String>,
+) -> StdResult<HandleResponse> {
+    let token: SecretContract = routable_token(config, &env.message.sender)?;
+    let alias_string = normalize_alias(&deps.storage, &alias_string)?;
+    validate_tip(&deps.storage, &deps.api, &alias_string, &message)?;
+    if amount.u128() == 0 {
+        return Err(StdError::generic_err("Tip amount must be greater than 0"));
+    }
+
+    add_tip(
+        &mut deps.storage,
+        &alias_string,
+        Tip {
+            asset: Asset::Snip20 { token },
+            amount,
+            message,
+            block_height: env.block.height,
+        },
+    )?;
+
+    Ok(HandleResponse {
+        messages: vec![],
+        log: vec![],
+        data: Some(to_binary(&ReceiveAnswer::Tip { status: Success })?),
+    })
+}
+
+fn try_transfer<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    env: Env,
+    alias_string: String,
+    recipient: HumanAddr,
+    recipient_code_hash: Option<String>,
+    msg: Option<Binary>,
+) -> StdResult<HandleResponse> {
+    let alias_string = normalize_alias(&deps.storage, &alias_string)?;
+    let alias_object: Alias = load_co_owned_alias(&deps.storage, &deps.api, &env, &alias_string)?;
+    let recipient: CanonicalAddr = deps.api.canonical_address(&recipient)?;
+    if RegistryReadonlyStorage::from_storage(&deps.storage, &deps.api)
+        .get_alias_for_address(&recipient)
+        .is_some()
+    {
+        return Err(StdError::generic_err("Recipient already has an alias"));
+    }
+    let callback: Option<TransferCallback> = match (recipient_code_hash, msg) {
+        (Some(code_hash), Some(msg)) => Some(TransferCallback { code_hash, msg }),
+        (None, Some(_)) => {
+            return Err(StdError::generic_err(
+                "A msg needs the recipient's code hash",
+            ))
+        }
+        _ => None,
+    };
+
+    let messages: Vec<CosmosMsg> = propose_action(
+        &mut deps.storage,
+        &deps.api,
+        &env,
+        alias_string,
+        alias_object,
+        StoredApprovalAction::Transfer {
+            recipient,
+            callback,
+        },
+    )?;
+
+    Ok(HandleResponse {
+        messages,
+        log: vec![],
+        data: Some(to_binary(&HandleAnswer::Transfer { status: Success })?),
+    })
+}
+
+fn try_unfollow<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    env: Env,
+    alias_string: String,
+) -> StdResult<HandleResponse> {
+    let alias_string = normalize_alias(&deps.storage, &alias_string)?;
+    let follower: CanonicalAddr = deps.api.canonical_address(&env.message.sender)?;
+    let followed: CanonicalAddr =
+        match RegistryReadonlyStorage::from_storage(&deps.storage, &deps.api)
+            .get_alias(alias_string.as_bytes())
+        {
+            Some(alias_object) => alias_object.owner,
+            None => return Err(StdError::not_found("Alias")),
+        };
+    let mut follows_storage = FollowsStorage::from_storage(&mut deps.storage);
+    let mut follower_follows: Follows = follows_storage.get_follows(&follower);
+    if !follower_follows.following.contains(&followed) {
+        return Err(StdError::generic_err("You don't follow this alias"));
+    }
+    let mut followed_follows: Follows = follows_storage.get_follows(&followed);
+
+    follower_follows
+        .following
+        .retain(|address| *address != followed);
+    followed_follows
+        .followers
+        .retain(|address| *address != follower);
+    follows_storage.set_follows(&follower, &follower_follows);
+    follows_storage.set_follows(&followed, &followed_follows);
+
+    Ok(HandleResponse {
+        messages: vec![],
+        log: vec![],
+        data: Some(to_binary(&HandleAnswer::Unfollow { status: Success })?),
+    })
+}
+
+fn try_unlink_address<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    env: Env,
+    alias_string: String,
+    address: HumanAddr,
+) -> StdResult<HandleResponse> {
+    let alias_string = normalize_alias(&deps.storage, &alias_string)?;
+    let alias_object: Alias = load_owned_alias(&deps.storage, &deps.api, &env, &alias_string)?;
+    ensure_not_frozen(&deps.storage, &alias_string)?;
+
+    let mut linked_addresses_storage = LinkedAddressesStorage::from_storage(&mut deps.storage);
+    let mut linked_addresses: Vec<LinkedAddress> =
+        linked_addresses_storage.get_linked_addresses(alias_string.as_bytes());
+    let linked_count: usize = linked_addresses.len();
+    linked_addresses.retain(|linked_address| linked_address.address != address);
+    if linked_addresses.len() == linked_count {
+        return Err(StdError::not_found("Linked address"));
+    }
+    if linked_addresses.is_empty() {
+        linked_addresses_storage.remove_linked_addresses(alias_string.as_bytes());
+    } else {
+        linked_addresses_storage.set_linked_addresses(alias_string.as_bytes(), &linked_addresses);
+    }
+    save_updated_alias(
+        &mut deps.storage,
+        &deps.api,
+        &env,
+        alias_string,
+        alias_object,
+    )?;
+
+    Ok(HandleResponse {
+        messages: vec![],
+        log: vec![],
+        data: Some(to_binary(&HandleAnswer::UnlinkAddress { status: Success })?),
+    })
+}
+
+fn try_unreserve_aliases<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    env: Env,
+    aliases: Vec<String>,
+) -> StdResult<HandleResponse> {
+    let config: Config = TypedStore::attach(&deps.storage).load(CONFIG_KEY)?;
+    authorize_admin_action(
+        &mut deps.storage,
+        &deps.api,
+        &config,
+        &env.message.sender,
+        AdminRole::Moderator,
+    )?;
+    validate_reservation_chunk(&aliases)?;
+
+    let mut removed: u32 = 0;
+    let mut reserved_aliases_storage = ReservedAliasesStorage::from_storage(&mut deps.storage);
+    for alias_string in aliases {
+        let alias_string = config.case_folding.fold(alias_string.trim());
+        if reserved_aliases_storage.is_reserved(alias_string.as_bytes()) {
+            reserved_aliases_storage.remove_reserved(alias_string.as_bytes());
+            removed += 1;
+        }
+    }
+    let mut count_store = TypedStoreMut::attach(&mut deps.storage);
+    let reserved_count: u64 = count_store
+        .may_load(RESERVED_ALIAS_COUNT_KEY)?
+        .unwrap_or(0)
+        .saturating_sub(u64::from(removed));
+    count_store.store(RESERVED_ALIAS_COUNT_KEY, &reserved_count)?;
+
+    Ok(HandleResponse {
+        messages: vec![],
+        log: vec![],
+        data: Some(to_binary(&HandleAnswer::UnreserveAliases {
+            removed,
+            reserved_count,
+        })?),
+    })
+}
+
+fn try_unsuspend<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    env: Env,
+    alias_string: String,
+) -> StdResult<HandleResponse> {
+    let config: Config = TypedStore::attach(&deps.storage).load(CONFIG_KEY)?;
+    authorize_admin_action(
+        &mut deps.storage,
+        &deps.api,
+        &config,
+        &env.message.sender,
+        AdminRole::Moderator,
+    )?;
+    let alias_string = normalize_alias(&deps.storage, &alias_string)?;
+    let mut suspensions_storage = SuspensionsStorage::from_storage(&mut deps.storage);
+    if suspensions_storage
+        .get_suspension(alias_string.as_bytes())
+        .is_none()
+    {
+        return Err(StdError::not_found("Suspension"));
+    }
+    suspensions_storage.remove_suspension(alias_string.as_bytes());
+
+    Ok(HandleResponse {
+        messages: vec![],
+        log: vec![],
+        data: Some(to_binary(&HandleAnswer::Unsuspend { status: Success })?),
+    })
+}
+
+fn try_update_profile<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    env: Env,
+    alias_string: String,
+    banner_url: Option<String>,
+    accent_color: Option<String>,
+) -> StdResult<HandleResponse> {
+    let config: Config = TypedStore::attach(&deps.storage).load(CONFIG_KEY)?;
+    let alias_string = normalize_alias(&deps.storage, &alias_string)?;
+    let mut alias_object: Alias =
+        load_editable_alias(&deps.storage, &deps.api, &env, &alias_string)?;
+    ensure_not_frozen(&deps.storage, &alias_string)?;
+    if let Some(banner_url) = &banner_url {
+        validate_image_url(&config, "Banner", banner_url)?;
+    }
+    let accent_color: Option<String> = accent_color.map(|color| color.trim().to_lowercase());
+    if let Some(accent_color) = &accent_color {
+        if accent_color.len() != 7
+            || !accent_color.starts_with('#')
+            || !accent_color[1..].chars().all(|c| c.is_ascii_hexdigit())
+        {
+            return Err(StdError::generic_err(
+                "Accent color must be a hex color such as #ff8800",
+            ));
+        }
+    }
+
+    alias_object.banner_url = banner_url;
+    alias_object.accent_color = accent_color;
+    save_updated_alias(
+        &mut deps.storage,
+        &deps.api,
+        &env,
+        alias_string,
+        alias_object,
+    )?;
+
+    Ok(HandleResponse {
+        messages: vec![],
+        log: vec![],
+        data: Some(to_binary(&HandleAnswer::UpdateProfile { status: Success })?),
+    })
+}
+
+// Every change is validated before any is saved, so a bad record leaves the others untouched
+fn try_update_records<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    env: Env,
+    alias_string: String,
+    set: Vec<Record>,
+    remove: Vec<String>,
+) -> StdResult<HandleResponse> {
+    let alias_string = normalize_alias(&deps.storage, &alias_string)?;
+    let alias_object: Alias = load_editable_alias(&deps.storage, &deps.api, &env, &alias_string)?;
+    ensure_not_frozen(&deps.storage, &alias_string)?;
+    let set: Vec<Record> = validate_records(set)?;
+    let remove: Vec<String> = remove.iter().map(|key| key.trim().to_lowercase()).collect();
+    if let Some(record) = set.iter().find(|record| remove.contains(&record.key)) {
+        return Err(StdError::generic_err(format!(
+            "Record {} is both set and removed",
+            record.key
+        )));
+    }
+
+    keep_previous_profile(&mut deps.storage, &alias_string, &alias_object);
+    let mut records_storage = RecordsStorage::from_storage(&mut deps.storage);
+    let mut records: Vec<Record> = records_storage.get_records(alias_string.as_bytes());
+    records.retain(|record| {
+        !remove.contains(&record.key) && !set.iter().any(|new_record| new_record.key == record.key)
+    });
+    records.extend(set);
+    if records.len() > MAX_RECORDS_PER_ALIAS {
+        return Err(StdError::generic_err(format!(
+            "An alias can have at most {} records",
+            MAX_RECORDS_PER_ALIAS
+        )));
+    }
+    if records.is_empty() {
+        records_storage.remove_records(alias_string.as_bytes());
+    } else {
+        records_storage.set_records(alias_string.as_bytes(), &records);
+    }
+    save_updated_alias(
+        &mut deps.storage,
+        &deps.api,
+        &env,
+        alias_string,
+        alias_object,
+    )?;
+
+    Ok(HandleResponse {
+        messages: vec![],
+        log: vec![],
+        data: Some(to_binary(&HandleAnswer::UpdateRecords { status: Success })?),
+    })
+}
+
+fn try_withdraw_offer<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    env: Env,
+    alias_string: String,
+) -> StdResult<HandleResponse> {
+    let alias_string = normalize_alias(&deps.storage, &alias_string)?;
+    let bidder: CanonicalAddr = deps.api.canonical_address(&env.message.sender)?;
+    let mut offers_storage = OffersStorage::from_storage(&mut deps.storage);
+    let mut offers: Vec<StoredOffer> = offers_storage.get_offers(alias_string.as_bytes());
+    let index: Option<usize> = offers.iter().position(|offer| offer.bidder == bidder);
+    if index.is_none() {
+        return Err(StdError::not_found("Offer"));
+    }
+    let offer: StoredOffer = offers.remove(index.unwrap());
+
+    if offers.is_empty() {
+        offers_storage.remove_offers(alias_string.as_bytes());
+    } else {
+        offers_storage.set_offers(alias_string.as_bytes(), &offers);
+    }
+
+    Ok(HandleResponse {
+        messages: vec![send_asset_msg(
+            env.contract.address,
+            env.message.sender,
+            offer.asset,
+            offer.amount,
+        )?],
+        log: vec![],
+        data: Some(to_binary(&HandleAnswer::WithdrawOffer { status: Success })?),
+    })
+}
+
+fn try_withdraw_tips<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    env: Env,
+    alias_string: String,
+) -> StdResult<HandleResponse> {
+    let alias_string = normalize_alias(&deps.storage, &alias_string)?;
+    load_owned_alias(&deps.storage, &deps.api, &env, &alias_string)?;
+    let messages: Vec<CosmosMsg> = withdraw_tips(
+        &mut deps.storage,
+        &alias_string,
+        env.contract.address,
+        env.message.sender,
+    )?;
+    if messages.is_empty() {
+        return Err(StdError::generic_err("There are no tips to withdraw"));
+    }
+
+    Ok(HandleResponse {
+        messages,
+        log: vec![],
+        data: Some(to_binary(&HandleAnswer::WithdrawTips { status: Success })?),
+    })
+}
+
+fn validate_tip<S: Storage, A: Api>(
+    storage: &S,
+    api: &A,
+    alias_string: &str,
+    message: &Option<String>,
+) -> StdResult<()> {
+    if RegistryReadonlyStorage::from_storage(storage, api)
+        .get_alias(alias_string.as_bytes())
+        .is_none()
+    {
+        return Err(StdError::not_found("Alias"));
+    }
+    if let Some(message) = message {
+        if message.len() > MAX_TIP_MESSAGE_LENGTH {
+            return Err(StdError::generic_err(format!(
+                "Tip message is too long. Maximum is {} bytes.",
+                MAX_TIP_MESSAGE_LENGTH
+            )));
+        }
+    }
+
+    Ok(())
+}
+
+// Empty the alias's balances into messages paying them to recipient. The tips stay so the owner
+// can still read their messages.
+fn withdraw_tips<S: Storage>(
+    storage: &mut S,
+    alias_string: &str,
+    contract_address: HumanAddr,
+    recipient: HumanAddr,
+) -> StdResult<Vec<CosmosMsg>> {
+    let mut tip_jars_storage = TipJarsStorage::from_storage(storage);
+    let mut tip_jar: TipJar = tip_jars_storage.get_tip_jar(alias_string.as_bytes());
+    if tip_jar.balances.is_empty() {
+        return Ok(vec![]);
+    }
+
+    let mut messages: Vec<CosmosMsg> = vec![];
+    for balance in tip_jar.balances.drain(..) {
+        messages.push(send_asset_msg(
+            contract_address.clone(),
+            recipient.clone(),
+            balance.asset,
+            balance.amount,
+        )?);
+    }
+    tip_jars_storage.set_tip_jar(alias_string.as_bytes(), &tip_jar);
+
+    Ok(messages)
+}
+
+// Credit a tip to the alias's balance in that asset and keep it in the jar's recent tips
+fn add_tip<S: Storage>(storage: &mut S, alias_string: &str, tip: Tip) -> StdResult<()> {
+    let mut tip_jars_storage = TipJarsStorage::from_storage(storage);
+    let mut tip_jar: TipJar = tip_jars_storage.get_tip_jar(alias_string.as_bytes());
+    match tip_jar
+        .balances
+        .iter_mut()
+        .find(|balance| balance.asset == tip.asset)
+    {
+        Some(balance) => {
+            balance.amount = Uint128(
+                balance
+                    .amount
+                    .u128()
+                    .checked_add(tip.amount.u128())
+                    .ok_or_else(|| StdError::generic_err("Tip balance is too large"))?,
+            )
+        }
+        None => tip_jar.balances.push(TipBalance {
+            asset: tip.asset.clone(),
+            amount: tip.amount,
+        }),
+    }
+    tip_jar.tips.push(tip);
+    if tip_jar.tips.len() > MAX_TIPS_PER_JAR {
+        tip_jar.tips.remove(0);
+    }
+    tip_jars_storage.set_tip_jar(alias_string.as_bytes(), &tip_jar);
+
+    Ok(())
+}
+
+// Tips belong to whoever owned the alias when they were sent, so they have to be withdrawn
+// before the alias changes hands or goes away
+fn ensure_no_tips<S: Storage>(storage: &S, alias_string: &str) -> StdResult<()> {
+    if !TipJarsReadonlyStorage::from_storage(storage)
+        .get_tip_jar(alias_string.as_bytes())
+        .balances
+        .is_empty()
+    {
+        return Err(StdError::generic_err("Withdraw the alias's tips first"));
+    }
+
+    Ok(())
+}
+
+// An alias being reclaimed can't change hands or be destroyed until the reclaim is settled, so the
+// winning bid always has an alias to go with it
+fn ensure_no_reclaim<S: Storage>(storage: &S, alias_string: &str) -> StdResult<()> {
+    if ReclaimsReadonlyStorage::from_storage(storage)
+        .get_reclaim(alias_string.as_bytes())
+        .is_some()
+    {
+        return Err(StdError::generic_err("Alias is being reclaimed"));
+    }
+
+    Ok(())
+}
+
+fn ensure_not_locked<S: Storage>(storage: &S, alias_string: &str, height: u64) -> StdResult<()> {
+    if let Some(locked_until) =
+        LocksReadonlyStorage::from_storage(storage).get_locked_until(alias_string.as_bytes())
+    {
+        if height < locked_until {
+            return Err(StdError::generic_err(format!(
+                "Alias is locked until block {}",
+                locked_until
+            )));
+        }
+    }
+
+    Ok(())
+}
+
+fn ensure_not_suspended<S: Storage>(storage: &S, alias_string: &str) -> StdResult<()> {
+    if SuspensionsReadonlyStorage::from_storage(storage)
+        .get_suspension(alias_string.as_bytes())
+        .is_some()
+    {
+        return Err(StdError::generic_err("Alias is suspended"));
+    }
+
+    Ok(())
+}
+
+// Suspended aliases only stop resolving when the Moderator asked for it
+fn hides_resolution<S: Storage>(storage: &S, alias_string: &str) -> bool {
+    SuspensionsReadonlyStorage::from_storage(storage)
+        .get_suspension(alias_string.as_bytes())
+        .map_or(false, |suspension| suspension.hide_resolution)
+}
+
+// Hash up from the leaf, ordering each pair so the proof doesn't need to say which side a
+// sibling is on
+fn verify_merkle_proof(merkle_root: &[u8], leaf: [u8; 32], proof: &[Binary]) -> bool {
+    let mut hash: [u8; 32] = leaf;
+    for sibling in proof {
+        if sibling.len() != 32 {
+            return false;
+        }
+        hash = if &hash[..] <= sibling.as_slice() {
+            sha_256(&[&hash[..], sibling.as_slice()].concat())
+        } else {
+            sha_256(&[sibling.as_slice(), &hash[..]].concat())
+        };
+    }
+
+    hash[..] == merkle_root[..]
+}
+
+// Falls linearly from the start price to the end price over the decay blocks, then stays there
+fn drop_price(drop: &Drop, height: u64) -> Uint128 {
+    let elapsed: u64 = height.saturating_sub(drop.start_height);
+    if elapsed >= drop.decay_blocks {
+        return drop.end_price;
+    }
+    let decay: Uint128 = Uint128(drop.start_price.u128() - drop.end_price.u128())
+        .multiply_ratio(elapsed, drop.decay_blocks);
+    Uint128(drop.start_price.u128() - decay.u128())
+}
+
+// The fee and the blocks bought for a number of lease periods
+fn lease_cost(fee: Uint128, period_blocks: u64, periods: u16) -> StdResult<(Uint128, u64)> {
+    if periods == 0 {
+        return Err(StdError::generic_err("Lease at least one period"));
+    }
+    let fee: u128 = fee
+        .u128()
+        .checked_mul(u128::from(periods))
+        .ok_or_else(|| StdError::generic_err("Lease fee is too large"))?;
+
+    Ok((
+        Uint128(fee),
+        period_blocks.saturating_mul(u64::from(periods)),
+    ))
+}
+
+// A lease that has run out leaves the alias to whoever registers it next, without the usual
+// tombstone for the previous holder. The lapsed holder can't act on the alias any more, so their
+// tips are paid out, a reclaim bid is refunded and an escrow, which holds no funds, is dropped.
+fn release_expired_lease<S: Storage, A: Api>(
+    storage: &mut S,
+    api: &A,
+    env: &Env,
+    config: &Config,
+    alias_string: &str,
+) -> StdResult<Vec<CosmosMsg>> {
+    match LeasesReadonlyStorage::from_storage(storage).get_lease(alias_string.as_bytes()) {
+        Some(lease) if lease.paid_until <= env.block.height => {}
+        _ => return Ok(vec![]),
+    }
+    let alias_object: Alias = RegistryReadonlyStorage::from_storage(storage, api)
+        .get_alias(alias_string.as_bytes())
+        .ok_or_else(|| StdError::not_found("Alias"))?;
+    let mut messages: Vec<CosmosMsg> = withdraw_tips(
+        storage,
+        alias_string,
+        env.contract.address.clone(),
+        api.human_address(&alias_object.owner)?,
+    )?;
+    let mut reclaims_storage = ReclaimsStorage::from_storage(storage);
+    if let Some(reclaim) = reclaims_storage.get_reclaim(alias_string.as_bytes()) {
+        reclaims_storage.remove_reclaim(alias_string.as_bytes());
+        if let Some(bidder) = reclaim.bidder {
+            messages.push(send_asset_msg(
+                env.contract.address.clone(),
+                api.human_address(&bidder)?,
+                Asset::Snip20 {
+                    token: config.buttcoin.clone(),
+                },
+                reclaim.bid,
+            )?);
+        }
+    }
+    EscrowsStorage::from_storage(storage).remove_escrow(alias_string.as_bytes());
+    destroy_alias(storage, api, env, alias_string.to_string(), alias_object)?;
+    TombstonesStorage::from_storage(storage).remove_tombstone(alias_string.as_bytes());
+
+    Ok(messages)
+}
+
+fn ensure_lease_current<S: Storage>(storage: &S, alias_string: &str, height: u64) -> StdResult<()> {
+    if let Some(lease) =
+        LeasesReadonlyStorage::from_storage(storage).get_lease(alias_string.as_bytes())
+    {
+        if lease.paid_until <= height {
+            return Err(StdError::generic_err("Lease has expired"));
+        }
+    }
+
+    Ok(())
+}
+
+fn launch_stage(config: &Config, height: u64) -> LaunchStage {
+    match &config.launch {
+        Some(launch) if height < launch.auctions_open_at => LaunchStage::Paused,
+        Some(launch) if height < launch.registration_opens_at => LaunchStage::AuctionOnly,
+        _ => LaunchStage::Open,
+    }
+}
+
+// Drops and offers open once the launch leaves Paused
+fn ensure_auctions_open(config: &Config, height: u64) -> StdResult<()> {
+    if launch_stage(config, height) < LaunchStage::AuctionOnly {
+        return Err(StdError::generic_err("Auctions haven't opened yet"));
+    }
+
+    Ok(())
+}
+
+fn ensure_registration_open(config: &Config, height: u64) -> StdResult<()> {
+    if launch_stage(config, height) < LaunchStage::Open {
+        return Err(StdError::generic_err("Registration hasn't opened yet"));
+    }
+
+    Ok(())
+}
+
+// Aliases are applied for rather than registered while a lottery is open
+fn ensure_no_lottery(config: &Config, height: u64) -> StdResult<()> {
+    if matches!(config.lottery_ends_at, Some(lottery_ends_at) if height < lottery_ends_at) {
+        return Err(StdError::generic_err(
+            "Registration is closed while the lottery is running",
+        ));
+    }
+
+    Ok(())
+}
+
+fn active_mint_phase(config: &Config, height: u64) -> Option<&MintPhase> {
+    config
+        .mint_phases
+        .iter()
+        .find(|phase| phase.start_height <= height && height < phase.end_height)
+}
+
+// Registration without an allowlist proof is closed while a mint phase is running
+fn ensure_no_mint_phase(config: &Config, height: u64) -> StdResult<()> {
+    if active_mint_phase(config, height).is_some() {
+        return Err(StdError::generic_err(
+            "Registering during a mint phase needs an allowlist proof",
+        ));
+    }
+
+    Ok(())
+}
+
+// The fee of the running mint phase, once the proof shows that address is on its allowlist
+fn allowlisted_mint_phase_fee(
+    config: &Config,
+    height: u64,
+    address: &HumanAddr,
+    proof: &[Binary],
+) -> StdResult<Uint128> {
+    let phase: &MintPhase = active_mint_phase(config, height)
+        .ok_or_else(|| StdError::generic_err("There is no mint phase running"))?;
+    if proof.len() > MAX_MERKLE_PROOF_LENGTH {
+        return Err(StdError::generic_err("Proof is too long"));
+    }
+    let leaf: [u8; 32] = sha_256(address.0.as_bytes());
+    if !verify_merkle_proof(phase.merkle_root.as_slice(), leaf, proof) {
+        return Err(StdError::generic_err("Proof is invalid"));
+    }
+
+    Ok(phase.fee)
+}
+
+// The address's leaf, once the proof shows it is in the discount's snapshot and hasn't used it
+fn verify_discount_proof<S: Storage>(
+    storage: &S,
+    discount: &Discount,
+    address: &HumanAddr,
+    proof: &[Binary],
+) -> StdResult<[u8; 32]> {
+    if proof.len() > MAX_MERKLE_PROOF_LENGTH {
+        return Err(StdError::generic_err("Proof is too long"));
+    }
+    let leaf: [u8; 32] = sha_256(address.0.as_bytes());
+    if !verify_merkle_proof(discount.merkle_root.as_slice(), leaf, proof) {
+        return Err(StdError::generic_err("Proof is invalid"));
+    }
+    if DiscountClaimsReadonlyStorage::from_storage(storage)
+        .is_claimed(discount.merkle_root.as_slice(), &leaf)
+    {
+        return Err(StdError::generic_err("Discount has already been claimed"));
+    }
+
+    Ok(leaf)
+}
+
+// Trims the alias and folds its case with the policy chosen at init, so handles and queries find
+// the same alias
+fn normalize_alias<S: Storage>(storage: &S, alias_string: &str) -> StdResult<String> {
+    let config: Config = TypedStore::attach(storage).load(CONFIG_KEY)?;
+    Ok(config.case_folding.fold(alias_string.trim()))
+}
+
+// The rules on a normalized alias itself, apart from whether it is available
+fn alias_violations(alias_string: &str) -> Vec<String> {
+    let mut violations: Vec<String> = vec![];
+    if alias_string.len() > u8::MAX.into() {
+        violations.push("Alias is too long".to_string());
+    }
+    // Otherwise an alias could pass itself off as a different account
+    if is_bech32_address(&alias_string.to_lowercase()) {
+        violations.push("Alias can't be an address".to_string());
+    }
+
+    violations
+}
+
+fn validate_alias_string(alias_string: &str) -> StdResult<()> {
+    match alias_violations(alias_string).into_iter().next() {
+        Some(violation) => Err(StdError::generic_err(violation)),
+        None => Ok(()),
+    }
+}
+
+// An alias is taken once it's registered or held as a gift
+fn ensure_alias_available<S: Storage, A: Api>(
+    storage: &S,
+    api: &A,
+    alias_string: &str,
+) -> StdResult<()> {
+    if RegistryReadonlyStorage::from_storage(storage, api)
+        .get_alias(alias_string.as_bytes())
+        .is_some()
+        || GiftsReadonlyStorage::from_storage(storage)
+            .get_gift(alias_string.as_bytes())
+            .is_some()
+    {
+        return Err(StdError::generic_err("Alias has already been taken"));
+    }
+    if RenouncedAliasesReadonlyStorage::from_storage(storage).is_renounced(alias_string.as_bytes())
+    {
+        return Err(StdError::generic_err("Alias has been renounced"));
+    }
+    if ReservedAliasesReadonlyStorage::from_storage(storage).is_reserved(alias_string.as_bytes()) {
+        return Err(StdError::generic_err("Alias is reserved"));
+    }
+    if !LotteryApplicationsReadonlyStorage::from_storage(storage)
+        .get_applications(alias_string.as_bytes())
+        .is_empty()
+    {
+        return Err(StdError::generic_err(
+            "Alias is waiting for its lottery draw",
+        ));
+    }
+    if let Some(address) = KnownContractsReadonlyStorage::from_storage(storage)
+        .get_known_contract(alias_string.as_bytes())
+    {
+        return Err(StdError::generic_err(format!(
+            "Alias is the label of the contract at {}",
+            address
+        )));
+    }
+
+    Ok(())
+}
+
+fn ensure_bootstrap_phase(config: &Config, height: u64) -> StdResult<()> {
+    match config.bootstrap_ends_at {
+        Some(bootstrap_ends_at) if height < bootstrap_ends_at => Ok(()),
+        _ => Err(StdError::generic_err("The bootstrap phase is over")),
+    }
+}
+
+// A destroyed alias can only be registered by its previous owner until its tombstone expires
+fn ensure_not_tombstoned<S: Storage>(
+    storage: &S,
+    alias_string: &str,
+    registrant: &CanonicalAddr,
+    height: u64,
+) -> StdResult<()> {
+    if let Some(tombstone) =
+        TombstonesReadonlyStorage::from_storage(storage).get_tombstone(alias_string.as_bytes())
+    {
+        if tombstone.expires_at > height && tombstone.previous_owner != *registrant {
+            return Err(StdError::generic_err(format!(
+                "Alias is reserved for its previous owner until block {}",
+                tombstone.expires_at
+            )));
+        }
+    }
+
+    Ok(())
+}
+
+fn ensure_not_frozen<S: Storage>(storage: &S, alias_string: &str) -> StdResult<()> {
+    if FrozenAliasesReadonlyStorage::from_storage(storage)
+        .get_frozen(alias_string.as_bytes())
+        .is_some()
+    {
+        return Err(StdError::generic_err("Alias is frozen"));
+    }
+
+    Ok(())
+}
+
+// Frozen aliases can only change hands if they were frozen with transfers allowed
+fn ensure_transferable<S: Storage>(storage: &S, alias_string: &str) -> StdResult<()> {
+    if let Some(frozen_alias) =
+        FrozenAliasesReadonlyStorage::from_storage(storage).get_frozen(alias_string.as_bytes())
+    {
+        if !frozen_alias.allow_transfers {
+            return Err(StdError::generic_err("Alias is frozen"));
+        }
+    }
+
+    Ok(())
+}
+
+fn ensure_not_in_escrow<S: Storage>(storage: &S, alias_string: &str) -> StdResult<()> {
+    if EscrowsReadonlyStorage::from_storage(storage)
+        .get_escrow(alias_string.as_bytes())
+        .is_some()
+    {
+        return Err(StdError::generic_err("Alias is in escrow"));
+    }
+
+    Ok(())
+}
+
+// Only Buttcoin is known to be burnable, so a Burn split can't be set while fees or payments can
+// come in other assets, and those can't be added while there is one
+fn ensure_fees_burnable(config: &Config) -> StdResult<()> {
+    if config
+        .fee_splits
+        .iter()
+        .any(|fee_split| fee_split.recipient == FeeRecipient::Burn)
+        && !(config.accepted_payments.is_empty() && config.routable_tokens.is_empty())
+    {
+        return Err(StdError::generic_err(
+            "Only Buttcoin can be burnt, so Burn can't be used with other assets",
+        ));
+    }
+
+    Ok(())
+}
+
+// The price of an escrow in a form people recognise
+fn asset_name(asset: &Asset) -> String {
+    match asset {
+        Asset::Native { denom } => denom.clone(),
+        Asset::Snip20 { token } => token.address.to_string(),
+    }
+}
+
+// Funds sent as native coins along with the handle msg, for a price or an offer
+fn single_coin_payment(env: &Env) -> StdResult<Payment> {
+    if env.message.sent_funds.len() != 1 {
+        return Err(StdError::generic_err("Send exactly one denomination"));
+    }
+    let coin: &Coin = &env.message.sent_funds[0];
+
+    Ok(Payment {
+        asset: Asset::Native {
+            denom: coin.denom.clone(),
+        },
+        amount: coin.amount,
+    })
+}
+
+// The governance contract, the admin that instantiated the contract and SuperAdmins hold every
+// role. Only the governance contract does once it's exclusive.
+fn authorize_role<A: Api>(
+    api: &A,
+    config: &Config,
+    sender: &HumanAddr,
+    role: AdminRole,
+) -> StdResult<()> {
+    let sender: CanonicalAddr = api.canonical_address(sender)?;
+    if let Some(governance) = &config.governance {
+        if sender == governance.contract {
+            return Ok(());
+        }
+        if governance.exclusive {
+            return Err(StdError::Unauthorized { backtrace: None });
+        }
+    }
+    let authorized: bool = sender == config.admin
+        || config.admin_grants.iter().any(|admin_grant| {
+            admin_grant.address == sender
+                && (admin_grant.role == role || admin_grant.role == AdminRole::SuperAdmin)
+        });
+    if !authorized {
+        return Err(StdError::Unauthorized { backtrace: None });
+    }
+
+    Ok(())
+}
+
+// Role-gated handlers authorize through here, so admin_actions only counts handles that needed a
+// role. A queued action is counted when it's executed. If the handle fails later on, the count is
+// reverted along with everything else.
+fn authorize_admin_action<S: Storage, A: Api>(
+    storage: &mut S,
+    api: &A,
+    config: &Config,
+    sender: &HumanAddr,
+    role: AdminRole,
+) -> StdResult<()> {
+    authorize_role(api, config, sender, role)?;
+    update_metrics(storage, |metrics| metrics.admin_actions += 1)
+}
+
+// The handle is only checked for its role now. Anything else that's wrong with it fails when it's
+// executed.
+fn try_queue_action<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    env: Env,
+    config: Config,
+    role: AdminRole,
+    msg: HandleMsg,
+) -> StdResult<HandleResponse> {
+    authorize_role(&deps.api, &config, &env.message.sender, role)?;
+    let mut queued_actions: Vec<StoredQueuedAction> = TypedStore::attach(&deps.storage)
+        .may_load(QUEUED_ACTIONS_KEY)?
+        .unwrap_or_default();
+    if queued_actions.len() >= MAX_QUEUED_ACTIONS {
+        return Err(StdError::generic_err(format!(
+            "There can't be more than {} queued actions",
+            MAX_QUEUED_ACTIONS
+        )));
+    }
+
+    let mut seq_store = TypedStoreMut::attach(&mut deps.storage);
+    let id: u64 = seq_store.may_load(QUEUED_ACTION_SEQ_KEY)?.unwrap_or(0) + 1;
+    seq_store.store(QUEUED_ACTION_SEQ_KEY, &id)?;
+    let executable_at: u64 = env.block.height + config.timelock_blocks;
+    queued_actions.push(StoredQueuedAction {
+        id,
+        msg: to_binary(&msg)?,
+        queued_by: deps.api.canonical_address(&env.message.sender)?,
+        queued_at: env.block.height,
+        executable_at,
+    });
+    TypedStoreMut::attach(&mut deps.storage).store(QUEUED_ACTIONS_KEY, &queued_actions)?;
+
+    Ok(HandleResponse {
+        messages: vec![],
+        log: vec![],
+        data: Some(to_binary(&HandleAnswer::Queued { id, executable_at })?),
+    })
+}
+
+// An alias that can still change: its lease is current and it isn't suspended
+fn load_current_alias<S: Storage, A: Api>(
+    storage: &S,
+    api: &A,
+    env: &Env,
+    alias_string: &str,
+) -> StdResult<Alias> {
+    let alias_object: Option<Alias> =
+        RegistryReadonlyStorage::from_storage(storage, api).get_alias(alias_string.as_bytes());
+    if alias_object.is_none() {
+        return Err(StdError::not_found("Alias"));
+    }
+    let alias_object: Alias = alias_object.unwrap();
+    ensure_lease_current(storage, alias_string, env.block.height)?;
+    ensure_not_suspended(storage, alias_string)?;
+
+    Ok(alias_object)
+}
+
+fn load_owned_alias<S: Storage, A: Api>(
+    storage: &S,
+    api: &A,
+    env: &Env,
+    alias_string: &str,
+) -> StdResult<Alias> {
+    let alias_object: Alias = load_current_alias(storage, api, env, alias_string)?;
+    authorize(
+        api.canonical_address(&env.message.sender)?,
+        alias_object.owner.clone(),
+    )?;
+
+    Ok(alias_object)
+}
+
+// Members and co-owners of the alias may update its records
+fn load_editable_alias<S: Storage, A: Api>(
+    storage: &S,
+    api: &A,
+    env: &Env,
+    alias_string: &str,
+) -> StdResult<Alias> {
+    let alias_object: Alias = load_current_alias(storage, api, env, alias_string)?;
+    let sender: CanonicalAddr = api.canonical_address(&env.message.sender)?;
+    if sender != alias_object.owner
+        && !is_co_owner(storage, alias_string, &sender)
+        && !MembersReadonlyStorage::from_storage(storage)
+            .get_members(alias_string.as_bytes())
+            .iter()
+            .any(|member| member.address == sender)
+    {
+        return Err(StdError::Unauthorized { backtrace: None });
+    }
+
+    Ok(alias_object)
+}
+
+// The owner and co-owners may propose, approve and reject the actions that need approval
+fn load_co_owned_alias<S: Storage, A: Api>(
+    storage: &S,
+    api: &A,
+    env: &Env,
+    alias_string: &str,
+) -> StdResult<Alias> {
+    let alias_object: Alias = load_current_alias(storage, api, env, alias_string)?;
+    let sender: CanonicalAddr = api.canonical_address(&env.message.sender)?;
+    if sender != alias_object.owner && !is_co_owner(storage, alias_string, &sender) {
+        return Err(StdError::Unauthorized { backtrace: None });
+    }
+
+    Ok(alias_object)
+}
+
+fn is_co_owner<S: Storage>(storage: &S, alias_string: &str, address: &CanonicalAddr) -> bool {
+    CoOwnershipsReadonlyStorage::from_storage(storage)
+        .get_co_ownership(alias_string.as_bytes())
+        .map_or(false, |co_ownership| {
+            co_ownership.co_owners.contains(address)
+        })
+}
+
+// Start an action with the sender's approval. It runs straight away if that meets the threshold,
+// which is always the case for an alias that isn't co-owned.
+fn propose_action<S: Storage, A: Api>(
+    storage: &mut S,
+    api: &A,
+    env: &Env,
+    alias_string: String,
+    alias_object: Alias,
+    action: StoredApprovalAction,
+) -> StdResult<Vec<CosmosMsg>> {
+    if PendingActionsReadonlyStorage::from_storage(storage)
+        .get_pending_action(alias_string.as_bytes())
+        .is_some()
+    {
+        return Err(StdError::generic_err("Another action is pending approval"));
+    }
+
+    approve_action(
+        storage,
+        api,
+        env,
+        alias_string,
+        alias_object,
+        StoredPendingAction {
+            action,
+            approvals: vec![api.canonical_address(&env.message.sender)?],
+            proposed_at: env.block.height,
+        },
+    )
+}
+
+fn approve_action<S: Storage, A: Api>(
+    storage: &mut S,
+    api: &A,
+    env: &Env,
+    alias_string: String,
+    alias_object: Alias,
+    pending_action: StoredPendingAction,
+) -> StdResult<Vec<CosmosMsg>> {
+    ensure_not_locked(storage, &alias_string, env.block.height)?;
+    let threshold: usize = CoOwnershipsReadonlyStorage::from_storage(storage)
+        .get_co_ownership(alias_string.as_bytes())
+        .map_or(1, |co_ownership| usize::from(co_ownership.threshold));
+    let mut pending_actions_storage = PendingActionsStorage::from_storage(storage);
+    if pending_action.approvals.len() < threshold {
+        pending_actions_storage.set_pending_action(alias_string.as_bytes(), &pending_action);
+        return Ok(vec![]);
+    }
+    pending_actions_storage.remove_pending_action(alias_string.as_bytes());
+
+    match pending_action.action {
+        StoredApprovalAction::Destroy => {
+            destroy_alias(storage, api, env, alias_string, alias_object)?;
+            Ok(vec![])
+        }
+        StoredApprovalAction::Freeze { allow_transfers } => {
+            FrozenAliasesStorage::from_storage(storage).set_frozen(
+                alias_string.as_bytes(),
+                &FrozenAlias {
+                    allow_transfers,
+                    frozen_at: env.block.height,
+                },
+            );
+            Ok(vec![])
+        }
+        StoredApprovalAction::Renounce => {
+            destroy_alias(storage, api, env, alias_string.clone(), alias_object)?;
+            RenouncedAliasesStorage::from_storage(storage).set_renounced(alias_string.as_bytes());
+            Ok(vec![])
+        }
+        StoredApprovalAction::SetCoOwners {
+            co_owners,
+            threshold,
+        } => {
+            let mut co_ownerships_storage = CoOwnershipsStorage::from_storage(storage);
+            if co_owners.is_empty() {
+                co_ownerships_storage.remove_co_ownership(alias_string.as_bytes());
+            } else {
+                co_ownerships_storage.set_co_ownership(
+                    alias_string.as_bytes(),
+                    &CoOwnership {
+                        co_owners,
+                        threshold,
+                    },
+                );
+            }
+            save_updated_alias(storage, api, env, alias_string, alias_object)?;
+            Ok(vec![])
+        }
+        StoredApprovalAction::Transfer {
+            recipient,
+            callback,
+        } => {
+            let from: HumanAddr = api.human_address(&alias_object.owner)?;
+            transfer_alias(
+                storage,
+                api,
+                env,
+                alias_string.clone(),
+                alias_object,
+                recipient.clone(),
+            )?;
+            // Let a receiving contract act on the deposit in the same transaction
+            match callback {
+                Some(callback) => Ok(vec![ReceiverHandleMsg::ReceiveAlias {
+                    alias: alias_string,
+                    from,
+                    msg: callback.msg,
+                }
+                .to_cosmos_msg(
+                    callback.code_hash,
+                    api.human_address(&recipient)?,
+                    None,
+                )?]),
+                None => Ok(vec![]),
+            }
+        }
+    }
+}
+
+fn destroy_alias<S: Storage, A: Api>(
+    storage: &mut S,
+    api: &A,
+    env: &Env,
+    alias_string: String,
+    alias_object: Alias,
+) -> StdResult<()> {
+    ensure_not_in_escrow(storage, &alias_string)?;
+    ensure_no_reclaim(storage, &alias_string)?;
+    ensure_no_tips(storage, &alias_string)?;
+    ensure_not_suspended(storage, &alias_string)?;
+    let alias_string_byte_slice: &[u8] = alias_string.as_bytes();
+    RegistryStorage::from_storage(storage, api).remove_alias(alias_string_byte_slice);
+    AttestationsStorage::from_storage(storage).remove_attestations(alias_string_byte_slice);
+    EndorsementsStorage::from_storage(storage).remove_endorsers(alias_string_byte_slice);
+    ImagesStorage::from_storage(storage).remove_images(alias_string_byte_slice);
+    RecordsStorage::from_storage(storage).remove_records(alias_string_byte_slice);
+    TipJarsStorage::from_storage(storage).remove_tip_jar(alias_string_byte_slice);
+    AliasViewingKeysStorage::from_storage(storage)
+        .remove_alias_viewing_keys(alias_string_byte_slice);
+    NotesStorage::from_storage(storage).remove_note(alias_string_byte_slice);
+    MembersStorage::from_storage(storage).remove_members(alias_string_byte_slice);
+    CoOwnershipsStorage::from_storage(storage).remove_co_ownership(alias_string_byte_slice);
+    FrozenAliasesStorage::from_storage(storage).remove_frozen(alias_string_byte_slice);
+    LeasesStorage::from_storage(storage).remove_lease(alias_string_byte_slice);
+    LocksStorage::from_storage(storage).remove_lock(alias_string_byte_slice);
+    SigningKeysStorage::from_storage(storage).remove_signing_key(alias_string_byte_slice);
+    EncryptionKeysStorage::from_storage(storage).remove_encryption_key(alias_string_byte_slice);
+    PaymentPreferencesStorage::from_storage(storage)
+        .remove_payment_preference(alias_string_byte_slice);
+    LinkedAddressesStorage::from_storage(storage).remove_linked_addresses(alias_string_byte_slice);
+    PreviousProfilesStorage::from_storage(storage).remove_previous_profile(alias_string_byte_slice);
+    ReleasesStorage::from_storage(storage)
+        .set_released_at(alias_string_byte_slice, env.block.height);
+    TombstonesStorage::from_storage(storage).set_tombstone(
+        alias_string_byte_slice,
+        &Tombstone {
+            previous_owner: alias_object.owner.clone(),
+            expires_at: env.block.height + TOMBSTONE_BLOCKS,
+        },
+    );
+    record_change(
+        storage,
+        api,
+        &env.block,
+        ChangeAction::Destroy,
+        alias_string,
+        alias_object.owner,
+    )
+}
+
+// The recipient gets the alias and its public profile. Attestations, records, the note, past tips
+// and the access granted by the previous owner are dropped, so address records can't send funds to the
+// previous owner.
+fn transfer_alias<S: Storage, A: Api>(
+    storage: &mut S,
+    api: &A,
+    env: &Env,
+    alias_string: String,
+    mut alias_object: Alias,
+    recipient: CanonicalAddr,
+) -> StdResult<()> {
+    ensure_not_in_escrow(storage, &alias_string)?;
+    ensure_no_reclaim(storage, &alias_string)?;
+    ensure_no_tips(storage, &alias_string)?;
+    ensure_transferable(storage, &alias_string)?;
+    ensure_not_suspended(storage, &alias_string)?;
+    let mut alias_storage = RegistryStorage::from_storage(storage, api);
+    // Checked again as the recipient may have registered an alias while this was pending
+    if alias_storage.get_alias_for_address(&recipient).is_some() {
+        return Err(StdError::generic_err("Recipient already has an alias"));
+    }
+    let previous_owner: CanonicalAddr = alias_object.owner.clone();
+    alias_object.owner = recipient.clone();
+    alias_object.updated_at = env.block.height;
+    alias_storage.set_alias(alias_string.as_bytes(), alias_object);
+
+    let alias_string_byte_slice: &[u8] = alias_string.as_bytes();
+    AttestationsStorage::from_storage(storage).remove_attestations(alias_string_byte_slice);
+    EndorsementsStorage::from_storage(storage).remove_endorsers(alias_string_byte_slice);
+    RecordsStorage::from_storage(storage).remove_records(alias_string_byte_slice);
+    TipJarsStorage::from_storage(storage).remove_tip_jar(alias_string_byte_slice);
+    AliasViewingKeysStorage::from_storage(storage)
+        .remove_alias_viewing_keys(alias_string_byte_slice);
+    NotesStorage::from_storage(storage).remove_note(alias_string_byte_slice);
+    MembersStorage::from_storage(storage).remove_members(alias_string_byte_slice);
+    CoOwnershipsStorage::from_storage(storage).remove_co_ownership(alias_string_byte_slice);
+    PreviousProfilesStorage::from_storage(storage).remove_previous_profile(alias_string_byte_slice);
+    SigningKeysStorage::from_storage(storage).remove_signing_key(alias_string_byte_slice);
+    EncryptionKeysStorage::from_storage(storage).remove_encryption_key(alias_string_byte_slice);
+    PaymentPreferencesStorage::from_storage(storage)
+        .remove_payment_preference(alias_string_byte_slice);
+    LinkedAddressesStorage::from_storage(storage).remove_linked_addresses(alias_string_byte_slice);
+    LocksStorage::from_storage(storage).remove_lock(alias_string_byte_slice);
+    record_change(
+        storage,
+        api,
+        &env.block,
+        ChangeAction::TransferFrom,
+        alias_string.clone(),
+        previous_owner,
+    )?;
+    record_change(
+        storage,
+        api,
+        &env.block,
+        ChangeAction::TransferTo,
+        alias_string,
+        recipient,
+    )
+}
+
+// Checks the sponsorship's signature over message and uses up the signer's next nonce
+fn use_sponsored_nonce<S: Storage, A: Api>(
+    storage: &mut S,
+    api: &A,
+    sponsorship: &Sponsorship,
+    message: &str,
+) -> StdResult<()> {
+    // The public key has to be the one the signer's address was derived from
+    let address_data: Vec<u8> = match decode(sponsorship.signer.as_str()) {
+        Some((_, data)) => data,
+        None => return Err(StdError::generic_err("Address is invalid")),
+    };
+    if !is_valid_public_key(sponsorship.public_key.as_slice())
+        || address_data != address_bytes(sponsorship.public_key.as_slice())
+    {
+        return Err(StdError::generic_err(
+            "Public key doesn't belong to the address",
+        ));
+    }
+    if !verify_signature(
+        sponsorship.public_key.as_slice(),
+        &adr36_sign_doc(sponsorship.signer.as_str(), message.as_bytes()),
+        sponsorship.signature.as_slice(),
+    ) {
+        return Err(StdError::generic_err("Signature is invalid"));
+    }
+    let signer: CanonicalAddr = api.canonical_address(&sponsorship.signer)?;
+    let mut nonces_storage = NoncesStorage::from_storage(storage);
+    let next_nonce: u64 = nonces_storage.get_nonce(&signer);
+    if sponsorship.nonce < next_nonce {
+        return Err(StdError::generic_err("Nonce has already been used"));
+    }
+    if sponsorship.nonce > next_nonce {
+        return Err(StdError::generic_err(format!(
+            "Nonce must be {}",
+            next_nonce
+        )));
+    }
+    nonces_storage.set_nonce(&signer, next_nonce + 1);
+
+    Ok(())
+}
+
+// The owner manages every member. Admins only manage editors and can't make anyone an admin.
+fn authorize_member_manager(
+    owner: &CanonicalAddr,
+    members: &[StoredMember],
+    sender: &CanonicalAddr,
+    address: &CanonicalAddr,
+    role: Option<Role>,
+) -> StdResult<()> {
+    if sender == owner {
+        return Ok(());
+    }
+    let role_of = |address: &CanonicalAddr| -> Option<Role> {
+        members
+            .iter()
+            .find(|member| &member.address == address)
+            .map(|member| member.role)
+    };
+    if role_of(sender) != Some(Role::Admin)
+        || role_of(address) == Some(Role::Admin)
+        || role == Some(Role::Admin)
+    {
+        return Err(StdError::Unauthorized { backtrace: None });
+    }
+
+    Ok(())
+}
+
+// Overwrites what was kept from before the previous change, so Revert only goes one step back
+fn keep_previous_profile<S: Storage>(storage: &mut S, alias_string: &str, alias_object: &Alias) {
+    let alias_string_byte_slice: &[u8] = alias_string.as_bytes();
+    let records: Vec<Record> =
+        RecordsReadonlyStorage::from_storage(storage).get_records(alias_string_byte_slice);
+    PreviousProfilesStorage::from_storage(storage).set_previous_profile(
+        alias_string_byte_slice,
+        &PreviousProfile {
+            display_name: alias_object.display_name.clone(),
+            avatar_url: alias_object.avatar_url.clone(),
+            avatar_metadata: alias_object.avatar_metadata.clone(),
+            records,
+        },
+    );
+}
+
+fn save_updated_alias<S: Storage, A: Api>(
+    storage: &mut S,
+    api: &A,
+    env: &Env,
+    alias_string: String,
+    mut alias_object: Alias,
+) -> StdResult<()> {
+    alias_object.updated_at = env.block.height;
+    let owner: CanonicalAddr = alias_object.owner.clone();
+    RegistryStorage::from_storage(storage, api).set_alias(alias_string.as_bytes(), alias_object);
+    record_change(
+        storage,
+        api,
+        &env.block,
+        ChangeAction::Update,
+        alias_string,
+        owner,
+    )
+}
+
+// Give every mutation of the registry the next sequence number so mirrors can sync deltas,
+// keep the alias count and count registrations and destroys per day for analytics. Renewals
+// don't change the registry, so try_renew_lease counts those itself.
+fn record_change<S: Storage, A: Api>(
+    storage: &mut S,
+    api: &A,
+    block: &BlockInfo,
+    action: ChangeAction,
+    alias: String,
+    address: CanonicalAddr,
+) -> StdResult<()> {
+    // Everything but an update adds an alias to or removes one from the registry
+    if action != ChangeAction::Update {
+        toggle_checksum_entry(storage, &alias, &api.human_address(&address)?)?;
+    }
+    if action == ChangeAction::Create || action == ChangeAction::Destroy {
+        let mut alias_count_store = TypedStoreMut::attach(storage);
+        let alias_count: u64 = alias_count_store.may_load(ALIAS_COUNT_KEY)?.unwrap_or(0);
+        let alias_count: u64 = if action == ChangeAction::Create {
+            alias_count + 1
+        } else {
+            alias_count.saturating_sub(1)
+        };
+        alias_count_store.store(ALIAS_COUNT_KEY, &alias_count)?;
+    }
+    match action {
+        ChangeAction::Destroy => update_metrics(storage, |metrics| metrics.destroys += 1)?,
+        ChangeAction::TransferTo => update_metrics(storage, |metrics| metrics.transfers += 1)?,
+        _ => {}
+    }
+
+    record_alias_version(storage, api, block, &alias)?;
+
+    let mut seq_store = TypedStoreMut::attach(storage);
+    let seq: u64 = seq_store.may_load(CHANGE_SEQ_KEY)?.unwrap_or(0) + 1;
+    seq_store.store(CHANGE_SEQ_KEY, &seq)?;
+    ChangesStorage::from_storage(storage).set_change(&StoredChange {
+        seq,
+        action,
+        alias,
+        address,
+        block_height: block.height,
+    });
+    prune_changes(storage, seq)?;
+
+    match action {
+        ChangeAction::Create => {
+            update_daily_stats(storage, block, |daily_stats| daily_stats.registrations += 1)
+        }
+        ChangeAction::Destroy => {
+            update_daily_stats(storage, block, |daily_stats| daily_stats.destroys += 1)
+        }
+        _ => {}
+    }
+
+    Ok(())
+}
+
+fn update_daily_stats<S: Storage>(
+    storage: &mut S,
+    block: &BlockInfo,
+    update: impl FnOnce(&mut DailyStats),
+) {
+    let day: u64 = block.time / SECONDS_PER_DAY;
+    let mut daily_stats_storage = DailyStatsStorage::from_storage(storage);
+    let mut daily_stats: DailyStats =
+        daily_stats_storage
+            .get_daily_stats(day)
+            .unwrap_or(DailyStats {
+                day,
+                registrations: 0,
+                renewals: 0,
+                destroys: 0,
+            });
+    update(&mut daily_stats);
+    daily_stats_storage.set_daily_stats(&daily_stats);
+}
+
+fn update_metrics<S: Storage>(storage: &mut S, update: impl FnOnce(&mut Metrics)) -> StdResult<()> {
+    let mut metrics_store = TypedStoreMut::attach(storage);
+    let mut metrics: Metrics = metrics_store.may_load(METRICS_KEY)?.unwrap_or_default();
+    update(&mut metrics);
+    metrics_store.store(METRICS_KEY, &metrics)
+}
+
+// Adds a version when the alias's owner or target has changed since its latest version, so
+// integrations can tell what an alias used to resolve to
+fn record_alias_version<S: Storage, A: Api>(
+    storage: &mut S,
+    api: &A,
+    block: &BlockInfo,
+    alias_string: &str,
+) -> StdResult<()> {
+    let (owner, target): (Option<CanonicalAddr>, Option<String>) =
+        match RegistryReadonlyStorage::from_storage(storage, api).get_alias(alias_string.as_bytes())
+        {
+            Some(alias_object) => (Some(alias_object.owner), alias_object.target),
+            None => (None, None),
+        };
+    let config: Config = TypedStore::attach(&*storage).load(CONFIG_KEY)?;
+    let mut alias_versions_storage = AliasVersionsStorage::from_storage(storage);
+    let mut versions: Vec<StoredAliasVersion> =
+        alias_versions_storage.get_versions(alias_string.as_bytes());
+    let version: u64 = match versions.last() {
+        Some(latest) if latest.owner == owner && latest.target == target => return Ok(()),
+        // A name left behind by a rename has no history to add to
+        None if owner.is_none() => return Ok(()),
+        Some(latest) => latest.version + 1,
+        None => 1,
+    };
+    versions.push(StoredAliasVersion {
+        version,
+        owner,
+        target,
+        block_height: block.height,
+    });
+    let excess: usize = versions
+        .len()
+        .saturating_sub(usize::from(config.alias_versions_kept));
+    versions.drain(..excess);
+    alias_versions_storage.set_versions(alias_string.as_bytes(), &versions);
+
+    Ok(())
+}
+
+// Removes up to MAX_CHANGES_TO_PRUNE of the oldest changes beyond the configured log size
+fn prune_changes<S: Storage>(storage: &mut S, latest_seq: u64) -> StdResult<()> {
+    let config: Config = TypedStore::attach(&*storage).load(CONFIG_KEY)?;
+    let change_log_size: u64 = match config.change_log_size {
+        Some(change_log_size) => change_log_size,
+        None => return Ok(()),
+    };
+    let start_seq: u64 = change_log_start(&*storage)?;
+    let new_start_seq: u64 = (latest_seq + 1)
+        .saturating_sub(change_log_size)
+        .min(start_seq + MAX_CHANGES_TO_PRUNE);
+    if new_start_seq <= start_seq {
+        return Ok(());
+    }
+
+    let mut changes_storage = ChangesStorage::from_storage(storage);
+    for seq in start_seq..new_start_seq {
+        changes_storage.remove_change(seq);
+    }
+    TypedStoreMut::attach(storage).store(CHANGE_LOG_START_KEY, &new_start_seq)
+}
+
+// The earliest change that hasn't been pruned
+fn change_log_start<S: Storage>(storage: &S) -> StdResult<u64> {
+    Ok(TypedStore::attach(storage)
+        .may_load(CHANGE_LOG_START_KEY)?
+        .unwrap_or(1))
+}
+
+// The checksum is the XOR of sha256(alias | 0 | address) over every registered alias,
+// so toggling an entry in or out keeps it independent of the order of mutations.
+fn toggle_checksum_entry<S: Storage>(
+    storage: &mut S,
+    alias: &str,
+    address: &HumanAddr,
+) -> StdResult<()> {
+    let entry_hash: [u8; 32] = checksum_entry(alias, address);
+    let mut checksum_store = TypedStoreMut::attach(storage);
+    let mut checksum: [u8; 32] = checksum_store.may_load(CHECKSUM_KEY)?.unwrap_or([0; 32]);
+    for (byte, entry_byte) in checksum.iter_mut().zip(entry_hash.iter()) {
+        *byte ^= entry_byte;
+    }
+    checksum_store.store(CHECKSUM_KEY, &checksum)
+}
+
+fn checksum_entry(alias: &str, address: &HumanAddr) -> [u8; 32] {
+    sha_256(&[alias.as_bytes(), &[0], address.0.as_bytes()].concat())
+}
+
+// The registry checksum taken over just the aliases in an export chunk
+fn chunk_checksum(aliases: &[AliasAttributes]) -> [u8; 32] {
+    let mut checksum: [u8; 32] = [0; 32];
+    for alias_attributes in aliases {
+        let entry_hash: [u8; 32] =
+            checksum_entry(&alias_attributes.alias, &alias_attributes.address);
+        for (byte, entry_byte) in checksum.iter_mut().zip(entry_hash.iter()) {
+            *byte ^= entry_byte;
+        }
+    }
+
+    checksum
+}
+
+pub fn query<S: Storage, A: Api, Q: Querier>(deps: &Extern<S, A, Q>, msg: QueryMsg) -> QueryResult {
+    let response = match msg {
+        QueryMsg::AliasAtVersion { alias, version } => query_alias_at_version(deps, alias, version),
+        QueryMsg::Analytics { start_day, end_day } => query_analytics(deps, start_day, end_day),
+        QueryMsg::ChangedSince { height, page } => query_changed_since(deps, height, page),
+        QueryMsg::Changes { start_seq, end_seq } => query_changes(deps, start_seq, end_seq),
+        QueryMsg::ChangesSince { seq, limit } => query_changes_since(deps, seq, limit),
+        QueryMsg::Checksum {} => query_checksum(deps),
+        QueryMsg::Config {} => query_config(deps),
+        QueryMsg::DidDocument { alias } => query_did_document(deps, alias),
+        QueryMsg::Drop { alias } => query_drop(deps, alias),
+        QueryMsg::Escrow { alias } => query_escrow(deps, alias),
+        QueryMsg::ExpiringWithin {
+            height,
+            blocks,
+            page,
+        } => query_expiring_within(deps, height, blocks, page),
+        QueryMsg::ExportChunk {
+            start_seq,
+            viewing_key,
+        } => query_export_chunk(deps, start_seq, viewing_key),
+        QueryMsg::Followers {
+            alias,
+            page,
+            viewing_key,
+        } => query_follows(deps, alias, page, viewing_key, true),
+        QueryMsg::Following {
+            alias,
+            page,
+            viewing_key,
+        } => query_follows(deps, alias, page, viewing_key, false),
+        QueryMsg::History { alias } => query_history(deps, alias),
+        QueryMsg::Metrics { viewing_key } => query_metrics(deps, viewing_key),
+        QueryMsg::Nonce { address } => query_nonce(deps, address),
+        QueryMsg::Offers { alias } => query_offers(deps, alias),
+        QueryMsg::Note {
+            alias,
+            address,
+            viewing_key,
+        } => query_note(deps, alias, address, viewing_key),
+        QueryMsg::PaymentUri { alias, amount } => query_payment_uri(deps, alias, amount),
+        QueryMsg::PendingAction { alias } => query_pending_action(deps, alias),
+        QueryMsg::Quote {
+            alias,
+            height,
+            periods,
+        } => query_quote(deps, alias, height, periods),
+        QueryMsg::QueuedActions {} => query_queued_actions(deps),
+        QueryMsg::Recent { limit } => query_recent(deps, limit),
+        QueryMsg::Reclaim { alias } => query_reclaim(deps, alias),
+        QueryMsg::ResolveAddresses { addresses } => query_resolve_addresses(deps, addresses),
+        QueryMsg::ResolveCanonicalAddresses { addresses } => {
+            query_resolve_canonical_addresses(deps, addresses)
+        }
+        QueryMsg::Roles {} => query_roles(deps),
+        QueryMsg::Search {
+            search_type,
+            search_value,
+            include_metadata,
+            viewing_key,
+        } => query_search(
+            deps,
+            search_type,
+            search_value,
+            include_metadata.unwrap_or(false),
+            viewing_key,
+        ),
+        QueryMsg::Status {} => query_status(deps),
+        QueryMsg::TipJar { alias, viewing_key } => query_tip_jar(deps, alias, viewing_key),
+        QueryMsg::Validate { alias } => query_validate(deps, alias),
+        QueryMsg::VerifySignIn {
+            alias,
+            challenge,
+            signature,
+        } => query_verify_sign_in(deps, alias, challenge, signature),
+    };
+    pad_query_result(
+        response.map_err(query_error),
+        response_block_size(&deps.storage)?,
+    )
+}
+
+// Pad responses so that their length doesn't reveal which operation ran or what it found
+fn response_block_size<S: Storage>(storage: &S) -> StdResult<usize> {
+    let config: Config = TypedStore::attach(storage).load(CONFIG_KEY)?;
+    Ok(config.response_block_size as usize)
+}
+
+// Wrap a failed query in a machine readable QueryError envelope
+fn query_error(error: StdError) -> StdError {
+    let (code, target, message) = match error {
+        StdError::GenericErr { msg, .. } => (QueryErrorCode::InvalidRequest, None, msg),
+        StdError::NotFound { kind, .. } => {
+            let message = format!("{} not found", kind);
+            (QueryErrorCode::NotFound, Some(kind), message)
+        }
+        StdError::ParseErr { target, msg, .. } => {
+            (QueryErrorCode::InvalidParameter, Some(target), msg)
+        }
+        StdError::Unauthorized { .. } => (
+            QueryErrorCode::Unauthorized,
+            None,
+            "Unauthorized".to_string(),
+        ),
+        error => (QueryErrorCode::InternalError, None, error.to_string()),
+    };
+    match to_vec(&QueryError {
+        code,
+        details: QueryErrorDetails { target, message },
+    }) {
+        Ok(json) => StdError::generic_err(String::from_utf8_lossy(&json)),
+        Err(error) => error,
+    }
+}
+
+fn query_alias_at_version<S: Storage, A: Api, Q: Querier>(
+    deps: &Extern<S, A, Q>,
+    alias_string: String,
+    version: u64,
+) -> QueryResult {
+    let alias_string = normalize_alias(&deps.storage, &alias_string)?;
+    let versions: Vec<StoredAliasVersion> =
+        AliasVersionsReadonlyStorage::from_storage(&deps.storage)
+            .get_versions(alias_string.as_bytes());
+    let latest_version: u64 = match versions.last() {
+        Some(latest) => latest.version,
+        None => return Err(StdError::not_found("Alias version")),
+    };
+    let alias_version: AliasVersion = versions
+        .into_iter()
+        .find(|alias_version| alias_version.version == version)
+        .ok_or_else(|| StdError::not_found("Alias version"))?
+        .into_humanized(&deps.api)?;
+
+    to_binary(&QueryAnswer::AliasAtVersion {
+        alias_version,
+        latest_version,
+    })
+}
+
+fn query_analytics<S: Storage, A: Api, Q: Querier>(
+    deps: &Extern<S, A, Q>,
+    start_day: u64,
+    end_day: u64,
+) -> QueryResult {
+    if end_day < start_day {
+        return Err(StdError::parse_err(
+            "end_day",
+            "must not be before start_day.",
+        ));
+    }
+    if end_day - start_day >= MAX_DAYS_PER_QUERY {
+        return Err(StdError::generic_err(format!(
+            "Too many days. Maximum is {}.",
+            MAX_DAYS_PER_QUERY
+        )));
+    }
+
+    // Days without any registrations, renewals or destroys are left out
+    let daily_stats_storage = DailyStatsReadonlyStorage::from_storage(&deps.storage);
+    let daily_stats: Vec<DailyStats> = (start_day..=end_day)
+        .filter_map(|day| daily_stats_storage.get_daily_stats(day))
+        .collect();
+
+    to_binary(&QueryAnswer::Analytics { daily_stats })
+}
+
+// Each page covers CHANGED_SINCE_PAGE_SIZE changes, so an alias changed again on a later page is
+// listed again
+fn query_changed_since<S: Storage, A: Api, Q: Querier>(
+    deps: &Extern<S, A, Q>,
+    height: u64,
+    page: u32,
+) -> QueryResult {
+    let latest_seq: u64 = TypedStore::attach(&deps.storage)
+        .may_load(CHANGE_SEQ_KEY)?
+        .unwrap_or(0);
+    let changes_storage = ChangesReadonlyStorage::from_storage(&deps.storage);
+    // Changes are recorded in block order, so search for the first one after height
+    let mut first_seq: u64 = 1;
+    let mut end_seq: u64 = latest_seq + 1;
+    while first_seq < end_seq {
+        let seq: u64 = first_seq + (end_seq - first_seq) / 2;
+        match changes_storage.get_change(seq) {
+            Some(change) if change.block_height > height => end_seq = seq,
+            _ => first_seq = seq + 1,
+        }
+    }
+    let start_seq: u64 =
+        first_seq.saturating_add(u64::from(page).saturating_mul(CHANGED_SINCE_PAGE_SIZE));
+    let end_seq: u64 = start_seq
+        .saturating_add(CHANGED_SINCE_PAGE_SIZE)
+        .min(latest_seq + 1);
+    let alias_storage = RegistryReadonlyStorage::from_storage(&deps.storage, &deps.api);
+    let mut aliases: Vec<ChangedAlias> = vec![];
+    for seq in start_seq..end_seq {
+        if let Some(change) = changes_storage.get_change(seq) {
+            if aliases.iter().any(|alias| alias.alias == change.alias) {
+                continue;
+            }
+            let address: Option<HumanAddr> = match alias_storage.get_alias(change.alias.as_bytes())
+            {
+                Some(alias_object) => Some(deps.api.human_address(&alias_object.owner)?),
+                None => None,
+            };
+            aliases.push(ChangedAlias {
+                alias: change.alias,
+                address,
+            });
+        }
+    }
+    let next_page: Option<u32> = if end_seq <= latest_seq {
+        Some(page + 1)
+    } else {
+        None
+    };
+
+    to_binary(&QueryAnswer::ChangedSince { aliases, next_page })
+}
+
+fn query_changes<S: Storage, A: Api, Q: Querier>(
+    deps: &Extern<S, A, Q>,
+    start_seq: u64,
+    end_seq: u64,
+) -> QueryResult {
+    if end_seq < start_seq {
+        return Err(StdError::parse_err(
+            "end_seq",
+            "must not be before start_seq.",
+        ));
+    }
+    if end_seq - start_seq >= u64::from(MAX_CHANGES_PER_QUERY) {
+        return Err(StdError::generic_err(format!(
+            "Too many changes. Maximum is {}.",
+            MAX_CHANGES_PER_QUERY
+        )));
+    }
+
+    let earliest_seq: u64 = change_log_start(&deps.storage)?;
+    let latest_seq: u64 = TypedStore::attach(&deps.storage)
+        .may_load(CHANGE_SEQ_KEY)?
+        .unwrap_or(0);
+    let changes_storage = ChangesReadonlyStorage::from_storage(&deps.storage);
+    let changes: Vec<Change> = (start_seq..=end_seq)
+        .filter_map(|seq| changes_storage.get_change(seq))
+        .map(|change| change.into_humanized(&deps.api))
+        .collect::<StdResult<Vec<Change>>>()?;
+
+    to_binary(&QueryAnswer::Changes {
+        changes,
+        earliest_seq,
+        latest_seq,
+    })
+}
+
+fn query_changes_since<S: Storage, A: Api, Q: Querier>(
+    deps: &Extern<S, A, Q>,
+    seq: u64,
+    limit: u32,
+) -> QueryResult {
+    let latest_seq: u64 = TypedStore::attach(&deps.storage)
+        .may_load(CHANGE_SEQ_KEY)?
+        .unwrap_or(0);
+    let limit: usize = limit.min(MAX_CHANGES_PER_QUERY) as usize;
+    let changes_storage = ChangesReadonlyStorage::from_storage(&deps.storage);
+    let mut changes: Vec<Change> = vec![];
+    let mut next_seq: u64 = seq.saturating_add(1).max(change_log_start(&deps.storage)?);
+    while next_seq <= latest_seq && changes.len() < limit {
+        if let Some(change) = changes_storage.get_change(next_seq) {
+            changes.push(change.into_humanized(&deps.api)?);
+        }
+        next_seq += 1;
+    }
+
+    to_binary(&QueryAnswer::ChangesSince {
+        changes,
+        latest_seq,
+    })
+}
+
+fn query_checksum<S: Storage, A: Api, Q: Querier>(deps: &Extern<S, A, Q>) -> QueryResult {
+    let checksum: [u8; 32] = TypedStore::attach(&deps.storage)
+        .may_load(CHECKSUM_KEY)?
+        .unwrap_or([0; 32]);
+    let latest_seq: u64 = TypedStore::attach(&deps.storage)
+        .may_load(CHANGE_SEQ_KEY)?
+        .unwrap_or(0);
+
+    to_binary(&QueryAnswer::Checksum {
+        checksum: Binary::from(checksum.to_vec()),
+        latest_seq,
+    })
+}
+
+fn query_config<S: Storage, A: Api, Q: Querier>(deps: &Extern<S, A, Q>) -> QueryResult {
+    let config: Config = TypedStore::attach(&deps.storage).load(CONFIG_KEY)?;
+
+    let oracles: Vec<HumanAddr> = config
+        .oracles
+        .iter()
+        .map(|oracle| deps.api.human_address(oracle))
+        .collect::<StdResult<Vec<HumanAddr>>>()?;
+
+    to_binary(&QueryAnswer::Config {
+        admin: deps.api.human_address(&config.admin)?,
+        airdrop_merkle_root: config.airdrop_merkle_root,
+        alias_versions_kept: config.alias_versions_kept,
+        avatar_domains: config.avatar_domains,
+        batch_limits: config.batch_limits,
+        bootstrap_ends_at: config.bootstrap_ends_at,
+        buttcoin: config.buttcoin,
+        butt_lode: config.butt_lode,
+        case_folding: config.case_folding,
+        chain_id: config.chain_id,
+        change_log_size: config.change_log_size,
+        cross_chain_snapshot: config.cross_chain_snapshot,
+        discounts: config.discounts,
+        fee_splits: config.fee_splits,
+        governance: config
+            .governance
+            .map(|governance| governance.into_humanized(&deps.api))
+            .transpose()?,
+        launch: config.launch,
+        lease_terms: config.lease_terms,
+        lottery_ends_at: config.lottery_ends_at,
+        mint_phases: config.mint_phases,
+        oracles,
+        price_oracle: config.price_oracle,
+        promotions: config.promotions,
+        reclaim_terms: config.reclaim_terms,
+        require_avatar: config.require_avatar,
+        response_block_size: config.response_block_size,
+        routable_tokens: config.routable_tokens,
+        timelock_blocks: config.timelock_blocks,
+    })
+}
+
+fn query_did_document<S: Storage, A: Api, Q: Querier>(
+    deps: &Extern<S, A, Q>,
+    alias_string: String,
+) -> QueryResult {
+    let alias_string = normalize_alias(&deps.storage, &alias_string)?;
+    let alias_object: Alias = RegistryReadonlyStorage::from_storage(&deps.storage, &deps.api)
+        .get_alias(alias_string.as_bytes())
+        .ok_or_else(|| StdError::not_found("Alias"))?;
+    if hides_resolution(&deps.storage, &alias_string) {
+        return Err(StdError::not_found("Alias"));
+    }
+    let did: String = did_for_alias(&alias_string);
+    let mut controllers: Vec<CanonicalAddr> = vec![alias_object.owner];
+    if let Some(co_ownership) = CoOwnershipsReadonlyStorage::from_storage(&deps.storage)
+        .get_co_ownership(alias_string.as_bytes())
+    {
+        for co_owner in co_ownership.co_owners {
+            if !controllers.contains(&co_owner) {
+                controllers.push(co_owner);
+            }
+        }
+    }
+    let verification_method: Vec<DidVerificationMethod> = controllers
+        .iter()
+        .enumerate()
+        .map(|(i, controller)| {
+            Ok(DidVerificationMethod {
+                id: format!("{}#controller-{}", did, i),
+                r#type: "CosmosAccount".to_string(),
+                controller: did.clone(),
+                blockchain_account_id: deps.api.human_address(controller)?,
+            })
+        })
+        .collect::<StdResult<Vec<DidVerificationMethod>>>()?;
+    let service: Vec<DidService> = RecordsReadonlyStorage::from_storage(&deps.storage)
+        .get_records(alias_string.as_bytes())
+        .into_iter()
+        .filter(|record| url_host(&record.value).is_some())
+        .map(|record| DidService {
+            id: format!("{}#{}", did, percent_encode(&record.key)),
+            r#type: record.key,
+            service_endpoint: record.value.trim().to_string(),
+        })
+        .collect();
+
+    to_binary(&QueryAnswer::DidDocument {
+        did_document: DidDocument {
+            context: vec![DID_CONTEXT.to_string()],
+            id: did,
+            authentication: verification_method
+                .iter()
+                .map(|method| method.id.clone())
+                .collect(),
+            verification_method,
+            service,
+        },
+    })
+}
+
+fn did_for_alias(alias_string: &str) -> String {
+    format!("{}{}", DID_METHOD_PREFIX, percent_encode(alias_string))
+}
+
+// DIDs and uris only allow unreserved characters unescaped, so the rest are percent encoded
+fn percent_encode(string: &str) -> String {
+    string
+        .bytes()
+        .map(|byte| {
+            if byte.is_ascii_alphanumeric() || byte == b'.' || byte == b'-' || byte == b'_' {
+                (byte as char).to_string()
+            } else {
+                format!("%{:02X}", byte)
+            }
+        })
+        .collect()
+}
+
+fn query_drop<S: Storage, A: Api, Q: Querier>(
+    deps: &Extern<S, A, Q>,
+    alias_string: String,
+) -> QueryResult {
+    let alias_string = normalize_alias(&deps.storage, &alias_string)?;
+    let drop: Option<Drop> =
+        DropsReadonlyStorage::from_storage(&deps.storage).get_drop(alias_string.as_bytes());
+
+    to_binary(&QueryAnswer::Drop { drop })
+}
+
+fn query_escrow<S: Storage, A: Api, Q: Querier>(
+    deps: &Extern<S, A, Q>,
+    alias_string: String,
+) -> QueryResult {
+    let alias_string = normalize_alias(&deps.storage, &alias_string)?;
+    if RegistryReadonlyStorage::from_storage(&deps.storage, &deps.api)
+        .get_alias(alias_string.as_bytes())
+        .is_none()
+    {
+        return Err(StdError::not_found("Alias"));
+    }
+    let escrow: Option<Escrow> = EscrowsReadonlyStorage::from_storage(&deps.storage)
+        .get_escrow(alias_string.as_bytes())
+        .map(|escrow| escrow.into_humanized(&deps.api))
+        .transpose()?;
+
+    to_binary(&QueryAnswer::Escrow { escrow })
+}
+
+fn query_metrics<S: Storage, A: Api, Q: Querier>(
+    deps: &Extern<S, A, Q>,
+    viewing_key: String,
+) -> QueryResult {
+    let config: Config = TypedStore::attach(&deps.storage).load(CONFIG_KEY)?;
+    if !holds_viewing_key(&deps.storage, &config.admin, Some(viewing_key)) {
+        return Err(StdError::Unauthorized { backtrace: None });
+    }
+    let metrics: Metrics = TypedStore::attach(&deps.storage)
+        .may_load(METRICS_KEY)?
+        .unwrap_or_default();
+
+    to_binary(&QueryAnswer::Metrics { metrics })
+}
+
+// Aliases hidden by a suspension are left out of the list
+fn query_expiring_within<S: Storage, A: Api, Q: Querier>(
+    deps: &Extern<S, A, Q>,
+    height: u64,
+    blocks: u64,
+    page: u32,
+) -> QueryResult {
+    let ends_at: u64 = height.saturating_add(blocks);
+    let leases_storage = LeasesReadonlyStorage::from_storage(&deps.storage);
+    let mut aliases: Vec<ExpiringAlias> = vec![];
+    for alias in leases_storage.get_leased_aliases() {
+        if hides_resolution(&deps.storage, &alias) {
+            continue;
+        }
+        if let Some(lease) = leases_storage.get_lease(alias.as_bytes()) {
+            if height < lease.paid_until && lease.paid_until <= ends_at {
+                aliases.push(ExpiringAlias {
+                    alias,
+                    expires_at: lease.paid_until,
+                });
+            }
+        }
+    }
+    aliases.sort_by_key(|expiring_alias| expiring_alias.expires_at);
+    let start: usize = (page as usize).saturating_mul(EXPIRING_WITHIN_PAGE_SIZE);
+    let end: usize = start.saturating_add(EXPIRING_WITHIN_PAGE_SIZE);
+    let next_page: Option<u32> = if end < aliases.len() {
+        Some(page + 1)
+    } else {
+        None
+    };
+    let aliases: Vec<ExpiringAlias> = aliases
+        .into_iter()
+        .skip(start)
+        .take(EXPIRING_WITHIN_PAGE_SIZE)
+        .collect();
+
+    to_binary(&QueryAnswer::ExpiringWithin { aliases, next_page })
+}
+
+// Every alias has a change, so walking the whole change log exports the whole registry. That needs
+// the change log to be complete.
+fn query_export_chunk<S: Storage, A: Api, Q: Querier>(
+    deps: &Extern<S, A, Q>,
+    start_seq: u64,
+    viewing_key: String,
+) -> QueryResult {
+    let config: Config = TypedStore::attach(&deps.storage).load(CONFIG_KEY)?;
+    if !holds_viewing_key(&deps.storage, &config.admin, Some(viewing_key)) {
+        return Err(StdError::Unauthorized { backtrace: None });
+    }
+    if change_log_start(&deps.storage)? > 1 {
+        return Err(StdError::generic_err(
+            "The change log has been pruned, so the registry can't be exported",
+        ));
+    }
+    let latest_seq: u64 = TypedStore::attach(&deps.storage)
+        .may_load(CHANGE_SEQ_KEY)?
+        .unwrap_or(0);
+
+    let start_seq: u64 = start_seq.max(1);
+    let end_seq: u64 = start_seq
+        .saturating_add(EXPORT_CHUNK_SIZE)
+        .min(latest_seq + 1);
+    let alias_storage = RegistryReadonlyStorage::from_storage(&deps.storage, &deps.api);
+    let changes_storage = ChangesReadonlyStorage::from_storage(&deps.storage);
+    let mut aliases: Vec<AliasAttributes> = vec![];
+    for seq in start_seq..end_seq {
+        if let Some(change) = changes_storage.get_change(seq) {
+            if aliases.iter().any(|alias| alias.alias == change.alias) {
+                continue;
+            }
+            if let Some(alias_object) = alias_storage.get_alias(change.alias.as_bytes()) {
+                aliases.push(AliasAttributes {
+                    alias: alias_object.alias,
+                    display_name: Some(alias_object.display_name),
+                    avatar_url: alias_object.avatar_url,
+                    avatar_metadata: alias_object.avatar_metadata,
+                    address: deps.api.human_address(&alias_object.owner)?,
+                });
+            }
+        }
+    }
+    let next_seq: Option<u64> = if end_seq <= latest_seq {
+        Some(end_seq)
+    } else {
+        None
+    };
+    let registry_checksum: [u8; 32] = TypedStore::attach(&deps.storage)
+        .may_load(CHECKSUM_KEY)?
+        .unwrap_or([0; 32]);
+    let alias_count: u64 = TypedStore::attach(&deps.storage)
+        .may_load(ALIAS_COUNT_KEY)?
+        .unwrap_or(0);
+
+    to_binary(&QueryAnswer::ExportChunk {
+        checksum: Binary::from(chunk_checksum(&aliases).to_vec()),
+        aliases,
+        next_seq,
+        alias_count,
+        registry_checksum: Binary::from(registry_checksum.to_vec()),
+    })
+}
+
+// Addresses without an alias and addresses with private follows are left out of the list
+fn query_follows<S: Storage, A: Api, Q: Querier>(
+    deps: &Extern<S, A, Q>,
+    alias_string: String,
+    page: u32,
+    viewing_key: Option<String>,
+    followers: bool,
+) -> QueryResult {
+    let alias_string = normalize_alias(&deps.storage, &alias_string)?;
+    let alias_storage = RegistryReadonlyStorage::from_storage(&deps.storage, &deps.api);
+    let owner: CanonicalAddr = match alias_storage.get_alias(alias_string.as_bytes()) {
+        Some(alias_object) => alias_object.owner,
+        None => return Err(StdError::not_found("Alias")),
+    };
+    let follows_storage = FollowsReadonlyStorage::from_storage(&deps.storage);
+    let follows: Follows = follows_storage.get_follows(&owner);
+    if follows.private && !holds_viewing_key(&deps.storage, &owner, viewing_key) {
+        return Err(StdError::Unauthorized { backtrace: None });
+    }
+
+    let addresses: Vec<CanonicalAddr> = if followers {
+        follows.followers
+    } else {
+        follows.following
+    };
+    let mut aliases: Vec<String> = vec![];
+    for address in addresses {
+        if follows_storage.get_follows(&address).private {
+            continue;
+        }
+        if let Some(alias_object) = alias_storage.get_alias_for_address(&address) {
+            aliases.push(alias_object.alias);
+        }
+    }
+    let start: usize = (page as usize).saturating_mul(FOLLOWS_PAGE_SIZE);
+    let end: usize = start.saturating_add(FOLLOWS_PAGE_SIZE);
+    let next_page: Option<u32> = if end < aliases.len() {
+        Some(page + 1)
+    } else {
+        None
+    };
+    let aliases: Vec<String> = aliases
+        .into_iter()
+        .skip(start)
+        .take(FOLLOWS_PAGE_SIZE)
+        .collect();
+
+    if followers {
+        to_binary(&QueryAnswer::Followers { aliases, next_page })
+    } else {
+        to_binary(&QueryAnswer::Following { aliases, next_page })
+    }
+}
+
+fn query_history<S: Storage, A: Api, Q: Querier>(
+    deps: &Extern<S, A, Q>,
+    alias_string: String,
+) -> QueryResult {
+    let alias_string = normalize_alias(&deps.storage, &alias_string)?;
+    let registered: bool = RegistryReadonlyStorage::from_storage(&deps.storage, &deps.api)
+        .get_alias(alias_string.as_bytes())
+        .is_some();
+    let last_released_at: Option<u64> = ReleasesReadonlyStorage::from_storage(&deps.storage)
+        .get_released_at(alias_string.as_bytes());
+    let renounced: bool = RenouncedAliasesReadonlyStorage::from_storage(&deps.storage)
+        .is_renounced(alias_string.as_bytes());
+
+    to_binary(&QueryAnswer::History {
+        alias: alias_string,
+        registered,
+        last_released_at,
+        renounced,
+    })
+}
+
+fn query_nonce<S: Storage, A: Api, Q: Querier>(
+    deps: &Extern<S, A, Q>,
+    address: HumanAddr,
+) -> QueryResult {
+    let nonce: u64 = NoncesReadonlyStorage::from_storage(&deps.storage)
+        .get_nonce(&deps.api.canonical_address(&address)?);
+
+    to_binary(&QueryAnswer::Nonce { nonce })
+}
+
+fn query_note<S: Storage, A: Api, Q: Querier>(
+    deps: &Extern<S, A, Q>,
+    alias_string: String,
+    address: Option<HumanAddr>,
+    viewing_key: String,
+) -> QueryResult {
+    let alias_string = normalize_alias(&deps.storage, &alias_string)?;
+    let alias_object: Option<Alias> =
+        RegistryReadonlyStorage::from_storage(&deps.storage, &deps.api)
+            .get_alias(alias_string.as_bytes());
+    if alias_object.is_none() {
+        return Err(StdError::not_found("Alias"));
+    }
+    let alias_object: Alias = alias_object.unwrap();
+    let note: Option<Note> =
+        NotesReadonlyStorage::from_storage(&deps.storage).get_note(alias_string.as_bytes());
+    // Answer not found rather than unauthorized so a wrong key doesn't confirm the note exists
+    if note.is_none() {
+        return Err(StdError::not_found("Note"));
+    }
+    let note: Note = note.unwrap();
+    let authorized: bool = match address {
+        Some(address) => match deps.api.canonical_address(&address) {
+            Ok(address) => {
+                (address == alias_object.owner || note.viewers.contains(&address))
+                    && holds_viewing_key(&deps.storage, &address, Some(viewing_key))
+            }
+            Err(_) => false,
+        },
+        None => holds_alias_viewing_key(&deps.storage, &alias_string, Some(viewing_key)),
+    };
+    if !authorized {
+        return Err(StdError::not_found("Note"));
+    }
+
+    to_binary(&QueryAnswer::Note { note: note.text })
+}
+
+// Offers are kept under the alias they were made for, even after it is renamed or destroyed
+fn query_offers<S: Storage, A: Api, Q: Querier>(
+    deps: &Extern<S, A, Q>,
+    alias_string: String,
+) -> QueryResult {
+    let alias_string = normalize_alias(&deps.storage, &alias_string)?;
+    let offers: Vec<Offer> = OffersReadonlyStorage::from_storage(&deps.storage)
+        .get_offers(alias_string.as_bytes())
+        .into_iter()
+        .map(|offer| offer.into_humanized(&deps.api))
+        .collect::<StdResult<Vec<Offer>>>()?;
+
+    to_binary(&QueryAnswer::Offers { offers })
+}
+
+fn query_payment_uri<S: Storage, A: Api, Q: Querier>(
+    deps: &Extern<S, A, Q>,
+    alias_string: String,
+    amount: Option<Uint128>,
+) -> QueryResult {
+    let config: Config = TypedStore::attach(&deps.storage).load(CONFIG_KEY)?;
+    let alias_string = normalize_alias(&deps.storage, &alias_string)?;
+    let alias_object: Alias = RegistryReadonlyStorage::from_storage(&deps.storage, &deps.api)
+        .get_alias(alias_string.as_bytes())
+        .ok_or_else(|| StdError::not_found("Alias"))?;
+    let resolved_alias: Alias = resolve_alias_target(&deps.storage, &deps.api, alias_object)?;
+    let payment_preference: Option<PaymentPreference> =
+        PaymentPreferencesReadonlyStorage::from_storage(&deps.storage)
+            .get_payment_preference(resolved_alias.alias.as_bytes())
+            .map(|payment_preference| payment_preference.into_humanized(&deps.api))
+            .transpose()?;
+    let address: HumanAddr = match payment_preference
+        .as_ref()
+        .and_then(|payment_preference| payment_preference.receiving_address.clone())
+    {
+        Some(receiving_address) => receiving_address,
+        None => deps.api.human_address(&resolved_alias.owner)?,
+    };
+
+    let mut uri: String = format!(
+        "secret:{}@{}?alias={}",
+        address,
+        config.chain_id,
+        percent_encode(&alias_string)
+    );
+    if let Some(amount) = amount {
+        uri.push_str(&format!("&amount={}", amount));
+    }
+    if let Some(payment_preference) = payment_preference {
+        match payment_preference.asset {
+            Some(Asset::Native { denom }) => {
+                uri.push_str(&format!("&denom={}", percent_encode(&denom)))
+            }
+            Some(Asset::Snip20 { token }) => uri.push_str(&format!("&token={}", token.address)),
+            None => {}
+        }
+        if payment_preference.memo_required {
+            uri.push_str("&memo_required=true");
+        }
+    }
+
+    to_binary(&QueryAnswer::PaymentUri {
+        uri,
+        address,
+        chain_id: config.chain_id,
+    })
+}
+
+fn query_pending_action<S: Storage, A: Api, Q: Querier>(
+    deps: &Extern<S, A, Q>,
+    alias_string: String,
+) -> QueryResult {
+    let alias_string = normalize_alias(&deps.storage, &alias_string)?;
+    if RegistryReadonlyStorage::from_storage(&deps.storage, &deps.api)
+        .get_alias(alias_string.as_bytes())
+        .is_none()
+    {
+        return Err(StdError::not_found("Alias"));
+    }
+    let pending_action: Option<PendingAction> =
+        PendingActionsReadonlyStorage::from_storage(&deps.storage)
+            .get_pending_action(alias_string.as_bytes())
+            .map(|pending_action| pending_action.into_humanized(&deps.api))
+            .transpose()?;
+
+    to_binary(&QueryAnswer::PendingAction { pending_action })
+}
+
+// The fee for creating or leasing the alias, checked the same way Create checks it
+fn query_quote<S: Storage, A: Api, Q: Querier>(
+    deps: &Extern<S, A, Q>,
+    alias_string: String,
+    height: Option<u64>,
+    periods: Option<u16>,
+) -> QueryResult {
+    let config: Config = TypedStore::attach(&deps.storage).load(CONFIG_KEY)?;
+    let alias_string = normalize_alias(&deps.storage, &alias_string)?;
+    validate_alias_string(&alias_string)?;
+    ensure_alias_available(&deps.storage, &deps.api, &alias_string)?;
+    // Leases are charged at the lease terms, without promotions
+    if let Some(periods) = periods {
+        let lease_terms: LeaseTerms = config
+            .lease_terms
+            .ok_or_else(|| StdError::generic_err("Aliases can't be leased"))?;
+        let (amount, blocks): (Uint128, u64) =
+            lease_cost(lease_terms.fee, lease_terms.period_blocks, periods)?;
+        return to_binary(&QueryAnswer::Quote {
+            alias: alias_string,
+            amount,
+            promotion: None,
+            token: config.buttcoin,
+            blocks: Some(blocks),
+        });
+    }
+    let fee: Uint128 = fee_amount(&deps.querier, &config, None)?;
+    let (amount, promotion): (Uint128, Option<Promotion>) = match height {
+        Some(height) => (
+            promotional_fee(&config, fee, height),
+            active_promotion(&config, height).cloned(),
+        ),
+        None => (fee, None),
+    };
+
+    to_binary(&QueryAnswer::Quote {
+        alias: alias_string,
+        amount,
+        promotion,
+        token: config.buttcoin,
+        blocks: None,
+    })
+}
+
+fn query_queued_actions<S: Storage, A: Api, Q: Querier>(deps: &Extern<S, A, Q>) -> QueryResult {
+    let config: Config = TypedStore::attach(&deps.storage).load(CONFIG_KEY)?;
+    let queued_actions: Vec<StoredQueuedAction> = TypedStore::attach(&deps.storage)
+        .may_load(QUEUED_ACTIONS_KEY)?
+        .unwrap_or_default();
+
+    to_binary(&QueryAnswer::QueuedActions {
+        queued_actions: queued_actions
+            .into_iter()
+            .map(|queued_action| {
+                Ok(QueuedAction {
+                    id: queued_action.id,
+                    msg: from_binary(&queued_action.msg)?,
+                    queued_by: deps.api.human_address(&queued_action.queued_by)?,
+                    queued_at: queued_action.queued_at,
+                    executable_at: queued_action.executable_at,
+                })
+            })
+            .collect::<StdResult<Vec<QueuedAction>>>()?,
+        timelock_blocks: config.timelock_blocks,
+    })
+}
+
+// Walk the change log backwards, keeping creates whose alias is still held by the same owner
+fn query_recent<S: Storage, A: Api, Q: Querier>(deps: &Extern<S, A, Q>, limit: u32) -> QueryResult {
+    let latest_seq: u64 = TypedStore::attach(&deps.storage)
+        .may_load(CHANGE_SEQ_KEY)?
+        .unwrap_or(0);
+    let limit: usize = limit.min(MAX_RECENT_ALIASES) as usize;
+    let alias_storage = RegistryReadonlyStorage::from_storage(&deps.storage, &deps.api);
+    let changes_storage = ChangesReadonlyStorage::from_storage(&deps.storage);
+    let mut aliases: Vec<AliasAttributes> = vec![];
+    let mut seq: u64 = latest_seq;
+    while seq > 0 && latest_seq - seq < MAX_CHANGES_TO_SCAN && aliases.len() < limit {
+        if let Some(change) = changes_storage.get_change(seq) {
+            if change.action == ChangeAction::Create
+                && !aliases.iter().any(|alias| alias.alias == change.alias)
+            {
+                if let Some(alias_object) = alias_storage.get_alias(change.alias.as_bytes()) {
+                    if alias_object.owner == change.address
+                        && alias_object.created_at == change.block_height
+                        && !hides_resolution(&deps.storage, &change.alias)
+                    {
+                        aliases.push(AliasAttributes {
+                            alias: change.alias,
+                            display_name: Some(alias_object.display_name),
+                            avatar_url: alias_object.avatar_url,
+                            avatar_metadata: alias_object.avatar_metadata,
+                            address: deps.api.human_address(&alias_object.owner)?,
+                        });
+                    }
+                }
+            }
+        }
+        seq -= 1;
+    }
+
+    to_binary(&QueryAnswer::Recent { aliases })
+}
+
+fn query_reclaim<S: Storage, A: Api, Q: Querier>(
+    deps: &Extern<S, A, Q>,
+    alias_string: String,
+) -> QueryResult {
+    let alias_string = normalize_alias(&deps.storage, &alias_string)?;
+    let reclaim: Option<Reclaim> = ReclaimsReadonlyStorage::from_storage(&deps.storage)
+        .get_reclaim(alias_string.as_bytes())
+        .map(|reclaim| reclaim.into_humanized(&deps.api))
+        .transpose()?;
+
+    to_binary(&QueryAnswer::Reclaim { reclaim })
+}
+
+fn query_resolve_addresses<S: Storage, A: Api, Q: Querier>(
+    deps: &Extern<S, A, Q>,
+    addresses: Vec<HumanAddr>,
+) -> QueryResult {
+    let config: Config = TypedStore::attach(&deps.storage).load(CONFIG_KEY)?;
+    validate_address_batch(config.batch_limits.resolve_addresses, addresses.len())?;
+
+    // Each address gets its own result so one bad address doesn't fail the rest
+    let alias_storage = RegistryReadonlyStorage::from_storage(&deps.storage, &deps.api);
+    let mut resolved_addresses: Vec<ResolvedAddress> = vec![];
+    for address in addresses {
+        let result: ResolveResult = match deps.api.canonical_address(&address) {
+            Ok(canonical_address) => {
+                resolve_address(&deps.storage, &alias_storage, &canonical_address)
+            }
+            Err(_) => ResolveResult::Invalid,
+        };
+        resolved_addresses.push(ResolvedAddress { address, result });
+    }
+
+    to_binary(&QueryAnswer::ResolveAddresses {
+        addresses: resolved_addresses,
+    })
+}
+
+fn query_resolve_canonical_addresses<S: Storage, A: Api, Q: Querier>(
+    deps: &Extern<S, A, Q>,
+    addresses: Vec<Binary>,
+) -> QueryResult {
+    let config: Config = TypedStore::attach(&deps.storage).load(CONFIG_KEY)?;
+    validate_address_batch(
+        config.batch_limits.resolve_canonical_addresses,
+        addresses.len(),
+    )?;
+
+    let alias_storage = RegistryReadonlyStorage::from_storage(&deps.storage, &deps.api);
+    let mut resolved_addresses: Vec<ResolvedCanonicalAddress> = vec![];
+    for address in addresses {
+        let result: ResolveResult = if address.is_empty() {
+            ResolveResult::Invalid
+        } else {
+            resolve_address(
+                &deps.storage,
+                &alias_storage,
+                &CanonicalAddr(address.clone()),
+            )
+        };
+        resolved_addresses.push(ResolvedCanonicalAddress { address, result });
+    }
+
+    to_binary(&QueryAnswer::ResolveCanonicalAddresses {
+        addresses: resolved_addresses,
+    })
+}
+
+fn validate_address_batch(batch_limit: u16, address_count: usize) -> StdResult<()> {
+    if address_count > batch_limit as usize {
+        return Err(StdError::generic_err(format!(
+            "Too many addresses. Maximum is {}.",
+            batch_limit
+        )));
+    }
+
+    Ok(())
+}
+
+// Aliases with private reverse lookup are reported the same as addresses without one
+fn resolve_address<S: Storage, A: Api>(
+    storage: &S,
+    alias_storage: &RegistryReadonlyStorage<S, A>,
+    address: &CanonicalAddr,
+) -> ResolveResult {
+    match alias_storage.get_alias_for_address(address) {
+        Some(alias_object)
+            if !alias_object.private_reverse_lookup
+                && !hides_resolution(storage, &alias_object.alias) =>
+        {
+            ResolveResult::Found {
+                alias: alias_object.alias,
+                display_name: alias_object.display_name,
+            }
+        }
+        _ => ResolveResult::NotFound,
+    }
+}
+
+fn query_roles<S: Storage, A: Api, Q: Querier>(deps: &Extern<S, A, Q>) -> QueryResult {
+    let config: Config = TypedStore::attach(&deps.storage).load(CONFIG_KEY)?;
+    let mut admin_grants: Vec<AdminGrant> = vec![AdminGrant {
+        address: deps.api.human_address(&config.admin)?,
+        role: AdminRole::SuperAdmin,
+    }];
+    for admin_grant in config.admin_grants {
+        admin_grants.push(admin_grant.into_humanized(&deps.api)?);
+    }
+
+    to_binary(&QueryAnswer::Roles { admin_grants })
+}
+
+fn query_search<S: Storage, A: Api, Q: Querier>(
+    deps: &Extern<S, A, Q>,
+    search_type: String,
+    mut search_value: String,
+    include_metadata: bool,
+    viewing_key: Option<String>,
+) -> QueryResult {
+    if search_type == "address" {
+        let alias_storage = RegistryReadonlyStorage::from_storage(&deps.storage, &deps.api);
+        let address: CanonicalAddr = deps
+            .api
+            .canonical_address(&HumanAddr::from(search_value))
+            .map_err(|_| StdError::not_found("Alias"))?;
+        let alias_object: Option<Alias> = alias_storage.get_alias_for_address(&address);
+        if alias_object.is_none() {
+            return Err(StdError::not_found("Alias"));
+        }
+        let alias_object: Alias = alias_object.unwrap();
+        if hides_resolution(&deps.storage, &alias_object.alias) {
+            return Err(StdError::not_found("Alias"));
+        }
+        // Answer not found rather than unauthorized so a missing key doesn't confirm the alias exists
+        if alias_object.private_reverse_lookup
+            && !holds_viewing_key(&deps.storage, &address, viewing_key.clone())
+            && !holds_alias_viewing_key(&deps.storage, &alias_object.alias, viewing_key)
+        {
+            return Err(StdError::not_found("Alias"));
+        }
+        search_value = alias_object.alias;
+    } else if search_type == "alias" {
+        search_value = normalize_alias(&deps.storage, &search_value)?;
+    } else {
+        return Err(StdError::parse_err(
+            "search_type",
+            "must be address or alias.",
+        ));
+    }
+
+    let alias_storage = RegistryReadonlyStorage::from_storage(&deps.storage, &deps.api);
+    let mut alias_object: Option<Alias> = alias_storage.get_alias(search_value.as_bytes());
+    if alias_object.is_none() && search_type == "alias" {
+        if let Some((wildcard_alias, wildcard_alias_object)) =
+            find_wildcard_alias(&deps.storage, &deps.api, &search_value)
+        {
+            search_value = wildcard_alias;
+            alias_object = Some(wildcard_alias_object);
+        }
+    }
+    if alias_object.is_none() {
+        return Err(StdError::not_found("Alias"));
+    }
+    let alias_object: Alias = alias_object.unwrap();
+    // Searching by alias resolves through targets, searching by address returns its owner
+    let address: CanonicalAddr = if search_type == "alias" {
+        resolve_alias_target(&deps.storage, &deps.api, alias_object.clone())?.owner
+    } else {
+        alias_object.owner
+    };
+    let metadata: Option<AliasMetadata> = if include_metadata {
+        let co_ownership: CoOwnership = CoOwnershipsReadonlyStorage::from_storage(&deps.storage)
+            .get_co_ownership(search_value.as_bytes())
+            .unwrap_or(CoOwnership {
+                co_owners: vec![],
+                threshold: 1,
+            });
+        let attestations: Vec<Attestation> =
+            AttestationsReadonlyStorage::from_storage(&deps.storage)
+                .get_attestations(search_value.as_bytes())
+                .into_iter()
+                .map(|attestation| attestation.into_humanized(&deps.api))
+                .collect::<StdResult<Vec<Attestation>>>()?;
+        let frozen: bool = FrozenAliasesReadonlyStorage::from_storage(&deps.storage)
+            .get_frozen(search_value.as_bytes())
+            .is_some();
+        let suspension: Option<Suspension> =
+            SuspensionsReadonlyStorage::from_storage(&deps.storage)
+                .get_suspension(search_value.as_bytes());
+        let lease: Option<Lease> =
+            LeasesReadonlyStorage::from_storage(&deps.storage).get_lease(search_value.as_bytes());
+        let status: AliasStatus = if suspension.is_some() {
+            AliasStatus::Suspended
+        } else if frozen {
+            AliasStatus::Frozen
+        } else if lease.is_some() {
+            AliasStatus::Leased
+        } else {
+            AliasStatus::Active
+        };
+        Some(AliasMetadata {
+            created_at: alias_object.created_at,
+            updated_at: alias_object.updated_at,
+            expires_at: lease.as_ref().map(|lease| lease.paid_until),
+            status,
+            verified: !attestations.is_empty(),
+            attestations,
+            endorsements: EndorsementsReadonlyStorage::from_storage(&deps.storage)
+                .get_endorsers(search_value.as_bytes())
+                .len() as u64,
+            target: alias_object.target,
+            wildcard: alias_object.wildcard,
+            frozen,
+            locked_until: LocksReadonlyStorage::from_storage(&deps.storage)
+                .get_locked_until(search_value.as_bytes()),
+            suspension,
+            lease,
+            images: ImagesReadonlyStorage::from_storage(&deps.storage)
+                .get_images(search_value.as_bytes()),
+            banner_url: alias_object.banner_url,
+            accent_color: alias_object.accent_color,
+            members: MembersReadonlyStorage::from_storage(&deps.storage)
+                .get_members(search_value.as_bytes())
+                .into_iter()
+                .map(|member| member.into_humanized(&deps.api))
+                .collect::<StdResult<Vec<Member>>>()?,
+            co_owners: co_ownership
+                .co_owners
+                .iter()
+                .map(|co_owner| deps.api.human_address(co_owner))
+                .collect::<StdResult<Vec<HumanAddr>>>()?,
+            approval_threshold: co_ownership.threshold,
+            records: RecordsReadonlyStorage::from_storage(&deps.storage)
+                .get_records(search_value.as_bytes()),
+            encryption_key: EncryptionKeysReadonlyStorage::from_storage(&deps.storage)
+                .get_encryption_key(search_value.as_bytes()),
+            payment_preference: PaymentPreferencesReadonlyStorage::from_storage(&deps.storage)
+                .get_payment_preference(search_value.as_bytes())
+                .map(|payment_preference| payment_preference.into_humanized(&deps.api))
+                .transpose()?,
+            linked_addresses: LinkedAddressesReadonlyStorage::from_storage(&deps.storage)
+                .get_linked_addresses(search_value.as_bytes()),
+        })
+    } else {
+        None
+    };
+
+    to_binary(&SearchResponse {
+        r#type: "aliases".to_string(),
+        attributes: AliasAttributes {
+            alias: search_value,
+            display_name: Some(alias_object.display_name),
+            avatar_url: alias_object.avatar_url,
+            avatar_metadata: alias_object.avatar_metadata,
+            address: deps.api.human_address(&address)?,
+        },
+        metadata,
+    })
+}
+
+fn holds_viewing_key<S: Storage>(
+    storage: &S,
+    address: &CanonicalAddr,
+    viewing_key: Option<String>,
+) -> bool {
+    let hashed_viewing_key: Option<Vec<u8>> =
+        ViewingKeysReadonlyStorage::from_storage(storage).get_viewing_key(address.as_slice());
+    match (viewing_key, hashed_viewing_key) {
+        (Some(viewing_key), Some(hashed_viewing_key)) => {
+            ViewingKey(viewing_key).check_viewing_key(&hashed_viewing_key)
+        }
+        _ => false,
+    }
+}
+
+fn holds_alias_viewing_key<S: Storage>(
+    storage: &S,
+    alias: &str,
+    viewing_key: Option<String>,
+) -> bool {
+    let viewing_key: ViewingKey = match viewing_key {
+        Some(viewing_key) => ViewingKey(viewing_key),
+        None => return false,
+    };
+    AliasViewingKeysReadonlyStorage::from_storage(storage)
+        .get_alias_viewing_keys(alias.as_bytes())
+        .iter()
+        .any(|alias_viewing_key| viewing_key.check_viewing_key(&alias_viewing_key.hashed_key))
+}
+
+// Resolve an alias the same way a search by alias does, through wildcards and targets
+fn resolve_alias_address<S: Storage, A: Api>(
+    storage: &S,
+    api: &A,
+    alias: &str,
+) -> StdResult<CanonicalAddr> {
+    let alias_object: Option<Alias> = RegistryReadonlyStorage::from_storage(storage, api)
+        .get_alias(alias.as_bytes())
+        .or_else(|| find_wildcard_alias(storage, api, alias).map(|(_, alias_object)| alias_object));
+    if alias_object.is_none() {
+        return Err(StdError::not_found("Alias"));
+    }
+
+    Ok(resolve_alias_target(storage, api, alias_object.unwrap())?.owner)
+}
+
+// Strip labels off the front of an unregistered alias until a registered one is found.
+// The closest registered parent decides: it only matches if its owner turned on wildcard.
+fn find_wildcard_alias<S: Storage, A: Api>(
+    storage: &S,
+    api: &A,
+    alias: &str,
+) -> Option<(String, Alias)> {
+    let alias_storage = RegistryReadonlyStorage::from_storage(storage, api);
+    let mut parent: &str = alias;
+    while let Some(index) = parent.find('.') {
+        parent = &parent[index + 1..];
+        if let Some(alias_object) = alias_storage.get_alias(parent.as_bytes()) {
+            if alias_object.wildcard {
+                return Some((parent.to_string(), alias_object));
+            }
+            return None;
+        }
+    }
+
+    None
+}
+
+fn query_status<S: Storage, A: Api, Q: Querier>(deps: &Extern<S, A, Q>) -> QueryResult {
+    let config: Config = TypedStore::attach(&deps.storage).load(CONFIG_KEY)?;
+    let alias_count: u64 = TypedStore::attach(&deps.storage)
+        .may_load(ALIAS_COUNT_KEY)?
+        .unwrap_or(0);
+
+    to_binary(&QueryAnswer::Status {
+        admin: deps.api.human_address(&config.admin)?,
+        alias_count,
+        fees: Fees {
+            token: config.buttcoin.clone(),
+            create: fee_amount(&deps.querier, &config, None)?,
+            rename: fee_amount(&deps.querier, &config, None)?,
+            accepted_payments: config.accepted_payments,
+        },
+        version: env!("CARGO_PKG_VERSION").to_string(),
+    })
+}
+
+fn query_tip_jar<S: Storage, A: Api, Q: Querier>(
+    deps: &Extern<S, A, Q>,
+    alias_string: String,
+    viewing_key: String,
+) -> QueryResult {
+    let alias_string = normalize_alias(&deps.storage, &alias_string)?;
+    let alias_object: Option<Alias> =
+        RegistryReadonlyStorage::from_storage(&deps.storage, &deps.api)
+            .get_alias(alias_string.as_bytes());
+    if alias_object.is_none() {
+        return Err(StdError::not_found("Alias"));
+    }
+    if !holds_viewing_key(
+        &deps.storage,
+        &alias_object.unwrap().owner,
+        Some(viewing_key),
+    ) {
+        return Err(StdError::Unauthorized { backtrace: None });
+    }
+
+    to_binary(&QueryAnswer::TipJar {
+        tip_jar: TipJarsReadonlyStorage::from_storage(&deps.storage)
+            .get_tip_jar(alias_string.as_bytes()),
+    })
+}
+
+// Runs the checks Create runs on the alias, apart from the ones that depend on the registrant
+fn query_validate<S: Storage, A: Api, Q: Querier>(
+    deps: &Extern<S, A, Q>,
+    alias_string: String,
+) -> QueryResult {
+    let alias_string = normalize_alias(&deps.storage, &alias_string)?;
+    let mut violations: Vec<String> = alias_violations(&alias_string);
+    if violations.is_empty() {
+        if let Err(StdError::GenericErr { msg, .. }) =
+            ensure_alias_available(&deps.storage, &deps.api, &alias_string)
+        {
+            violations.push(msg);
+        }
+    }
+
+    to_binary(&QueryAnswer::Validate {
+        alias: alias_string,
+        violations,
+    })
+}
+
+fn query_verify_sign_in<S: Storage, A: Api, Q: Querier>(
+    deps: &Extern<S, A, Q>,
+    alias_string: String,
+    challenge: Binary,
+    signature: Binary,
+) -> QueryResult {
+    let alias_string = normalize_alias(&deps.storage, &alias_string)?;
+    if RegistryReadonlyStorage::from_storage(&deps.storage, &deps.api)
+        .get_alias(alias_string.as_bytes())
+        .is_none()
+    {
+        return Err(StdError::not_found("Alias"));
+    }
+    let signing_key: Binary = SigningKeysReadonlyStorage::from_storage(&deps.storage)
+        .get_signing_key(alias_string.as_bytes())
+        .ok_or_else(|| StdError::not_found("Signing key"))?;
+
+    to_binary(&QueryAnswer::VerifySignIn {
+        valid: verify_signature(
+            signing_key.as_slice(),
+            challenge.as_slice(),
+            signature.as_slice(),
+        ),
+    })
+}
+
+// Follow alias targets to the alias that holds the address
+fn resolve_alias_target<S: Storage, A: Api>(
+    storage: &S,
+    api: &A,
+    mut alias_object: Alias,
+) -> StdResult<Alias> {
+    let alias_storage = RegistryReadonlyStorage::from_storage(storage, api);
+    let mut depth: u8 = 0;
+    loop {
+        if hides_resolution(storage, &alias_object.alias) {
+            return Err(StdError::generic_err("Alias is suspended"));
+        }
+        let target: String = match alias_object.target {
+            Some(target) => target,
+            None => break,
+        };
+        depth += 1;
+        if depth > MAX_ALIAS_TARGET_DEPTH {
+            return Err(StdError::generic_err("Alias target chain is too long"));
+        }
+        let target_object: Option<Alias> = alias_storage.get_alias(target.as_bytes());
+        if target_object.is_none() {
+            return Err(StdError::not_found("Alias target"));
+        }
+        alias_object = target_object.unwrap();
+    }
+
+    Ok(alias_object)
+}
+
+// === TESTS ===
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::msg::PriceResponse;
+    use crate::signatures::{ethereum_address, ethereum_signed_message};
+    use crate::state::{
+        ApprovalAction, LegacyAlias, SecretContract, V1Alias, ADDRESSES_ALIASES_PREFIX,
+        ALIASES_PREFIX, V1_ADDRESSES_ALIASES_PREFIX, V1_ALIASES_PREFIX,
+    };
+    use crate::testing::{
+        alias_attributes, mock_admin, mock_butt_lode, mock_buttcoin, mock_init_msg,
+        resolve_addresses_msg, search_address_msg, seed_alias,
+    };
+    use cosmwasm_std::testing::*;
+    use cosmwasm_std::HumanAddr;
+    use cosmwasm_std::{coins, from_binary, from_slice, BankMsg, QuerierResult, ReadonlyStorage};
+    use cosmwasm_storage::{PrefixedStorage, ReadonlyPrefixedStorage};
+    use secret_toolkit::serialization::{Bincode2, Serde};
+    use std::any::Any;
+
+    fn extract_error_msg<T: Any>(error: StdResult<T>) -> String {
+        match error {
+            Ok(_response) => {
+                panic!("Unexpected query answer")
+            }
+            Err(err) => match err {
+                StdError::GenericErr { msg, .. } => msg,
+                StdError::NotFound { kind, .. } => format!("{} not found", kind),
+                StdError::Unauthorized { .. } => "Unauthorized".to_string(),
+                _ => panic!("Unexpected result from init"),
+            },
+        }
+    }
+
+    fn extract_query_error<T: Any>(error: StdResult<T>) -> QueryError {
+        match error {
+            Ok(_response) => {
+                panic!("Unexpected query answer")
+            }
+            Err(StdError::GenericErr { msg, .. }) => from_slice(msg.as_bytes()).unwrap(),
+            Err(_) => panic!("Unexpected error from query"),
+        }
+    }
+
+    //=== HELPER FUNCTIONS ===
+    fn init_helper() -> (
+        StdResult<InitResponse>,
+        Extern<MockStorage, MockApi, MockQuerier>,
+    ) {
+        let mut deps = mock_dependencies(20, &[]);
+        let env = mock_env(mock_user_address(), &[]);
+
+        let init_msg = mock_init_msg(vec![alias_attributes(
+            "epstein didn't kill himself",
+            "frump",
+        )]);
+
+        (init(&mut deps, env, init_msg), deps)
+    }
+
+    // Answers every query with the same oracle price
+    struct PriceOracleQuerier {
+        price: PriceResponse,
+    }
+    impl Querier for PriceOracleQuerier {
+        fn raw_query(&self, _bin_request: &[u8]) -> QuerierResult {
+            Ok(to_binary(&self.price))
+        }
+    }
+
+    fn mock_user_address() -> HumanAddr {
+        mock_admin()
+    }
+
+    // === TESTS ===
+    #[test]
+    fn test_handle_with_sent_funds() {
+        let (_init_result, mut deps) = init_helper();
+
+        // = when native coins are sent with a handle msg
+        // = * it raises an error
+        let handle_msg = HandleMsg::Destroy {
+            alias: "Epstein didn't kill himself".to_string(),
+            padding: None,
+        };
+        let handle_result = handle(
+            &mut deps,
+            mock_env("frump", &coins(1_000_000, "uscrt")),
+            handle_msg,
+        );
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, "This contract does not accept native coins");
+    }
+
+    #[test]
+    fn test_try_manage_images() {
+        let (_init_result, mut deps) = init_helper();
+        let alias = "Epstein didn't kill himself";
+        let images_of = |deps: &Extern<MockStorage, MockApi, MockQuerier>| -> Vec<Image> {
+            let search_response = query(
+                deps,
+                QueryMsg::Search {
+                    search_type: "alias".to_string(),
+                    search_value: alias.to_string(),
+                    include_metadata: Some(true),
+                    viewing_key: None,
+                },
+            )
+            .unwrap();
+            let val: SearchResponse = from_binary(&search_response).unwrap();
+            val.metadata.unwrap().images
+        };
+        let add_image = |url: &str| HandleMsg::AddImage {
+            alias: alias.to_string(),
+            url: url.to_string(),
+            metadata: None,
+            padding: None,
+        };
+
+        // = when someone other than the owner adds an image
+        // = * it raises an error
+        let handle_result = handle(
+            &mut deps,
+            mock_env(mock_user_address(), &[]),
+            add_image("https://www.btn.group/1.png"),
+        );
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, "Unauthorized");
+
+        // = when the url isn't http(s) or is too long
+        // = * it raises an error
+        for url in [
+            "javascript:alert(1)".to_string(),
+            format!("https://www.btn.group/{}", "a".repeat(MAX_URL_LENGTH)),
+        ]
+        .iter()
+        {
+            let handle_result = handle(&mut deps, mock_env("frump", &[]), add_image(url));
+            assert_eq!(extract_error_msg(handle_result), "Image url is invalid");
+        }
+
+        // = when the owner adds images
+        // = * the first one is primary
+        handle(
+            &mut deps,
+            mock_env("frump", &[]),
+            add_image("https://www.btn.group/1.png"),
+        )
+        .unwrap();
+        handle(
+            &mut deps,
+            mock_env("frump", &[]),
+            add_image("https://www.btn.group/2.png"),
+        )
+        .unwrap();
+        let images: Vec<Image> = images_of(&deps);
+        assert_eq!(images.len(), 2);
+        assert!(images[0].primary);
+        assert!(!images[1].primary);
+
+        // = when the owner sets the primary image
+        // = * only that image is primary
+        let handle_msg = HandleMsg::SetPrimaryImage {
+            alias: alias.to_string(),
+            index: 1,
+            padding: None,
+        };
+        handle(&mut deps, mock_env("frump", &[]), handle_msg).unwrap();
+        let images: Vec<Image> = images_of(&deps);
+        assert!(!images[0].primary);
+        assert!(images[1].primary);
+
+        // = when the owner removes an image that does not exist
+        // = * it raises an error
+        let handle_msg = HandleMsg::RemoveImage {
+            alias: alias.to_string(),
+            index: 2,
+            padding: None,
+        };
+        let handle_result = handle(&mut deps, mock_env("frump", &[]), handle_msg);
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, "Image not found");
+
+        // = when the owner removes the primary image
+        // = * the remaining image becomes primary
+        let handle_msg = HandleMsg::RemoveImage {
+            alias: alias.to_string(),
+            index: 1,
+            padding: None,
+        };
+        handle(&mut deps, mock_env("frump", &[]), handle_msg).unwrap();
+        let images: Vec<Image> = images_of(&deps);
+        assert_eq!(images.len(), 1);
+        assert_eq!(images[0].url, "https://www.btn.group/1.png");
+        assert!(images[0].primary);
+
+        // = when the alias is full
+        // = * it raises an error
+        for _ in 1..MAX_IMAGES_PER_ALIAS {
+            handle(
+                &mut deps,
+                mock_env("frump", &[]),
+                add_image("https://www.btn.group/more.png"),
+            )
+            .unwrap();
+        }
+        let handle_result = handle(
+            &mut deps,
+            mock_env("frump", &[]),
+            add_image("https://www.btn.group/more.png"),
+        );
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, "Too many images. Maximum is 10.");
+    }
+
+    #[test]
+    fn test_try_manage_members() {
+        let (_init_result, mut deps) = init_helper();
+        let alias = "Epstein didn't kill himself";
+        let members_of = |deps: &Extern<MockStorage, MockApi, MockQuerier>| -> Vec<Member> {
+            let search_response = query(
+                deps,
+                QueryMsg::Search {
+                    search_type: "alias".to_string(),
+                    search_value: alias.to_string(),
+                    include_metadata: Some(true),
+                    viewing_key: None,
+                },
+            )
+            .unwrap();
+            let val: SearchResponse = from_binary(&search_response).unwrap();
+            val.metadata.unwrap().members
+        };
+        let add_member = |address: &str, role: Role| HandleMsg::AddMember {
+            alias: alias.to_string(),
+            address: HumanAddr::from(address),
+            role,
+            padding: None,
+        };
+        let remove_member = |address: &str| HandleMsg::RemoveMember {
+            alias: alias.to_string(),
+            address: HumanAddr::from(address),
+            padding: None,
+        };
+        let set_wildcard = HandleMsg::SetWildcard {
+            alias: alias.to_string(),
+            wildcard: true,
+            padding: None,
+        };
+
+        // = when someone who isn't a member adds a member
+        // = * it raises an error
+        let handle_result = handle(
+            &mut deps,
+            mock_env(mock_user_address(), &[]),
+            add_member("mr-admin", Role::Admin),
+        );
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, "Unauthorized");
+
+        // = when the owner adds themselves
+        // = * it raises an error
+        let handle_result = handle(
+            &mut deps,
+            mock_env("frump", &[]),
+            add_member("frump", Role::Admin),
+        );
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, "Owner can not be a member");
+
+        // = when the owner adds an admin
+        let handle_result = handle(
+            &mut deps,
+            mock_env("frump", &[]),
+            add_member("mr-admin", Role::Admin),
+        );
+        let handle_result_data: HandleAnswer =
+            from_binary(&handle_result.unwrap().data.unwrap()).unwrap();
+        assert_eq!(
+            to_binary(&handle_result_data).unwrap(),
+            to_binary(&HandleAnswer::AddMember { status: Success }).unwrap()
+        );
+        // = * it is returned with the alias metadata
+        assert_eq!(
+            members_of(&deps),
+            vec![Member {
+                address: HumanAddr::from("mr-admin"),
+                role: Role::Admin
+            }]
+        );
+
+        // = when the admin makes someone else an admin
+        // = * it raises an error
+        let handle_result = handle(
+            &mut deps,
+            mock_env("mr-admin", &[]),
+            add_member("mr-editor", Role::Admin),
+        );
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, "Unauthorized");
+
+        // = when the admin adds an editor
+        handle(
+            &mut deps,
+            mock_env("mr-admin", &[]),
+            add_member("mr-editor", Role::Editor),
+        )
+        .unwrap();
+        assert_eq!(members_of(&deps).len(), 2);
+        // = * the editor can update the alias's records
+        handle(&mut deps, mock_env("mr-editor", &[]), set_wildcard.clone()).unwrap();
+        // = * the editor can't manage members
+        let handle_result = handle(
+            &mut deps,
+            mock_env("mr-editor", &[]),
+            add_member("mr-nobody", Role::Editor),
+        );
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, "Unauthorized");
+        // = * the editor can't destroy the alias
+        let handle_msg = HandleMsg::Destroy {
+            alias: alias.to_string(),
+            padding: None,
+        };
+        let handle_result = handle(&mut deps, mock_env("mr-editor", &[]), handle_msg);
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, "Unauthorized");
+
+        // = when the editor removes the admin
+        // = * it raises an error
+        let handle_result = handle(
+            &mut deps,
+            mock_env("mr-editor", &[]),
+            remove_member("mr-admin"),
+        );
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, "Unauthorized");
+
+        // = when the admin removes the editor
+        handle(
+            &mut deps,
+            mock_env("mr-admin", &[]),
+            remove_member("mr-editor"),
+        )
+        .unwrap();
+        // = * the editor can no longer update the alias's records
+        let handle_result = handle(&mut deps, mock_env("mr-editor", &[]), set_wildcard);
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, "Unauthorized");
+        // = * removing them again raises an error
+        let handle_result = handle(
+            &mut deps,
+            mock_env("mr-admin", &[]),
+            remove_member("mr-editor"),
+        );
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, "Member not found");
+
+        // = when the owner removes the admin
+        // = * there are no members left
+        handle(&mut deps, mock_env("frump", &[]), remove_member("mr-admin")).unwrap();
+        assert!(members_of(&deps).is_empty());
+    }
+
+    #[test]
+    fn test_try_manage_known_contracts() {
+        let (_init_result, mut deps) = init_helper();
+        let create = HandleMsg::Receive {
+            sender: mock_user_address(),
+            from: mock_user_address(),
+            amount: Uint128(AMOUNT_FOR_TRANSACTION),
+            msg: to_binary(&ReceiveMsg::Create {
+                alias: "SSCRT".to_string(),
+                avatar_url: None,
+                avatar_metadata: None,
+                records: None,
+                fallback: None,
+            })
+            .unwrap(),
+            padding: None,
+        };
+        let add_known_contract = HandleMsg::AddKnownContract {
+            label: " sSCRT".to_string(),
+            address: HumanAddr::from("sscrt-address"),
+            padding: None,
+        };
+
+        // = when someone other than the admin adds a known contract
+        // = * it raises an Unauthorized error
+        let handle_result = handle(
+            &mut deps,
+            mock_env(HumanAddr::from("frump"), &[]),
+            add_known_contract.clone(),
+        );
+        assert_eq!(
+            handle_result.unwrap_err(),
+            StdError::Unauthorized { backtrace: None }
+        );
+
+        // = when the admin adds a known contract
+        // = * it stops its label from being registered as an alias
+        let handle_result = handle(
+            &mut deps,
+            mock_env(mock_user_address(), &[]),
+            add_known_contract,
+        );
+        assert_eq!(
+            handle_result.unwrap().data,
+            Some(to_binary(&HandleAnswer::AddKnownContract { status: Success }).unwrap())
+        );
+        let handle_result = handle(
+            &mut deps,
+            mock_env(mock_buttcoin().address, &[]),
+            create.clone(),
+        );
+        assert_eq!(
+            extract_error_msg(handle_result),
+            "Alias is the label of the contract at sscrt-address"
+        );
+
+        // = when the admin removes the known contract
+        // = * it lets the label be registered again
+        let handle_result = handle(
+            &mut deps,
+            mock_env(mock_user_address(), &[]),
+            HandleMsg::RemoveKnownContract {
+                label: "sscrt".to_string(),
+                padding: None,
+            },
+        );
+        assert_eq!(
+            handle_result.unwrap().data,
+            Some(to_binary(&HandleAnswer::RemoveKnownContract { status: Success }).unwrap())
+        );
+        handle(&mut deps, mock_env(mock_buttcoin().address, &[]), create).unwrap();
+        let alias_storage = RegistryReadonlyStorage::from_storage(&deps.storage, &deps.api);
+        assert!(alias_storage.get_alias(b"sscrt").is_some());
+    }
+
+    #[test]
+    fn test_try_create_alias_viewing_key() {
+        let (_init_result, mut deps) = init_helper();
+        let alias: &str = "epstein didn't kill himself";
+        handle(
+            &mut deps,
+            mock_env("frump", &[]),
+            HandleMsg::SetPrivateReverseLookup {
+                alias: alias.to_string(),
+                enabled: true,
+                padding: None,
+            },
+        )
+        .unwrap();
+        let search_by_address = |viewing_key: String| QueryMsg::Search {
+            search_type: "address".to_string(),
+            search_value: "frump".to_string(),
+            include_metadata: None,
+            viewing_key: Some(viewing_key),
+        };
+        let create_alias_viewing_key = HandleMsg::CreateAliasViewingKey {
+            alias: alias.to_string(),
+            label: "merchant".to_string(),
+            entropy: "bunnies".to_string(),
+            padding: None,
+        };
+
+        // = when the alias belongs to someone else
+        // = * it raises an error
+        let handle_result = handle(
+            &mut deps,
+            mock_env(mock_user_address(), &[]),
+            create_alias_viewing_key.clone(),
+        );
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, "Unauthorized");
+
+        // = when the owner creates a viewing key for the alias
+        let handle_result = handle(
+            &mut deps,
+            mock_env("frump", &[]),
+            create_alias_viewing_key.clone(),
+        );
+        let key: ViewingKey = match from_binary(&handle_result.unwrap().data.unwrap()).unwrap() {
+            HandleAnswer::CreateAliasViewingKey { key } => key,
+            _ => panic!("Unexpected handle answer"),
+        };
+        // = * searching by address with the key finds the alias
+        let search_response = query(&deps, search_by_address(key.to_string())).unwrap();
+        let val: SearchResponse = from_binary(&search_response).unwrap();
+        assert_eq!(val.attributes.alias, alias);
+
+        // = when the label is already in use
+        // = * it raises an error
+        let handle_result = handle(&mut deps, mock_env("frump", &[]), create_alias_viewing_key);
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, "Label has already been taken");
+
+        // = when the owner revokes the key
+        let revoke_alias_viewing_key = HandleMsg::RevokeAliasViewingKey {
+            alias: alias.to_string(),
+            label: "merchant".to_string(),
+            padding: None,
+        };
+        let handle_result = handle(
+            &mut deps,
+            mock_env("frump", &[]),
+            revoke_alias_viewing_key.clone(),
+        );
+        let handle_result_data: HandleAnswer =
+            from_binary(&handle_result.unwrap().data.unwrap()).unwrap();
+        assert_eq!(
+            to_binary(&handle_result_data).unwrap(),
+            to_binary(&HandleAnswer::RevokeAliasViewingKey { status: Success }).unwrap()
+        );
+        // = * searching by address with the key finds nothing
+        let query_response = query(&deps, search_by_address(key.to_string()));
+        let error = extract_query_error(query_response);
+        assert_eq!(error.code, QueryErrorCode::NotFound);
+        // = * revoking it again raises an error
+        let handle_result = handle(&mut deps, mock_env("frump", &[]), revoke_alias_viewing_key);
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, "Viewing key not found");
+    }
+
+    #[test]
+    fn test_try_create_viewing_key() {
+        let (_init_result, mut deps) = init_helper();
+        let handle_msg = HandleMsg::CreateViewingKey {
+            entropy: "bunnies".to_string(),
+            padding: None,
+        };
+        let handle_result = handle(&mut deps, mock_env(mock_user_address(), &[]), handle_msg);
+        let handle_result_data: HandleAnswer =
+            from_binary(&handle_result.unwrap().data.unwrap()).unwrap();
+        match handle_result_data {
+            HandleAnswer::CreateViewingKey { key } => {
+                // = * it stores the hash of the key for the sender
+                let hashed_viewing_key: Vec<u8> =
+                    ViewingKeysReadonlyStorage::from_storage(&deps.storage)
+                        .get_viewing_key(
+                            deps.api
+                                .canonical_address(&mock_user_address())
+                                .unwrap()
+                                .as_slice(),
+                        )
+                        .unwrap();
+                assert!(key.check_viewing_key(&hashed_viewing_key));
+            }
+            _ => panic!("Unexpected handle answer"),
+        }
+    }
+
+    #[test]
+    fn test_try_destroy() {
+        let alias: &str = "nailbiter";
+
+        // Initialize
+        let (_init_result, mut deps) = init_helper();
+
+        // when alias exists
+        let create_alias_message = ReceiveMsg::Create {
+            alias: alias.to_string(),
+            avatar_url: None,
+            avatar_metadata: None,
+            records: None,
+            fallback: None,
+        };
+        let receive_msg = HandleMsg::Receive {
+            sender: mock_user_address(),
+            from: mock_user_address(),
+            amount: Uint128(AMOUNT_FOR_TRANSACTION),
+            msg: to_binary(&create_alias_message).unwrap(),
+            padding: None,
+        };
+        let handle_result = handle(
+            &mut deps,
+            mock_env(mock_buttcoin().address, &[]),
+            receive_msg.clone(),
+        );
+        handle_result.unwrap();
+
+        // = when user tries to delete an alias that does not exist
+        // = * it raises an error
+        let destroy_alias_message = HandleMsg::Destroy {
+            alias: "idonotexist".to_string(),
+            padding: None,
+        };
+        let res = handle(
+            &mut deps,
+            mock_env(mock_user_address(), &[]),
+            destroy_alias_message,
+        );
+        let error = extract_error_msg(res);
+        assert_eq!(error, "Alias not found");
+
+        // = when user tries to delete an alias that belongs to someone else
+        // = * it raises an error
+        let destroy_alias_message = HandleMsg::Destroy {
+            alias: alias.to_string(),
+            padding: None,
+        };
+        let res = handle(
+            &mut deps,
+            mock_env(mock_buttcoin().address, &[]),
+            destroy_alias_message,
+        );
+        let error = extract_error_msg(res);
+        assert_eq!(error, "Unauthorized");
+
+        // = when user destroy their own alias
+        // = * alias is removed
+        let destroy_alias_message = HandleMsg::Destroy {
+            alias: alias.to_string(),
+            padding: None,
+        };
+        handle(
+            &mut deps,
+            mock_env(mock_user_address(), &[]),
+            destroy_alias_message,
+        )
+        .unwrap();
+        // Query destroyed alias via alias
+        let query_response = query(
+            &mut deps,
+            QueryMsg::Search {
+                search_type: "alias".to_string(),
+                search_value: alias.to_string(),
+                include_metadata: None,
+                viewing_key: None,
+            },
+        );
+        let error = extract_query_error(query_response);
+        assert_eq!(error.code, QueryErrorCode::NotFound);
+        assert_eq!(error.details.message, "Alias not found");
+        // Query destroyed alias via address
+        let query_response = query(
+            &mut deps,
+            QueryMsg::Search {
+                search_type: "address".to_string(),
+                search_value: mock_user_address().to_string(),
+                include_metadata: None,
+                viewing_key: None,
+            },
+        );
+        let error = extract_query_error(query_response);
+        assert_eq!(error.code, QueryErrorCode::NotFound);
+        assert_eq!(error.details.message, "Alias not found");
+
+        // = when someone else registers the destroyed alias before its tombstone expires
+        // = * it raises an error
+        let create_for = |from: &str| HandleMsg::Receive {
+            sender: HumanAddr::from(from),
+            from: HumanAddr::from(from),
+            amount: Uint128(AMOUNT_FOR_TRANSACTION),
+            msg: to_binary(&create_alias_message).unwrap(),
+            padding: None,
+        };
+        let mut env = mock_env(mock_buttcoin().address, &[]);
+        let handle_result = handle(&mut deps, env.clone(), create_for("sniper"));
+        assert_eq!(
+            extract_error_msg(handle_result),
+            format!(
+                "Alias is reserved for its previous owner until block {}",
+                env.block.height + TOMBSTONE_BLOCKS
+            )
+        );
+
+        // = when the previous owner registers it before the tombstone expires
+        // = * it registers the alias
+        handle(&mut deps, env.clone(), receive_msg).unwrap();
+
+        // = when someone else registers it after the tombstone expires
+        // = * it registers the alias
+        handle(
+            &mut deps,
+            mock_env(mock_user_address(), &[]),
+            HandleMsg::Destroy {
+                alias: alias.to_string(),
+                padding: None,
+            },
+        )
+        .unwrap();
+        env.block.height += TOMBSTONE_BLOCKS;
+        handle(&mut deps, env, create_for("sniper")).unwrap();
+    }
+
+    #[test]
+    fn test_try_freeze() {
+        let alias = "Epstein didn't kill himself";
+        let freeze = |allow_transfers: bool| HandleMsg::Freeze {
+            alias: alias.to_string(),
+            allow_transfers,
+            padding: None,
+        };
+        let transfer = HandleMsg::Transfer {
+            alias: alias.to_string(),
+            recipient: mock_user_address(),
+            recipient_code_hash: None,
+            msg: None,
+            padding: None,
+        };
+        let set_wildcard = HandleMsg::SetWildcard {
+            alias: alias.to_string(),
+            wildcard: true,
+            padding: None,
+        };
+        let (_init_result, mut deps) = init_helper();
+
+        // = when the sender doesn't own the alias
+        // = * it raises an Unauthorized error
+        let handle_result = handle(&mut deps, mock_env(mock_user_address(), &[]), freeze(false));
+        assert_eq!(
+            handle_result.unwrap_err(),
+            StdError::Unauthorized { backtrace: None }
+        );
+
+        // = when the owner freezes the alias
+        // = * it marks the alias as frozen
+        let handle_result = handle(&mut deps, mock_env("frump", &[]), freeze(false));
+        assert_eq!(
+            handle_result.unwrap().data,
+            Some(to_binary(&HandleAnswer::Freeze { status: Success }).unwrap())
+        );
+        let search_response = query(
+            &deps,
+            QueryMsg::Search {
+                search_type: "alias".to_string(),
+                search_value: alias.to_string(),
+                include_metadata: Some(true),
+                viewing_key: None,
+            },
+        )
+        .unwrap();
+        let val: SearchResponse = from_binary(&search_response).unwrap();
+        assert!(val.metadata.unwrap().frozen);
+        // = * it can't be edited, frozen again or transferred
+        let handle_result = handle(&mut deps, mock_env("frump", &[]), set_wildcard.clone());
+        assert_eq!(extract_error_msg(handle_result), "Alias is frozen");
+        let handle_result = handle(&mut deps, mock_env("frump", &[]), freeze(true));
+        assert_eq!(extract_error_msg(handle_result), "Alias is frozen");
+        let handle_result = handle(&mut deps, mock_env("frump", &[]), transfer.clone());
+        assert_eq!(extract_error_msg(handle_result), "Alias is frozen");
+
+        // = when the alias is frozen with transfers allowed
+        // = * it can still be transferred but not edited by its new owner
+        let (_init_result, mut deps) = init_helper();
+        handle(&mut deps, mock_env("frump", &[]), freeze(true)).unwrap();
+        handle(&mut deps, mock_env("frump", &[]), transfer).unwrap();
+        let alias_storage = RegistryReadonlyStorage::from_storage(&deps.storage, &deps.api);
+        assert_eq!(
+            alias_storage
+                .get_alias(b"epstein didn't kill himself")
+                .unwrap()
+                .owner,
+            deps.api.canonical_address(&mock_user_address()).unwrap()
+        );
+        let handle_result = handle(&mut deps, mock_env(mock_user_address(), &[]), set_wildcard);
+        assert_eq!(extract_error_msg(handle_result), "Alias is frozen");
+    }
+
+    #[test]
+    fn test_try_renounce() {
+        let (_init_result, mut deps) = init_helper();
+        let renounce = HandleMsg::Renounce {
+            alias: "Epstein didn't kill himself".to_string(),
+            padding: None,
+        };
+        let create = |alias: &str| HandleMsg::Receive {
+            sender: HumanAddr::from("frump"),
+            from: HumanAddr::from("frump"),
+            amount: Uint128(AMOUNT_FOR_TRANSACTION),
+            msg: to_binary(&ReceiveMsg::Create {
+                alias: alias.to_string(),
+                avatar_url: None,
+                avatar_metadata: None,
+                records: None,
+                fallback: None,
+            })
+            .unwrap(),
+            padding: None,
+        };
+
+        // = when the sender doesn't own the alias
+        // = * it raises an Unauthorized error
+        let handle_result = handle(
+            &mut deps,
+            mock_env(mock_user_address(), &[]),
+            renounce.clone(),
+        );
+        assert_eq!(
+            handle_result.unwrap_err(),
+            StdError::Unauthorized { backtrace: None }
+        );
+
+        // = when the owner renounces the alias
+        // = * it destroys the alias
+        let handle_result = handle(&mut deps, mock_env("frump", &[]), renounce);
+        assert_eq!(
+            handle_result.unwrap().data,
+            Some(to_binary(&HandleAnswer::Renounce { status: Success }).unwrap())
+        );
+        assert!(
+            RegistryReadonlyStorage::from_storage(&deps.storage, &deps.api)
+                .get_alias(b"epstein didn't kill himself")
+                .is_none()
+        );
+
+        // = when anyone tries to register the alias again, including the previous owner
+        // = * it raises an error
+        let handle_result = handle(
+            &mut deps,
+            mock_env(mock_buttcoin().address, &[]),
+            create("EPSTEIN didn't kill himself"),
+        );
+        assert_eq!(extract_error_msg(handle_result), "Alias has been renounced");
+        let query_result = query(
+            &deps,
+            QueryMsg::Quote {
+                alias: "epstein didn't kill himself".to_string(),
+                height: None,
+                periods: None,
+            },
+        );
+        let error = extract_query_error(query_result);
+        assert_eq!(error.details.message, "Alias has been renounced");
+
+        // = * the previous owner can still register another alias
+        handle(
+            &mut deps,
+            mock_env(mock_buttcoin().address, &[]),
+            create("nailbiter"),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_try_endorse() {
+        let (_init_result, mut deps) = init_helper();
+        let endorse = |alias: &str| HandleMsg::Endorse {
+            alias: alias.to_string(),
+            padding: None,
+        };
+        let revoke_endorsement = HandleMsg::RevokeEndorsement {
+            alias: "Epstein didn't kill himself".to_string(),
+            padding: None,
+        };
+        let endorsements_of = |deps: &Extern<MockStorage, MockApi, MockQuerier>| -> u64 {
+            let search_response = query(
+                deps,
+                QueryMsg::Search {
+                    search_type: "alias".to_string(),
+                    search_value: "epstein didn't kill himself".to_string(),
+                    include_metadata: Some(true),
+                    viewing_key: None,
+                },
+            )
+            .unwrap();
+            let val: SearchResponse = from_binary(&search_response).unwrap();
+            val.metadata.unwrap().endorsements
+        };
+
+        // = when the alias doesn't exist
+        // = * it raises an error
+        let handle_result = handle(
+            &mut deps,
+            mock_env(mock_user_address(), &[]),
+            endorse("nailbiter"),
+        );
+        assert_eq!(extract_error_msg(handle_result), "Alias not found");
+
+        // = when the owner endorses their own alias
+        // = * it raises an error
+        let handle_result = handle(
+            &mut deps,
+            mock_env("frump", &[]),
+            endorse("Epstein didn't kill himself"),
+        );
+        assert_eq!(
+            extract_error_msg(handle_result),
+            "You can't endorse your own alias"
+        );
+
+        // = when someone else endorses the alias
+        // = * it counts the endorsement
+        let handle_result = handle(
+            &mut deps,
+            mock_env(mock_user_address(), &[]),
+            endorse("Epstein didn't kill himself"),
+        );
+        assert_eq!(
+            handle_result.unwrap().data,
+            Some(to_binary(&HandleAnswer::Endorse { status: Success }).unwrap())
+        );
+        assert_eq!(endorsements_of(&deps), 1);
+        // = * they can't endorse it again
+        let handle_result = handle(
+            &mut deps,
+            mock_env(mock_user_address(), &[]),
+            endorse("Epstein didn't kill himself"),
+        );
+        assert_eq!(
+            extract_error_msg(handle_result),
+            "You already endorse this alias"
+        );
+
+        // = when an address that doesn't endorse the alias revokes an endorsement
+        // = * it raises an error
+        let handle_result = handle(
+            &mut deps,
+            mock_env("someone", &[]),
+            revoke_endorsement.clone(),
+        );
+        assert_eq!(extract_error_msg(handle_result), "Endorsement not found");
+
+        // = when the endorser revokes their endorsement
+        // = * it is no longer counted
+        let handle_result = handle(
+            &mut deps,
+            mock_env(mock_user_address(), &[]),
+            revoke_endorsement,
+        );
+        assert_eq!(
+            handle_result.unwrap().data,
+            Some(to_binary(&HandleAnswer::RevokeEndorsement { status: Success }).unwrap())
+        );
+        assert_eq!(endorsements_of(&deps), 0);
+    }
+
+    #[test]
+    fn test_try_finalize_lottery() {
+        let (_init_result, mut deps) = init_helper();
+        let height: u64 = mock_env(mock_user_address(), &[]).block.height;
+        let apply_for_alias = |applicant: &str, alias: &str| HandleMsg::Receive {
+            sender: HumanAddr::from(applicant),
+            from: HumanAddr::from(applicant),
+            amount: Uint128(AMOUNT_FOR_TRANSACTION),
+            msg: to_binary(&ReceiveMsg::ApplyForAlias {
+                alias: alias.to_string(),
+            })
+            .unwrap(),
+            padding: None,
+        };
+        let finalize_lottery = |alias: &str| HandleMsg::FinalizeLottery {
+            alias: alias.to_string(),
+            padding: None,
+        };
+
+        // = when there is no lottery running
+        // = * it refuses applications
+        let handle_result = handle(
+            &mut deps,
+            mock_env(mock_buttcoin().address, &[]),
+            apply_for_alias("alice", "contested"),
+        );
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, "There is no lottery running");
+
+        // = when someone other than the admin opens a lottery
+        // = * it raises an error
+        let handle_result = handle(
+            &mut deps,
+            mock_env("alice", &[]),
+            HandleMsg::OpenLottery {
+                blocks: 10,
+                padding: None,
+            },
+        );
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, "Unauthorized");
+
+        // = when the admin opens a lottery
+        // = * it saves when the lottery ends
+        let handle_result = handle(
+            &mut deps,
+            mock_env(mock_user_address(), &[]),
+            HandleMsg::OpenLottery {
+                blocks: 10,
+                padding: None,
+            },
+        )
+        .unwrap();
+        assert_eq!(
+            handle_result.data,
+            Some(to_binary(&HandleAnswer::OpenLottery { status: Success }).unwrap())
+        );
+        let config: Config = TypedStore::attach(&deps.storage).load(CONFIG_KEY).unwrap();
+        assert_eq!(config.lottery_ends_at, Some(height + 10));
+
+        // = when registering during the lottery
+        // = * it raises an error
+        let handle_result = handle(
+            &mut deps,
+            mock_env(mock_buttcoin().address, &[]),
+            HandleMsg::Receive {
+                sender: HumanAddr::from("alice"),
+                from: HumanAddr::from("alice"),
+                amount: Uint128(AMOUNT_FOR_TRANSACTION),
+                msg: to_binary(&ReceiveMsg::Create {
+                    alias: "contested".to_string(),
+                    avatar_url: None,
+                    avatar_metadata: None,
+                    records: None,
+                    fallback: None,
+                })
+                .unwrap(),
+                padding: None,
+            },
+        );
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, "Registration is closed while the lottery is running");
+
+        // = when applying for an alias
+        // = * it holds the fee until the draw
+        for (applicant, alias) in &[
+            ("alice", "Contested"),
+            ("bob", "Contested"),
+            ("carol", "Contested"),
+            ("erin", "disputed"),
+            ("frank", "disputed"),
+        ] {
+            let handle_result = handle(
+                &mut deps,
+                mock_env(mock_buttcoin().address, &[]),
+                apply_for_alias(applicant, alias),
+            )
+            .unwrap();
+            assert!(handle_result.messages.is_empty());
+            assert_eq!(
+                handle_result.data,
+                Some(to_binary(&ReceiveAnswer::ApplyForAlias { status: Success }).unwrap())
+            );
+        }
+
+        // = when an applicant applies for the same alias again
+        // = * it raises an error
+        let handle_result = handle(
+            &mut deps,
+            mock_env(mock_buttcoin().address, &[]),
+            apply_for_alias("bob", "contested"),
+        );
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, "You have already applied for this alias");
+
+        // = when an address with an alias applies
+        // = * it raises an error
+        let handle_result = handle(
+            &mut deps,
+            mock_env(mock_buttcoin().address, &[]),
+            apply_for_alias("frump", "other"),
+        );
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, "Address already has an alias");
+
+        // = when finalizing before the lottery ends
+        // = * it raises an error
+        let handle_result = handle(
+            &mut deps,
+            mock_env("dave", &[]),
+            finalize_lottery("contested"),
+        );
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, "The lottery is still running");
+
+        // = when the lottery has ended
+        let mut env = mock_env("dave", &[]);
+        env.block.height = height + 10;
+
+        // = * an alias without applications can't be finalized
+        let handle_result = handle(&mut deps, env.clone(), finalize_lottery("uncontested"));
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, "Lottery application not found");
+
+        // = * an alias with applications waits for its draw
+        let mut buttcoin_env = mock_env(mock_buttcoin().address, &[]);
+        buttcoin_env.block.height = height + 10;
+        let handle_result = handle(
+            &mut deps,
+            buttcoin_env,
+            HandleMsg::Receive {
+                sender: HumanAddr::from("dave"),
+                from: HumanAddr::from("dave"),
+                amount: Uint128(AMOUNT_FOR_TRANSACTION),
+                msg: to_binary(&ReceiveMsg::Create {
+                    alias: "contested".to_string(),
+                    avatar_url: None,
+                    avatar_metadata: None,
+                    records: None,
+                    fallback: None,
+                })
+                .unwrap(),
+                padding: None,
+            },
+        );
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, "Alias is waiting for its lottery draw");
+
+        // = * anyone can finalize it, which gives the alias to one applicant, sends their fee to
+        // the BUTT lode and refunds the others
+        let handle_result = handle(&mut deps, env.clone(), finalize_lottery("contested")).unwrap();
+        let handle_answer: HandleAnswer = from_binary(&handle_result.data.unwrap()).unwrap();
+        let winner: HumanAddr = match handle_answer {
+            HandleAnswer::FinalizeLottery { winner } => winner.unwrap(),
+            _ => panic!("Unexpected handle answer"),
+        };
+        let alias_object: Alias = RegistryReadonlyStorage::from_storage(&deps.storage, &deps.api)
+            .get_alias(b"contested")
+            .unwrap();
+        assert_eq!(
+            alias_object.owner,
+            deps.api.canonical_address(&winner).unwrap()
+        );
+        let mut expected_messages: Vec<CosmosMsg> = vec![snip20::transfer_msg(
+            mock_butt_lode().address,
+            Uint128(AMOUNT_FOR_TRANSACTION),
+            None,
+            BLOCK_SIZE,
+            mock_buttcoin().contract_hash,
+            mock_buttcoin().address,
+        )
+        .unwrap()];
+        for applicant in &["alice", "bob", "carol"] {
+            if HumanAddr::from(*applicant) != winner {
+                expected_messages.push(
+                    snip20::transfer_msg(
+                        HumanAddr::from(*applicant),
+                        Uint128(AMOUNT_FOR_TRANSACTION),
+                        None,
+                        BLOCK_SIZE,
+                        mock_buttcoin().contract_hash,
+                        mock_buttcoin().address,
+                    )
+                    .unwrap(),
+                );
+            }
+        }
+        assert_eq!(handle_result.messages, expected_messages);
+
+        // = * it can't be finalized twice
+        let handle_result = handle(&mut deps, env.clone(), finalize_lottery("contested"));
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, "Lottery application not found");
+
+        // = when avatars have become required since the applications were made
+        handle(
+            &mut deps,
+            mock_env(mock_user_address(), &[]),
+            HandleMsg::SetRequireAvatar {
+                required: true,
+                padding: None,
+            },
+        )
+        .unwrap();
+        // = * finalizing gives the alias to nobody and refunds every applicant
+        let handle_result = handle(&mut deps, env, finalize_lottery("disputed")).unwrap();
+        assert_eq!(
+            handle_result.data,
+            Some(to_binary(&HandleAnswer::FinalizeLottery { winner: None }).unwrap())
+        );
+        let refunds: Vec<CosmosMsg> = ["erin", "frank"]
+            .iter()
+            .map(|applicant| {
+                snip20::transfer_msg(
+                    HumanAddr::from(*applicant),
+                    Uint128(AMOUNT_FOR_TRANSACTION),
+                    None,
+                    BLOCK_SIZE,
+                    mock_buttcoin().contract_hash,
+                    mock_buttcoin().address,
+                )
+                .unwrap()
+            })
+            .collect();
+        assert_eq!(handle_result.messages, refunds);
+        assert!(
+            RegistryReadonlyStorage::from_storage(&deps.storage, &deps.api)
+                .get_alias(b"disputed")
+                .is_none()
+        );
+        assert!(
+            LotteryApplicationsReadonlyStorage::from_storage(&deps.storage)
+                .get_applications(b"disputed")
+                .is_empty()
+        );
+        // = * applications are refused while avatars are required
+        let mut env = mock_env(mock_user_address(), &[]);
+        env.block.height = height + 10;
+        handle(
+            &mut deps,
+            env,
+            HandleMsg::OpenLottery {
+                blocks: 10,
+                padding: None,
+            },
+        )
+        .unwrap();
+        let mut buttcoin_env = mock_env(mock_buttcoin().address, &[]);
+        buttcoin_env.block.height = height + 10;
+        let handle_result = handle(&mut deps, buttcoin_env, apply_for_alias("grace", "other"));
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, "Avatar url is required");
+    }
+
+    #[test]
+    fn test_try_follow() {
+        let (_init_result, mut deps) = init_helper();
+        let follow = |alias: &str| HandleMsg::Follow {
+            alias: alias.to_string(),
+            padding: None,
+        };
+        let followers_of = |deps: &Extern<MockStorage, MockApi, MockQuerier>,
+                            alias: &str|
+         -> StdResult<Vec<String>> {
+            let query_response = query(
+                deps,
+                QueryMsg::Followers {
+                    alias: alias.to_string(),
+                    page: 0,
+                    viewing_key: None,
+                },
+            )?;
+            match from_binary(&query_response).unwrap() {
+                QueryAnswer::Followers { aliases, .. } => Ok(aliases),
+                _ => panic!("Unexpected query answer"),
+            }
+        };
+        let following_of = |deps: &Extern<MockStorage, MockApi, MockQuerier>,
+                            viewing_key: Option<&str>|
+         -> StdResult<Vec<String>> {
+            let query_response = query(
+                deps,
+                QueryMsg::Following {
+                    alias: "nailbiter".to_string(),
+                    page: 0,
+                    viewing_key: viewing_key.map(|viewing_key| viewing_key.to_string()),
+                },
+            )?;
+            match from_binary(&query_response).unwrap() {
+                QueryAnswer::Following { aliases, .. } => Ok(aliases),
+                _ => panic!("Unexpected query answer"),
+            }
+        };
+        let create_alias_message = ReceiveMsg::Create {
+            alias: "nailbiter".to_string(),
+            avatar_url: None,
+            avatar_metadata: None,
+            records: None,
+            fallback: None,
+        };
+        let receive_msg = HandleMsg::Receive {
+            sender: mock_user_address(),
+            from: mock_user_address(),
+            amount: Uint128(AMOUNT_FOR_TRANSACTION),
+            msg: to_binary(&create_alias_message).unwrap(),
+            padding: None,
+        };
+        handle(
+            &mut deps,
+            mock_env(mock_buttcoin().address, &[]),
+            receive_msg,
+        )
+        .unwrap();
+
+        // = when the sender doesn't have an alias
+        // = * it raises an error
+        let handle_result = handle(
+            &mut deps,
+            mock_env("someone", &[]),
+            follow("Epstein didn't kill himself"),
+        );
+        assert_eq!(
+            extract_error_msg(handle_result),
+            "You need an alias to follow other aliases"
+        );
+
+        // = when the sender follows their own alias
+        // = * it raises an error
+        let handle_result = handle(
+            &mut deps,
+            mock_env(mock_user_address(), &[]),
+            follow("nailbiter"),
+        );
+        assert_eq!(
+            extract_error_msg(handle_result),
+            "You can't follow your own alias"
+        );
+
+        // = when the sender follows another alias
+        // = * it lists them as following each other
+        let handle_result = handle(
+            &mut deps,
+            mock_env(mock_user_address(), &[]),
+            follow("Epstein didn't kill himself"),
+        );
+        assert_eq!(
+            handle_result.unwrap().data,
+            Some(to_binary(&HandleAnswer::Follow { status: Success }).unwrap())
+        );
+        assert_eq!(
+            followers_of(&deps, "epstein didn't kill himself").unwrap(),
+            vec!["nailbiter"]
+        );
+        assert_eq!(
+            following_of(&deps, None).unwrap(),
+            vec!["epstein didn't kill himself"]
+        );
+        // = * they can't follow it again
+        let handle_result = handle(
+            &mut deps,
+            mock_env(mock_user_address(), &[]),
+            follow("Epstein didn't kill himself"),
+        );
+        assert_eq!(
+            extract_error_msg(handle_result),
+            "You already follow this alias"
+        );
+
+        // = when the sender makes their follows private
+        handle(
+            &mut deps,
+            mock_env(mock_user_address(), &[]),
+            HandleMsg::SetFollowsPrivate {
+                private: true,
+                padding: None,
+            },
+        )
+        .unwrap();
+        handle(
+            &mut deps,
+            mock_env(mock_user_address(), &[]),
+            HandleMsg::SetViewingKey {
+                key: "hunter2".to_string(),
+                padding: None,
+            },
+        )
+        .unwrap();
+        // = * their lists need their viewing key
+        let error = extract_query_error(following_of(&deps, Some("hunter3")));
+        assert_eq!(error.code, QueryErrorCode::Unauthorized);
+        assert_eq!(
+            following_of(&deps, Some("hunter2")).unwrap(),
+            vec!["epstein didn't kill himself"]
+        );
+        // = * they are left out of other aliases' lists
+        assert!(followers_of(&deps, "epstein didn't kill himself")
+            .unwrap()
+            .is_empty());
+
+        // = when the sender unfollows the alias
+        // = * they no longer follow it
+        let handle_result = handle(
+            &mut deps,
+            mock_env(mock_user_address(), &[]),
+            HandleMsg::Unfollow {
+                alias: "Epstein didn't kill himself".to_string(),
+                padding: None,
+            },
+        );
+        assert_eq!(
+            handle_result.unwrap().data,
+            Some(to_binary(&HandleAnswer::Unfollow { status: Success }).unwrap())
+        );
+        assert!(following_of(&deps, Some("hunter2")).unwrap().is_empty());
+        let handle_result = handle(
+            &mut deps,
+            mock_env(mock_user_address(), &[]),
+            HandleMsg::Unfollow {
+                alias: "Epstein didn't kill himself".to_string(),
+                padding: None,
+            },
+        );
+        assert_eq!(
+            extract_error_msg(handle_result),
+            "You don't follow this alias"
+        );
+    }
+
+    #[test]
+    fn test_try_escrow() {
+        let (_init_result, mut deps) = init_helper();
+        let alias = "Epstein didn't kill himself";
+        let uscrt = Asset::Native {
+            denom: "uscrt".to_string(),
+        };
+        let open_escrow = |amount: u128| HandleMsg::OpenEscrow {
+            alias: alias.to_string(),
+            buyer: HumanAddr::from("buyer"),
+            asset: Asset::Native {
+                denom: "uscrt".to_string(),
+            },
+            amount: Uint128(amount),
+            padding: None,
+        };
+        let fund_escrow = HandleMsg::FundEscrow {
+            alias: alias.to_string(),
+            padding: None,
+        };
+        let escrow_of = |deps: &Extern<MockStorage, MockApi, MockQuerier>| -> Option<Escrow> {
+            let query_response = query(
+                deps,
+                QueryMsg::Escrow {
+                    alias: alias.to_string(),
+                },
+            )
+            .unwrap();
+            match from_binary(&query_response).unwrap() {
+                QueryAnswer::Escrow { escrow } => escrow,
+                _ => panic!("Unexpected query answer"),
+            }
+        };
+
+        // = when someone other than the owner opens an escrow
+        // = * it raises an error
+        let handle_result = handle(&mut deps, mock_env("buyer", &[]), open_escrow(1_000));
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, "Unauthorized");
+
+        // = when the price is 0
+        // = * it raises an error
+        let handle_result = handle(&mut deps, mock_env("frump", &[]), open_escrow(0));
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, "Price must be greater than 0");
+
+        // = when the owner opens an escrow
+        // = * it saves the escrow
+        let handle_result = handle(&mut deps, mock_env("frump", &[]), open_escrow(1_000)).unwrap();
+        assert_eq!(
+            handle_result.data,
+            Some(to_binary(&HandleAnswer::OpenEscrow { status: Success }).unwrap())
+        );
+        assert_eq!(
+            escrow_of(&deps),
+            Some(Escrow {
+                buyer: HumanAddr::from("buyer"),
+                asset: uscrt.clone(),
+                amount: Uint128(1_000),
+                opened_at: mock_env("frump", &[]).block.height,
+            })
+        );
+        // = * the alias can't be transferred while it is in escrow
+        let handle_result = handle(
+            &mut deps,
+            mock_env("frump", &[]),
+            HandleMsg::Transfer {
+                alias: alias.to_string(),
+                recipient: HumanAddr::from("someone"),
+                recipient_code_hash: None,
+                msg: None,
+                padding: None,
+            },
+        );
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, "Alias is in escrow");
+        // = * another escrow can't be opened
+        let handle_result = handle(&mut deps, mock_env("frump", &[]), open_escrow(2_000));
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, "Alias is in escrow");
+        // = * the alias can't be locked until the escrow is settled
+        let handle_result = handle(
+            &mut deps,
+            mock_env("frump", &[]),
+            HandleMsg::Lock {
+                alias: alias.to_string(),
+                until_height: mock_env("frump", &[]).block.height + 10,
+                padding: None,
+            },
+        );
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, "Alias is in escrow");
+
+        // = when someone other than the buyer funds the escrow
+        // = * it raises an error
+        let handle_result = handle(
+            &mut deps,
+            mock_env("someone", &coins(1_000, "uscrt")),
+            fund_escrow.clone(),
+        );
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, "Unauthorized");
+
+        // = when the buyer sends the wrong amount
+        // = * it raises an error
+        let handle_result = handle(
+            &mut deps,
+            mock_env("buyer", &coins(999, "uscrt")),
+            fund_escrow.clone(),
+        );
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, "The price is 1000 of uscrt");
+
+        // = when someone other than the seller or buyer cancels the escrow
+        // = * it raises an error
+        let cancel_escrow = HandleMsg::CancelEscrow {
+            alias: alias.to_string(),
+            padding: None,
+        };
+        let handle_result = handle(&mut deps, mock_env("someone", &[]), cancel_escrow.clone());
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, "Unauthorized");
+
+        // = when the buyer cancels the escrow
+        // = * it removes the escrow
+        handle(&mut deps, mock_env("buyer", &[]), cancel_escrow).unwrap();
+        assert_eq!(escrow_of(&deps), None);
+
+        // = when the buyer funds the escrow
+        // = * it transfers the alias to the buyer and pays the seller
+        handle(&mut deps, mock_env("frump", &[]), open_escrow(1_000)).unwrap();
+        let env = mock_env("buyer", &coins(1_000, "uscrt"));
+        let handle_result = handle(&mut deps, env.clone(), fund_escrow).unwrap();
+        assert_eq!(
+            handle_result.data,
+            Some(to_binary(&HandleAnswer::FundEscrow { status: Success }).unwrap())
+        );
+        assert_eq!(
+            handle_result.messages,
+            vec![CosmosMsg::Bank(BankMsg::Send {
+                from_address: env.contract.address,
+                to_address: HumanAddr::from("frump"),
+                amount: coins(1_000, "uscrt"),
+            })]
+        );
+        let alias_object: Alias = RegistryReadonlyStorage::from_storage(&deps.storage, &deps.api)
+            .get_alias(alias.to_lowercase().as_bytes())
+            .unwrap();
+        assert_eq!(
+            alias_object.owner,
+            deps.api
+                .canonical_address(&HumanAddr::from("buyer"))
+                .unwrap()
+        );
+        assert_eq!(escrow_of(&deps), None);
+    }
+
+    #[test]
+    fn test_try_grant_role() {
+        let (_init_result, mut deps) = init_helper();
+        let grant_role = HandleMsg::GrantRole {
+            address: HumanAddr::from("moderator"),
+            role: AdminRole::Moderator,
+            padding: None,
+        };
+        let revoke_role = HandleMsg::RevokeRole {
+            address: HumanAddr::from("moderator"),
+            role: AdminRole::Moderator,
+            padding: None,
+        };
+        let reserve_aliases = HandleMsg::ReserveAliases {
+            aliases: vec!["spam".to_string()],
+            padding: None,
+        };
+
+        // = when someone other than a SuperAdmin grants a role
+        // = * it raises an error
+        let handle_result = handle(&mut deps, mock_env("moderator", &[]), grant_role.clone());
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, "Unauthorized");
+
+        // = when the admin grants a role
+        // = * the grantee can use that role's handles
+        handle(
+            &mut deps,
+            mock_env(mock_user_address(), &[]),
+            grant_role.clone(),
+        )
+        .unwrap();
+        handle(
+            &mut deps,
+            mock_env("moderator", &[]),
+            reserve_aliases.clone(),
+        )
+        .unwrap();
+        // = * the grantee can't use another role's handles
+        let handle_result = handle(
+            &mut deps,
+            mock_env("moderator", &[]),
+            HandleMsg::SetAcceptedPayments {
+                accepted_payments: vec![],
+                padding: None,
+            },
+        );
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, "Unauthorized");
+        // = * the grantee can't grant roles
+        let handle_result = handle(&mut deps, mock_env("moderator", &[]), grant_role.clone());
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, "Unauthorized");
+        // = * the roles query lists the grant after the admin
+        let query_answer: QueryAnswer =
+            from_binary(&query(&deps, QueryMsg::Roles {}).unwrap()).unwrap();
+        match query_answer {
+            QueryAnswer::Roles { admin_grants } => assert_eq!(
+                admin_grants,
+                vec![
+                    AdminGrant {
+                        address: mock_user_address(),
+                        role: AdminRole::SuperAdmin,
+                    },
+                    AdminGrant {
+                        address: HumanAddr::from("moderator"),
+                        role: AdminRole::Moderator,
+                    },
+                ]
+            ),
+            _ => panic!("Unexpected query answer"),
+        }
+
+        // = when the role is granted again
+        // = * it raises an error
+        let handle_result = handle(&mut deps, mock_env(mock_user_address(), &[]), grant_role);
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, "Address already has the role");
+
+        // = when the admin revokes the role
+        // = * the address can no longer use its handles
+        handle(
+            &mut deps,
+            mock_env(mock_user_address(), &[]),
+            revoke_role.clone(),
+        )
+        .unwrap();
+        let handle_result = handle(&mut deps, mock_env("moderator", &[]), reserve_aliases);
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, "Unauthorized");
+
+        // = when the address doesn't have the role
+        // = * it raises a not found error
+        let handle_result = handle(&mut deps, mock_env(mock_user_address(), &[]), revoke_role);
+        assert_eq!(handle_result.unwrap_err(), StdError::not_found("Role"));
+    }
+
+    #[test]
+    fn test_try_import_chunk() {
+        let (_init_result, mut deps) = init_helper();
+        let (_init_result, mut new_deps) = init_helper();
+        let export_chunk = |deps: &Extern<MockStorage, MockApi, MockQuerier>,
+                            start_seq: u64,
+                            viewing_key: &str|
+         -> StdResult<Binary> {
+            query(
+                deps,
+                QueryMsg::ExportChunk {
+                    start_seq,
+                    viewing_key: viewing_key.to_string(),
+                },
+            )
+        };
+        let create_alias_message = ReceiveMsg::Create {
+            alias: "nailbiter".to_string(),
+            avatar_url: None,
+            avatar_metadata: None,
+            records: None,
+            fallback: None,
+        };
+        let receive_msg = HandleMsg::Receive {
+            sender: HumanAddr::from("nailbiter"),
+            from: HumanAddr::from("nailbiter"),
+            amount: Uint128(AMOUNT_FOR_TRANSACTION),
+            msg: to_binary(&create_alias_message).unwrap(),
+            padding: None,
+        };
+        handle(
+            &mut deps,
+            mock_env(mock_buttcoin().address, &[]),
+            receive_msg,
+        )
+        .unwrap();
+        let handle_msg = HandleMsg::SetViewingKey {
+            key: "hunter2".to_string(),
+            padding: None,
+        };
+        handle(&mut deps, mock_env(mock_user_address(), &[]), handle_msg).unwrap();
+
+        // = when exporting without the admin's viewing key
+        // = * it raises an Unauthorized error
+        let error = extract_query_error(export_chunk(&deps, 1, "hunter3"));
+        assert_eq!(error.code, QueryErrorCode::Unauthorized);
+
+        // = when exporting with the admin's viewing key
+        // = * it returns the registered aliases with their checksum and the registry's totals
+        let (aliases, checksum, alias_count, registry_checksum) =
+            match from_binary(&export_chunk(&deps, 1, "hunter2").unwrap()).unwrap() {
+                QueryAnswer::ExportChunk {
+                    aliases,
+                    checksum,
+                    next_seq,
+                    alias_count,
+                    registry_checksum,
+                } => {
+                    assert_eq!(next_seq, None);
+                    (aliases, checksum, alias_count, registry_checksum)
+                }
+                _ => panic!("Unexpected query answer"),
+            };
+        assert_eq!(aliases.len(), 2);
+        assert_eq!(aliases[1].alias, "nailbiter");
+        assert_eq!(aliases[1].address, HumanAddr::from("nailbiter"));
+        assert_eq!(alias_count, 2);
+
+        // = when the chunk is imported by someone other than the admin
+        // = * it raises an Unauthorized error
+        let import_msg = HandleMsg::ImportChunk {
+            aliases: aliases.clone(),
+            checksum: checksum.clone(),
+            padding: None,
+        };
+        let handle_result = handle(
+            &mut new_deps,
+            mock_env(HumanAddr::from("nailbiter"), &[]),
+            import_msg.clone(),
+        );
+        assert_eq!(
+            handle_result.unwrap_err(),
+            StdError::Unauthorized { backtrace: None }
+        );
+
+        // = when the contract has no bootstrap phase
+        // = * it raises an error
+        let handle_result = handle(
+            &mut new_deps,
+            mock_env(mock_user_address(), &[]),
+            import_msg.clone(),
+        );
+        assert_eq!(
+            extract_error_msg(handle_result),
+            "The bootstrap phase is over"
+        );
+
+        // = when the bootstrap phase has ended by block height
+        // = * it raises an error
+        let bootstrap_ends_at: u64 = mock_env(mock_user_address(), &[]).block.height + 10;
+        let mut config: Config = TypedStore::attach(&new_deps.storage)
+            .load(CONFIG_KEY)
+            .unwrap();
+        config.bootstrap_ends_at = Some(bootstrap_ends_at);
+        TypedStoreMut::attach(&mut new_deps.storage)
+            .store(CONFIG_KEY, &config)
+            .unwrap();
+        let mut env = mock_env(mock_user_address(), &[]);
+        env.block.height = bootstrap_ends_at;
+        let handle_result = handle(&mut new_deps, env, import_msg.clone());
+        assert_eq!(
+            extract_error_msg(handle_result),
+            "The bootstrap phase is over"
+        );
+
+        // = when the checksum doesn't match the chunk
+        // = * it raises an error
+        let handle_result = handle(
+            &mut new_deps,
+            mock_env(mock_user_address(), &[]),
+            HandleMsg::ImportChunk {
+                aliases: aliases[1..].to_vec(),
+                checksum: checksum.clone(),
+                padding: None,
+            },
+        );
+        assert_eq!(
+            extract_error_msg(handle_result),
+            "Chunk checksum does not match its aliases"
+        );
+
+        // = when the admin imports the chunk
+        // = * it skips the alias that's already registered to the same address
+        // = * it registers the rest and returns totals matching the export
+        let handle_result = handle(
+            &mut new_deps,
+            mock_env(mock_user_address(), &[]),
+            import_msg.clone(),
+        );
+        assert_eq!(
+            handle_result.unwrap().data.unwrap(),
+            to_binary(&HandleAnswer::ImportChunk {
+                alias_count,
+                checksum: registry_checksum.clone(),
+            })
+            .unwrap()
+        );
+        let alias_storage = RegistryReadonlyStorage::from_storage(&new_deps.storage, &new_deps.api);
+        assert_eq!(
+            alias_storage.get_alias(b"nailbiter").unwrap().owner,
+            new_deps
+                .api
+                .canonical_address(&HumanAddr::from("nailbiter"))
+                .unwrap()
+        );
+
+        // = when the chunk is imported again
+        // = * the totals stay the same
+        let handle_result = handle(
+            &mut new_deps,
+            mock_env(mock_user_address(), &[]),
+            import_msg,
+        );
+        assert_eq!(
+            handle_result.unwrap().data.unwrap(),
+            to_binary(&HandleAnswer::ImportChunk {
+                alias_count,
+                checksum: registry_checksum,
+            })
+            .unwrap()
+        );
+
+        // = when an imported alias is registered to another address
+        // = * it raises an error
+        let import_chunk = |deps: &mut Extern<MockStorage, MockApi, MockQuerier>,
+                            aliases: Vec<AliasAttributes>| {
+            handle(
+                deps,
+                mock_env(mock_user_address(), &[]),
+                HandleMsg::ImportChunk {
+                    checksum: Binary::from(chunk_checksum(&aliases).to_vec()),
+                    aliases,
+                    padding: None,
+                },
+            )
+        };
+        let mut conflicting_aliases: Vec<AliasAttributes> = aliases.clone();
+        conflicting_aliases[1].address = HumanAddr::from("someone");
+        let handle_result = import_chunk(&mut new_deps, conflicting_aliases);
+        assert_eq!(
+            extract_error_msg(handle_result),
+            "nailbiter is already registered to another address"
+        );
+
+        // = when an imported alias differs from a registered one only in capitalization
+        // = * it is treated as the same alias
+        let mut conflicting_aliases: Vec<AliasAttributes> = aliases.clone();
+        conflicting_aliases[1].alias = " NailBiter ".to_string();
+        conflicting_aliases[1].display_name = None;
+        conflicting_aliases[1].address = HumanAddr::from("someone");
+        let handle_result = import_chunk(&mut new_deps, conflicting_aliases);
+        assert_eq!(
+            extract_error_msg(handle_result),
+            "nailbiter is already registered to another address"
+        );
+
+        // = when an imported alias's address already has a different alias
+        // = * it raises an error
+        let mut conflicting_aliases: Vec<AliasAttributes> = aliases.clone();
+        conflicting_aliases[1].alias = "nail biter".to_string();
+        conflicting_aliases[1].display_name = None;
+        let handle_result = import_chunk(&mut new_deps, conflicting_aliases);
+        assert_eq!(
+            extract_error_msg(handle_result),
+            "nailbiter already has an alias"
+        );
+
+        // = when an imported alias breaks the rules Create checks
+        // = * it raises an error
+        let mut conflicting_aliases: Vec<AliasAttributes> = aliases;
+        conflicting_aliases[1] =
+            alias_attributes("cosmos150rtrmj2f8vl9tem8qpfw36ylw5jg9j2nr2fee", "someone");
+        let handle_result = import_chunk(&mut new_deps, conflicting_aliases);
+        assert_eq!(
+            extract_error_msg(handle_result),
+            "Alias can't be an address"
+        );
+        assert!(
+            RegistryReadonlyStorage::from_storage(&new_deps.storage, &new_deps.api)
+                .get_alias(b"nail biter")
+                .is_none()
+        );
+
+        // = when someone other than the admin ends the bootstrap phase
+        // = * it raises an Unauthorized error
+        let handle_result = handle(
+            &mut new_deps,
+            mock_env(HumanAddr::from("nailbiter"), &[]),
+            HandleMsg::EndBootstrap { padding: None },
+        );
+        assert_eq!(
+            handle_result.unwrap_err(),
+            StdError::Unauthorized { backtrace: None }
+        );
+
+        // = when the admin ends the bootstrap phase
+        // = * it stops chunks from being imported for good
+        let handle_result = handle(
+            &mut new_deps,
+            mock_env(mock_user_address(), &[]),
+            HandleMsg::EndBootstrap { padding: None },
+        );
+        assert_eq!(
+            handle_result.unwrap().data,
+            Some(to_binary(&HandleAnswer::EndBootstrap { status: Success }).unwrap())
+        );
+        let config: Config = TypedStore::attach(&new_deps.storage)
+            .load(CONFIG_KEY)
+            .unwrap();
+        assert_eq!(config.bootstrap_ends_at, None);
+        let handle_result = handle(
+            &mut new_deps,
+            mock_env(mock_user_address(), &[]),
+            HandleMsg::ImportChunk {
+                aliases: vec![],
+                checksum: Binary::from(chunk_checksum(&[]).to_vec()),
+                padding: None,
+            },
+        );
+        assert_eq!(
+            extract_error_msg(handle_result),
+            "The bootstrap phase is over"
+        );
+        let handle_result = handle(
+            &mut new_deps,
+            mock_env(mock_user_address(), &[]),
+            HandleMsg::EndBootstrap { padding: None },
+        );
+        assert_eq!(
+            extract_error_msg(handle_result),
+            "The bootstrap phase is over"
+        );
+    }
+
+    #[test]
+    fn test_try_lease() {
+        let (_init_result, mut deps) = init_helper();
+        let height: u64 = mock_env(mock_user_address(), &[]).block.height;
+        let fee: u128 = AMOUNT_FOR_TRANSACTION / 10;
+        let buttcoin_env_at = |height: u64| {
+            let mut env = mock_env(mock_buttcoin().address, &[]);
+            env.block.height = height;
+            env
+        };
+        let receive = |from: &str, amount: u128, msg: ReceiveMsg| HandleMsg::Receive {
+            sender: HumanAddr::from(from),
+            from: HumanAddr::from(from),
+            amount: Uint128(amount),
+            msg: to_binary(&msg).unwrap(),
+            padding: None,
+        };
+        let lease = |periods: u16| ReceiveMsg::Lease {
+            alias: "rented".to_string(),
+            periods,
+        };
+        let renew_lease = |periods: u16| ReceiveMsg::RenewLease {
+            alias: "rented".to_string(),
+            periods,
+        };
+
+        // = when leases aren't offered
+        // = * it raises an error
+        let handle_result = handle(
+            &mut deps,
+            buttcoin_env_at(height),
+            receive("tenant", fee * 3, lease(3)),
+        );
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, "Aliases can't be leased");
+
+        // = when someone other than the admin sets the lease terms
+        // = * it raises an error
+        let set_lease_terms = HandleMsg::SetLeaseTerms {
+            lease_terms: Some(LeaseTerms {
+                period_blocks: 100,
+                fee: Uint128(fee),
+            }),
+            padding: None,
+        };
+        let handle_result = handle(&mut deps, mock_env("tenant", &[]), set_lease_terms.clone());
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, "Unauthorized");
+
+        // = when the admin sets the lease terms
+        // = * it saves them
+        let handle_result = handle(
+            &mut deps,
+            mock_env(mock_user_address(), &[]),
+            set_lease_terms,
+        )
+        .unwrap();
+        assert_eq!(
+            handle_result.data,
+            Some(to_binary(&HandleAnswer::SetLeaseTerms { status: Success }).unwrap())
+        );
+
+        // = when the fee for the periods isn't sent
+        // = * it raises an error
+        let handle_result = handle(
+            &mut deps,
+            buttcoin_env_at(height),
+            receive("tenant", fee, lease(3)),
+        );
+        let error = extract_error_msg(handle_result);
+        assert_eq!(
+            error,
+            format!("Amount sent in: {}. Amount required {}.", fee, fee * 3)
+        );
+
+        // = when an alias is leased
+        // = * it registers the alias with the lease terms
+        let handle_result = handle(
+            &mut deps,
+            buttcoin_env_at(height),
+            receive("tenant", fee * 3, lease(3)),
+        )
+        .unwrap();
+        assert_eq!(
+            handle_result.data,
+            Some(to_binary(&ReceiveAnswer::Lease { status: Success }).unwrap())
+        );
+        let search_lease = |deps: &Extern<MockStorage, MockApi, MockQuerier>| {
+            let search_response = query(
+                deps,
+                QueryMsg::Search {
+                    search_type: "alias".to_string(),
+                    search_value: "rented".to_string(),
+                    include_metadata: Some(true),
+                    viewing_key: None,
+                },
+            )
+            .unwrap();
+            let search_response: SearchResponse = from_binary(&search_response).unwrap();
+            search_response.metadata.unwrap().lease
+        };
+        assert_eq!(
+            search_lease(&deps),
+            Some(Lease {
+                period_blocks: 100,
+                fee: Uint128(fee),
+                paid_until: height + 300,
+            })
+        );
+
+        // = when a lease is renewed before it runs out
+        // = * it extends the lease
+        handle(
+            &mut deps,
+            buttcoin_env_at(height + 299),
+            receive("landlord", fee, renew_lease(1)),
+        )
+        .unwrap();
+        assert_eq!(search_lease(&deps).unwrap().paid_until, height + 400);
+        // = * it counts the renewal in the day's analytics
+        let day: u64 = buttcoin_env_at(height + 299).block.time / SECONDS_PER_DAY;
+        let query_result = query(
+            &deps,
+            QueryMsg::Analytics {
+                start_day: day,
+                end_day: day,
+            },
+        )
+        .unwrap();
+        match from_binary(&query_result).unwrap() {
+            QueryAnswer::Analytics { daily_stats } => assert_eq!(daily_stats[0].renewals, 1),
+            _ => panic!("Unexpected query answer"),
+        }
+
+        // = when an escrow opened during the lease is funded after the lease has run out
+        // = * it raises an error
+        handle(
+            &mut deps,
+            mock_env("tenant", &[]),
+            HandleMsg::OpenEscrow {
+                alias: "rented".to_string(),
+                buyer: HumanAddr::from("buyer"),
+                asset: Asset::Native {
+                    denom: "uscrt".to_string(),
+                },
+                amount: Uint128(1_000),
+                padding: None,
+            },
+        )
+        .unwrap();
+        let mut env = mock_env("buyer", &coins(1_000, "uscrt"));
+        env.block.height = height + 400;
+        let handle_result = handle(
+            &mut deps,
+            env,
+            HandleMsg::FundEscrow {
+                alias: "rented".to_string(),
+                padding: None,
+            },
+        );
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, "Lease has expired");
+        handle(
+            &mut deps,
+            mock_env("buyer", &[]),
+            HandleMsg::CancelEscrow {
+                alias: "rented".to_string(),
+                padding: None,
+            },
+        )
+        .unwrap();
+
+        // = when the lease has run out with an escrow open and tips in the jar
+        handle(
+            &mut deps,
+            mock_env("tenant", &[]),
+            HandleMsg::OpenEscrow {
+                alias: "rented".to_string(),
+                buyer: HumanAddr::from("buyer"),
+                asset: Asset::Native {
+                    denom: "uscrt".to_string(),
+                },
+                amount: Uint128(1_000),
+                padding: None,
+            },
+        )
+        .unwrap();
+        handle(
+            &mut deps,
+            mock_env("fan", &coins(1_000, "uscrt")),
+            HandleMsg::Tip {
+                alias: "rented".to_string(),
+                message: None,
+                padding: None,
+            },
+        )
+        .unwrap();
+        let mut env = mock_env("tenant", &[]);
+        env.block.height = height + 400;
+
+        // = * the holder can't use the alias
+        let handle_result = handle(
+            &mut deps,
+            env,
+            HandleMsg::Destroy {
+                alias: "rented".to_string(),
+                padding: None,
+            },
+        );
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, "Lease has expired");
+
+        // = * the holder can't hide it from reverse lookups
+        let mut env = mock_env("tenant", &[]);
+        env.block.height = height + 400;
+        let handle_result = handle(
+            &mut deps,
+            env,
+            HandleMsg::SetPrivateReverseLookup {
+                alias: "rented".to_string(),
+                enabled: true,
+                padding: None,
+            },
+        );
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, "Lease has expired");
+
+        // = * it can't be renewed
+        let handle_result = handle(
+            &mut deps,
+            buttcoin_env_at(height + 400),
+            receive("tenant", fee, renew_lease(1)),
+        );
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, "Lease has expired");
+
+        // = * someone else can register the alias
+        let env = buttcoin_env_at(height + 400);
+        let handle_result = handle(
+            &mut deps,
+            env.clone(),
+            receive(
+                "newcomer",
+                AMOUNT_FOR_TRANSACTION,
+                ReceiveMsg::Create {
+                    alias: "rented".to_string(),
+                    avatar_url: None,
+                    avatar_metadata: None,
+                    records: None,
+                    fallback: None,
+                },
+            ),
+        )
+        .unwrap();
+        assert_eq!(search_lease(&deps), None);
+        // = * it pays the tips to the lapsed holder
+        assert!(handle_result
+            .messages
+            .contains(&CosmosMsg::Bank(BankMsg::Send {
+                from_address: env.contract.address,
+                to_address: HumanAddr::from("tenant"),
+                amount: coins(1_000, "uscrt"),
+            })));
+        // = * it drops the escrow
+        assert!(EscrowsReadonlyStorage::from_storage(&deps.storage)
+            .get_escrow(b"rented")
+            .is_none());
+    }
+
+    #[test]
+    fn test_try_purge_expired() {
+        let (_init_result, mut deps) = init_helper();
+        let height: u64 = mock_env(mock_user_address(), &[]).block.height;
+        let fee: u128 = AMOUNT_FOR_TRANSACTION / 10;
+        handle(
+            &mut deps,
+            mock_env(mock_user_address(), &[]),
+            HandleMsg::SetLeaseTerms {
+                lease_terms: Some(LeaseTerms {
+                    period_blocks: 100,
+                    fee: Uint128(fee),
+                }),
+                padding: None,
+            },
+        )
+        .unwrap();
+        for (from, alias, periods) in vec![("tenant", "sooner", 1), ("renter", "later", 3)] {
+            handle(
+                &mut deps,
+                mock_env(mock_buttcoin().address, &[]),
+                HandleMsg::Receive {
+                    sender: HumanAddr::from(from),
+                    from: HumanAddr::from(from),
+                    amount: Uint128(fee * u128::from(periods)),
+                    msg: to_binary(&ReceiveMsg::Lease {
+                        alias: alias.to_string(),
+                        periods,
+                    })
+                    .unwrap(),
+                    padding: None,
+                },
+            )
+            .unwrap();
+        }
+        handle(
+            &mut deps,
+            mock_env("fan", &coins(1_000, "uscrt")),
+            HandleMsg::Tip {
+                alias: "sooner".to_string(),
+                message: None,
+                padding: None,
+            },
+        )
+        .unwrap();
+        let purge_expired = |limit: u32| HandleMsg::PurgeExpired {
+            limit,
+            padding: None,
+        };
+        let is_registered = |deps: &Extern<MockStorage, MockApi, MockQuerier>, alias: &str| {
+            RegistryReadonlyStorage::from_storage(&deps.storage, &deps.api)
+                .get_alias(alias.as_bytes())
+                .is_some()
+        };
+
+        // = when no lease has run out
+        // = * it purges nothing
+        let handle_result = handle(&mut deps, mock_env("crank", &[]), purge_expired(10)).unwrap();
+        assert_eq!(
+            handle_result.data,
+            Some(to_binary(&HandleAnswer::PurgeExpired { purged: 0 }).unwrap())
+        );
+
+        // = when a lease has run out
+        let mut env = mock_env("crank", &[]);
+        env.block.height = height + 100;
+        let handle_result = handle(&mut deps, env.clone(), purge_expired(10)).unwrap();
+        // = * it releases that alias only
+        assert_eq!(
+            handle_result.data,
+            Some(to_binary(&HandleAnswer::PurgeExpired { purged: 1 }).unwrap())
+        );
+        assert!(!is_registered(&deps, "sooner"));
+        assert!(is_registered(&deps, "later"));
+        // = * it pays the tips to the lapsed holder
+        assert_eq!(
+            handle_result.messages,
+            vec![CosmosMsg::Bank(BankMsg::Send {
+                from_address: env.contract.address,
+                to_address: HumanAddr::from("tenant"),
+                amount: coins(1_000, "uscrt"),
+            })]
+        );
+
+        // = when the limit is reached
+        // = * it leaves the rest for the next call
+        let mut env = mock_env("crank", &[]);
+        env.block.height = height + 300;
+        let handle_result = handle(&mut deps, env.clone(), purge_expired(0)).unwrap();
+        assert_eq!(
+            handle_result.data,
+            Some(to_binary(&HandleAnswer::PurgeExpired { purged: 0 }).unwrap())
+        );
+        assert!(is_registered(&deps, "later"));
+        handle(&mut deps, env, purge_expired(10)).unwrap();
+        assert!(!is_registered(&deps, "later"));
+    }
+
+    #[test]
+    fn test_try_link_address() {
+        use k256::ecdsa::signature::Signer;
+        use k256::ecdsa::{Signature, SigningKey};
+
+        let (_init_result, mut deps) = init_helper();
+        let alias: &str = "epstein didn't kill himself";
+        let address: HumanAddr = HumanAddr::from("cosmos150rtrmj2f8vl9tem8qpfw36ylw5jg9j2nr2fee");
+        let signing_key: SigningKey = SigningKey::from_bytes(&[7; 32]).unwrap();
+        let public_key: Binary = Binary(signing_key.verifying_key().to_bytes().to_vec());
+        let sign = |signing_key: &SigningKey, message: &str| -> Binary {
+            let signature: Signature =
+                signing_key.sign(&adr36_sign_doc(address.as_str(), message.as_bytes()));
+            Binary(signature.as_ref().to_vec())
+        };
+        let message: String = format!("Link to {} on cosmos2contract", alias);
+        let link_address = |public_key: &Binary, signature: Binary| HandleMsg::LinkAddress {
+            alias: alias.to_string(),
+            address: address.clone(),
+            public_key: public_key.clone(),
+            signature,
+            padding: None,
+        };
+        let linked_addresses = |deps: &Extern<MockStorage, MockApi, MockQuerier>| {
+            LinkedAddressesReadonlyStorage::from_storage(&deps.storage)
+                .get_linked_addresses(alias.as_bytes())
+        };
+
+        // = when someone other than the owner links an address
+        // = * it raises an error
+        let handle_result = handle(
+            &mut deps,
+            mock_env("merchant", &[]),
+            link_address(&public_key, sign(&signing_key, &message)),
+        );
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, "Unauthorized");
+
+        // = when the public key isn't the one the address is derived from
+        // = * it raises an error
+        let other_signing_key: SigningKey = SigningKey::from_bytes(&[8; 32]).unwrap();
+        let other_public_key: Binary =
+            Binary(other_signing_key.verifying_key().to_bytes().to_vec());
+        let handle_result = handle(
+            &mut deps,
+            mock_env("frump", &[]),
+            link_address(&other_public_key, sign(&other_signing_key, &message)),
+        );
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, "Public key doesn't belong to the address");
+
+        // = when the signature is for another message
+        // = * it raises an error
+        let handle_result = handle(
+            &mut deps,
+            mock_env("frump", &[]),
+            link_address(
+                &public_key,
+                sign(&signing_key, "Link to frump on cosmos2contract"),
+            ),
+        );
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, "Signature is invalid");
+        assert_eq!(linked_addresses(&deps), vec![]);
+
+        // = when the owner links an address signed for by its holder
+        // = * it is returned in the alias's metadata
+        let mut env = mock_env("frump", &[]);
+        env.block.height = 12_346;
+        handle(
+            &mut deps,
+            env,
+            link_address(&public_key, sign(&signing_key, &message)),
+        )
+        .unwrap();
+        let search_response = query(
+            &deps,
+            QueryMsg::Search {
+                search_type: "alias".to_string(),
+                search_value: alias.to_string(),
+                include_metadata: Some(true),
+                viewing_key: None,
+            },
+        )
+        .unwrap();
+        let val: SearchResponse = from_binary(&search_response).unwrap();
+        assert_eq!(
+            val.metadata.unwrap().linked_addresses,
+            vec![LinkedAddress {
+                address: address.clone(),
+                linked_at: 12_346,
+            }]
+        );
+
+        // = when the address is linked again
+        // = * it raises an error
+        let handle_result = handle(
+            &mut deps,
+            mock_env("frump", &[]),
+            link_address(&public_key, sign(&signing_key, &message)),
+        );
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, "Address is already linked");
+
+        // = when the owner unlinks the address
+        // = * it is removed
+        let unlink_address = HandleMsg::UnlinkAddress {
+            alias: alias.to_string(),
+            address: address.clone(),
+            padding: None,
+        };
+        handle(&mut deps, mock_env("frump", &[]), unlink_address.clone()).unwrap();
+        assert_eq!(linked_addresses(&deps), vec![]);
+
+        // = when the address isn't linked
+        // = * it raises a not found error
+        let handle_result = handle(&mut deps, mock_env("frump", &[]), unlink_address);
+        assert_eq!(
+            handle_result.unwrap_err(),
+            StdError::not_found("Linked address")
+        );
+    }
+
+    #[test]
+    fn test_try_lock() {
+        let (_init_result, mut deps) = init_helper();
+        let height: u64 = mock_env(mock_user_address(), &[]).block.height;
+        let lock = |until_height: u64| HandleMsg::Lock {
+            alias: "Epstein didn't kill himself".to_string(),
+            until_height,
+            padding: None,
+        };
+        let transfer = HandleMsg::Transfer {
+            alias: "Epstein didn't kill himself".to_string(),
+            recipient: HumanAddr::from("nail biter"),
+            recipient_code_hash: None,
+            msg: None,
+            padding: None,
+        };
+
+        // = when someone other than the owner locks the alias
+        // = * it raises an error
+        let handle_result = handle(
+            &mut deps,
+            mock_env(mock_user_address(), &[]),
+            lock(height + 100),
+        );
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, "Unauthorized");
+
+        // = when the lock doesn't end after the current block
+        // = * it raises an error
+        let handle_result = handle(&mut deps, mock_env("frump", &[]), lock(height));
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, "A lock must end after the current block");
+
+        // = when the lock is too long
+        // = * it raises an error
+        let handle_result = handle(
+            &mut deps,
+            mock_env("frump", &[]),
+            lock(height + MAX_LOCK_BLOCKS + 1),
+        );
+        let error = extract_error_msg(handle_result);
+        assert_eq!(
+            error,
+            format!(
+                "An alias can be locked for at most {} blocks",
+                MAX_LOCK_BLOCKS
+            )
+        );
+
+        // = when the owner locks the alias
+        let handle_result = handle(&mut deps, mock_env("frump", &[]), lock(height + 100));
+        assert_eq!(
+            handle_result.unwrap().data,
+            Some(to_binary(&HandleAnswer::Lock { status: Success }).unwrap())
+        );
+
+        // = * the lock can't be shortened
+        let handle_result = handle(&mut deps, mock_env("frump", &[]), lock(height + 50));
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, "A lock can't be shortened");
+
+        // = * the alias can't be transferred until the lock ends
+        let mut env = mock_env("frump", &[]);
+        env.block.height = height + 99;
+        let handle_result = handle(&mut deps, env, transfer.clone());
+        let error = extract_error_msg(handle_result);
+        assert_eq!(
+            error,
+            format!("Alias is locked until block {}", height + 100)
+        );
+        let mut env = mock_env("frump", &[]);
+        env.block.height = height + 100;
+        handle(&mut deps, env, transfer).unwrap();
+        assert_eq!(
+            LocksReadonlyStorage::from_storage(&deps.storage)
+                .get_locked_until(b"epstein didn't kill himself"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_try_make_offer() {
+        let (_init_result, mut deps) = init_helper();
+        let alias = "Epstein didn't kill himself";
+        let make_offer = HandleMsg::MakeOffer {
+            alias: alias.to_string(),
+            padding: None,
+        };
+        let accept_offer = |bidder: &str| HandleMsg::AcceptOffer {
+            alias: alias.to_string(),
+            bidder: HumanAddr::from(bidder),
+            padding: None,
+        };
+        let offers_of = |deps: &Extern<MockStorage, MockApi, MockQuerier>| -> Vec<Offer> {
+            let query_response = query(
+                deps,
+                QueryMsg::Offers {
+                    alias: alias.to_string(),
+                },
+            )
+            .unwrap();
+            match from_binary(&query_response).unwrap() {
+                QueryAnswer::Offers { offers } => offers,
+                _ => panic!("Unexpected query answer"),
+            }
+        };
+
+        // = when the alias does not exist
+        // = * it raises an error
+        let handle_result = handle(
+            &mut deps,
+            mock_env("bidder", &coins(500, "uscrt")),
+            HandleMsg::MakeOffer {
+                alias: "nobody".to_string(),
+                padding: None,
+            },
+        );
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, "Alias not found");
+
+        // = when the owner makes an offer on their own alias
+        // = * it raises an error
+        let handle_result = handle(
+            &mut deps,
+            mock_env("frump", &coins(500, "uscrt")),
+            make_offer.clone(),
+        );
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, "You can't make an offer on your own alias");
+
+        // = when someone makes an offer
+        // = * it holds the funds as an offer on the alias
+        let env = mock_env("bidder", &coins(500, "uscrt"));
+        let handle_result = handle(&mut deps, env.clone(), make_offer.clone()).unwrap();
+        assert_eq!(
+            handle_result.data,
+            Some(to_binary(&HandleAnswer::MakeOffer { status: Success }).unwrap())
+        );
+        assert_eq!(
+            offers_of(&deps),
+            vec![Offer {
+                bidder: HumanAddr::from("bidder"),
+                asset: Asset::Native {
+                    denom: "uscrt".to_string(),
+                },
+                amount: Uint128(500),
+                made_at: env.block.height,
+                expires_at: env.block.time + OFFER_DURATION,
+            }]
+        );
+        // = * the bidder can't make a second offer
+        let handle_result = handle(
+            &mut deps,
+            mock_env("bidder", &coins(600, "uscrt")),
+            make_offer.clone(),
+        );
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, "You already have an offer on this alias");
+
+        // = when the owner accepts an offer that wasn't made
+        // = * it raises an error
+        let handle_result = handle(&mut deps, mock_env("frump", &[]), accept_offer("nobody"));
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, "Offer not found");
+
+        // = when someone other than the owner accepts an offer
+        // = * it raises an error
+        let handle_result = handle(&mut deps, mock_env("bidder", &[]), accept_offer("bidder"));
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, "Unauthorized");
+
+        // = when the bidder withdraws their offer
+        // = * it refunds the bidder
+        let env = mock_env("bidder", &[]);
+        let handle_result = handle(
+            &mut deps,
+            env.clone(),
+            HandleMsg::WithdrawOffer {
+                alias: alias.to_string(),
+                padding: None,
+            },
+        )
+        .unwrap();
+        assert_eq!(
+            handle_result.messages,
+            vec![CosmosMsg::Bank(BankMsg::Send {
+                from_address: env.contract.address,
+                to_address: HumanAddr::from("bidder"),
+                amount: coins(500, "uscrt"),
+            })]
+        );
+        assert!(offers_of(&deps).is_empty());
+
+        // = when the owner accepts an offer that has expired
+        // = * it raises an error
+        handle(
+            &mut deps,
+            mock_env("bidder", &coins(700, "uscrt")),
+            make_offer.clone(),
+        )
+        .unwrap();
+        let mut env = mock_env("frump", &[]);
+        env.block.time += OFFER_DURATION;
+        let handle_result = handle(&mut deps, env, accept_offer("bidder"));
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, "Offer has expired");
+
+        // = when the bidder makes a new offer after theirs has expired
+        // = * it refunds the expired offer and holds the new one
+        let mut env = mock_env("bidder", &coins(800, "uscrt"));
+        env.block.time += OFFER_DURATION;
+        let handle_result = handle(&mut deps, env.clone(), make_offer).unwrap();
+        assert_eq!(
+            handle_result.messages,
+            vec![CosmosMsg::Bank(BankMsg::Send {
+                from_address: env.contract.address.clone(),
+                to_address: HumanAddr::from("bidder"),
+                amount: coins(700, "uscrt"),
+            })]
+        );
+        assert_eq!(
+            offers_of(&deps),
+            vec![Offer {
+                bidder: HumanAddr::from("bidder"),
+                asset: Asset::Native {
+                    denom: "uscrt".to_string(),
+                },
+                amount: Uint128(800),
+                made_at: env.block.height,
+                expires_at: env.block.time + OFFER_DURATION,
+            }]
+        );
+
+        // = when the owner accepts an offer
+        // = * it transfers the alias to the bidder and pays the owner
+        let env = mock_env("frump", &[]);
+        let handle_result = handle(&mut deps, env.clone(), accept_offer("bidder")).unwrap();
+        assert_eq!(
+            handle_result.messages,
+            vec![CosmosMsg::Bank(BankMsg::Send {
+                from_address: env.contract.address,
+                to_address: HumanAddr::from("frump"),
+                amount: coins(800, "uscrt"),
+            })]
+        );
+        let alias_object: Alias = RegistryReadonlyStorage::from_storage(&deps.storage, &deps.api)
+            .get_alias(alias.to_lowercase().as_bytes())
+            .unwrap();
+        assert_eq!(
+            alias_object.owner,
+            deps.api
+                .canonical_address(&HumanAddr::from("bidder"))
+                .unwrap()
+        );
+        assert!(offers_of(&deps).is_empty());
+    }
+
+    #[test]
+    fn test_try_attest() {
+        let alias: &str = "epstein didn't kill himself";
+        let oracle: HumanAddr = HumanAddr::from("oracle");
+        let (_init_result, mut deps) = init_helper();
+        let attest_message = HandleMsg::Attest {
+            alias: alias.to_string(),
+            platform: "Twitter".to_string(),
+            handle: "@frump".to_string(),
+            padding: None,
+        };
+
+        // = when sender is not an approved oracle
+        // = * it raises an error
+        let handle_result = handle(
+            &mut deps,
+            mock_env(oracle.clone(), &[]),
+            attest_message.clone(),
+        );
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, "Unauthorized");
+
+        // = when a non-admin tries to approve an oracle
+        // = * it raises an error
+        let add_oracle_message = HandleMsg::AddOracle {
+            address: oracle.clone(),
+            padding: None,
+        };
+        let handle_result = handle(
+            &mut deps,
+            mock_env(oracle.clone(), &[]),
+            add_oracle_message.clone(),
+        );
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, "Unauthorized");
+
+        // = when an approved oracle attests
+        handle(
+            &mut deps,
+            mock_env(mock_user_address(), &[]),
+            add_oracle_message,
+        )
+        .unwrap();
+        handle(
+            &mut deps,
+            mock_env(oracle.clone(), &[]),
+            attest_message.clone(),
+        )
+        .unwrap();
+        // = * the attestation is returned with the alias metadata
+        let search_response = query(
+            &deps,
+            QueryMsg::Search {
+                search_type: "alias".to_string(),
+                search_value: alias.to_string(),
+                include_metadata: Some(true),
+                viewing_key: None,
+            },
+        )
+        .unwrap();
+        let val: SearchResponse = from_binary(&search_response).unwrap();
+        let env = mock_env(oracle.clone(), &[]);
+        assert_eq!(
+            val.metadata.unwrap().attestations,
+            vec![Attestation {
+                platform: "twitter".to_string(),
+                handle: "@frump".to_string(),
+                oracle: oracle.clone(),
+                block_height: env.block.height,
+            }]
+        );
+
+        // = when someone other than the owner or an oracle revokes the attestation
+        // = * it raises an error
+        let revoke_attestation_message = HandleMsg::RevokeAttestation {
+            alias: alias.to_string(),
+            platform: "twitter".to_string(),
+            padding: None,
+        };
+        let handle_result = handle(
+            &mut deps,
+            mock_env(mock_user_address(), &[]),
+            revoke_attestation_message.clone(),
+        );
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, "Unauthorized");
+
+        // = when the owner revokes the attestation
+        // = * the attestation is removed
+        handle(
+            &mut deps,
+            mock_env(HumanAddr::from("frump"), &[]),
+            revoke_attestation_message.clone(),
+        )
+        .unwrap();
+        let search_response = query(
+            &deps,
+            QueryMsg::Search {
+                search_type: "alias".to_string(),
+                search_value: alias.to_string(),
+                include_metadata: Some(true),
+                viewing_key: None,
+            },
+        )
+        .unwrap();
+        let val: SearchResponse = from_binary(&search_response).unwrap();
+        assert_eq!(val.metadata.unwrap().attestations, vec![]);
+
+        // = when the attestation does not exist
+        // = * it raises an error
+        let handle_result = handle(
+            &mut deps,
+            mock_env(oracle.clone(), &[]),
+            revoke_attestation_message,
+        );
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, "Attestation not found");
+
+        // = when the alias is frozen
+        // = * it can't be attested
+        handle(
+            &mut deps,
+            mock_env(HumanAddr::from("frump"), &[]),
+            HandleMsg::Freeze {
+                alias: alias.to_string(),
+                allow_transfers: true,
+                padding: None,
+            },
+        )
+        .unwrap();
+        let handle_result = handle(&mut deps, mock_env(oracle, &[]), attest_message);
+        assert_eq!(extract_error_msg(handle_result), "Alias is frozen");
+    }
+
+    #[test]
+    fn test_try_claim_airdrop() {
+        let (_init_result, mut deps) = init_helper();
+        let leaf = |alias: &str, address: &str| -> [u8; 32] {
+            sha_256(&[alias.as_bytes(), &[0], address.as_bytes()].concat())
+        };
+        let satoshi_leaf: [u8; 32] = leaf("satoshi", "claimer");
+        let vitalik_leaf: [u8; 32] = leaf("vitalik", "other");
+        let merkle_root: [u8; 32] = if satoshi_leaf <= vitalik_leaf {
+            sha_256(&[satoshi_leaf, vitalik_leaf].concat())
+        } else {
+            sha_256(&[vitalik_leaf, satoshi_leaf].concat())
+        };
+        let claim_airdrop = |alias: &str| HandleMsg::ClaimAirdrop {
+            alias: alias.to_string(),
+            proof: vec![Binary(vitalik_leaf.to_vec())],
+            avatar_url: None,
+            padding: None,
+        };
+        let set_airdrop_merkle_root = |merkle_root: &[u8]| HandleMsg::SetAirdropMerkleRoot {
+            merkle_root: Some(Binary(merkle_root.to_vec())),
+            padding: None,
+        };
+
+        // = when there is no airdrop
+        // = * it raises an error
+        let handle_result = handle(
+            &mut deps,
+            mock_env("claimer", &[]),
+            claim_airdrop("Satoshi"),
+        );
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, "There is no airdrop");
+
+        // = when someone other than the admin sets the merkle root
+        // = * it raises an error
+        let handle_result = handle(
+            &mut deps,
+            mock_env("claimer", &[]),
+            set_airdrop_merkle_root(&merkle_root),
+        );
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, "Unauthorized");
+
+        // = when the merkle root isn't a sha256 hash
+        // = * it raises an error
+        let handle_result = handle(
+            &mut deps,
+            mock_env(mock_user_address(), &[]),
+            set_airdrop_merkle_root(b"root"),
+        );
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, "Merkle root must be a sha256 hash");
+
+        // = when the admin sets the merkle root
+        // = * it saves it
+        let handle_result = handle(
+            &mut deps,
+            mock_env(mock_user_address(), &[]),
+            set_airdrop_merkle_root(&merkle_root),
+        )
+        .unwrap();
+        assert_eq!(
+            handle_result.data,
+            Some(to_binary(&HandleAnswer::SetAirdropMerkleRoot { status: Success }).unwrap())
+        );
+
+        // = when the alias and address aren't in the airdrop
+        // = * it raises an error
+        let handle_result = handle(
+            &mut deps,
+            mock_env("claimer", &[]),
+            claim_airdrop("vitalik"),
+        );
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, "Proof is invalid");
+
+        // = when the alias is claimed with a valid proof
+        // = * it registers the alias to the claimant without a fee
+        let handle_result = handle(
+            &mut deps,
+            mock_env("claimer", &[]),
+            claim_airdrop("Satoshi"),
+        )
+        .unwrap();
+        assert_eq!(
+            handle_result.data,
+            Some(to_binary(&HandleAnswer::ClaimAirdrop { status: Success }).unwrap())
+        );
+        assert!(handle_result.messages.is_empty());
+        let alias_object: Alias = RegistryReadonlyStorage::from_storage(&deps.storage, &deps.api)
+            .get_alias(b"satoshi")
+            .unwrap();
+        assert_eq!(
+            alias_object.owner,
+            deps.api
+                .canonical_address(&HumanAddr::from("claimer"))
+                .unwrap()
+        );
+
+        // = when the alias is claimed again after being destroyed
+        // = * it raises an error
+        handle(
+            &mut deps,
+            mock_env("claimer", &[]),
+            HandleMsg::Destroy {
+                alias: "satoshi".to_string(),
+                padding: None,
+            },
+        )
+        .unwrap();
+        let handle_result = handle(
+            &mut deps,
+            mock_env("claimer", &[]),
+            claim_airdrop("satoshi"),
+        );
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, "Airdrop has already been claimed");
+    }
+
+    #[test]
+    fn test_try_buy_drop() {
+        let (_init_result, mut deps) = init_helper();
+        let height: u64 = mock_env(mock_user_address(), &[]).block.height;
+        let drop = Drop {
+            start_height: height + 10,
+            start_price: Uint128(AMOUNT_FOR_TRANSACTION * 100),
+            end_price: Uint128(AMOUNT_FOR_TRANSACTION * 10),
+            decay_blocks: 90,
+        };
+        let schedule_drop = |alias: &str| HandleMsg::ScheduleDrop {
+            alias: alias.to_string(),
+            drop: drop.clone(),
+            padding: None,
+        };
+        let buy_drop = |amount: u128| HandleMsg::Receive {
+            sender: HumanAddr::from("buyer"),
+            from: HumanAddr::from("buyer"),
+            amount: Uint128(amount),
+            msg: to_binary(&ReceiveMsg::BuyDrop {
+                alias: "Premium".to_string(),
+            })
+            .unwrap(),
+            padding: None,
+        };
+        let buttcoin_env_at = |height: u64| {
+            let mut env = mock_env(mock_buttcoin().address, &[]);
+            env.block.height = height;
+            env
+        };
+        handle(
+            &mut deps,
+            mock_env(mock_user_address(), &[]),
+            HandleMsg::ReserveAliases {
+                aliases: vec!["premium".to_string()],
+                padding: None,
+            },
+        )
+        .unwrap();
+
+        // = when someone other than the admin schedules a drop
+        // = * it raises an error
+        let handle_result = handle(&mut deps, mock_env("buyer", &[]), schedule_drop("premium"));
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, "Unauthorized");
+
+        // = when the alias isn't reserved
+        // = * it raises an error
+        let handle_result = handle(
+            &mut deps,
+            mock_env(mock_user_address(), &[]),
+            schedule_drop("ordinary"),
+        );
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, "Only reserved aliases can be dropped");
+
+        // = when the admin schedules a drop of a reserved alias
+        // = * it saves the drop
+        let handle_result = handle(
+            &mut deps,
+            mock_env(mock_user_address(), &[]),
+            schedule_drop("premium"),
+        )
+        .unwrap();
+        assert_eq!(
+            handle_result.data,
+            Some(to_binary(&HandleAnswer::ScheduleDrop { status: Success }).unwrap())
+        );
+        let query_answer: QueryAnswer = from_binary(
+            &query(
+                &deps,
+                QueryMsg::Drop {
+                    alias: "premium".to_string(),
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        match query_answer {
+            QueryAnswer::Drop { drop: saved_drop } => assert_eq!(saved_drop, Some(drop.clone())),
+            _ => panic!("Unexpected query answer"),
+        }
+
+        // = when buying before the drop starts
+        // = * it raises an error
+        let handle_result = handle(
+            &mut deps,
+            buttcoin_env_at(height),
+            buy_drop(AMOUNT_FOR_TRANSACTION * 100),
+        );
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, format!("Drop starts at block {}", height + 10));
+
+        // = when buying with something other than Buttcoin
+        // = * it raises an error
+        let mut env = buttcoin_env_at(height + 10);
+        env.message.sender = HumanAddr::from("other-token");
+        let handle_result = handle(&mut deps, env, buy_drop(AMOUNT_FOR_TRANSACTION * 100));
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, "Drops are paid for in Buttcoin");
+
+        // = when less than the current price is sent
+        // = * it raises an error
+        // Halfway through the decay the price is halfway between the start and end prices
+        let handle_result = handle(
+            &mut deps,
+            buttcoin_env_at(height + 55),
+            buy_drop(AMOUNT_FOR_TRANSACTION * 54),
+        );
+        let error = extract_error_msg(handle_result);
+        assert_eq!(
+            error,
+            format!(
+                "Amount sent in: {}. Amount required {}.",
+                AMOUNT_FOR_TRANSACTION * 54,
+                AMOUNT_FOR_TRANSACTION * 55
+            )
+        );
+
+        // = when the current price or more is sent
+        // = * it registers the alias to the buyer, sends the price to the BUTT lode and refunds
+        // the rest
+        let handle_result = handle(
+            &mut deps,
+            buttcoin_env_at(height + 55),
+            buy_drop(AMOUNT_FOR_TRANSACTION * 60),
+        )
+        .unwrap();
+        assert_eq!(
+            handle_result.messages,
+            vec![
+                snip20::transfer_msg(
+                    HumanAddr::from("buyer"),
+                    Uint128(AMOUNT_FOR_TRANSACTION * 5),
+                    None,
+                    BLOCK_SIZE,
+                    mock_buttcoin().contract_hash,
+                    mock_buttcoin().address,
+                )
+                .unwrap(),
+                snip20::transfer_msg(
+                    mock_butt_lode().address,
+                    Uint128(AMOUNT_FOR_TRANSACTION * 55),
+                    None,
+                    BLOCK_SIZE,
+                    mock_buttcoin().contract_hash,
+                    mock_buttcoin().address,
+                )
+                .unwrap(),
+            ]
+        );
+        let alias_object: Alias = RegistryReadonlyStorage::from_storage(&deps.storage, &deps.api)
+            .get_alias(b"premium")
+            .unwrap();
+        assert_eq!(alias_object.display_name, "Premium");
+        assert!(
+            !ReservedAliasesReadonlyStorage::from_storage(&deps.storage).is_reserved(b"premium")
+        );
+
+        // = * the drop is over
+        let handle_result = handle(
+            &mut deps,
+            buttcoin_env_at(height + 55),
+            buy_drop(AMOUNT_FOR_TRANSACTION * 60),
+        );
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, "Drop not found");
+    }
+
+    #[test]
+    fn test_try_claim_cross_chain() {
+        use k256::ecdsa::recoverable;
+        use k256::ecdsa::signature::DigestSigner;
+        use k256::ecdsa::SigningKey;
+        use sha3::{Digest, Keccak256};
+
+        let (_init_result, mut deps) = init_helper();
+        let height: u64 = mock_env(mock_user_address(), &[]).block.height;
+        let signing_key: SigningKey = SigningKey::from_bytes(&[7; 32]).unwrap();
+        let owner: String = ethereum_address(&signing_key.verifying_key());
+        let sign = |signing_key: &SigningKey, alias: &str| -> Binary {
+            let message: String = format!("Claim {} for claimer on cosmos2contract", alias);
+            let signature: recoverable::Signature = signing_key
+                .sign_digest(Keccak256::new().chain(&ethereum_signed_message(message.as_bytes())));
+            let mut signature: Vec<u8> = signature.as_ref().to_vec();
+            signature[64] += 27;
+            Binary(signature)
+        };
+        // A snapshot of one name is a tree whose root is its leaf
+        let merkle_root: [u8; 32] =
+            sha_256(&["vitalik".as_bytes(), &[0], owner.as_bytes()].concat());
+        let claim_cross_chain = |signature: Binary| HandleMsg::ClaimCrossChain {
+            alias: "Vitalik".to_string(),
+            signature,
+            proof: vec![],
+            avatar_url: None,
+            padding: None,
+        };
+        let set_cross_chain_snapshot = |ends_at: u64| HandleMsg::SetCrossChainSnapshot {
+            cross_chain_snapshot: Some(CrossChainSnapshot {
+                merkle_root: Binary(merkle_root.to_vec()),
+                ends_at,
+            }),
+            padding: None,
+        };
+
+        // = when there is no snapshot
+        // = * it raises an error
+        let handle_result = handle(
+            &mut deps,
+            mock_env("claimer", &[]),
+            claim_cross_chain(sign(&signing_key, "vitalik")),
+        );
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, "There are no cross-chain claims");
+
+        // = when someone other than the admin sets the snapshot
+        // = * it raises an error
+        let handle_result = handle(
+            &mut deps,
+            mock_env("claimer", &[]),
+            set_cross_chain_snapshot(height + 100),
+        );
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, "Unauthorized");
+
+        // = when the snapshot has ended
+        // = * it raises an error
+        handle(
+            &mut deps,
+            mock_env(mock_user_address(), &[]),
+            set_cross_chain_snapshot(height),
+        )
+        .unwrap();
+        let handle_result = handle(
+            &mut deps,
+            mock_env("claimer", &[]),
+            claim_cross_chain(sign(&signing_key, "vitalik")),
+        );
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, "Cross-chain claims have ended");
+
+        // = when the signer doesn't own the name on the other chain
+        // = * it raises an error
+        handle(
+            &mut deps,
+            mock_env(mock_user_address(), &[]),
+            set_cross_chain_snapshot(height + 100),
+        )
+        .unwrap();
+        let other_signing_key: SigningKey = SigningKey::from_bytes(&[8; 32]).unwrap();
+        let handle_result = handle(
+            &mut deps,
+            mock_env("claimer", &[]),
+            claim_cross_chain(sign(&other_signing_key, "vitalik")),
+        );
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, "Proof is invalid");
+
+        // = when the signature is malformed
+        // = * it raises an error
+        let handle_result = handle(
+            &mut deps,
+            mock_env("claimer", &[]),
+            claim_cross_chain(Binary(vec![1; 64])),
+        );
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, "Signature is invalid");
+
+        // = when the owner on the other chain signs the claim
+        // = * it registers the alias to the claimant
+        let handle_result = handle(
+            &mut deps,
+            mock_env("claimer", &[]),
+            claim_cross_chain(sign(&signing_key, "vitalik")),
+        )
+        .unwrap();
+        assert_eq!(
+            handle_result.data,
+            Some(to_binary(&HandleAnswer::ClaimCrossChain { status: Success }).unwrap())
+        );
+        let alias_object: Alias = RegistryReadonlyStorage::from_storage(&deps.storage, &deps.api)
+            .get_alias(b"vitalik")
+            .unwrap();
+        assert_eq!(
+            alias_object.owner,
+            deps.api
+                .canonical_address(&HumanAddr::from("claimer"))
+                .unwrap()
+        );
+
+        // = when the alias is claimed again after being destroyed
+        // = * it raises an error
+        handle(
+            &mut deps,
+            mock_env("claimer", &[]),
+            HandleMsg::Destroy {
+                alias: "vitalik".to_string(),
+                padding: None,
+            },
+        )
+        .unwrap();
+        let handle_result = handle(
+            &mut deps,
+            mock_env("claimer", &[]),
+            claim_cross_chain(sign(&signing_key, "vitalik")),
+        );
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, "Alias has already been claimed");
+    }
+
+    #[test]
+    fn test_try_claim_gift() {
+        let (_init_result, mut deps) = init_helper();
+        let claim_gift = |code: &str| HandleMsg::ClaimGift {
+            alias: "Birthday".to_string(),
+            code: code.to_string(),
+            avatar_url: None,
+            padding: None,
+        };
+
+        // = when the code hash isn't a sha256 hash
+        // = * it raises an error
+        let receive_msg = |code_hash: Binary| HandleMsg::Receive {
+            sender: HumanAddr::from("giver"),
+            from: HumanAddr::from("giver"),
+            amount: Uint128(AMOUNT_FOR_TRANSACTION),
+            msg: to_binary(&ReceiveMsg::CreateGift {
+                alias: "Birthday".to_string(),
+                code_hash,
+            })
+            .unwrap(),
+            padding: None,
+        };
+        let handle_result = handle(
+            &mut deps,
+            mock_env(mock_buttcoin().address, &[]),
+            receive_msg(Binary(b"secret".to_vec())),
+        );
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, "Code hash must be a sha256 hash");
+
+        // = when a gift is paid for
+        // = * it passes the fee on to the butt lode
+        let handle_result = handle(
+            &mut deps,
+            mock_env(mock_buttcoin().address, &[]),
+            receive_msg(Binary(sha_256(b"secret").to_vec())),
+        )
+        .unwrap();
+        assert_eq!(
+            handle_result.data,
+            Some(to_binary(&ReceiveAnswer::CreateGift { status: Success }).unwrap())
+        );
+        assert_eq!(handle_result.messages.len(), 1);
+        // = * the alias can't be registered by anyone else
+        let handle_result = handle(
+            &mut deps,
+            mock_env(mock_buttcoin().address, &[]),
+            HandleMsg::Receive {
+                sender: HumanAddr::from("sniper"),
+                from: HumanAddr::from("sniper"),
+                amount: Uint128(AMOUNT_FOR_TRANSACTION),
+                msg: to_binary(&ReceiveMsg::Create {
+                    alias: "birthday".to_string(),
+                    avatar_url: None,
+                    avatar_metadata: None,
+                    records: None,
+                    fallback: None,
+                })
+                .unwrap(),
+                padding: None,
+            },
+        );
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, "Alias has already been taken");
+
+        // = when the code is wrong
+        // = * it raises an error
+        let handle_result = handle(&mut deps, mock_env("friend", &[]), claim_gift("guess"));
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, "Gift not found");
+
+        // = when the claimant already has an alias
+        // = * it raises an error
+        let handle_result = handle(&mut deps, mock_env("frump", &[]), claim_gift("secret"));
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, "Address already has an alias");
+
+        // = when the code is right
+        // = * it registers the alias to the claimant
+        let handle_result =
+            handle(&mut deps, mock_env("friend", &[]), claim_gift("secret")).unwrap();
+        assert_eq!(
+            handle_result.data,
+            Some(to_binary(&HandleAnswer::ClaimGift { status: Success }).unwrap())
+        );
+        let alias_object: Alias = RegistryReadonlyStorage::from_storage(&deps.storage, &deps.api)
+            .get_alias(b"birthday")
+            .unwrap();
+        assert_eq!(alias_object.display_name, "Birthday");
+        assert_eq!(
+            alias_object.owner,
+            deps.api
+                .canonical_address(&HumanAddr::from("friend"))
+                .unwrap()
+        );
+        // = * the gift can't be claimed again
+        let handle_result = handle(&mut deps, mock_env("friend", &[]), claim_gift("secret"));
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, "Gift not found");
+    }
+
+    #[test]
+    fn test_try_create() {
+        let alias = "   nail biter    ";
+        let avatar_url = "https://www.btn.group";
+
+        // Initialize
+        let (_init_result, mut deps) = init_helper();
+
+        // when creating an alias
+        let create_alias_message = ReceiveMsg::Create {
+            alias: alias.to_string(),
+            avatar_url: Some(avatar_url.to_string()),
+            avatar_metadata: None,
+            records: None,
+            fallback: None,
+        };
+        let receive_msg = HandleMsg::Receive {
+            sender: mock_user_address(),
+            from: mock_user_address(),
+            amount: Uint128(AMOUNT_FOR_TRANSACTION),
+            msg: to_binary(&create_alias_message).unwrap(),
+            padding: None,
+        };
+
+        // = when user sends in a token that is not buttcoin
+        // = * it raises an error
+        let handle_result = handle(
+            &mut deps,
+            mock_env(mock_user_address(), &[]),
+            receive_msg.clone(),
+        );
+        assert_eq!(
+            handle_result.unwrap_err(),
+            StdError::Unauthorized { backtrace: None }
+        );
+
+        // = when user sends in buttcoin
+        let handle_result = handle(
+            &mut deps,
+            mock_env(mock_buttcoin().address, &[]),
+            receive_msg.clone(),
+        );
+        let handle_result_unwrapped = handle_result.unwrap();
+
+        // = * it sends the BUTT to the BUTT lode
+        assert_eq!(
+            handle_result_unwrapped.messages,
+            vec![snip20::transfer_msg(
+                mock_butt_lode().address,
+                Uint128(AMOUNT_FOR_TRANSACTION),
+                None,
+                BLOCK_SIZE,
+                mock_buttcoin().contract_hash,
+                mock_buttcoin().address,
+            )
+            .unwrap()],
+        );
+
+        // = * It creates alias without trailing and leading whitespaces
+        let search_response = query(
+            &mut deps,
+            QueryMsg::Search {
+                search_type: "alias".to_string(),
+                search_value: "nail biter".to_string(),
+                include_metadata: None,
+                viewing_key: None,
+            },
+        )
+        .unwrap();
+        let val: SearchResponse = from_binary(&search_response).unwrap();
+        assert_eq!(mock_user_address(), val.attributes.clone().address);
+        assert_eq!(
+            avatar_url,
+            val.attributes.clone().avatar_url.unwrap().to_string()
+        );
+        let search_response = query(
+            &mut deps,
+            QueryMsg::Search {
+                search_type: "address".to_string(),
+                search_value: mock_user_address().to_string(),
+                include_metadata: None,
+                viewing_key: None,
+            },
+        )
+        .unwrap();
+        let val: SearchResponse = from_binary(&search_response).unwrap();
+        assert_eq!("nail biter", val.attributes.clone().alias.to_string());
+        assert_eq!(mock_user_address(), val.attributes.clone().address);
+        assert_eq!(
+            avatar_url,
+            val.attributes.clone().avatar_url.unwrap().to_string()
+        );
+        // = * it stores the alias under the hash of the alias
+        let aliases_storage = ReadonlyPrefixedStorage::new(ALIASES_PREFIX, &deps.storage);
+        assert!(aliases_storage.get(b"nail biter").is_none());
+        assert!(aliases_storage.get(&sha_256(b"nail biter")).is_some());
+        let alias_object: Alias = RegistryReadonlyStorage::from_storage(&deps.storage, &deps.api)
+            .get_alias(b"nail biter")
+            .unwrap();
+        assert_eq!(alias_object.alias, "nail biter");
+
+        // = when alias already exists
+        let create_alias_message_two = ReceiveMsg::Create {
+            alias: "Epstein didn't kill himself".to_string(),
+            avatar_url: Some(avatar_url.to_string()),
+            avatar_metadata: None,
+            records: None,
+            fallback: None,
+        };
+        let receive_msg_two = HandleMsg::Receive {
+            sender: HumanAddr::from("crump"),
+            from: HumanAddr::from("crump"),
+            amount: Uint128(AMOUNT_FOR_TRANSACTION),
+            msg: to_binary(&create_alias_message_two).unwrap(),
+            padding: None,
+        };
+        let handle_result = handle(
+            &mut deps,
+            mock_env(mock_buttcoin().address, &[]),
+            receive_msg_two,
+        );
+
+        // = * it raises an error
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, "Alias has already been taken");
+
+        // = when alias exists but is a different case
+        let create_alias_message = ReceiveMsg::Create {
+            alias: alias.to_uppercase().to_string(),
+            avatar_url: None,
+            avatar_metadata: None,
+            records: None,
+            fallback: None,
+        };
+        let receive_msg = HandleMsg::Receive {
+            sender: mock_user_address(),
+            from: mock_user_address(),
+            amount: Uint128(AMOUNT_FOR_TRANSACTION),
+            msg: to_binary(&create_alias_message).unwrap(),
+            padding: None,
+        };
+
+        // = * it raises an error
+        let response = handle(
+            &mut deps,
+            mock_env(mock_buttcoin().address, &[]),
+            receive_msg,
+        );
+        let error = extract_error_msg(response);
+        assert_eq!(error, "Alias has already been taken");
+
+        // = when alias is too long
+        let alias = "Epstein didn't kill himself".repeat(20);
+        let create_alias_message = ReceiveMsg::Create {
+            alias: alias.to_uppercase().to_string(),
+            avatar_url: None,
+            avatar_metadata: None,
+            records: None,
+            fallback: None,
+        };
+        let receive_msg = HandleMsg::Receive {
+            sender: mock_user_address(),
+            from: mock_user_address(),
+            amount: Uint128(AMOUNT_FOR_TRANSACTION),
+            msg: to_binary(&create_alias_message).unwrap(),
+            padding: None,
+        };
+
+        // = * it raises an error
+        let response = handle(
+            &mut deps,
+            mock_env(mock_buttcoin().address, &[]),
+            receive_msg,
+        );
+        let error = extract_error_msg(response);
+        assert_eq!(error, "Alias is too long");
+
+        // = when alias is a bech32 address
+        // = * it raises an error
+        let create_alias_message = ReceiveMsg::Create {
+            alias: "Secret1qypqxpq9qcrsszg2pvxq6rs0zqg3yyc5a8chmq".to_string(),
+            avatar_url: None,
+            avatar_metadata: None,
+            records: None,
+            fallback: None,
+        };
+        let receive_msg = HandleMsg::Receive {
+            sender: mock_user_address(),
+            from: mock_user_address(),
+            amount: Uint128(AMOUNT_FOR_TRANSACTION),
+            msg: to_binary(&create_alias_message).unwrap(),
+            padding: None,
+        };
+        let response = handle(
+            &mut deps,
+            mock_env(mock_buttcoin().address, &[]),
+            receive_msg,
+        );
+        assert_eq!(extract_error_msg(response), "Alias can't be an address");
+
+        // = when user already has an alias
+        // = * it raises an error
+        let alias = "Epstein didn't kill himself".repeat(5);
+        let create_alias_message = ReceiveMsg::Create {
+            alias: alias.to_string(),
+            avatar_url: None,
+            avatar_metadata: None,
+            records: None,
+            fallback: None,
+        };
+        let receive_msg = HandleMsg::Receive {
+            sender: HumanAddr::from("frump"),
+            from: HumanAddr::from("frump"),
+            amount: Uint128(AMOUNT_FOR_TRANSACTION),
+            msg: to_binary(&create_alias_message).unwrap(),
+            padding: None,
+        };
+        let response = handle(
+            &mut deps,
+            mock_env(mock_buttcoin().address, &[]),
+            receive_msg,
+        );
+        let error = extract_error_msg(response);
+        assert_eq!(error, "Address already has an alias");
+
+        // = when the receive msg uses the register key
+        // = * it creates the alias
+        let receive_msg = HandleMsg::Receive {
+            sender: HumanAddr::from("new-user"),
+            from: HumanAddr::from("new-user"),
+            amount: Uint128(AMOUNT_FOR_TRANSACTION),
+            msg: Binary::from(
+                r#"{"register": {"alias": "Registered", "avatar_url": null}}"#.as_bytes(),
+            ),
+            padding: None,
+        };
+        handle(
+            &mut deps,
+            mock_env(mock_buttcoin().address, &[]),
+            receive_msg,
+        )
+        .unwrap();
+        let search_response = query(
+            &deps,
+            QueryMsg::Search {
+                search_type: "address".to_string(),
+                search_value: "new-user".to_string(),
+                include_metadata: None,
+                viewing_key: None,
+            },
+        )
+        .unwrap();
+        let val: SearchResponse = from_binary(&search_response).unwrap();
+        assert_eq!(val.attributes.alias, "registered");
+
+        let avatar_metadata = ImageMetadata {
+            mime_type: Some("image/png".to_string()),
+            width: Some(400),
+            height: Some(400),
+        };
+        let create_alias_message = ReceiveMsg::Create {
+            alias: "pictured".to_string(),
+            avatar_url: None,
+            avatar_metadata: Some(avatar_metadata.clone()),
+            records: None,
+            fallback: None,
+        };
+        let receive_msg = HandleMsg::Receive {
+            sender: HumanAddr::from("pictured-user"),
+            from: HumanAddr::from("pictured-user"),
+            amount: Uint128(AMOUNT_FOR_TRANSACTION),
+            msg: to_binary(&create_alias_message).unwrap(),
+            padding: None,
+        };
+        // = when avatar metadata comes without an avatar url
+        // = * it raises an error
+        let response = handle(
+            &mut deps,
+            mock_env(mock_buttcoin().address, &[]),
+            receive_msg,
+        );
+        let error = extract_error_msg(response);
+        assert_eq!(error, "Avatar metadata requires an avatar url");
+        // = when the avatar mime type is not an image
+        // = * it raises an error
+        let create_alias_message = ReceiveMsg::Create {
+            alias: "pictured".to_string(),
+            avatar_url: Some("https://www.btn.group".to_string()),
+            avatar_metadata: Some(ImageMetadata {
+                mime_type: Some("text/html".to_string()),
+                width: None,
+                height: None,
+            }),
+            records: None,
+            fallback: None,
+        };
+        let receive_msg = HandleMsg::Receive {
+            sender: HumanAddr::from("pictured-user"),
+            from: HumanAddr::from("pictured-user"),
+            amount: Uint128(AMOUNT_FOR_TRANSACTION),
+            msg: to_binary(&create_alias_message).unwrap(),
+            padding: None,
+        };
+        let response = handle(
+            &mut deps,
+            mock_env(mock_buttcoin().address, &[]),
+            receive_msg,
+        );
+        let error = extract_error_msg(response);
+        assert_eq!(error, "Avatar mime type is invalid");
+        // = when avatar metadata comes with an avatar url
+        // = * it stores and returns the metadata
+        let create_alias_message = ReceiveMsg::Create {
+            alias: "pictured".to_string(),
+            avatar_url: Some("https://www.btn.group".to_string()),
+            avatar_metadata: Some(avatar_metadata.clone()),
+            records: None,
+            fallback: None,
+        };
+        let receive_msg = HandleMsg::Receive {
+            sender: HumanAddr::from("pictured-user"),
+            from: HumanAddr::from("pictured-user"),
+            amount: Uint128(AMOUNT_FOR_TRANSACTION),
+            msg: to_binary(&create_alias_message).unwrap(),
+            padding: None,
+        };
+        handle(
+            &mut deps,
+            mock_env(mock_buttcoin().address, &[]),
+            receive_msg,
+        )
+        .unwrap();
+        let search_response = query(
+            &deps,
+            QueryMsg::Search {
+                search_type: "alias".to_string(),
+                search_value: "pictured".to_string(),
+                include_metadata: None,
+                viewing_key: None,
+            },
+        )
+        .unwrap();
+        let val: SearchResponse = from_binary(&search_response).unwrap();
+        assert_eq!(val.attributes.avatar_metadata, Some(avatar_metadata));
+
+        // = when records come with the alias
+        let create_with_records = |records: Vec<Record>| HandleMsg::Receive {
+            sender: HumanAddr::from("recorded-user"),
+            from: HumanAddr::from("recorded-user"),
+            amount: Uint128(AMOUNT_FOR_TRANSACTION),
+            msg: to_binary(&ReceiveMsg::Create {
+                alias: "recorded".to_string(),
+                avatar_url: None,
+                avatar_metadata: None,
+                records: Some(records),
+                fallback: None,
+            })
+            .unwrap(),
+            padding: None,
+        };
+        // = * it raises an error when a key is set more than once
+        let response = handle(
+            &mut deps,
+            mock_env(mock_buttcoin().address, &[]),
+            create_with_records(vec![
+                Record {
+                    key: "url".to_string(),
+                    value: "https://www.btn.group".to_string(),
+                },
+                Record {
+                    key: " URL".to_string(),
+                    value: "https://btn.group".to_string(),
+                },
+            ]),
+        );
+        let error = extract_error_msg(response);
+        assert_eq!(error, "Record url is set more than once");
+        // = * it raises an error when a value is empty and doesn't create the alias
+        let response = handle(
+            &mut deps,
+            mock_env(mock_buttcoin().address, &[]),
+            create_with_records(vec![Record {
+                key: "addr.eth".to_string(),
+                value: " ".to_string(),
+            }]),
+        );
+        let error = extract_error_msg(response);
+        assert_eq!(error, "Record value for addr.eth is invalid");
+        assert!(
+            RegistryReadonlyStorage::from_storage(&deps.storage, &deps.api)
+                .get_alias(b"recorded")
+                .is_none()
+        );
+        // = * it stores the records with the alias
+        handle(
+            &mut deps,
+            mock_env(mock_buttcoin().address, &[]),
+            create_with_records(vec![
+                Record {
+                    key: "URL".to_string(),
+                    value: "https://www.btn.group".to_string(),
+                },
+                Record {
+                    key: "addr.eth".to_string(),
+                    value: "0x0000000000000000000000000000000000000000".to_string(),
+                },
+            ]),
+        )
+        .unwrap();
+        let search_response = query(
+            &deps,
+            QueryMsg::Search {
+                search_type: "alias".to_string(),
+                search_value: "recorded".to_string(),
+                include_metadata: Some(true),
+                viewing_key: None,
+            },
+        )
+        .unwrap();
+        let val: SearchResponse = from_binary(&search_response).unwrap();
+        assert_eq!(
+            val.metadata.unwrap().records,
+            vec![
+                Record {
+                    key: "url".to_string(),
+                    value: "https://www.btn.group".to_string(),
+                },
+                Record {
+                    key: "addr.eth".to_string(),
+                    value: "0x0000000000000000000000000000000000000000".to_string(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_try_create_with_fallback() {
+        let (_init_result, mut deps) = init_helper();
+        let receive = |from: &str, alias: &str, fallback: Option<bool>| HandleMsg::Receive {
+            sender: HumanAddr::from(from),
+            from: HumanAddr::from(from),
+            amount: Uint128(AMOUNT_FOR_TRANSACTION),
+            msg: to_binary(&ReceiveMsg::Create {
+                alias: alias.to_string(),
+                avatar_url: None,
+                avatar_metadata: None,
+                records: None,
+                fallback,
+            })
+            .unwrap(),
+            padding: None,
+        };
+
+        // = when the alias is taken and fallback is not set
+        // = * it raises an error
+        let handle_result = handle(
+            &mut deps,
+            mock_env(mock_buttcoin().address, &[]),
+            receive("nailbiter", "Epstein didn't kill himself", None),
+        );
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, "Alias has already been taken");
+
+        // = when the alias is taken and fallback is set
+        // = * it registers the first available variant and answers with it
+        let handle_result = handle(
+            &mut deps,
+            mock_env(mock_buttcoin().address, &[]),
+            receive("nailbiter", "Epstein didn't kill himself", Some(true)),
+        );
+        match from_binary(&handle_result.unwrap().data.unwrap()).unwrap() {
+            ReceiveAnswer::CreateWithFallback { alias } => {
+                assert_eq!(alias, "epstein didn't kill himself2")
+            }
+            _ => panic!("Unexpected handle answer"),
+        }
+        let alias_storage = RegistryReadonlyStorage::from_storage(&deps.storage, &deps.api);
+        assert_eq!(
+            alias_storage
+                .get_alias(b"epstein didn't kill himself2")
+                .unwrap()
+                .owner,
+            deps.api
+                .canonical_address(&HumanAddr::from("nailbiter"))
+                .unwrap()
+        );
+
+        // = when the alias is an address
+        // = * it raises the error Create raises without the fallback
+        let handle_result = handle(
+            &mut deps,
+            mock_env(mock_buttcoin().address, &[]),
+            receive(
+                "addressed",
+                "Secret1qypqxpq9qcrsszg2pvxq6rs0zqg3yyc5a8chmq",
+                Some(true),
+            ),
+        );
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, "Alias can't be an address");
+    }
+
+    #[test]
+    fn test_try_create_with_case_folding() {
+        let create = |alias: &str, from: &str| HandleMsg::Receive {
+            sender: HumanAddr::from(from),
+            from: HumanAddr::from(from),
+            amount: Uint128(AMOUNT_FOR_TRANSACTION),
+            msg: to_binary(&ReceiveMsg::Create {
+                alias: alias.to_string(),
+                avatar_url: None,
+                avatar_metadata: None,
+                records: None,
+                fallback: None,
+            })
+            .unwrap(),
+            padding: None,
+        };
+        let search = |deps: &Extern<MockStorage, MockApi, MockQuerier>, alias: &str| {
+            query(
+                deps,
+                QueryMsg::Search {
+                    search_type: "alias".to_string(),
+                    search_value: alias.to_string(),
+                    include_metadata: None,
+                    viewing_key: None,
+                },
+            )
+        };
+        let init_with = |case_folding: CaseFolding| {
+            let (_init_result, mut deps) = init_helper();
+            let mut config: Config = TypedStore::attach(&deps.storage).load(CONFIG_KEY).unwrap();
+            config.case_folding = case_folding;
+            TypedStoreMut::attach(&mut deps.storage)
+                .store(CONFIG_KEY, &config)
+                .unwrap();
+            deps
+        };
+
+        // = when the contract folds ASCII only
+        // = * it lowercases A to Z and leaves other letters as they are
+        let mut deps = init_with(CaseFolding::Ascii);
+        handle(
+            &mut deps,
+            mock_env(mock_buttcoin().address, &[]),
+            create("ÉCOLE", "nailbiter"),
+        )
+        .unwrap();
+        let alias_storage = RegistryReadonlyStorage::from_storage(&deps.storage, &deps.api);
+        assert!(alias_storage.get_alias("École".as_bytes()).is_some());
+        // = * queries fold the same way
+        search(&deps, " ÉcOLE").unwrap();
+        let error = extract_query_error(search(&deps, "école"));
+        assert_eq!(error.code, QueryErrorCode::NotFound);
+
+        // = when the contract doesn't fold case
+        // = * aliases differing only in case are different aliases
+        let mut deps = init_with(CaseFolding::None);
+        handle(
+            &mut deps,
+            mock_env(mock_buttcoin().address, &[]),
+            create("NailBiter", "nailbiter"),
+        )
+        .unwrap();
+        handle(
+            &mut deps,
+            mock_env(mock_buttcoin().address, &[]),
+            create("nailbiter", mock_user_address().as_str()),
+        )
+        .unwrap();
+        let val: SearchResponse = from_binary(&search(&deps, "NailBiter").unwrap()).unwrap();
+        assert_eq!(val.attributes.address, HumanAddr::from("nailbiter"));
+        let val: SearchResponse = from_binary(&search(&deps, "nailbiter").unwrap()).unwrap();
+        assert_eq!(val.attributes.address, mock_user_address());
+    }
+
+    #[test]
+    fn test_try_rename() {
+        let avatar_url = "https://www.btn.group";
+        let (_init_result, mut deps) = init_helper();
+        let create_alias_message = ReceiveMsg::Create {
+            alias: "nailbiter".to_string(),
+            avatar_url: Some(avatar_url.to_string()),
+            avatar_metadata: None,
+            records: None,
+            fallback: None,
+        };
+        let receive_msg = HandleMsg::Receive {
+            sender: mock_user_address(),
+            from: mock_user_address(),
+            amount: Uint128(AMOUNT_FOR_TRANSACTION),
+            msg: to_binary(&create_alias_message).unwrap(),
+            padding: None,
+        };
+        handle(
+            &mut deps,
+            mock_env(mock_buttcoin().address, &[]),
+            receive_msg,
+        )
+        .unwrap();
+
+        // = when the alias belongs to someone else
+        // = * it raises an error
+        let rename_alias_message = ReceiveMsg::Rename {
+            from: "nailbiter".to_string(),
+            to: "Nail Biter".to_string(),
+        };
+        let receive_msg = HandleMsg::Receive {
+            sender: HumanAddr::from("frump"),
+            from: HumanAddr::from("frump"),
+            amount: Uint128(AMOUNT_FOR_TRANSACTION),
+            msg: to_binary(&rename_alias_message).unwrap(),
+            padding: None,
+        };
+        let handle_result = handle(
+            &mut deps,
+            mock_env(mock_buttcoin().address, &[]),
+            receive_msg,
+        );
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, "Unauthorized");
+
+        // = when the new alias is taken
+        // = * it raises an error
+        let rename_alias_message = ReceiveMsg::Rename {
+            from: "nailbiter".to_string(),
+            to: "Epstein didn't kill himself".to_string(),
+        };
+        let receive_msg = HandleMsg::Receive {
+            sender: mock_user_address(),
+            from: mock_user_address(),
+            amount: Uint128(AMOUNT_FOR_TRANSACTION),
+            msg: to_binary(&rename_alias_message).unwrap(),
+            padding: None,
+        };
+        let handle_result = handle(
+            &mut deps,
+            mock_env(mock_buttcoin().address, &[]),
+            receive_msg,
+        );
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, "Alias has already been taken");
+
+        // = when registration is closed
+        // = * it raises an error
+        let height: u64 = mock_env(mock_user_address(), &[]).block.height;
+        let rename_with_config = |deps: &mut Extern<MockStorage, MockApi, MockQuerier>,
+                                  config: &Config| {
+            let original_config: Config =
+                TypedStore::attach(&deps.storage).load(CONFIG_KEY).unwrap();
+            TypedStoreMut::attach(&mut deps.storage)
+                .store(CONFIG_KEY, config)
+                .unwrap();
+            let handle_result = handle(
+                deps,
+                mock_env(mock_buttcoin().address, &[]),
+                HandleMsg::Receive {
+                    sender: mock_user_address(),
+                    from: mock_user_address(),
+                    amount: Uint128(AMOUNT_FOR_TRANSACTION),
+                    msg: to_binary(&ReceiveMsg::Rename {
+                        from: "nailbiter".to_string(),
+                        to: "Nail Biter".to_string(),
+                    })
+                    .unwrap(),
+                    padding: None,
+                },
+            );
+            TypedStoreMut::attach(&mut deps.storage)
+                .store(CONFIG_KEY, &original_config)
+                .unwrap();
+            extract_error_msg(handle_result)
+        };
+        let config: Config = TypedStore::attach(&deps.storage).load(CONFIG_KEY).unwrap();
+        let mut launch_config: Config = config.clone();
+        launch_config.launch = Some(Launch {
+            auctions_open_at: height,
+            registration_opens_at: height + 10,
+        });
+        let error = rename_with_config(&mut deps, &launch_config);
+        assert_eq!(error, "Registration hasn't opened yet");
+
+        // = when a lottery is running
+        // = * it raises an error
+        let mut lottery_config: Config = config.clone();
+        lottery_config.lottery_ends_at = Some(height + 10);
+        let error = rename_with_config(&mut deps, &lottery_config);
+        assert_eq!(error, "Registration is closed while the lottery is running");
+
+        // = when a mint phase is running
+        // = * it raises an error
+        let mut mint_phase_config: Config = config;
+        mint_phase_config.mint_phases = vec![MintPhase {
+            merkle_root: Binary(vec![0; 32]),
+            start_height: height,
+            end_height: height + 10,
+            fee: Uint128(AMOUNT_FOR_TRANSACTION / 2),
+        }];
+        let error = rename_with_config(&mut deps, &mint_phase_config);
+        assert_eq!(
+            error,
+            "Registering during a mint phase needs an allowlist proof"
+        );
+
+        // = when the owner renames to an available alias
+        let rename_alias_message = ReceiveMsg::Rename {
+            from: "nailbiter".to_string(),
+            to: "Nail Biter".to_string(),
+        };
+        let receive_msg = HandleMsg::Receive {
+            sender: mock_user_address(),
+            from: mock_user_address(),
+            amount: Uint128(AMOUNT_FOR_TRANSACTION),
+            msg: to_binary(&rename_alias_message).unwrap(),
+            padding: None,
+        };
+        let handle_result = handle(
+            &mut deps,
+            mock_env(mock_buttcoin().address, &[]),
+            receive_msg,
+        )
+        .unwrap();
+        // = * it sends the BUTT to the BUTT lode
+        assert_eq!(
+            handle_result.messages,
+            vec![snip20::transfer_msg(
+                mock_butt_lode().address,
+                Uint128(AMOUNT_FOR_TRANSACTION),
+                None,
+                BLOCK_SIZE,
+                mock_buttcoin().contract_hash,
+                mock_buttcoin().address,
+            )
+            .unwrap()],
+        );
+        // = * the old alias is released
+        let query_response = query(
+            &deps,
+            QueryMsg::Search {
+                search_type: "alias".to_string(),
+                search_value: "nailbiter".to_string(),
+                include_metadata: None,
+                viewing_key: None,
+            },
+        );
+        let error = extract_query_error(query_response);
+        assert_eq!(error.code, QueryErrorCode::NotFound);
+        // = * the address resolves to the new alias with the avatar kept
+        let search_response = query(
+            &deps,
+            QueryMsg::Search {
+                search_type: "address".to_string(),
+                search_value: mock_user_address().to_string(),
+                include_metadata: None,
+                viewing_key: None,
+            },
+        )
+        .unwrap();
+        let val: SearchResponse = from_binary(&search_response).unwrap();
+        assert_eq!(val.attributes.alias, "nail biter");
+        assert_eq!(val.attributes.avatar_url, Some(avatar_url.to_string()));
+        // = * the history moves to the new alias
+        let alias_at_version = |deps: &Extern<MockStorage, MockApi, MockQuerier>, alias: &str| {
+            query(
+                deps,
+                QueryMsg::AliasAtVersion {
+                    alias: alias.to_string(),
+                    version: 1,
+                },
+            )
+        };
+        match from_binary(&alias_at_version(&deps, "Nail Biter").unwrap()).unwrap() {
+            QueryAnswer::AliasAtVersion {
+                alias_version,
+                latest_version,
+            } => {
+                assert_eq!(alias_version.owner, Some(mock_user_address()));
+                assert_eq!(latest_version, 1);
+            }
+            _ => panic!("Unexpected query answer"),
+        }
+        let error = extract_query_error(alias_at_version(&deps, "nailbiter"));
+        assert_eq!(error.code, QueryErrorCode::NotFound);
+
+        // = when a leased alias is renamed
+        // = * the lease moves to the new alias
+        let fee: Uint128 = Uint128(AMOUNT_FOR_TRANSACTION / 10);
+        handle(
+            &mut deps,
+            mock_env(mock_user_address(), &[]),
+            HandleMsg::SetLeaseTerms {
+                lease_terms: Some(LeaseTerms {
+                    period_blocks: 100,
+                    fee,
+                }),
+                padding: None,
+            },
+        )
+        .unwrap();
+        let receive = |amount: Uint128, msg: ReceiveMsg| HandleMsg::Receive {
+            sender: HumanAddr::from("tenant"),
+            from: HumanAddr::from("tenant"),
+            amount,
+            msg: to_binary(&msg).unwrap(),
+            padding: None,
+        };
+        handle(
+            &mut deps,
+            mock_env(mock_buttcoin().address, &[]),
+            receive(
+                fee,
+                ReceiveMsg::Lease {
+                    alias: "rented".to_string(),
+                    periods: 1,
+                },
+            ),
+        )
+        .unwrap();
+        handle(
+            &mut deps,
+            mock_env(mock_buttcoin().address, &[]),
+            receive(
+                Uint128(AMOUNT_FOR_TRANSACTION),
+                ReceiveMsg::Rename {
+                    from: "rented".to_string(),
+                    to: "tenanted".to_string(),
+                },
+            ),
+        )
+        .unwrap();
+        let leases_storage = LeasesReadonlyStorage::from_storage(&deps.storage);
+        assert_eq!(leases_storage.get_lease(b"rented"), None);
+        assert_eq!(
+            leases_storage.get_lease(b"tenanted"),
+            Some(Lease {
+                period_blocks: 100,
+                fee,
+                paid_until: height + 100,
+            })
+        );
+    }
+
+    #[test]
+    fn test_try_reserve_aliases() {
+        let (_init_result, mut deps) = init_helper();
+        let reserve_aliases = HandleMsg::ReserveAliases {
+            aliases: vec!["Nailbiter ".to_string(), "btn".to_string()],
+            padding: None,
+        };
+
+        // = when called by someone other than the admin
+        // = * it raises an Unauthorized error
+        let handle_result = handle(
+            &mut deps,
+            mock_env(HumanAddr::from("frump"), &[]),
+            reserve_aliases.clone(),
+        );
+        assert_eq!(
+            handle_result.unwrap_err(),
+            StdError::Unauthorized { backtrace: None }
+        );
+
+        // = when the chunk has too many aliases
+        // = * it raises an error
+        let handle_result = handle(
+            &mut deps,
+            mock_env(mock_user_address(), &[]),
+            HandleMsg::ReserveAliases {
+                aliases: vec!["btn".to_string(); RESERVATION_CHUNK_SIZE + 1],
+                padding: None,
+            },
+        );
+        assert_eq!(
+            extract_error_msg(handle_result),
+            format!(
+                "A chunk can have at most {} aliases",
+                RESERVATION_CHUNK_SIZE
+            )
+        );
+
+        // = when called by the admin
+        // = * it reserves the aliases and reports the progress
+        let handle_result = handle(
+            &mut deps,
+            mock_env(mock_user_address(), &[]),
+            reserve_aliases.clone(),
+        );
+        assert_eq!(
+            handle_result.unwrap().data,
+            Some(
+                to_binary(&HandleAnswer::ReserveAliases {
+                    added: 2,
+                    reserved_count: 2
+                })
+                .unwrap()
+            )
+        );
+
+        // = when a chunk is sent again
+        // = * it skips the aliases that are already reserved
+        let handle_result = handle(
+            &mut deps,
+            mock_env(mock_user_address(), &[]),
+            HandleMsg::ReserveAliases {
+                aliases: vec!["btn".to_string(), "butt".to_string()],
+                padding: None,
+            },
+        );
+        assert_eq!(
+            handle_result.unwrap().data,
+            Some(
+                to_binary(&HandleAnswer::ReserveAliases {
+                    added: 1,
+                    reserved_count: 3
+                })
+                .unwrap()
+            )
+        );
+
+        // = when someone tries to create a reserved alias
+        // = * it raises an error
+        let create = HandleMsg::Receive {
+            sender: mock_user_address(),
+            from: mock_user_address(),
+            amount: Uint128(AMOUNT_FOR_TRANSACTION),
+            msg: to_binary(&ReceiveMsg::Create {
+                alias: "nailbiter".to_string(),
+                avatar_url: None,
+                avatar_metadata: None,
+                records: None,
+                fallback: None,
+            })
+            .unwrap(),
+            padding: None,
+        };
+        let handle_result = handle(
+            &mut deps,
+            mock_env(mock_buttcoin().address, &[]),
+            create.clone(),
+        );
+        assert_eq!(extract_error_msg(handle_result), "Alias is reserved");
+
+        // = when the admin unreserves aliases
+        // = * it lets them be registered again
+        let handle_result = handle(
+            &mut deps,
+            mock_env(mock_user_address(), &[]),
+            HandleMsg::UnreserveAliases {
+                aliases: vec!["nailbiter".to_string(), "nobody".to_string()],
+                padding: None,
+            },
+        );
+        assert_eq!(
+            handle_result.unwrap().data,
+            Some(
+                to_binary(&HandleAnswer::UnreserveAliases {
+                    removed: 1,
+                    reserved_count: 2
+                })
+                .unwrap()
+            )
+        );
+        handle(&mut deps, mock_env(mock_buttcoin().address, &[]), create).unwrap();
+        let alias_storage = RegistryReadonlyStorage::from_storage(&deps.storage, &deps.api);
+        assert!(alias_storage.get_alias(b"nailbiter").is_some());
+    }
+
+    #[test]
+    fn test_try_revert() {
+        let (_init_result, mut deps) = init_helper();
+        let alias: &str = "epstein didn't kill himself";
+        let revert = HandleMsg::Revert {
+            alias: alias.to_string(),
+            padding: None,
+        };
+        let display_name_of = |deps: &Extern<MockStorage, MockApi, MockQuerier>| -> String {
+            RegistryReadonlyStorage::from_storage(&deps.storage, &deps.api)
+                .get_alias(alias.as_bytes())
+                .unwrap()
+                .display_name
+        };
+
+        // = when nothing has changed since the alias was created
+        // = * it raises an error
+        let handle_result = handle(&mut deps, mock_env("frump", &[]), revert.clone());
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, "Previous profile not found");
+
+        // The owner changes the display name and then the records
+        handle(
+            &mut deps,
+            mock_env("frump", &[]),
+            HandleMsg::SetDisplayName {
+                alias: alias.to_string(),
+                display_name: "Epstein Didn't Kill Himself".to_string(),
+                padding: None,
+            },
+        )
+        .unwrap();
+        handle(
+            &mut deps,
+            mock_env("frump", &[]),
+            HandleMsg::UpdateRecords {
+                alias: alias.to_string(),
+                set: vec![Record {
+                    key: "url".to_string(),
+                    value: "https://www.btn.group".to_string(),
+                }],
+                remove: vec![],
+                padding: None,
+            },
+        )
+        .unwrap();
+
+        // = when someone other than the owner reverts
+        // = * it raises an error
+        let handle_result = handle(&mut deps, mock_env("merchant", &[]), revert.clone());
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, "Unauthorized");
+
+        // = when the previous avatar breaks the current avatar rules
+        // = * it raises an error
+        let mut config: Config = TypedStore::attach(&deps.storage).load(CONFIG_KEY).unwrap();
+        config.require_avatar = true;
+        TypedStoreMut::attach(&mut deps.storage)
+            .store(CONFIG_KEY, &config)
+            .unwrap();
+        let handle_result = handle(&mut deps, mock_env("frump", &[]), revert.clone());
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, "Avatar url is required");
+        config.require_avatar = false;
+        TypedStoreMut::attach(&mut deps.storage)
+            .store(CONFIG_KEY, &config)
+            .unwrap();
+
+        // = when the owner reverts
+        // = * it undoes the records change only
+        let handle_result = handle(&mut deps, mock_env("frump", &[]), revert.clone());
+        assert_eq!(
+            handle_result.unwrap().data,
+            Some(to_binary(&HandleAnswer::Revert { status: Success }).unwrap())
+        );
+        assert!(RecordsReadonlyStorage::from_storage(&deps.storage)
+            .get_records(alias.as_bytes())
+            .is_empty());
+        assert_eq!(display_name_of(&deps), "Epstein Didn't Kill Himself");
+
+        // = when the owner reverts again
+        // = * it raises an error as only one step back is kept
+        let handle_result = handle(&mut deps, mock_env("frump", &[]), revert);
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, "Previous profile not found");
+    }
+
+    #[test]
+    fn test_try_send_to_alias() {
+        let (_init_result, mut deps) = init_helper();
+        let sscrt = SecretContract {
+            address: HumanAddr::from("sscrt-address"),
+            contract_hash: "sscrt-contract-hash".to_string(),
+        };
+        let send_to_alias_message = ReceiveMsg::SendToAlias {
+            alias: "Epstein didn't kill himself".to_string(),
+        };
+        let receive_msg = HandleMsg::Receive {
+            sender: mock_user_address(),
+            from: mock_user_address(),
+            amount: Uint128(5),
+            msg: to_binary(&send_to_alias_message).unwrap(),
+            padding: None,
+        };
+
+        // = when the token is not routable
+        // = * it raises an error
+        let handle_result = handle(
+            &mut deps,
+            mock_env(sscrt.address.clone(), &[]),
+            receive_msg.clone(),
+        );
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, "Unauthorized");
+
+        // = when someone other than the admin adds a routable token
+        // = * it raises an error
+        let add_routable_token_msg = HandleMsg::AddRoutableToken {
+            token: sscrt.clone(),
+            padding: None,
+        };
+        let handle_result = handle(
+            &mut deps,
+            mock_env("frump", &[]),
+            add_routable_token_msg.clone(),
+        );
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, "Unauthorized");
+
+        // = when the admin adds a routable token
+        // = * it registers receive with the token
+        let handle_result = handle(
+            &mut deps,
+            mock_env(mock_user_address(), &[]),
+            add_routable_token_msg,
+        )
+        .unwrap();
+        assert_eq!(
+            handle_result.messages,
+            vec![snip20::register_receive_msg(
+                mock_env(mock_user_address(), &[]).contract_code_hash,
+                None,
+                BLOCK_SIZE,
+                sscrt.contract_hash.clone(),
+                sscrt.address.clone(),
+            )
+            .unwrap()]
+        );
+
+        // = when the token is sent to an alias
+        // = * it forwards the tokens to the alias's address
+        let handle_result =
+            handle(&mut deps, mock_env(sscrt.address.clone(), &[]), receive_msg).unwrap();
+        assert_eq!(
+            handle_result.messages,
+            vec![snip20::transfer_msg(
+                HumanAddr::from("frump"),
+                Uint128(5),
+                None,
+                BLOCK_SIZE,
+                sscrt.contract_hash.clone(),
+                sscrt.address.clone(),
+            )
+            .unwrap()]
+        );
+
+        // = when the alias does not exist
+        // = * it raises an error
+        let send_to_alias_message = ReceiveMsg::SendToAlias {
+            alias: "nobody".to_string(),
+        };
+        let receive_msg = HandleMsg::Receive {
+            sender: mock_user_address(),
+            from: mock_user_address(),
+            amount: Uint128(5),
+            msg: to_binary(&send_to_alias_message).unwrap(),
+            padding: None,
+        };
+        let handle_result = handle(&mut deps, mock_env(sscrt.address, &[]), receive_msg);
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, "Alias not found");
+    }
+
+    #[test]
+    fn test_try_set_accepted_payments() {
+        let (_init_result, mut deps) = init_helper();
+        let sscrt = SecretContract {
+            address: HumanAddr::from("sscrt-address"),
+            contract_hash: "sscrt-contract-hash".to_string(),
+        };
+        let accepted_payments = vec![
+            AcceptedPayment {
+                asset: Asset::Native {
+                    denom: "uscrt".to_string(),
+                },
+                fee: Uint128(2_000_000),
+            },
+            AcceptedPayment {
+                asset: Asset::Snip20 {
+                    token: sscrt.clone(),
+                },
+                fee: Uint128(2_000_000),
+            },
+        ];
+        let create = |alias: &str| HandleMsg::Create {
+            alias: alias.to_string(),
+            avatar_url: None,
+            avatar_metadata: None,
+            records: None,
+            fallback: None,
+            padding: None,
+        };
+
+        // = when someone other than the admin sets the accepted payments
+        // = * it raises an error
+        let handle_msg = HandleMsg::SetAcceptedPayments {
+            accepted_payments: accepted_payments.clone(),
+            padding: None,
+        };
+        let handle_result = handle(&mut deps, mock_env("frump", &[]), handle_msg.clone());
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, "Unauthorized");
+
+        // = when Buttcoin is one of the accepted payments
+        // = * it raises an error
+        let handle_result = handle(
+            &mut deps,
+            mock_env(mock_user_address(), &[]),
+            HandleMsg::SetAcceptedPayments {
+                accepted_payments: vec![AcceptedPayment {
+                    asset: Asset::Snip20 {
+                        token: mock_buttcoin(),
+                    },
+                    fee: Uint128(2_000_000),
+                }],
+                padding: None,
+            },
+        );
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, "Buttcoin is always accepted");
+
+        // = when the admin sets the accepted payments
+        // = * it registers receive with the SNIP-20 tokens
+        let handle_result =
+            handle(&mut deps, mock_env(mock_user_address(), &[]), handle_msg).unwrap();
+        assert_eq!(
+            handle_result.messages,
+            vec![snip20::register_receive_msg(
+                mock_env(mock_user_address(), &[]).contract_code_hash,
+                None,
+                BLOCK_SIZE,
+                sscrt.contract_hash.clone(),
+                sscrt.address.clone(),
+            )
+            .unwrap()]
+        );
+
+        // = when an alias is paid for in a native denomination that isn't accepted
+        // = * it raises an error
+        let handle_result = handle(
+            &mut deps,
+            mock_env("native-user", &coins(2_000_000, "uatom")),
+            create("native"),
+        );
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, "uatom is not accepted");
+        // = when an alias is paid for in an accepted native denomination
+        // = * it creates the alias and passes the fee on to the butt lode
+        let env = mock_env("native-user", &coins(2_000_000, "uscrt"));
+        let handle_result = handle(&mut deps, env.clone(), create("native")).unwrap();
+        assert_eq!(
+            handle_result.messages,
+            vec![CosmosMsg::Bank(BankMsg::Send {
+                from_address: env.contract.address,
+                to_address: mock_butt_lode().address,
+                amount: coins(2_000_000, "uscrt"),
+            })]
+        );
+        let alias_object: Alias = RegistryReadonlyStorage::from_storage(&deps.storage, &deps.api)
+            .get_alias(b"native")
+            .unwrap();
+        assert_eq!(
+            alias_object.owner,
+            deps.api
+                .canonical_address(&HumanAddr::from("native-user"))
+                .unwrap()
+        );
+
+        // = when an alias is paid for in an accepted SNIP-20 token
+        // = * it creates the alias and passes the fee on to the butt lode
+        let receive_msg = HandleMsg::Receive {
+            sender: HumanAddr::from("sscrt-user"),
+            from: HumanAddr::from("sscrt-user"),
+            amount: Uint128(2_000_000),
+            msg: to_binary(&ReceiveMsg::Create {
+                alias: "wrapped".to_string(),
+                avatar_url: None,
+                avatar_metadata: None,
+                records: None,
+                fallback: None,
+            })
+            .unwrap(),
+            padding: None,
+        };
+        let handle_result =
+            handle(&mut deps, mock_env(sscrt.address.clone(), &[]), receive_msg).unwrap();
+        assert_eq!(
+            handle_result.messages,
+            vec![snip20::transfer_msg(
+                mock_butt_lode().address,
+                Uint128(2_000_000),
+                None,
+                BLOCK_SIZE,
+                sscrt.contract_hash,
+                sscrt.address,
+            )
+            .unwrap()]
+        );
+    }
+
+    #[test]
+    fn test_try_set_avatar_domains() {
+        let (_init_result, mut deps) = init_helper();
+        let set_avatar_domains = |domains: Vec<&str>| HandleMsg::SetAvatarDomains {
+            domains: domains.iter().map(|domain| domain.to_string()).collect(),
+            padding: None,
+        };
+        let create = |alias: &str, avatar_url: &str| HandleMsg::Receive {
+            sender: HumanAddr::from(alias),
+            from: HumanAddr::from(alias),
+            amount: Uint128(AMOUNT_FOR_TRANSACTION),
+            msg: to_binary(&ReceiveMsg::Create {
+                alias: alias.to_string(),
+                avatar_url: Some(avatar_url.to_string()),
+                avatar_metadata: None,
+                records: None,
+                fallback: None,
+            })
+            .unwrap(),
+            padding: None,
+        };
+
+        // = when called by someone other than the admin
+        // = * it raises an Unauthorized error
+        let handle_result = handle(
+            &mut deps,
+            mock_env(HumanAddr::from("frump"), &[]),
+            set_avatar_domains(vec!["ipfs.io"]),
+        );
+        assert_eq!(
+            handle_result.unwrap_err(),
+            StdError::Unauthorized { backtrace: None }
+        );
+
+        // = when a domain is invalid
+        // = * it raises an error
+        let handle_result = handle(
+            &mut deps,
+            mock_env(mock_user_address(), &[]),
+            set_avatar_domains(vec!["https://ipfs.io"]),
+        );
+        assert_eq!(
+            extract_error_msg(handle_result),
+            "https://ipfs.io is not a valid domain"
+        );
+
+        // = when called by the admin with valid domains
+        // = * it stores the normalized domains
+        let handle_result = handle(
+            &mut deps,
+            mock_env(mock_user_address(), &[]),
+            set_avatar_domains(vec![" IPFS.io", "arweave.net", "ipfs.io"]),
+        );
+        assert_eq!(
+            handle_result.unwrap().data,
+            Some(to_binary(&HandleAnswer::SetAvatarDomains { status: Success }).unwrap())
+        );
+        let config: Config = TypedStore::attach(&deps.storage).load(CONFIG_KEY).unwrap();
+        assert_eq!(config.avatar_domains, vec!["ipfs.io", "arweave.net"]);
+
+        // = when an alias is created with an avatar on another host
+        // = * it raises an error
+        let handle_result = handle(
+            &mut deps,
+            mock_env(mock_buttcoin().address, &[]),
+            create("nailbiter", "https://tracker.com/pixel.gif"),
+        );
+        assert_eq!(
+            extract_error_msg(handle_result),
+            "Avatars can't be hosted on tracker.com"
+        );
+        // = * credentials in the url don't get around it
+        let handle_result = handle(
+            &mut deps,
+            mock_env(mock_buttcoin().address, &[]),
+            create("nailbiter", "https://ipfs.io@tracker.com/pixel.gif"),
+        );
+        assert_eq!(
+            extract_error_msg(handle_result),
+            "Avatars can't be hosted on tracker.com"
+        );
+        // = * a lookalike domain doesn't get around it
+        let handle_result = handle(
+            &mut deps,
+            mock_env(mock_buttcoin().address, &[]),
+            create("nailbiter", "https://notipfs.io/pixel.gif"),
+        );
+        assert_eq!(
+            extract_error_msg(handle_result),
+            "Avatars can't be hosted on notipfs.io"
+        );
+
+        // = when an alias is created with an avatar on an allowed host or its subdomain
+        // = * it creates the alias
+        handle(
+            &mut deps,
+            mock_env(mock_buttcoin().address, &[]),
+            create("nailbiter", "https://ipfs.io/ipfs/Qm"),
+        )
+        .unwrap();
+        handle(
+            &mut deps,
+            mock_env(mock_buttcoin().address, &[]),
+            create("woodcutter", "https://abc.arweave.net:443/tx"),
+        )
+        .unwrap();
+
+        // = when an image on another host is added to an alias's gallery
+        // = * it raises an error
+        let handle_result = handle(
+            &mut deps,
+            mock_env(HumanAddr::from("nailbiter"), &[]),
+            HandleMsg::AddImage {
+                alias: "nailbiter".to_string(),
+                url: "https://tracker.com/pixel.gif".to_string(),
+                metadata: None,
+                padding: None,
+            },
+        );
+        assert_eq!(
+            extract_error_msg(handle_result),
+            "Images can't be hosted on tracker.com"
+        );
+    }
+
+    #[test]
+    fn test_try_set_co_owners() {
+        let (_init_result, mut deps) = init_helper();
+        let alias = "Epstein didn't kill himself";
+        let set_co_owners = |threshold: u8| HandleMsg::SetCoOwners {
+            alias: alias.to_string(),
+            co_owners: vec![HumanAddr::from("co-owner-1"), HumanAddr::from("co-owner-2")],
+            threshold,
+            padding: None,
+        };
+        let destroy = HandleMsg::Destroy {
+            alias: alias.to_string(),
+            padding: None,
+        };
+        let approve = HandleMsg::Approve {
+            alias: alias.to_string(),
+            padding: None,
+        };
+        let pending_action_of =
+            |deps: &Extern<MockStorage, MockApi, MockQuerier>| -> Option<PendingAction> {
+                let query_response = query(
+                    deps,
+                    QueryMsg::PendingAction {
+                        alias: alias.to_string(),
+                    },
+                )
+                .unwrap();
+                match from_binary(&query_response).unwrap() {
+                    QueryAnswer::PendingAction { pending_action } => pending_action,
+                    _ => panic!("Unexpected query answer"),
+                }
+            };
+        let search_alias = QueryMsg::Search {
+            search_type: "alias".to_string(),
+            search_value: alias.to_string(),
+            include_metadata: Some(true),
+            viewing_key: None,
+        };
+
+        // = when someone other than the owner sets co-owners
+        // = * it raises an error
+        let handle_result = handle(
+            &mut deps,
+            mock_env(mock_user_address(), &[]),
+            set_co_owners(2),
+        );
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, "Unauthorized");
+
+        // = when the threshold is more than the number of owners
+        // = * it raises an error
+        let handle_result = handle(&mut deps, mock_env("frump", &[]), set_co_owners(4));
+        let error = extract_error_msg(handle_result);
+        assert_eq!(
+            error,
+            "Threshold must be between 1 and the number of owners"
+        );
+
+        // = when the sole owner sets co-owners
+        // = * they are set straight away
+        handle(&mut deps, mock_env("frump", &[]), set_co_owners(2)).unwrap();
+        let search_response = query(&deps, search_alias.clone()).unwrap();
+        let val: SearchResponse = from_binary(&search_response).unwrap();
+        let metadata: AliasMetadata = val.metadata.unwrap();
+        assert_eq!(
+            metadata.co_owners,
+            vec![HumanAddr::from("co-owner-1"), HumanAddr::from("co-owner-2")]
+        );
+        assert_eq!(metadata.approval_threshold, 2);
+
+        // = when a co-owner destroys the alias
+        // = * it waits for approval
+        handle(&mut deps, mock_env("co-owner-1", &[]), destroy.clone()).unwrap();
+        let pending_action: PendingAction = pending_action_of(&deps).unwrap();
+        assert_eq!(pending_action.action, ApprovalAction::Destroy);
+        assert_eq!(
+            pending_action.approvals,
+            vec![HumanAddr::from("co-owner-1")]
+        );
+        query(&deps, search_alias.clone()).unwrap();
+        // = * another action can't be proposed
+        let handle_msg = HandleMsg::Transfer {
+            alias: alias.to_string(),
+            recipient: mock_user_address(),
+            recipient_code_hash: None,
+            msg: None,
+            padding: None,
+        };
+        let handle_result = handle(&mut deps, mock_env("frump", &[]), handle_msg);
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, "Another action is pending approval");
+        // = * the proposer can't approve it again
+        let handle_result = handle(&mut deps, mock_env("co-owner-1", &[]), approve.clone());
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, "Pending action is already approved");
+
+        // = when another co-owner rejects it
+        // = * it is cancelled
+        let handle_msg = HandleMsg::Reject {
+            alias: alias.to_string(),
+            padding: None,
+        };
+        handle(&mut deps, mock_env("co-owner-2", &[]), handle_msg).unwrap();
+        assert_eq!(pending_action_of(&deps), None);
+        // = * approving it raises an error
+        let handle_result = handle(&mut deps, mock_env("frump", &[]), approve.clone());
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, "Pending action not found");
+
+        // = when the owner destroys the alias and a co-owner approves
+        // = * the alias is destroyed
+        handle(&mut deps, mock_env("frump", &[]), destroy).unwrap();
+        let handle_result = handle(&mut deps, mock_env("co-owner-2", &[]), approve);
+        let handle_result_data: HandleAnswer =
+            from_binary(&handle_result.unwrap().data.unwrap()).unwrap();
+        assert_eq!(
+            to_binary(&handle_result_data).unwrap(),
+            to_binary(&HandleAnswer::Approve { status: Success }).unwrap()
+        );
+        let query_response = query(&deps, search_alias);
+        let error = extract_query_error(query_response);
+        assert_eq!(error.code, QueryErrorCode::NotFound);
+    }
+
+    #[test]
+    fn test_try_set_require_avatar() {
+        let (_init_result, mut deps) = init_helper();
+        let create = |avatar_url: Option<&str>| HandleMsg::Receive {
+            sender: mock_user_address(),
+            from: mock_user_address(),
+            amount: Uint128(AMOUNT_FOR_TRANSACTION),
+            msg: to_binary(&ReceiveMsg::Create {
+                alias: "nailbiter".to_string(),
+                avatar_url: avatar_url.map(|avatar_url| avatar_url.to_string()),
+                avatar_metadata: None,
+                records: None,
+                fallback: None,
+            })
+            .unwrap(),
+            padding: None,
+        };
+        let set_require_avatar = HandleMsg::SetRequireAvatar {
+            required: true,
+            padding: None,
+        };
+
+        // = when called by someone other than the admin
+        // = * it raises an Unauthorized error
+        let handle_result = handle(
+            &mut deps,
+            mock_env(HumanAddr::from("frump"), &[]),
+            set_require_avatar.clone(),
+        );
+        assert_eq!(
+            handle_result.unwrap_err(),
+            StdError::Unauthorized { backtrace: None }
+        );
+
+        // = when called by the admin
+        // = * it makes an avatar url required
+        let handle_result = handle(
+            &mut deps,
+            mock_env(mock_user_address(), &[]),
+            set_require_avatar,
+        );
+        assert_eq!(
+            handle_result.unwrap().data,
+            Some(to_binary(&HandleAnswer::SetRequireAvatar { status: Success }).unwrap())
+        );
+        let config: Config = TypedStore::attach(&deps.storage).load(CONFIG_KEY).unwrap();
+        assert!(config.require_avatar);
+
+        // = when an alias is created without an avatar url
+        // = * it raises an error
+        let handle_result = handle(
+            &mut deps,
+            mock_env(mock_buttcoin().address, &[]),
+            create(None),
+        );
+        assert_eq!(extract_error_msg(handle_result), "Avatar url is required");
+
+        // = when an alias is created with an avatar url
+        // = * it creates the alias
+        handle(
+            &mut deps,
+            mock_env(mock_buttcoin().address, &[]),
+            create(Some("https://www.btn.group")),
+        )
+        .unwrap();
+        let alias_storage = RegistryReadonlyStorage::from_storage(&deps.storage, &deps.api);
+        assert_eq!(
+            alias_storage.get_alias(b"nailbiter").unwrap().avatar_url,
+            Some("https://www.btn.group".to_string())
+        );
+    }
+
+    #[test]
+    fn test_try_set_signing_key() {
+        use k256::ecdsa::signature::Signer;
+        use k256::ecdsa::{Signature, SigningKey};
+
+        let (_init_result, mut deps) = init_helper();
+        let alias: &str = "epstein didn't kill himself";
+        let signing_key: SigningKey = SigningKey::from_bytes(&[7; 32]).unwrap();
+        let public_key: Binary = Binary(signing_key.verifying_key().to_bytes().to_vec());
+        let challenge: &[u8] = b"Sign in to btn.group with nonce 42";
+        let signature: Signature = signing_key.sign(challenge);
+        let verify_sign_in =
+            |deps: &Extern<MockStorage, MockApi, MockQuerier>, challenge: &[u8]| -> QueryResult {
+                query(
+                    deps,
+                    QueryMsg::VerifySignIn {
+                        alias: alias.to_string(),
+                        challenge: Binary(challenge.to_vec()),
+                        signature: Binary(signature.as_ref().to_vec()),
+                    },
+                )
+            };
+        let set_signing_key = |public_key: Option<Binary>| HandleMsg::SetSigningKey {
+            alias: alias.to_string(),
+            public_key,
+            padding: None,
+        };
+
+        // = when the alias has no signing key
+        // = * verifying a sign in raises a not found error
+        assert_eq!(
+            extract_query_error(verify_sign_in(&deps, challenge)).code,
+            QueryErrorCode::NotFound
+        );
+
+        // = when someone other than the owner sets the signing key
+        // = * it raises an error
+        let handle_result = handle(
+            &mut deps,
+            mock_env("merchant", &[]),
+            set_signing_key(Some(public_key.clone())),
+        );
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, "Unauthorized");
+
+        // = when the key is not a compressed secp256k1 public key
+        // = * it raises an error
+        let handle_result = handle(
+            &mut deps,
+            mock_env("frump", &[]),
+            set_signing_key(Some(Binary(vec![2; 32]))),
+        );
+        let error = extract_error_msg(handle_result);
+        assert_eq!(
+            error,
+            "Signing key must be a compressed secp256k1 public key"
+        );
+
+        // = when the owner sets the signing key
+        handle(
+            &mut deps,
+            mock_env("frump", &[]),
+            set_signing_key(Some(public_key)),
+        )
+        .unwrap();
+        // = * the signed challenge verifies
+        let query_answer: QueryAnswer =
+            from_binary(&verify_sign_in(&deps, challenge).unwrap()).unwrap();
+        match query_answer {
+            QueryAnswer::VerifySignIn { valid } => assert!(valid),
+            _ => panic!("Unexpected query answer"),
+        }
+        // = * another challenge does not
+        let query_answer: QueryAnswer =
+            from_binary(&verify_sign_in(&deps, b"Sign in with nonce 43").unwrap()).unwrap();
+        match query_answer {
+            QueryAnswer::VerifySignIn { valid } => assert!(!valid),
+            _ => panic!("Unexpected query answer"),
+        }
+
+        // = when the owner removes the signing key
+        // = * verifying a sign in raises a not found error
+        handle(&mut deps, mock_env("frump", &[]), set_signing_key(None)).unwrap();
+        assert_eq!(
+            extract_query_error(verify_sign_in(&deps, challenge)).code,
+            QueryErrorCode::NotFound
+        );
+    }
+
+    #[test]
+    fn test_try_set_encryption_key() {
+        let (_init_result, mut deps) = init_helper();
+        let alias: &str = "epstein didn't kill himself";
+        let set_encryption_key =
+            |algorithm: EncryptionKeyAlgorithm, public_key: Vec<u8>| HandleMsg::SetEncryptionKey {
+                alias: alias.to_string(),
+                encryption_key: Some(EncryptionKey {
+                    algorithm,
+                    public_key: Binary(public_key),
+                }),
+                padding: None,
+            };
+        let encryption_key_of = |deps: &Extern<MockStorage, MockApi, MockQuerier>| {
+            EncryptionKeysReadonlyStorage::from_storage(&deps.storage)
+                .get_encryption_key(alias.as_bytes())
+        };
+
+        // = when someone other than the owner sets the encryption key
+        // = * it raises an error
+        let handle_result = handle(
+            &mut deps,
+            mock_env("merchant", &[]),
+            set_encryption_key(EncryptionKeyAlgorithm::X25519, vec![9; 32]),
+        );
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, "Unauthorized");
+
+        // = when the key doesn't fit the algorithm
+        // = * it raises an error
+        for (algorithm, public_key) in vec![
+            (EncryptionKeyAlgorithm::X25519, vec![9; 33]),
+            (EncryptionKeyAlgorithm::X25519, vec![0; 32]),
+            (EncryptionKeyAlgorithm::Secp256k1, vec![9; 32]),
+            (EncryptionKeyAlgorithm::Secp256k1, vec![9; 33]),
+        ] {
+            let handle_result = handle(
+                &mut deps,
+                mock_env("frump", &[]),
+                set_encryption_key(algorithm, public_key),
+            );
+            let error = extract_error_msg(handle_result);
+            assert_eq!(error, "Encryption key is invalid");
+        }
+
+        // = when the owner sets an X25519 key
+        // = * it stores the key
+        handle(
+            &mut deps,
+            mock_env("frump", &[]),
+            set_encryption_key(EncryptionKeyAlgorithm::X25519, vec![9; 32]),
+        )
+        .unwrap();
+        assert_eq!(
+            encryption_key_of(&deps),
+            Some(EncryptionKey {
+                algorithm: EncryptionKeyAlgorithm::X25519,
+                public_key: Binary(vec![9; 32]),
+            })
+        );
+
+        // = when the owner removes the key
+        // = * it is removed
+        let handle_msg = HandleMsg::SetEncryptionKey {
+            alias: alias.to_string(),
+            encryption_key: None,
+            padding: None,
+        };
+        handle(&mut deps, mock_env("frump", &[]), handle_msg).unwrap();
+        assert_eq!(encryption_key_of(&deps), None);
+    }
+
+    #[test]
+    fn test_try_set_payment_preference() {
+        let (_init_result, mut deps) = init_helper();
+        let alias: &str = "epstein didn't kill himself";
+        let set_payment_preference = |denom: &str| HandleMsg::SetPaymentPreference {
+            alias: alias.to_string(),
+            payment_preference: Some(PaymentPreference {
+                asset: Some(Asset::Native {
+                    denom: denom.to_string(),
+                }),
+                memo_required: true,
+                receiving_address: Some(HumanAddr::from("cold-wallet")),
+            }),
+            padding: None,
+        };
+
+        // = when someone other than the owner sets the payment preference
+        // = * it raises an error
+        let handle_result = handle(
+            &mut deps,
+            mock_env("merchant", &[]),
+            set_payment_preference("uscrt"),
+        );
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, "Unauthorized");
+
+        // = when the denom is blank
+        // = * it raises an error
+        let handle_result = handle(
+            &mut deps,
+            mock_env("frump", &[]),
+            set_payment_preference(" "),
+        );
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, "Denom is invalid");
+
+        // = when the owner sets the payment preference
+        // = * it is returned in the alias's metadata
+        handle(
+            &mut deps,
+            mock_env("frump", &[]),
+            set_payment_preference("uscrt"),
+        )
+        .unwrap();
+        let search_response = query(
+            &deps,
+            QueryMsg::Search {
+                search_type: "alias".to_string(),
+                search_value: alias.to_string(),
+                include_metadata: Some(true),
+                viewing_key: None,
+            },
+        )
+        .unwrap();
+        let val: SearchResponse = from_binary(&search_response).unwrap();
+        assert_eq!(
+            val.metadata.unwrap().payment_preference,
+            Some(PaymentPreference {
+                asset: Some(Asset::Native {
+                    denom: "uscrt".to_string(),
+                }),
+                memo_required: true,
+                receiving_address: Some(HumanAddr::from("cold-wallet")),
+            })
+        );
+
+        // = when the owner removes the payment preference
+        // = * it is removed
+        let handle_msg = HandleMsg::SetPaymentPreference {
+            alias: alias.to_string(),
+            payment_preference: None,
+            padding: None,
+        };
+        handle(&mut deps, mock_env("frump", &[]), handle_msg).unwrap();
+        assert!(
+            PaymentPreferencesReadonlyStorage::from_storage(&deps.storage)
+                .get_payment_preference(alias.as_bytes())
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn test_try_set_discounts() {
+        let (_init_result, mut deps) = init_helper();
+        let merkle_root: [u8; 32] = sha_256(b"staker");
+        let set_discounts = |percent_off: u8| HandleMsg::SetDiscounts {
+            discounts: vec![Discount {
+                merkle_root: Binary(merkle_root.to_vec()),
+                percent_off,
+            }],
+            padding: None,
+        };
+        let create_with_discount = |from: &str, alias: &str, amount: u128| HandleMsg::Receive {
+            sender: HumanAddr::from(from),
+            from: HumanAddr::from(from),
+            amount: Uint128(amount),
+            msg: to_binary(&ReceiveMsg::CreateWithDiscount {
+                alias: alias.to_string(),
+                avatar_url: None,
+                avatar_metadata: None,
+                records: None,
+                discount: 0,
+                proof: vec![],
+            })
+            .unwrap(),
+            padding: None,
+        };
+
+        // = when there is no discount at the index
+        // = * it raises an error
+        let handle_result = handle(
+            &mut deps,
+            mock_env(mock_buttcoin().address, &[]),
+            create_with_discount("staker", "cheap", AMOUNT_FOR_TRANSACTION / 2),
+        );
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, "Discount not found");
+
+        // = when someone other than the admin sets the discounts
+        // = * it raises an error
+        let handle_result = handle(&mut deps, mock_env("staker", &[]), set_discounts(50));
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, "Unauthorized");
+
+        // = when a discount is more than 100 percent
+        // = * it raises an error
+        let handle_result = handle(
+            &mut deps,
+            mock_env(mock_user_address(), &[]),
+            set_discounts(101),
+        );
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, "Discount must be between 1 and 100 percent");
+
+        // = when the admin sets the discounts
+        // = * it saves them
+        let handle_result = handle(
+            &mut deps,
+            mock_env(mock_user_address(), &[]),
+            set_discounts(50),
+        )
+        .unwrap();
+        assert_eq!(
+            handle_result.data,
+            Some(to_binary(&HandleAnswer::SetDiscounts { status: Success }).unwrap())
+        );
+
+        // = when the sender isn't in the snapshot
+        // = * it raises an error
+        let handle_result = handle(
+            &mut deps,
+            mock_env(mock_buttcoin().address, &[]),
+            create_with_discount("stranger", "cheap", AMOUNT_FOR_TRANSACTION / 2),
+        );
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, "Proof is invalid");
+
+        // = when the full fee is sent
+        // = * it raises an error
+        let handle_result = handle(
+            &mut deps,
+            mock_env(mock_buttcoin().address, &[]),
+            create_with_discount("staker", "cheap", AMOUNT_FOR_TRANSACTION),
+        );
+        let error = extract_error_msg(handle_result);
+        assert_eq!(
+            error,
+            format!(
+                "Amount sent in: {}. Amount required {}.",
+                AMOUNT_FOR_TRANSACTION,
+                AMOUNT_FOR_TRANSACTION / 2
+            )
+        );
+
+        // = when the sender is in the snapshot and sends the discounted fee
+        // = * it registers the alias and sends the fee to the BUTT lode
+        let handle_result = handle(
+            &mut deps,
+            mock_env(mock_buttcoin().address, &[]),
+            create_with_discount("staker", "cheap", AMOUNT_FOR_TRANSACTION / 2),
+        )
+        .unwrap();
+        assert_eq!(
+            handle_result.messages,
+            vec![snip20::transfer_msg(
+                mock_butt_lode().address,
+                Uint128(AMOUNT_FOR_TRANSACTION / 2),
+                None,
+                BLOCK_SIZE,
+                mock_buttcoin().contract_hash,
+                mock_buttcoin().address,
+            )
+            .unwrap()],
+        );
+        assert!(DiscountClaimsReadonlyStorage::from_storage(&deps.storage)
+            .is_claimed(&merkle_root, &merkle_root));
+
+        // = when the discount is used again
+        // = * it raises an error
+        handle(
+            &mut deps,
+            mock_env("staker", &[]),
+            HandleMsg::Destroy {
+                alias: "cheap".to_string(),
+                padding: None,
+            },
+        )
+        .unwrap();
+        let handle_result = handle(
+            &mut deps,
+            mock_env(mock_buttcoin().address, &[]),
+            create_with_discount("staker", "cheaper", AMOUNT_FOR_TRANSACTION / 2),
+        );
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, "Discount has already been claimed");
+    }
+
+    #[test]
+    fn test_try_set_display_name() {
+        let (_init_result, mut deps) = init_helper();
+        let alias: &str = "epstein didn't kill himself";
+        let search_display_name =
+            |deps: &Extern<MockStorage, MockApi, MockQuerier>| -> Option<String> {
+                let search_response = query(
+                    deps,
+                    QueryMsg::Search {
+                        search_type: "alias".to_string(),
+                        search_value: alias.to_string(),
+                        include_metadata: None,
+                        viewing_key: None,
+                    },
+                )
+                .unwrap();
+                let val: SearchResponse = from_binary(&search_response).unwrap();
+                val.attributes.display_name
+            };
+
+        // = when the display name spells a different alias
+        // = * it raises an error
+        let handle_msg = HandleMsg::SetDisplayName {
+            alias: alias.to_string(),
+            display_name: "Epstein did kill himself".to_string(),
+            padding: None,
+        };
+        let handle_result = handle(&mut deps, mock_env("frump", &[]), handle_msg);
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, "Display name must match the alias");
+
+        // = when the alias belongs to someone else
+        // = * it raises an error
+        let handle_msg = HandleMsg::SetDisplayName {
+            alias: alias.to_string(),
+            display_name: "Epstein Didn't Kill Himself".to_string(),
+            padding: None,
+        };
+        let handle_result = handle(&mut deps, mock_env("merchant", &[]), handle_msg.clone());
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, "Unauthorized");
+
+        // = when the owner changes the capitalization
+        // = * it shows the alias with the new capitalization
+        handle(&mut deps, mock_env("frump", &[]), handle_msg).unwrap();
+        assert_eq!(
+            search_display_name(&deps),
+            Some("Epstein Didn't Kill Himself".to_string())
+        );
+    }
+
+    #[test]
+    fn test_try_set_fee_splits() {
+        let (_init_result, mut deps) = init_helper();
+        let fee_splits: Vec<FeeSplit> = vec![
+            FeeSplit {
+                recipient: FeeRecipient::Address {
+                    address: HumanAddr::from("treasury"),
+                },
+                weight: 70,
+            },
+            FeeSplit {
+                recipient: FeeRecipient::Address {
+                    address: HumanAddr::from("dev-fund"),
+                },
+                weight: 20,
+            },
+            FeeSplit {
+                recipient: FeeRecipient::Burn,
+                weight: 10,
+            },
+        ];
+        let set_fee_splits = |fee_splits: Vec<FeeSplit>| HandleMsg::SetFeeSplits {
+            fee_splits,
+            padding: None,
+        };
+
+        // = when someone other than a Treasurer sets the fee splits
+        // = * it raises an error
+        let handle_result = handle(
+            &mut deps,
+            mock_env("frump", &[]),
+            set_fee_splits(fee_splits.clone()),
+        );
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, "Unauthorized");
+
+        // = when a split has no weight
+        // = * it raises an error
+        let handle_result = handle(
+            &mut deps,
+            mock_env(mock_user_address(), &[]),
+            set_fee_splits(vec![FeeSplit {
+                recipient: FeeRecipient::Burn,
+                weight: 0,
+            }]),
+        );
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, "Fee split weight must be above 0");
+
+        // = when the fee splits are set
+        // = * fees are shared out by weight, with the burn share burnt
+        handle(
+            &mut deps,
+            mock_env(mock_user_address(), &[]),
+            set_fee_splits(fee_splits.clone()),
+        )
+        .unwrap();
+        let receive_msg = HandleMsg::Receive {
+            sender: mock_user_address(),
+            from: mock_user_address(),
+            amount: Uint128(AMOUNT_FOR_TRANSACTION),
+            msg: to_binary(&ReceiveMsg::Create {
+                alias: "nail biter".to_string(),
+                avatar_url: None,
+                avatar_metadata: None,
+                records: None,
+                fallback: None,
+            })
+            .unwrap(),
+            padding: None,
+        };
+        let handle_result = handle(
+            &mut deps,
+            mock_env(mock_buttcoin().address, &[]),
+            receive_msg,
+        )
+        .unwrap();
+        let transfer_msg = |recipient: &str, amount: u128| {
+            snip20::transfer_msg(
+                HumanAddr::from(recipient),
+                Uint128(amount),
+                None,
+                BLOCK_SIZE,
+                mock_buttcoin().contract_hash,
+                mock_buttcoin().address,
+            )
+            .unwrap()
+        };
+        assert_eq!(
+            handle_result.messages,
+            vec![
+                transfer_msg("treasury", AMOUNT_FOR_TRANSACTION * 7 / 10),
+                transfer_msg("dev-fund", AMOUNT_FOR_TRANSACTION * 2 / 10),
+                snip20::burn_msg(
+                    Uint128(AMOUNT_FOR_TRANSACTION / 10),
+                    None,
+                    BLOCK_SIZE,
+                    mock_buttcoin().contract_hash,
+                    mock_buttcoin().address,
+                )
+                .unwrap(),
+            ]
+        );
+
+        // = when a native denom is accepted while fees are burnt
+        // = * it raises an error
+        let set_accepted_payments = HandleMsg::SetAcceptedPayments {
+            accepted_payments: vec![AcceptedPayment {
+                asset: Asset::Native {
+                    denom: "uscrt".to_string(),
+                },
+                fee: Uint128(2_000_000),
+            }],
+            padding: None,
+        };
+        let handle_result = handle(
+            &mut deps,
+            mock_env(mock_user_address(), &[]),
+            set_accepted_payments.clone(),
+        );
+        let error = extract_error_msg(handle_result);
+        assert_eq!(
+            error,
+            "Only Buttcoin can be burnt, so Burn can't be used with other assets"
+        );
+
+        // = when fees are burnt while a native denom is accepted
+        // = * it raises an error
+        handle(
+            &mut deps,
+            mock_env(mock_user_address(), &[]),
+            set_fee_splits(vec![]),
+        )
+        .unwrap();
+        handle(
+            &mut deps,
+            mock_env(mock_user_address(), &[]),
+            set_accepted_payments,
+        )
+        .unwrap();
+        let handle_result = handle(
+            &mut deps,
+            mock_env(mock_user_address(), &[]),
+            set_fee_splits(fee_splits.clone()),
+        );
+        let error = extract_error_msg(handle_result);
+        assert_eq!(
+            error,
+            "Only Buttcoin can be burnt, so Burn can't be used with other assets"
+        );
+
+        // = when a fee is paid in a native denom
+        // = * it is shared out by weight in that denom
+        handle(
+            &mut deps,
+            mock_env(mock_user_address(), &[]),
+            set_fee_splits(fee_splits[..2].to_vec()),
+        )
+        .unwrap();
+        let env = mock_env("newcomer", &coins(2_000_000, "uscrt"));
+        let handle_result = handle(
+            &mut deps,
+            env.clone(),
+            HandleMsg::Create {
+                alias: "newcomer".to_string(),
+                avatar_url: None,
+                avatar_metadata: None,
+                records: None,
+                fallback: None,
+                padding: None,
+            },
+        )
+        .unwrap();
+        let send_msg = |recipient: &str, amount: u128| {
+            CosmosMsg::Bank(BankMsg::Send {
+                from_address: env.contract.address.clone(),
+                to_address: HumanAddr::from(recipient),
+                amount: coins(amount, "uscrt"),
+            })
+        };
+        assert_eq!(
+            handle_result.messages,
+            vec![
+                send_msg("treasury", 2_000_000 * 70 / 90 + 1),
+                send_msg("dev-fund", 2_000_000 * 20 / 90),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_try_set_governance() {
+        let (_init_result, mut deps) = init_helper();
+        let set_governance = |exclusive: bool| HandleMsg::SetGovernance {
+            governance: Some(Governance {
+                contract: HumanAddr::from("dao"),
+                exclusive,
+            }),
+            padding: None,
+        };
+        let set_accepted_payments = HandleMsg::SetAcceptedPayments {
+            accepted_payments: vec![],
+            padding: None,
+        };
+
+        // = when someone other than a SuperAdmin sets the governance contract
+        // = * it raises an error
+        let handle_result = handle(&mut deps, mock_env("dao", &[]), set_governance(false));
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, "Unauthorized");
+
+        // = when the admin sets a governance contract alongside the admins
+        // = * the governance contract and the admin can both send admin handles
+        handle(
+            &mut deps,
+            mock_env(mock_user_address(), &[]),
+            set_governance(false),
+        )
+        .unwrap();
+        handle(
+            &mut deps,
+            mock_env("dao", &[]),
+            set_accepted_payments.clone(),
+        )
+        .unwrap();
+        handle(
+            &mut deps,
+            mock_env(mock_user_address(), &[]),
+            set_accepted_payments.clone(),
+        )
+        .unwrap();
+
+        // = when the governance contract is exclusive
+        // = * only it can send admin handles
+        handle(
+            &mut deps,
+            mock_env(mock_user_address(), &[]),
+            set_governance(true),
+        )
+        .unwrap();
+        let handle_result = handle(
+            &mut deps,
+            mock_env(mock_user_address(), &[]),
+            set_accepted_payments.clone(),
+        );
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, "Unauthorized");
+        handle(
+            &mut deps,
+            mock_env("dao", &[]),
+            set_accepted_payments.clone(),
+        )
+        .unwrap();
+        // = * the admin can't remove it
+        let remove_governance = HandleMsg::SetGovernance {
+            governance: None,
+            padding: None,
+        };
+        let handle_result = handle(
+            &mut deps,
+            mock_env(mock_user_address(), &[]),
+            remove_governance.clone(),
+        );
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, "Unauthorized");
+
+        // = when the governance contract removes itself
+        // = * the admin can send admin handles again
+        handle(&mut deps, mock_env("dao", &[]), remove_governance).unwrap();
+        handle(
+            &mut deps,
+            mock_env(mock_user_address(), &[]),
+            set_accepted_payments,
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_try_set_launch() {
+        let (_init_result, mut deps) = init_helper();
+        let height: u64 = mock_env(mock_user_address(), &[]).block.height;
+        let set_launch = |auctions_open_at: u64, registration_opens_at: u64| HandleMsg::SetLaunch {
+            launch: Some(Launch {
+                auctions_open_at,
+                registration_opens_at,
+            }),
+            padding: None,
+        };
+        let create = |alias: &str| HandleMsg::Receive {
+            sender: mock_user_address(),
+            from: mock_user_address(),
+            amount: Uint128(AMOUNT_FOR_TRANSACTION),
+            msg: to_binary(&ReceiveMsg::Create {
+                alias: alias.to_string(),
+                avatar_url: None,
+                avatar_metadata: None,
+                records: None,
+                fallback: None,
+            })
+            .unwrap(),
+            padding: None,
+        };
+        let make_offer = HandleMsg::MakeOffer {
+            alias: "Epstein didn't kill himself".to_string(),
+            padding: None,
+        };
+        let env_at = |sender: HumanAddr, block_height: u64, sent_funds: &[Coin]| {
+            let mut env = mock_env(sender, sent_funds);
+            env.block.height = block_height;
+            env
+        };
+
+        // = when someone other than a SuperAdmin sets the launch
+        // = * it raises an error
+        let handle_result = handle(
+            &mut deps,
+            mock_env("frump", &[]),
+            set_launch(height + 10, height + 20),
+        );
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, "Unauthorized");
+
+        // = when registration would open before auctions
+        // = * it raises an error
+        let handle_result = handle(
+            &mut deps,
+            mock_env(mock_user_address(), &[]),
+            set_launch(height + 20, height + 10),
+        );
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, "Registration can't open before auctions");
+
+        // = when the SuperAdmin sets the launch
+        // = * it stores the launch
+        let handle_result = handle(
+            &mut deps,
+            mock_env(mock_user_address(), &[]),
+            set_launch(height + 10, height + 20),
+        );
+        assert_eq!(
+            handle_result.unwrap().data,
+            Some(to_binary(&HandleAnswer::SetLaunch { status: Success }).unwrap())
+        );
+
+        // = * while paused, offers and registration are refused
+        let handle_result = handle(
+            &mut deps,
+            env_at(HumanAddr::from("bidder"), height, &coins(500, "uscrt")),
+            make_offer.clone(),
+        );
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, "Auctions haven't opened yet");
+        let handle_result = handle(
+            &mut deps,
+            env_at(mock_buttcoin().address, height, &[]),
+            create("nail biter"),
+        );
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, "Registration hasn't opened yet");
+
+        // = * once auctions open, offers are taken but registration is still refused
+        handle(
+            &mut deps,
+            env_at(HumanAddr::from("bidder"), height + 10, &coins(500, "uscrt")),
+            make_offer,
+        )
+        .unwrap();
+        let handle_result = handle(
+            &mut deps,
+            env_at(mock_buttcoin().address, height + 10, &[]),
+            create("nail biter"),
+        );
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, "Registration hasn't opened yet");
+
+        // = * once registration opens, anyone can register
+        handle(
+            &mut deps,
+            env_at(mock_buttcoin().address, height + 20, &[]),
+            create("nail biter"),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_try_set_mint_phases() {
+        let (_init_result, mut deps) = init_helper();
+        let height: u64 = mock_env(mock_user_address(), &[]).block.height;
+        let phaser_leaf: [u8; 32] = sha_256(b"phaser");
+        let other_leaf: [u8; 32] = sha_256(b"other");
+        let merkle_root: [u8; 32] = if phaser_leaf <= other_leaf {
+            sha_256(&[phaser_leaf, other_leaf].concat())
+        } else {
+            sha_256(&[other_leaf, phaser_leaf].concat())
+        };
+        let mint_phase = |start_height: u64, end_height: u64| MintPhase {
+            merkle_root: Binary(merkle_root.to_vec()),
+            start_height,
+            end_height,
+            fee: Uint128(AMOUNT_FOR_TRANSACTION / 2),
+        };
+        let set_mint_phases = |phases: Vec<MintPhase>| HandleMsg::SetMintPhases {
+            phases,
+            padding: None,
+        };
+        let receive = |from: &str, amount: u128, msg: &ReceiveMsg| HandleMsg::Receive {
+            sender: HumanAddr::from(from),
+            from: HumanAddr::from(from),
+            amount: Uint128(amount),
+            msg: to_binary(msg).unwrap(),
+            padding: None,
+        };
+        let create = |alias: &str| ReceiveMsg::Create {
+            alias: alias.to_string(),
+            avatar_url: None,
+            avatar_metadata: None,
+            records: None,
+            fallback: None,
+        };
+        let create_with_proof = |alias: &str, proof: &[u8; 32]| ReceiveMsg::CreateWithProof {
+            alias: alias.to_string(),
+            avatar_url: None,
+            avatar_metadata: None,
+            records: None,
+            proof: vec![Binary(proof.to_vec())],
+        };
+
+        // = when someone other than the admin sets the mint phases
+        // = * it raises an error
+        let handle_result = handle(
+            &mut deps,
+            mock_env("phaser", &[]),
+            set_mint_phases(vec![mint_phase(height, height + 10)]),
+        );
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, "Unauthorized");
+
+        // = when a mint phase ends before it starts
+        // = * it raises an error
+        let handle_result = handle(
+            &mut deps,
+            mock_env(mock_user_address(), &[]),
+            set_mint_phases(vec![mint_phase(height, height)]),
+        );
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, "A mint phase must end after it starts");
+
+        // = when mint phases overlap
+        // = * it raises an error
+        let handle_result = handle(
+            &mut deps,
+            mock_env(mock_user_address(), &[]),
+            set_mint_phases(vec![
+                mint_phase(height, height + 10),
+                mint_phase(height + 5, height + 20),
+            ]),
+        );
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, "Mint phases must be in order and not overlap");
+
+        // = when there is no mint phase running
+        // = * it refuses registration with a proof
+        let handle_result = handle(
+            &mut deps,
+            mock_env(mock_buttcoin().address, &[]),
+            receive(
+                "phaser",
+                AMOUNT_FOR_TRANSACTION / 2,
+                &create_with_proof("early", &other_leaf),
+            ),
+        );
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, "There is no mint phase running");
+
+        // = when the admin sets the mint phases
+        // = * it saves them
+        let handle_result = handle(
+            &mut deps,
+            mock_env(mock_user_address(), &[]),
+            set_mint_phases(vec![
+                mint_phase(height, height + 10),
+                mint_phase(height + 10, height + 20),
+            ]),
+        )
+        .unwrap();
+        assert_eq!(
+            handle_result.data,
+            Some(to_binary(&HandleAnswer::SetMintPhases { status: Success }).unwrap())
+        );
+        let config: Config = TypedStore::attach(&deps.storage).load(CONFIG_KEY).unwrap();
+        assert_eq!(config.mint_phases.len(), 2);
+
+        // = when registering without a proof during a mint phase
+        // = * it raises an error
+        let handle_result = handle(
+            &mut deps,
+            mock_env(mock_buttcoin().address, &[]),
+            receive("phaser", AMOUNT_FOR_TRANSACTION, &create("early")),
+        );
+        let error = extract_error_msg(handle_result);
+        assert_eq!(
+            error,
+            "Registering during a mint phase needs an allowlist proof"
+        );
+
+        // = when the sender isn't on the allowlist
+        // = * it raises an error
+        let handle_result = handle(
+            &mut deps,
+            mock_env(mock_buttcoin().address, &[]),
+            receive(
+                "stranger",
+                AMOUNT_FOR_TRANSACTION / 2,
+                &create_with_proof("early", &other_leaf),
+            ),
+        );
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, "Proof is invalid");
+
+        // = when the phase's fee isn't paid in Buttcoin
+        // = * it raises an error
+        let handle_result = handle(
+            &mut deps,
+            mock_env("other-token", &[]),
+            receive(
+                "phaser",
+                AMOUNT_FOR_TRANSACTION / 2,
+                &create_with_proof("early", &other_leaf),
+            ),
+        );
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, "This fee is paid in Buttcoin");
+
+        // = when the usual fee is paid instead of the phase's fee
+        // = * it raises an error
+        let handle_result = handle(
+            &mut deps,
+            mock_env(mock_buttcoin().address, &[]),
+            receive(
+                "phaser",
+                AMOUNT_FOR_TRANSACTION,
+                &create_with_proof("early", &other_leaf),
+            ),
+        );
+        let error = extract_error_msg(handle_result);
+        assert_eq!(
+            error,
+            format!(
+                "Amount sent in: {}. Amount required {}.",
+                AMOUNT_FOR_TRANSACTION,
+                AMOUNT_FOR_TRANSACTION / 2
+            )
+        );
+
+        // = when an allowlisted sender pays the phase's fee
+        // = * it registers the alias and sends the fee to the BUTT lode
+        let handle_result = handle(
+            &mut deps,
+            mock_env(mock_buttcoin().address, &[]),
+            receive(
+                "phaser",
+                AMOUNT_FOR_TRANSACTION / 2,
+                &create_with_proof("early", &other_leaf),
+            ),
+        )
+        .unwrap();
+        assert_eq!(
+            handle_result.messages,
+            vec![snip20::transfer_msg(
+                mock_butt_lode().address,
+                Uint128(AMOUNT_FOR_TRANSACTION / 2),
+                None,
+                BLOCK_SIZE,
+                mock_buttcoin().contract_hash,
+                mock_buttcoin().address,
+            )
+            .unwrap()],
+        );
+        let alias_object: Alias = AliasesReadonlyStorage::from_storage(&deps.storage)
+            .get_alias(b"early")
+            .unwrap();
+        assert_eq!(
+            alias_object.owner,
+            deps.api
+                .canonical_address(&HumanAddr::from("phaser"))
+                .unwrap()
+        );
+
+        // = when the mint phases are over
+        // = * it opens registration to everyone
+        let mut env = mock_env(mock_buttcoin().address, &[]);
+        env.block.height = height + 20;
+        handle(
+            &mut deps,
+            env,
+            receive("stranger", AMOUNT_FOR_TRANSACTION, &create("late")),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_try_set_note() {
+        let (_init_result, mut deps) = init_helper();
+        let alias: &str = "epstein didn't kill himself";
+        for (address, key) in &[("frump", "frump-key"), ("merchant", "merchant-key")] {
+            handle(
+                &mut deps,
+                mock_env(*address, &[]),
+                HandleMsg::SetViewingKey {
+                    key: key.to_string(),
+                    padding: None,
+                },
+            )
+            .unwrap();
+        }
+        let query_note = |address: &str, viewing_key: &str| QueryMsg::Note {
+            alias: alias.to_string(),
+            address: Some(HumanAddr::from(address)),
+            viewing_key: viewing_key.to_string(),
+        };
+
+        // = when the alias belongs to someone else
+        // = * it raises an error
+        let set_note = HandleMsg::SetNote {
+            alias: alias.to_string(),
+            note: Some("1 Main St".to_string()),
+            padding: None,
+        };
+        let handle_result = handle(&mut deps, mock_env("merchant", &[]), set_note.clone());
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, "Unauthorized");
+
+        // = when the owner sets a note
+        let handle_result = handle(&mut deps, mock_env("frump", &[]), set_note);
+        let handle_result_data: HandleAnswer =
+            from_binary(&handle_result.unwrap().data.unwrap()).unwrap();
+        assert_eq!(
+            to_binary(&handle_result_data).unwrap(),
+            to_binary(&HandleAnswer::SetNote { status: Success }).unwrap()
+        );
+        // = * the owner can read it with their viewing key
+        let query_response = query(&deps, query_note("frump", "frump-key")).unwrap();
+        match from_binary(&query_response).unwrap() {
+            QueryAnswer::Note { note } => assert_eq!(note, "1 Main St"),
+            _ => panic!("Unexpected query answer"),
+        }
+        // = * the owner can't read it with the wrong viewing key
+        let query_response = query(&deps, query_note("frump", "merchant-key"));
+        let error = extract_query_error(query_response);
+        assert_eq!(error.code, QueryErrorCode::NotFound);
+        // = * other addresses can't read it
+        let query_response = query(&deps, query_note("merchant", "merchant-key"));
+        let error = extract_query_error(query_response);
+        assert_eq!(error.code, QueryErrorCode::NotFound);
+
+        // = when the owner adds a viewer
+        let handle_msg = HandleMsg::AddNoteViewer {
+            alias: alias.to_string(),
+            address: HumanAddr::from("merchant"),
+            padding: None,
+        };
+        handle(&mut deps, mock_env("frump", &[]), handle_msg).unwrap();
+        // = * the viewer can read it with their viewing key
+        let query_response = query(&deps, query_note("merchant", "merchant-key")).unwrap();
+        match from_binary(&query_response).unwrap() {
+            QueryAnswer::Note { note } => assert_eq!(note, "1 Main St"),
+            _ => panic!("Unexpected query answer"),
+        }
+
+        // = when the owner removes the viewer
+        let handle_msg = HandleMsg::RemoveNoteViewer {
+            alias: alias.to_string(),
+            address: HumanAddr::from("merchant"),
+            padding: None,
+        };
+        handle(&mut deps, mock_env("frump", &[]), handle_msg.clone()).unwrap();
+        // = * the viewer can no longer read it
+        let query_response = query(&deps, query_note("merchant", "merchant-key"));
+        let error = extract_query_error(query_response);
+        assert_eq!(error.code, QueryErrorCode::NotFound);
+        // = * removing them again raises an error
+        let handle_result = handle(&mut deps, mock_env("frump", &[]), handle_msg);
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, "Viewer not found");
+
+        // = when the owner clears the note
+        let handle_msg = HandleMsg::SetNote {
+            alias: alias.to_string(),
+            note: None,
+            padding: None,
+        };
+        handle(&mut deps, mock_env("frump", &[]), handle_msg).unwrap();
+        // = * it can no longer be read
+        let query_response = query(&deps, query_note("frump", "frump-key"));
+        let error = extract_query_error(query_response);
+        assert_eq!(error.code, QueryErrorCode::NotFound);
+    }
+
+    #[test]
+    fn test_try_set_price_oracle() {
+        let (_init_result, deps) = init_helper();
+        let env = mock_env(mock_user_address(), &[]);
+        let mut deps = Extern {
+            storage: deps.storage,
+            api: deps.api,
+            querier: PriceOracleQuerier {
+                price: PriceResponse {
+                    // $0.40 for 1 Buttcoin
+                    rate: Uint128(400_000_000_000_000_000),
+                    last_updated: env.block.time,
+                },
+            },
+        };
+        let set_price_oracle = HandleMsg::SetPriceOracle {
+            price_oracle: Some(PriceOracle {
+                contract: SecretContract {
+                    address: HumanAddr::from("price-oracle"),
+                    contract_hash: "price-oracle-hash".to_string(),
+                },
+                symbol: "BUTT".to_string(),
+                // $1
+                usd_fee: Uint128(1_000_000_000_000_000_000),
+                max_staleness: 60,
+            }),
+            padding: None,
+        };
+        let create = |from: &str, alias: &str, amount: u128| HandleMsg::Receive {
+            sender: HumanAddr::from(from),
+            from: HumanAddr::from(from),
+            amount: Uint128(amount),
+            msg: to_binary(&ReceiveMsg::Create {
+                alias: alias.to_string(),
+                avatar_url: None,
+                avatar_metadata: None,
+                records: None,
+                fallback: None,
+            })
+            .unwrap(),
+            padding: None,
+        };
+
+        // = when someone other than the admin sets the price oracle
+        // = * it raises an error
+        let handle_result = handle(&mut deps, mock_env("frump", &[]), set_price_oracle.clone());
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, "Unauthorized");
+
+        // = when the admin sets the price oracle
+        handle(&mut deps, env, set_price_oracle).unwrap();
+        // = * the fee is the USD fee at the oracle's rate
+        let handle_result = handle(
+            &mut deps,
+            mock_env(mock_buttcoin().address, &[]),
+            create("priced-user", "nailbiter", AMOUNT_FOR_TRANSACTION),
+        );
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, "Amount sent in: 1000000. Amount required 2500000.");
+        // = * the fee paid is passed on to the butt lode
+        let handle_result = handle(
+            &mut deps,
+            mock_env(mock_buttcoin().address, &[]),
+            create("priced-user", "nailbiter", 2_500_000),
+        )
+        .unwrap();
+        assert_eq!(
+            handle_result.messages,
+            vec![snip20::transfer_msg(
+                mock_butt_lode().address,
+                Uint128(2_500_000),
+                None,
+                BLOCK_SIZE,
+                mock_buttcoin().contract_hash,
+                mock_buttcoin().address,
+            )
+            .unwrap()],
+        );
+        // = * the fee is quoted at the oracle's rate
+        let query_result = query(&deps, QueryMsg::Status {}).unwrap();
+        match from_binary(&query_result).unwrap() {
+            QueryAnswer::Status { fees, .. } => assert_eq!(fees.create, Uint128(2_500_000)),
+            _ => panic!("Unexpected query answer"),
+        }
+
+        // = when the oracle rate is older than the max staleness
+        // = * it raises an error
+        let mut env = mock_env(mock_buttcoin().address, &[]);
+        env.block.time += 61;
+        let handle_result = handle(&mut deps, env, create("stale-user", "stale", 2_500_000));
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, "Price oracle rate is stale");
+    }
+
+    #[test]
+    fn test_try_set_private_reverse_lookup() {
+        let (_init_result, mut deps) = init_helper();
+        let create_alias_message = ReceiveMsg::Create {
+            alias: "nailbiter".to_string(),
+            avatar_url: None,
+            avatar_metadata: None,
+            records: None,
+            fallback: None,
+        };
+        let receive_msg = HandleMsg::Receive {
+            sender: mock_user_address(),
+            from: mock_user_address(),
+            amount: Uint128(AMOUNT_FOR_TRANSACTION),
+            msg: to_binary(&create_alias_message).unwrap(),
+            padding: None,
+        };
+        handle(
+            &mut deps,
+            mock_env(mock_buttcoin().address, &[]),
+            receive_msg,
+        )
+        .unwrap();
+        let search_by_address = |viewing_key: Option<&str>| QueryMsg::Search {
+            search_type: "address".to_string(),
+            search_value: mock_user_address().to_string(),
+            include_metadata: None,
+            viewing_key: viewing_key.map(|key| key.to_string()),
+        };
+
+        // = when the alias belongs to someone else
+        // = * it raises an error
+        let handle_msg = HandleMsg::SetPrivateReverseLookup {
+            alias: "nailbiter".to_string(),
+            enabled: true,
+            padding: None,
+        };
+        let handle_result = handle(&mut deps, mock_env("frump", &[]), handle_msg.clone());
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, "Unauthorized");
+
+        // = when the owner turns private reverse lookup on
+        handle(&mut deps, mock_env(mock_user_address(), &[]), handle_msg).unwrap();
+        // = * searching by address without a viewing key finds nothing
+        let query_response = query(&deps, search_by_address(None));
+        let error = extract_query_error(query_response);
+        assert_eq!(error.code, QueryErrorCode::NotFound);
+        // = * resolving the address leaves it without an alias
+        let query_response = query(
+            &deps,
+            QueryMsg::ResolveAddresses {
+                addresses: vec![mock_user_address()],
+            },
+        )
+        .unwrap();
+        match from_binary(&query_response).unwrap() {
+            QueryAnswer::ResolveAddresses { addresses } => {
+                assert_eq!(addresses[0].result, ResolveResult::NotFound);
+            }
+            _ => panic!("Unexpected query answer"),
+        }
+        // = * searching by alias still works
+        let search_response = query(
+            &deps,
+            QueryMsg::Search {
+                search_type: "alias".to_string(),
+                search_value: "nailbiter".to_string(),
+                include_metadata: None,
+                viewing_key: None,
+            },
+        )
+        .unwrap();
+        let val: SearchResponse = from_binary(&search_response).unwrap();
+        assert_eq!(val.attributes.address, mock_user_address());
+
+        // = when the address has set a viewing key
+        let handle_msg = HandleMsg::SetViewingKey {
+            key: "hunter2".to_string(),
+            padding: None,
+        };
+        let handle_result = handle(&mut deps, mock_env(mock_user_address(), &[]), handle_msg);
+        let handle_result_unwrapped = handle_result.unwrap();
+        let handle_result_data: HandleAnswer =
+            from_binary(&handle_result_unwrapped.data.unwrap()).unwrap();
+        assert_eq!(
+            to_binary(&handle_result_data).unwrap(),
+            to_binary(&HandleAnswer::SetViewingKey { status: Success }).unwrap()
+        );
+        // = * searching by address with the wrong viewing key finds nothing
+        let query_response = query(&deps, search_by_address(Some("hunter3")));
+        let error = extract_query_error(query_response);
+        assert_eq!(error.code, QueryErrorCode::NotFound);
+        // = * searching by address with the viewing key finds the alias
+        let search_response = query(&deps, search_by_address(Some("hunter2"))).unwrap();
+        let val: SearchResponse = from_binary(&search_response).unwrap();
+        assert_eq!(val.attributes.alias, "nailbiter");
+    }
+
+    #[test]
+    fn test_try_set_promotions() {
+        let (_init_result, mut deps) = init_helper();
+        let height: u64 = mock_env(mock_user_address(), &[]).block.height;
+        let promotion = |start_height: u64, end_height: u64| Promotion {
+            start_height,
+            end_height,
+            percent_off: 25,
+        };
+        let set_promotions = |promotions: Vec<Promotion>| HandleMsg::SetPromotions {
+            promotions,
+            padding: None,
+        };
+        let create = |alias: &str, amount: u128| HandleMsg::Receive {
+            sender: mock_user_address(),
+            from: mock_user_address(),
+            amount: Uint128(amount),
+            msg: to_binary(&ReceiveMsg::Create {
+                alias: alias.to_string(),
+                avatar_url: None,
+                avatar_metadata: None,
+                records: None,
+                fallback: None,
+            })
+            .unwrap(),
+            padding: None,
+        };
+        let quote = |deps: &Extern<MockStorage, MockApi, MockQuerier>, height: Option<u64>| {
+            let query_result = query(
+                deps,
+                QueryMsg::Quote {
+                    alias: "nail biter".to_string(),
+                    height,
+                    periods: None,
+                },
+            )
+            .unwrap();
+            match from_binary(&query_result).unwrap() {
+                QueryAnswer::Quote {
+                    amount, promotion, ..
+                } => (amount, promotion),
+                _ => panic!("Unexpected query answer"),
+            }
+        };
+
+        // = when someone other than a Treasurer sets the promotions
+        // = * it raises an error
+        let handle_result = handle(
+            &mut deps,
+            mock_env("frump", &[]),
+            set_promotions(vec![promotion(height, height + 10)]),
+        );
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, "Unauthorized");
+
+        // = when a promotion ends before it starts
+        // = * it raises an error
+        let handle_result = handle(
+            &mut deps,
+            mock_env(mock_user_address(), &[]),
+            set_promotions(vec![promotion(height, height)]),
+        );
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, "A promotion must end after it starts");
+
+        // = when promotions overlap
+        // = * it raises an error
+        let handle_result = handle(
+            &mut deps,
+            mock_env(mock_user_address(), &[]),
+            set_promotions(vec![
+                promotion(height, height + 10),
+                promotion(height + 5, height + 20),
+            ]),
+        );
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, "Promotions must be in order and not overlap");
+
+        // = when the Treasurer schedules a promotion
+        // = * it stores the promotion
+        let handle_result = handle(
+            &mut deps,
+            mock_env(mock_user_address(), &[]),
+            set_promotions(vec![promotion(height, height + 10)]),
+        );
+        assert_eq!(
+            handle_result.unwrap().data,
+            Some(to_binary(&HandleAnswer::SetPromotions { status: Success }).unwrap())
+        );
+
+        // = * quotes at a height during the promotion have it taken off
+        assert_eq!(
+            quote(&deps, Some(height)),
+            (
+                Uint128(AMOUNT_FOR_TRANSACTION * 3 / 4),
+                Some(promotion(height, height + 10))
+            )
+        );
+        assert_eq!(
+            quote(&deps, Some(height + 10)),
+            (Uint128(AMOUNT_FOR_TRANSACTION), None)
+        );
+        assert_eq!(quote(&deps, None), (Uint128(AMOUNT_FOR_TRANSACTION), None));
+
+        // = * registering while it runs costs the promotional fee
+        let handle_result = handle(
+            &mut deps,
+            mock_env(mock_buttcoin().address, &[]),
+            create("nail biter", AMOUNT_FOR_TRANSACTION),
+        );
+        let error = extract_error_msg(handle_result);
+        assert_eq!(
+            error,
+            format!(
+                "Amount sent in: {}. Amount required {}.",
+                AMOUNT_FOR_TRANSACTION,
+                AMOUNT_FOR_TRANSACTION * 3 / 4
+            )
+        );
+        handle(
+            &mut deps,
+            mock_env(mock_buttcoin().address, &[]),
+            create("nail biter", AMOUNT_FOR_TRANSACTION * 3 / 4),
+        )
+        .unwrap();
+
+        // = * registering once it has ended costs the full fee
+        let mut env = mock_env(mock_buttcoin().address, &[]);
+        env.block.height = height + 10;
+        handle(&mut deps, env, create("nailbiter", AMOUNT_FOR_TRANSACTION)).unwrap();
+    }
+
+    #[test]
+    fn test_try_set_target() {
+        let (_init_result, mut deps) = init_helper();
+        let create_alias_message = ReceiveMsg::Create {
+            alias: "nailbiter".to_string(),
+            avatar_url: None,
+            avatar_metadata: None,
+            records: None,
+            fallback: None,
+        };
+        let receive_msg = HandleMsg::Receive {
+            sender: mock_user_address(),
+            from: mock_user_address(),
+            amount: Uint128(AMOUNT_FOR_TRANSACTION),
+            msg: to_binary(&create_alias_message).unwrap(),
+            padding: None,
+        };
+        handle(
+            &mut deps,
+            mock_env(mock_buttcoin().address, &[]),
+            receive_msg,
+        )
+        .unwrap();
+
+        // = when the alias belongs to someone else
+        // = * it raises an error
+        let handle_msg = HandleMsg::SetTarget {
+            alias: "nailbiter".to_string(),
+            target: Some("Epstein didn't kill himself".to_string()),
+            padding: None,
+        };
+        let handle_result = handle(&mut deps, mock_env("frump", &[]), handle_msg);
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, "Unauthorized");
+
+        // = when the target does not exist
+        // = * it raises an error
+        let handle_msg = HandleMsg::SetTarget {
+            alias: "nailbiter".to_string(),
+            target: Some("nobody".to_string()),
+            padding: None,
+        };
+        let handle_result = handle(&mut deps, mock_env(mock_user_address(), &[]), handle_msg);
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, "Alias target not found");
+
+        // = when the owner points the alias at another alias
+        let handle_msg = HandleMsg::SetTarget {
+            alias: "nailbiter".to_string(),
+            target: Some("Epstein didn't kill himself".to_string()),
+            padding: None,
+        };
+        let handle_result = handle(&mut deps, mock_env(mock_user_address(), &[]), handle_msg);
+        let handle_result_unwrapped = handle_result.unwrap();
+        let handle_result_data: HandleAnswer =
+            from_binary(&handle_result_unwrapped.data.unwrap()).unwrap();
+        assert_eq!(
+            to_binary(&handle_result_data).unwrap(),
+            to_binary(&HandleAnswer::SetTarget { status: Success }).unwrap()
+        );
+        // = * searching by alias resolves to the target's address
+        let search_response = query(
+            &deps,
+            QueryMsg::Search {
+                search_type: "alias".to_string(),
+                search_value: "nailbiter".to_string(),
+                include_metadata: Some(true),
+                viewing_key: None,
+            },
+        )
+        .unwrap();
+        let val: SearchResponse = from_binary(&search_response).unwrap();
+        assert_eq!(val.attributes.address, HumanAddr::from("frump"));
+        assert_eq!(
+            val.metadata.unwrap().target,
+            Some("epstein didn't kill himself".to_string())
+        );
+        // = * searching by address still returns the owner's alias
+        let search_response = query(
+            &deps,
+            QueryMsg::Search {
+                search_type: "address".to_string(),
+                search_value: mock_user_address().to_string(),
+                include_metadata: None,
+                viewing_key: None,
+            },
+        )
+        .unwrap();
+        let val: SearchResponse = from_binary(&search_response).unwrap();
+        assert_eq!(val.attributes.address, mock_user_address());
+
+        // = when the target would loop back
+        // = * it raises an error
+        let handle_msg = HandleMsg::SetTarget {
+            alias: "Epstein didn't kill himself".to_string(),
+            target: Some("nailbiter".to_string()),
+            padding: None,
+        };
+        let handle_result = handle(&mut deps, mock_env("frump", &[]), handle_msg);
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, "Alias target can not loop back");
+
+        // = when the owner clears the target
+        // = * searching by alias resolves to the owner's address again
+        let handle_msg = HandleMsg::SetTarget {
+            alias: "nailbiter".to_string(),
+            target: None,
+            padding: None,
+        };
+        handle(&mut deps, mock_env(mock_user_address(), &[]), handle_msg).unwrap();
+        let search_response = query(
+            &deps,
+            QueryMsg::Search {
+                search_type: "alias".to_string(),
+                search_value: "nailbiter".to_string(),
+                include_metadata: None,
+                viewing_key: None,
+            },
+        )
+        .unwrap();
+        let val: SearchResponse = from_binary(&search_response).unwrap();
+        assert_eq!(val.attributes.address, mock_user_address());
+    }
+
+    #[test]
+    fn test_try_set_timelock() {
+        let (_init_result, mut deps) = init_helper();
+        let height: u64 = mock_env(mock_user_address(), &[]).block.height;
+        let set_batch_limits = HandleMsg::SetBatchLimits {
+            batch_limits: BatchLimits {
+                resolve_addresses: 20,
+                resolve_canonical_addresses: 20,
+            },
+            padding: None,
+        };
+        let resolve_addresses_limit = |deps: &Extern<MockStorage, MockApi, MockQuerier>| -> u16 {
+            let config: Config = TypedStore::attach(&deps.storage).load(CONFIG_KEY).unwrap();
+            config.batch_limits.resolve_addresses
+        };
+
+        // = when someone other than a SuperAdmin sets the timelock
+        // = * it raises an error
+        let handle_result = handle(
+            &mut deps,
+            mock_env("frump", &[]),
+            HandleMsg::SetTimelock {
+                blocks: 100,
+                padding: None,
+            },
+        );
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, "Unauthorized");
+
+        // = when the admin sets the timelock
+        // = * config updates are queued instead of applied
+        handle(
+            &mut deps,
+            mock_env(mock_user_address(), &[]),
+            HandleMsg::SetTimelock {
+                blocks: 100,
+                padding: None,
+            },
+        )
+        .unwrap();
+        let handle_result = handle(
+            &mut deps,
+            mock_env(mock_user_address(), &[]),
+            set_batch_limits.clone(),
+        )
+        .unwrap();
+        assert_eq!(
+            handle_result.data,
+            Some(
+                to_binary(&HandleAnswer::Queued {
+                    id: 1,
+                    executable_at: height + 100
+                })
+                .unwrap()
+            )
+        );
+        assert_eq!(resolve_addresses_limit(&deps), DEFAULT_MAX_BATCH_SIZE);
+        // = * the queued action is public
+        let query_answer: QueryAnswer =
+            from_binary(&query(&deps, QueryMsg::QueuedActions {}).unwrap()).unwrap();
+        match query_answer {
+            QueryAnswer::QueuedActions {
+                queued_actions,
+                timelock_blocks,
+            } => {
+                assert_eq!(
+                    queued_actions,
+                    vec![QueuedAction {
+                        id: 1,
+                        msg: set_batch_limits.clone(),
+                        queued_by: mock_user_address(),
+                        queued_at: height,
+                        executable_at: height + 100,
+                    }]
+                );
+                assert_eq!(timelock_blocks, 100);
+            }
+            _ => panic!("Unexpected query answer"),
+        }
+
+        // = when someone without the role sends a timelocked handle
+        // = * it raises an error instead of being queued
+        let handle_result = handle(&mut deps, mock_env("frump", &[]), set_batch_limits.clone());
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, "Unauthorized");
+
+        // = when a queued action is executed before its timelock is over
+        // = * it raises an error
+        let execute_queued_action = |id: u64| HandleMsg::ExecuteQueuedAction { id, padding: None };
+        let mut env = mock_env("frump", &[]);
+        env.block.height = height + 99;
+        let handle_result = handle(&mut deps, env, execute_queued_action(1));
+        let error = extract_error_msg(handle_result);
+        assert_eq!(
+            error,
+            format!(
+                "Queued action can't be executed until block {}",
+                height + 100
+            )
+        );
+
+        // = when anyone executes a queued action after its timelock
+        // = * it is applied as sent by the admin and removed from the queue
+        let mut env = mock_env("frump", &[]);
+        env.block.height = height + 100;
+        handle(&mut deps, env, execute_queued_action(1)).unwrap();
+        assert_eq!(resolve_addresses_limit(&deps), 20);
+        let handle_result = handle(&mut deps, mock_env("frump", &[]), execute_queued_action(1));
+        assert_eq!(
+            handle_result.unwrap_err(),
+            StdError::not_found("Queued action")
+        );
+
+        // = when a SuperAdmin cancels a queued action
+        // = * it is removed from the queue
+        handle(
+            &mut deps,
+            mock_env(mock_user_address(), &[]),
+            set_batch_limits,
+        )
+        .unwrap();
+        let cancel_queued_action = HandleMsg::CancelQueuedAction {
+            id: 2,
+            padding: None,
+        };
+        let handle_result = handle(
+            &mut deps,
+            mock_env("frump", &[]),
+            cancel_queued_action.clone(),
+        );
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, "Unauthorized");
+        handle(
+            &mut deps,
+            mock_env(mock_user_address(), &[]),
+            cancel_queued_action,
+        )
+        .unwrap();
+        let handle_result = handle(&mut deps, mock_env("frump", &[]), execute_queued_action(2));
+        assert_eq!(
+            handle_result.unwrap_err(),
+            StdError::not_found("Queued action")
+        );
+
+        // = when an admin sends any other handle that changes fees, bans or config
+        // = * it is queued for the role that sends it
+        let handle_result = handle(
+            &mut deps,
+            mock_env(mock_user_address(), &[]),
+            HandleMsg::OpenLottery {
+                blocks: 10,
+                padding: None,
+            },
+        )
+        .unwrap();
+        assert_eq!(
+            handle_result.data,
+            Some(
+                to_binary(&HandleAnswer::Queued {
+                    id: 3,
+                    executable_at: height + 100
+                })
+                .unwrap()
+            )
+        );
+        let config: Config = TypedStore::attach(&deps.storage).load(CONFIG_KEY).unwrap();
+        assert_eq!(config.lottery_ends_at, None);
+        let token = SecretContract {
+            address: HumanAddr::from("token"),
+            contract_hash: "token-hash".to_string(),
+        };
+        let drop = Drop {
+            start_height: height,
+            start_price: Uint128(1_000),
+            end_price: Uint128(100),
+            decay_blocks: 100,
+        };
+        for (msg, role) in vec![
+            (
+                HandleMsg::AddRoutableToken {
+                    token,
+                    padding: None,
+                },
+                AdminRole::Treasurer,
+            ),
+            (
+                HandleMsg::CancelDrop {
+                    alias: "btn".to_string(),
+                    padding: None,
+                },
+                AdminRole::Treasurer,
+            ),
+            (
+                HandleMsg::ScheduleDrop {
+                    alias: "btn".to_string(),
+                    drop,
+                    padding: None,
+                },
+                AdminRole::Treasurer,
+            ),
+            (
+                HandleMsg::UnreserveAliases {
+                    aliases: vec!["btn".to_string()],
+                    padding: None,
+                },
+                AdminRole::Moderator,
+            ),
+            (
+                HandleMsg::AddKnownContract {
+                    label: "btn".to_string(),
+                    address: HumanAddr::from("btn-contract"),
+                    padding: None,
+                },
+                AdminRole::SuperAdmin,
+            ),
+            (
+                HandleMsg::AddOracle {
+                    address: HumanAddr::from("oracle"),
+                    padding: None,
+                },
+                AdminRole::SuperAdmin,
+            ),
+            (
+                HandleMsg::OpenLottery {
+                    blocks: 10,
+                    padding: None,
+                },
+                AdminRole::SuperAdmin,
+            ),
+            (
+                HandleMsg::RemoveKnownContract {
+                    label: "btn".to_string(),
+                    padding: None,
+                },
+                AdminRole::SuperAdmin,
+            ),
+            (
+                HandleMsg::RemoveOracle {
+                    address: HumanAddr::from("oracle"),
+                    padding: None,
+                },
+                AdminRole::SuperAdmin,
+            ),
+            (
+                HandleMsg::SetAirdropMerkleRoot {
+                    merkle_root: None,
+                    padding: None,
+                },
+                AdminRole::SuperAdmin,
+            ),
+            (
+                HandleMsg::SetCrossChainSnapshot {
+                    cross_chain_snapshot: None,
+                    padding: None,
+                },
+                AdminRole::SuperAdmin,
+            ),
+        ] {
+            assert_eq!(timelocked_role(&msg), Some(role));
+        }
+        // = * handles that can't wait aren't queued
+        for msg in vec![
+            HandleMsg::CancelQueuedAction {
+                id: 3,
+                padding: None,
+            },
+            HandleMsg::EndBootstrap { padding: None },
+            HandleMsg::Suspend {
+                alias: "btn".to_string(),
+                hide_resolution: false,
+                padding: None,
+            },
+            HandleMsg::Unsuspend {
+                alias: "btn".to_string(),
+                padding: None,
+            },
+        ] {
+            assert_eq!(timelocked_role(&msg), None);
+        }
+    }
+
+    #[test]
+    fn test_try_set_wildcard() {
+        let (_init_result, mut deps) = init_helper();
+        let create_alias_message = ReceiveMsg::Create {
+            alias: "nailbiter".to_string(),
+            avatar_url: None,
+            avatar_metadata: None,
+            records: None,
+            fallback: None,
+        };
+        let receive_msg = HandleMsg::Receive {
+            sender: mock_user_address(),
+            from: mock_user_address(),
+            amount: Uint128(AMOUNT_FOR_TRANSACTION),
+            msg: to_binary(&create_alias_message).unwrap(),
+            padding: None,
+        };
+        handle(
+            &mut deps,
+            mock_env(mock_buttcoin().address, &[]),
+            receive_msg,
+        )
+        .unwrap();
+        let search_subalias = QueryMsg::Search {
+            search_type: "alias".to_string(),
+            search_value: "Team.X.nailbiter".to_string(),
+            include_metadata: None,
+            viewing_key: None,
+        };
+
+        // = when wildcard is off
+        // = * subaliases are not found
+        let query_response = query(&deps, search_subalias.clone());
+        let error = extract_query_error(query_response);
+        assert_eq!(error.code, QueryErrorCode::NotFound);
+
+        // = when the alias belongs to someone else
+        // = * it raises an error
+        let handle_msg = HandleMsg::SetWildcard {
+            alias: "nailbiter".to_string(),
+            wildcard: true,
+            padding: None,
+        };
+        let handle_result = handle(&mut deps, mock_env("frump", &[]), handle_msg.clone());
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, "Unauthorized");
+
+        // = when the owner turns wildcard on
+        let handle_result = handle(&mut deps, mock_env(mock_user_address(), &[]), handle_msg);
+        let handle_result_unwrapped = handle_result.unwrap();
+        let handle_result_data: HandleAnswer =
+            from_binary(&handle_result_unwrapped.data.unwrap()).unwrap();
+        assert_eq!(
+            to_binary(&handle_result_data).unwrap(),
+            to_binary(&HandleAnswer::SetWildcard { status: Success }).unwrap()
+        );
+        // = * subaliases resolve to the alias
+        let search_response = query(&deps, search_subalias).unwrap();
+        let val: SearchResponse = from_binary(&search_response).unwrap();
+        assert_eq!(val.attributes.alias, "nailbiter");
+        assert_eq!(val.attributes.address, mock_user_address());
+        // = * aliases that only share a suffix are not matched
+        let query_response = query(
+            &deps,
+            QueryMsg::Search {
+                search_type: "alias".to_string(),
+                search_value: "xnailbiter".to_string(),
+                include_metadata: None,
+                viewing_key: None,
+            },
+        );
+        let error = extract_query_error(query_response);
+        assert_eq!(error.code, QueryErrorCode::NotFound);
+    }
+
+    #[test]
+    fn test_try_sponsored_transfer() {
+        use k256::ecdsa::signature::Signer;
+        use k256::ecdsa::{Signature, SigningKey};
+
+        // The signer's bech32 address is longer than the default mock canonical length
+        let mut deps = mock_dependencies(45, &[]);
+        let alias: &str = "epstein didn't kill himself";
+        let signer: HumanAddr = HumanAddr::from("cosmos150rtrmj2f8vl9tem8qpfw36ylw5jg9j2nr2fee");
+        init(
+            &mut deps,
+            mock_env(mock_user_address(), &[]),
+            mock_init_msg(vec![alias_attributes(alias, signer.as_str())]),
+        )
+        .unwrap();
+        let signing_key: SigningKey = SigningKey::from_bytes(&[7; 32]).unwrap();
+        let sponsorship = |signing_key: &SigningKey, message: &str, nonce: u64| {
+            let signature: Signature =
+                signing_key.sign(&adr36_sign_doc(signer.as_str(), message.as_bytes()));
+            Sponsorship {
+                signer: signer.clone(),
+                nonce,
+                public_key: Binary(signing_key.verifying_key().to_bytes().to_vec()),
+                signature: Binary(signature.as_ref().to_vec()),
+            }
+        };
+        let transfer_message = |nonce: u64| {
+            format!(
+                "Transfer {} to nail biter with nonce {} on cosmos2contract",
+                alias, nonce
+            )
+        };
+        let sponsored_transfer = |sponsorship: Sponsorship| HandleMsg::SponsoredTransfer {
+            alias: alias.to_string(),
+            recipient: HumanAddr::from("nail biter"),
+            sponsorship,
+            padding: None,
+        };
+
+        // = when the public key isn't the one the signer's address is derived from
+        // = * it raises an error
+        let other_signing_key: SigningKey = SigningKey::from_bytes(&[8; 32]).unwrap();
+        let handle_result = handle(
+            &mut deps,
+            mock_env("relayer", &[]),
+            sponsored_transfer(sponsorship(&other_signing_key, &transfer_message(0), 0)),
+        );
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, "Public key doesn't belong to the address");
+
+        // = when the signature is for another message
+        // = * it raises an error
+        let handle_result = handle(
+            &mut deps,
+            mock_env("relayer", &[]),
+            sponsored_transfer(sponsorship(&signing_key, &transfer_message(1), 0)),
+        );
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, "Signature is invalid");
+
+        // = when the nonce isn't the signer's next one
+        // = * it raises an error
+        let handle_result = handle(
+            &mut deps,
+            mock_env("relayer", &[]),
+            sponsored_transfer(sponsorship(&signing_key, &transfer_message(1), 1)),
+        );
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, "Nonce must be 0");
+
+        // = when a relayer sends a transfer signed by the owner
+        // = * it transfers the alias
+        let handle_result = handle(
+            &mut deps,
+            mock_env("relayer", &[]),
+            sponsored_transfer(sponsorship(&signing_key, &transfer_message(0), 0)),
+        )
+        .unwrap();
+        let handle_result_data: HandleAnswer = from_binary(&handle_result.data.unwrap()).unwrap();
+        assert_eq!(
+            to_binary(&handle_result_data).unwrap(),
+            to_binary(&HandleAnswer::SponsoredTransfer { status: Success }).unwrap()
+        );
+        let search_response = query(&deps, search_address_msg("nail biter")).unwrap();
+        let val: SearchResponse = from_binary(&search_response).unwrap();
+        assert_eq!(val.attributes.alias, alias);
+        // = * it uses up the nonce
+        let query_result = query(
+            &deps,
+            QueryMsg::Nonce {
+                address: signer.clone(),
+            },
+        )
+        .unwrap();
+        let query_answer: QueryAnswer = from_binary(&query_result).unwrap();
+        assert_eq!(
+            to_binary(&query_answer).unwrap(),
+            to_binary(&QueryAnswer::Nonce { nonce: 1 }).unwrap()
+        );
+
+        // = when the signed transfer is replayed
+        // = * it raises an error
+        let handle_result = handle(
+            &mut deps,
+            mock_env("relayer", &[]),
+            sponsored_transfer(sponsorship(&signing_key, &transfer_message(0), 0)),
+        );
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, "Nonce has already been used");
+
+        // = when a relayer pays for a create signed by the signer
+        // = * it creates the alias for the signer
+        let message: String = "Create nancy with nonce 1 on cosmos2contract".to_string();
+        let receive = HandleMsg::Receive {
+            sender: HumanAddr::from("relayer"),
+            from: HumanAddr::from("relayer"),
+            amount: Uint128(AMOUNT_FOR_TRANSACTION),
+            msg: to_binary(&ReceiveMsg::SponsoredCreate {
+                alias: "nancy".to_string(),
+                sponsorship: sponsorship(&signing_key, &message, 1),
+            })
+            .unwrap(),
+            padding: None,
+        };
+        handle(&mut deps, mock_env(mock_buttcoin().address, &[]), receive).unwrap();
+        let search_response = query(&deps, search_address_msg(signer.as_str())).unwrap();
+        let val: SearchResponse = from_binary(&search_response).unwrap();
+        assert_eq!(val.attributes.alias, "nancy");
+    }
+
+    #[test]
+    fn test_try_start_reclaim() {
+        let (_init_result, mut deps) = init_helper();
+        let alias = "Epstein didn't kill himself";
+        let height: u64 = mock_env(mock_user_address(), &[]).block.height;
+        let env_at = |sender: &str, block_height: u64| {
+            let mut env = mock_env(sender, &[]);
+            env.block.height = block_height;
+            env
+        };
+        let start_reclaim = HandleMsg::StartReclaim {
+            alias: alias.to_string(),
+            padding: None,
+        };
+        let settle_reclaim = HandleMsg::SettleReclaim {
+            alias: alias.to_string(),
+            padding: None,
+        };
+        let receive = |from: &str, amount: u128, msg: ReceiveMsg| HandleMsg::Receive {
+            sender: HumanAddr::from(from),
+            from: HumanAddr::from(from),
+            amount: Uint128(amount),
+            msg: to_binary(&msg).unwrap(),
+            padding: None,
+        };
+        let bid = |from: &str, amount: u128| {
+            receive(
+                from,
+                amount,
+                ReceiveMsg::BidReclaim {
+                    alias: alias.to_string(),
+                },
+            )
+        };
+        let defend = |amount: u128| {
+            receive(
+                "frump",
+                amount,
+                ReceiveMsg::DefendReclaim {
+                    alias: alias.to_string(),
+                },
+            )
+        };
+        let buttcoin_transfer = |recipient: HumanAddr, amount: u128| {
+            snip20::transfer_msg(
+                recipient,
+                Uint128(amount),
+                None,
+                BLOCK_SIZE,
+                mock_buttcoin().contract_hash,
+                mock_buttcoin().address,
+            )
+            .unwrap()
+        };
+        let reclaim_of = |deps: &Extern<MockStorage, MockApi, MockQuerier>| -> Option<Reclaim> {
+            let query_result = query(
+                deps,
+                QueryMsg::Reclaim {
+                    alias: alias.to_string(),
+                },
+            )
+            .unwrap();
+            match from_binary(&query_result).unwrap() {
+                QueryAnswer::Reclaim { reclaim } => reclaim,
+                _ => panic!("Unexpected query answer"),
+            }
+        };
+
+        // = when reclaims are turned off
+        // = * it raises an error
+        let handle_result = handle(
+            &mut deps,
+            env_at("stranger", height + 100),
+            start_reclaim.clone(),
+        );
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, "Reclaims are turned off");
+
+        // = when someone other than a Moderator sets the reclaim terms
+        // = * it raises an error
+        let set_reclaim_terms = HandleMsg::SetReclaimTerms {
+            reclaim_terms: Some(ReclaimTerms {
+                idle_blocks: 100,
+                bidding_blocks: 10,
+                defense_blocks: 10,
+            }),
+            padding: None,
+        };
+        let handle_result = handle(&mut deps, mock_env("frump", &[]), set_reclaim_terms.clone());
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, "Unauthorized");
+        handle(
+            &mut deps,
+            mock_env(mock_user_address(), &[]),
+            set_reclaim_terms,
+        )
+        .unwrap();
+
+        // = when the alias hasn't been idle for long enough
+        // = * it raises an error
+        let handle_result = handle(
+            &mut deps,
+            env_at("stranger", height + 99),
+            start_reclaim.clone(),
+        );
+        let error = extract_error_msg(handle_result);
+        assert_eq!(
+            error,
+            "Aliases can be reclaimed after 100 blocks without records, an avatar or updates"
+        );
+
+        // = when anyone starts a reclaim on an idle alias
+        // = * it opens bidding
+        handle(
+            &mut deps,
+            env_at("stranger", height + 100),
+            start_reclaim.clone(),
+        )
+        .unwrap();
+        assert_eq!(
+            reclaim_of(&deps),
+            Some(Reclaim {
+                started_by: HumanAddr::from("stranger"),
+                started_at: height + 100,
+                bidding_ends_at: height + 110,
+                defense_ends_at: height + 120,
+                bidder: None,
+                bid: Uint128(0),
+            })
+        );
+
+        // = * the owner can't destroy the alias while it is being reclaimed
+        let handle_result = handle(
+            &mut deps,
+            env_at("frump", height + 100),
+            HandleMsg::Destroy {
+                alias: alias.to_string(),
+                padding: None,
+            },
+        );
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, "Alias is being reclaimed");
+
+        // = * the owner can't bid
+        let handle_result = handle(
+            &mut deps,
+            env_at(mock_buttcoin().address.as_str(), height + 100),
+            bid("frump", 500),
+        );
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, "Bidder already has an alias");
+
+        // = * a higher bid refunds the previous one
+        handle(
+            &mut deps,
+            env_at(mock_buttcoin().address.as_str(), height + 100),
+            bid("bidder", 500),
+        )
+        .unwrap();
+        let handle_result = handle(
+            &mut deps,
+            env_at(mock_buttcoin().address.as_str(), height + 101),
+            bid("outbidder", 500),
+        );
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, "Bid must be more than 500");
+        let handle_result = handle(
+            &mut deps,
+            env_at(mock_buttcoin().address.as_str(), height + 101),
+            bid("outbidder", 600),
+        )
+        .unwrap();
+        assert_eq!(
+            handle_result.messages,
+            vec![buttcoin_transfer(HumanAddr::from("bidder"), 500)]
+        );
+
+        // = * the owner can only defend once bidding has ended
+        let handle_result = handle(
+            &mut deps,
+            env_at(mock_buttcoin().address.as_str(), height + 109),
+            defend(600),
+        );
+        let error = extract_error_msg(handle_result);
+        assert_eq!(
+            error,
+            format!(
+                "A reclaim can only be defended from block {} until block {}",
+                height + 110,
+                height + 120
+            )
+        );
+
+        // = * it can't be settled while the owner can still defend
+        let handle_result = handle(
+            &mut deps,
+            env_at("stranger", height + 119),
+            settle_reclaim.clone(),
+        );
+        let error = extract_error_msg(handle_result);
+        assert_eq!(
+            error,
+            format!("Reclaim can't be settled until block {}", height + 120)
+        );
+
+        // = when the owner matches the highest bid
+        // = * the bid is refunded, the payment is kept as a fee and the reclaim ends
+        let handle_result = handle(
+            &mut deps,
+            env_at(mock_buttcoin().address.as_str(), height + 110),
+            defend(600),
+        )
+        .unwrap();
+        assert_eq!(
+            handle_result.messages,
+            vec![
+                buttcoin_transfer(HumanAddr::from("outbidder"), 600),
+                buttcoin_transfer(mock_butt_lode().address, 600),
+            ]
+        );
+        assert_eq!(reclaim_of(&deps), None);
+
+        // = * the alias counts as active again
+        let handle_result = handle(
+            &mut deps,
+            env_at("stranger", height + 200),
+            start_reclaim.clone(),
+        );
+        assert!(handle_result.is_err());
+
+        // = when the owner doesn't defend
+        // = * settling gives the alias to the highest bidder and keeps the bid as a fee
+        handle(&mut deps, env_at("stranger", height + 210), start_reclaim).unwrap();
+        handle(
+            &mut deps,
+            env_at(mock_buttcoin().address.as_str(), height + 210),
+            bid("outbidder", 700),
+        )
+        .unwrap();
+        let handle_result =
+            handle(&mut deps, env_at("stranger", height + 230), settle_reclaim).unwrap();
+        assert_eq!(
+            handle_result.messages,
+            vec![buttcoin_transfer(mock_butt_lode().address, 700)]
+        );
+        let alias_object: Alias = RegistryReadonlyStorage::from_storage(&deps.storage, &deps.api)
+            .get_alias(b"epstein didn't kill himself")
+            .unwrap();
+        assert_eq!(
+            alias_object.owner,
+            deps.api
+                .canonical_address(&HumanAddr::from("outbidder"))
+                .unwrap()
+        );
+        assert_eq!(reclaim_of(&deps), None);
+    }
+
+    #[test]
+    fn test_try_suspend() {
+        let (_init_result, mut deps) = init_helper();
+        let alias = "Epstein didn't kill himself";
+        let suspend = |hide_resolution: bool| HandleMsg::Suspend {
+            alias: alias.to_string(),
+            hide_resolution,
+            padding: None,
+        };
+        let unsuspend = HandleMsg::Unsuspend {
+            alias: alias.to_string(),
+            padding: None,
+        };
+        let set_wildcard = HandleMsg::SetWildcard {
+            alias: alias.to_string(),
+            wildcard: true,
+            padding: None,
+        };
+        let search = |deps: &Extern<MockStorage, MockApi, MockQuerier>| {
+            query(
+                deps,
+                QueryMsg::Search {
+                    search_type: "alias".to_string(),
+                    search_value: alias.to_string(),
+                    include_metadata: None,
+                    viewing_key: None,
+                },
+            )
+        };
+        let recent_aliases = |deps: &Extern<MockStorage, MockApi, MockQuerier>| -> Vec<String> {
+            let query_result = query(deps, QueryMsg::Recent { limit: 10 }).unwrap();
+            match from_binary(&query_result).unwrap() {
+                QueryAnswer::Recent { aliases } => {
+                    aliases.into_iter().map(|alias| alias.alias).collect()
+                }
+                _ => panic!("Unexpected query answer"),
+            }
+        };
+        let did_document = |deps: &Extern<MockStorage, MockApi, MockQuerier>| {
+            query(
+                deps,
+                QueryMsg::DidDocument {
+                    alias: alias.to_string(),
+                },
+            )
+        };
+
+        // = when the sender isn't a Moderator
+        // = * it raises an error
+        let handle_result = handle(
+            &mut deps,
+            mock_env(HumanAddr::from("frump"), &[]),
+            suspend(false),
+        );
+        assert_eq!(
+            handle_result.unwrap_err(),
+            StdError::Unauthorized { backtrace: None }
+        );
+
+        // = when the alias doesn't exist
+        // = * it raises an error
+        let handle_result = handle(
+            &mut deps,
+            mock_env(mock_user_address(), &[]),
+            HandleMsg::Suspend {
+                alias: "nobody".to_string(),
+                hide_resolution: false,
+                padding: None,
+            },
+        );
+        assert_eq!(handle_result.unwrap_err(), StdError::not_found("Alias"));
+
+        // = when a Moderator suspends the alias
+        // = * it stops the owner from changing it
+        handle(
+            &mut deps,
+            mock_env(mock_user_address(), &[]),
+            suspend(false),
+        )
+        .unwrap();
+        let handle_result = handle(
+            &mut deps,
+            mock_env(HumanAddr::from("frump"), &[]),
+            set_wildcard.clone(),
+        );
+        assert_eq!(extract_error_msg(handle_result), "Alias is suspended");
+        // = * it still resolves, with the alias's status showing the suspension
+        search(&deps).unwrap();
+        let search_response = query(
+            &deps,
+            QueryMsg::Search {
+                search_type: "alias".to_string(),
+                search_value: alias.to_string(),
+                include_metadata: Some(true),
+                viewing_key: None,
+            },
+        )
+        .unwrap();
+        let val: SearchResponse = from_binary(&search_response).unwrap();
+        assert_eq!(val.metadata.unwrap().status, AliasStatus::Suspended);
+
+        // = when the suspension hides resolution
+        // = * it stops the alias and its address from resolving
+        handle(&mut deps, mock_env(mock_user_address(), &[]), suspend(true)).unwrap();
+        let error = extract_query_error(search(&deps));
+        assert_eq!(error.details.message, "Alias is suspended");
+        let query_result = query(
+            &deps,
+            QueryMsg::ResolveAddresses {
+                addresses: vec![HumanAddr::from("frump")],
+            },
+        )
+        .unwrap();
+        match from_binary(&query_result).unwrap() {
+            QueryAnswer::ResolveAddresses { addresses } => {
+                assert_eq!(addresses[0].result, ResolveResult::NotFound);
+            }
+            _ => panic!("Unexpected query answer"),
+        }
+        // = * it leaves the alias out of the DID document and Recent queries
+        let error = extract_query_error(did_document(&deps));
+        assert_eq!(error.code, QueryErrorCode::NotFound);
+        assert!(!recent_aliases(&deps).contains(&alias.to_lowercase()));
+
+        // = when a Moderator unsuspends the alias
+        // = * it lets the owner change it again
+        handle(
+            &mut deps,
+            mock_env(mock_user_address(), &[]),
+            unsuspend.clone(),
+        )
+        .unwrap();
+        search(&deps).unwrap();
+        did_document(&deps).unwrap();
+        assert!(recent_aliases(&deps).contains(&alias.to_lowercase()));
+        handle(
+            &mut deps,
+            mock_env(HumanAddr::from("frump"), &[]),
+            set_wildcard,
+        )
+        .unwrap();
+
+        // = when the alias isn't suspended
+        // = * it raises an error
+        let handle_result = handle(&mut deps, mock_env(mock_user_address(), &[]), unsuspend);
+        assert_eq!(
+            handle_result.unwrap_err(),
+            StdError::not_found("Suspension")
+        );
+    }
+
+    #[test]
+    fn test_try_tip() {
+        let (_init_result, mut deps) = init_helper();
+        let alias = "Epstein didn't kill himself";
+        let tip = |message: Option<&str>| HandleMsg::Tip {
+            alias: alias.to_string(),
+            message: message.map(|message| message.to_string()),
+            padding: None,
+        };
+        let withdraw_tips = HandleMsg::WithdrawTips {
+            alias: alias.to_string(),
+            padding: None,
+        };
+        let query_tip_jar = |viewing_key: &str| QueryMsg::TipJar {
+            alias: alias.to_string(),
+            viewing_key: viewing_key.to_string(),
+        };
+        handle(
+            &mut deps,
+            mock_env("frump", &[]),
+            HandleMsg::SetViewingKey {
+                key: "frump-key".to_string(),
+                padding: None,
+            },
+        )
+        .unwrap();
+
+        // = when the alias does not exist
+        // = * it raises an error
+        let handle_result = handle(
+            &mut deps,
+            mock_env("fan", &coins(1_000, "uscrt")),
+            HandleMsg::Tip {
+                alias: "nobody".to_string(),
+                message: None,
+                padding: None,
+            },
+        );
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, "Alias not found");
+
+        // = when no coins are sent
+        // = * it raises an error
+        let handle_result = handle(&mut deps, mock_env("fan", &[]), tip(None));
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, "Send the tip in native coins");
+
+        // = when the message is too long
+        // = * it raises an error
+        let message: String = "a".repeat(MAX_TIP_MESSAGE_LENGTH + 1);
+        let handle_result = handle(
+            &mut deps,
+            mock_env("fan", &coins(1_000, "uscrt")),
+            tip(Some(&message)),
+        );
+        let error = extract_error_msg(handle_result);
+        assert_eq!(
+            error,
+            format!(
+                "Tip message is too long. Maximum is {} bytes.",
+                MAX_TIP_MESSAGE_LENGTH
+            )
+        );
+
+        // = when coins are sent to an alias
+        // = * it adds them to the alias's tip jar
+        let handle_result = handle(
+            &mut deps,
+            mock_env("fan", &coins(1_000, "uscrt")),
+            tip(Some("Great podcast")),
+        )
+        .unwrap();
+        assert_eq!(
+            handle_result.data,
+            Some(to_binary(&HandleAnswer::Tip { status: Success }).unwrap())
+        );
+        let mut env = mock_env("other-fan", &coins(500, "uscrt"));
+        env.block.height = 20_000;
+        handle(&mut deps, env, tip(None)).unwrap();
+        let query_response = query(&deps, query_tip_jar("frump-key")).unwrap();
+        let tip_jar: TipJar = match from_binary(&query_response).unwrap() {
+            QueryAnswer::TipJar { tip_jar } => tip_jar,
+            _ => panic!("unexpected"),
+        };
+        let uscrt = Asset::Native {
+            denom: "uscrt".to_string(),
+        };
+        assert_eq!(
+            tip_jar.balances,
+            vec![TipBalance {
+                asset: uscrt.clone(),
+                amount: Uint128(1_500),
+            }]
+        );
+        assert_eq!(tip_jar.tips.len(), 2);
+        assert_eq!(tip_jar.tips[0].message, Some("Great podcast".to_string()));
+        assert_eq!(tip_jar.tips[1].block_height, 20_000);
+        // = * only the owner can see the tip jar
+        let query_response = query(&deps, query_tip_jar("wrong-key"));
+        let error = extract_query_error(query_response);
+        assert_eq!(error.code, QueryErrorCode::Unauthorized);
+        // = * the alias can't be destroyed until the tips are withdrawn
+        let handle_result = handle(
+            &mut deps,
+            mock_env("frump", &[]),
+            HandleMsg::Destroy {
+                alias: alias.to_string(),
+                padding: None,
+            },
+        );
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, "Withdraw the alias's tips first");
+
+        // = when someone other than the owner withdraws the tips
+        // = * it raises an error
+        let handle_result = handle(&mut deps, mock_env("fan", &[]), withdraw_tips.clone());
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, "Unauthorized");
+
+        // = when the owner withdraws the tips
+        // = * it sends them to the owner and empties the balances
+        let env = mock_env("frump", &[]);
+        let handle_result = handle(&mut deps, env.clone(), withdraw_tips.clone()).unwrap();
+        assert_eq!(
+            handle_result.messages,
+            vec![CosmosMsg::Bank(BankMsg::Send {
+                from_address: env.contract.address,
+                to_address: HumanAddr::from("frump"),
+                amount: coins(1_500, "uscrt"),
+            })]
+        );
+        let query_response = query(&deps, query_tip_jar("frump-key")).unwrap();
+        let tip_jar: TipJar = match from_binary(&query_response).unwrap() {
+            QueryAnswer::TipJar { tip_jar } => tip_jar,
+            _ => panic!("unexpected"),
+        };
+        assert!(tip_jar.balances.is_empty());
+        assert_eq!(tip_jar.tips.len(), 2);
+        // = * there is nothing left to withdraw
+        let handle_result = handle(&mut deps, mock_env("frump", &[]), withdraw_tips);
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, "There are no tips to withdraw");
+    }
+
+    #[test]
+    fn test_try_tip_token() {
+        let (_init_result, mut deps) = init_helper();
+        let alias = "Epstein didn't kill himself";
+        let receive_msg = |amount: u128| HandleMsg::Receive {
+            sender: HumanAddr::from("fan"),
+            from: HumanAddr::from("fan"),
+            amount: Uint128(amount),
+            msg: to_binary(&ReceiveMsg::Tip {
+                alias: alias.to_string(),
+                message: Some("Thanks".to_string()),
+            })
+            .unwrap(),
+            padding: None,
+        };
+
+        // = when the token is not routable
+        // = * it raises an error
+        let handle_result = handle(&mut deps, mock_env("sscrt-address", &[]), receive_msg(5));
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, "Unauthorized");
+
+        // = when nothing is sent
+        // = * it raises an error
+        let handle_result = handle(
+            &mut deps,
+            mock_env(mock_buttcoin().address, &[]),
+            receive_msg(0),
+        );
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, "Tip amount must be greater than 0");
+
+        // = when Buttcoin is sent to an alias as a tip
+        // = * it adds it to the alias's tip jar
+        let handle_result = handle(
+            &mut deps,
+            mock_env(mock_buttcoin().address, &[]),
+            receive_msg(5),
+        )
+        .unwrap();
+        assert_eq!(
+            handle_result.data,
+            Some(to_binary(&ReceiveAnswer::Tip { status: Success }).unwrap())
+        );
+        assert!(handle_result.messages.is_empty());
+        let tip_jar: TipJar = TipJarsReadonlyStorage::from_storage(&deps.storage)
+            .get_tip_jar(alias.to_lowercase().as_bytes());
+        assert_eq!(
+            tip_jar.balances,
+            vec![TipBalance {
+                asset: Asset::Snip20 {
+                    token: mock_buttcoin()
+                },
+                amount: Uint128(5),
+            }]
+        );
+        assert_eq!(tip_jar.tips[0].message, Some("Thanks".to_string()));
+
+        // = when the owner withdraws token and native tips
+        // = * it sends each balance back to the owner
+        handle(
+            &mut deps,
+            mock_env("fan", &coins(1_000, "uscrt")),
+            HandleMsg::Tip {
+                alias: alias.to_string(),
+                message: None,
+                padding: None,
+            },
+        )
+        .unwrap();
+        let env = mock_env("frump", &[]);
+        let handle_result = handle(
+            &mut deps,
+            env.clone(),
+            HandleMsg::WithdrawTips {
+                alias: alias.to_string(),
+                padding: None,
+            },
+        )
+        .unwrap();
+        assert_eq!(
+            handle_result.messages,
+            vec![
+                snip20::transfer_msg(
+                    HumanAddr::from("frump"),
+                    Uint128(5),
+                    None,
+                    BLOCK_SIZE,
+                    mock_buttcoin().contract_hash,
+                    mock_buttcoin().address,
+                )
+                .unwrap(),
+                CosmosMsg::Bank(BankMsg::Send {
+                    from_address: env.contract.address,
+                    to_address: HumanAddr::from("frump"),
+                    amount: coins(1_000, "uscrt"),
+                })
+            ]
+        );
+    }
+
+    #[test]
+    fn test_try_transfer() {
+        let (_init_result, mut deps) = init_helper();
+        let alias = "Epstein didn't kill himself";
+        let transfer = |recipient: &str| HandleMsg::Transfer {
+            alias: alias.to_string(),
+            recipient: HumanAddr::from(recipient),
+            recipient_code_hash: None,
+            msg: None,
+            padding: None,
+        };
+        handle(
+            &mut deps,
+            mock_env("frump", &[]),
+            HandleMsg::AddMember {
+                alias: alias.to_string(),
+                address: HumanAddr::from("mr-editor"),
+                role: Role::Editor,
+                padding: None,
+            },
+        )
+        .unwrap();
+
+        // = when someone other than the owner transfers the alias
+        // = * it raises an error
+        let handle_result = handle(
+            &mut deps,
+            mock_env(mock_user_address(), &[]),
+            transfer("some-geezer"),
+        );
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, "Unauthorized");
+
+        // = when the recipient already has an alias
+        // = * it raises an error
+        let handle_result = handle(&mut deps, mock_env("frump", &[]), transfer("frump"));
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, "Recipient already has an alias");
+
+        // = when the owner transfers the alias
+        let handle_result = handle(&mut deps, mock_env("frump", &[]), transfer("some-geezer"));
+        let handle_result_data: HandleAnswer =
+            from_binary(&handle_result.unwrap().data.unwrap()).unwrap();
+        assert_eq!(
+            to_binary(&handle_result_data).unwrap(),
+            to_binary(&HandleAnswer::Transfer { status: Success }).unwrap()
+        );
+        // = * the alias resolves to the recipient without the previous owner's members
+        let search_response = query(
+            &deps,
+            QueryMsg::Search {
+                search_type: "alias".to_string(),
+                search_value: alias.to_string(),
+                include_metadata: Some(true),
+                viewing_key: None,
+            },
+        )
+        .unwrap();
+        let val: SearchResponse = from_binary(&search_response).unwrap();
+        assert_eq!(val.attributes.address, mock_user_address());
+        assert!(val.metadata.unwrap().members.is_empty());
+        // = * the previous owner's address no longer resolves to it
+        let query_response = query(
+            &deps,
+            QueryMsg::Search {
+                search_type: "address".to_string(),
+                search_value: "frump".to_string(),
+                include_metadata: None,
+                viewing_key: None,
+            },
+        );
+        let error = extract_query_error(query_response);
+        assert_eq!(error.code, QueryErrorCode::NotFound);
+
+        // = when a msg is sent without the recipient's code hash
+        // = * it raises an error
+        let handle_msg = HandleMsg::Transfer {
+            alias: alias.to_string(),
+            recipient: HumanAddr::from("alias-vault"),
+            recipient_code_hash: None,
+            msg: Some(Binary::from("deposit".as_bytes())),
+            padding: None,
+        };
+        let handle_result = handle(&mut deps, mock_env(mock_user_address(), &[]), handle_msg);
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, "A msg needs the recipient's code hash");
+
+        // = when the alias is transferred to a contract with a msg
+        // = * the msg is forwarded to the contract
+        let handle_msg = HandleMsg::Transfer {
+            alias: alias.to_string(),
+            recipient: HumanAddr::from("alias-vault"),
+            recipient_code_hash: Some("alias-vault-hash".to_string()),
+            msg: Some(Binary::from("deposit".as_bytes())),
+            padding: None,
+        };
+        let handle_result = handle(&mut deps, mock_env(mock_user_address(), &[]), handle_msg);
+        assert_eq!(
+            handle_result.unwrap().messages,
+            vec![ReceiverHandleMsg::ReceiveAlias {
+                alias: "epstein didn't kill himself".to_string(),
+                from: mock_user_address(),
+                msg: Binary::from("deposit".as_bytes()),
+            }
+            .to_cosmos_msg(
+                "alias-vault-hash".to_string(),
+                HumanAddr::from("alias-vault"),
+                None
+            )
+            .unwrap()]
+        );
+    }
+
+    #[test]
+    fn test_try_update_profile() {
+        let (_init_result, mut deps) = init_helper();
+        let update_profile = |banner_url: &str, accent_color: &str| HandleMsg::UpdateProfile {
+            alias: "Epstein didn't kill himself".to_string(),
+            banner_url: Some(banner_url.to_string()),
+            accent_color: Some(accent_color.to_string()),
+            padding: None,
+        };
+
+        // = when someone other than the owner updates the profile
+        // = * it raises an error
+        let handle_result = handle(
+            &mut deps,
+            mock_env(mock_user_address(), &[]),
+            update_profile("https://www.btn.group/banner.png", "#FF8800"),
+        );
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, "Unauthorized");
+
+        // = when the banner url is not a web url
+        // = * it raises an error
+        let handle_result = handle(
+            &mut deps,
+            mock_env("frump", &[]),
+            update_profile("javascript:alert(1)", "#FF8800"),
+        );
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, "Banner url is invalid");
+
+        // = when the banner url has no host
+        // = * it raises an error
+        let handle_result = handle(
+            &mut deps,
+            mock_env("frump", &[]),
+            update_profile("https://", "#FF8800"),
+        );
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, "Banner url is invalid");
+
+        // = when the banner is hosted outside the avatar domains
+        // = * it raises an error
+        let mut config: Config = TypedStore::attach(&deps.storage).load(CONFIG_KEY).unwrap();
+        config.avatar_domains = vec!["ipfs.io".to_string()];
+        TypedStoreMut::attach(&mut deps.storage)
+            .store(CONFIG_KEY, &config)
+            .unwrap();
+        let handle_result = handle(
+            &mut deps,
+            mock_env("frump", &[]),
+            update_profile("https://tracker.example/pixel.png", "#FF8800"),
+        );
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, "Banners can't be hosted on tracker.example");
+        config.avatar_domains = vec![];
+        TypedStoreMut::attach(&mut deps.storage)
+            .store(CONFIG_KEY, &config)
+            .unwrap();
+
+        // = when the accent color is not a hex color
+        // = * it raises an error
+        let handle_result = handle(
+            &mut deps,
+            mock_env("frump", &[]),
+            update_profile("https://www.btn.group/banner.png", "orange"),
+        );
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, "Accent color must be a hex color such as #ff8800");
+
+        // = when the owner updates the profile
+        // = * it is returned with the alias metadata
+        handle(
+            &mut deps,
+            mock_env("frump", &[]),
+            update_profile("https://www.btn.group/banner.png", "#FF8800"),
+        )
+        .unwrap();
+        let search_response = query(
+            &deps,
+            QueryMsg::Search {
+                search_type: "alias".to_string(),
+                search_value: "Epstein didn't kill himself".to_string(),
+                include_metadata: Some(true),
+                viewing_key: None,
+            },
+        )
+        .unwrap();
+        let val: SearchResponse = from_binary(&search_response).unwrap();
+        let metadata: AliasMetadata = val.metadata.unwrap();
+        assert_eq!(
+            metadata.banner_url,
+            Some("https://www.btn.group/banner.png".to_string())
+        );
+        assert_eq!(metadata.accent_color, Some("#ff8800".to_string()));
+    }
+
+    #[test]
+    fn test_try_update_records() {
+        let (_init_result, mut deps) = init_helper();
+        let record = |key: &str, value: &str| Record {
+            key: key.to_string(),
+            value: value.to_string(),
+        };
+        let update_records = |set: Vec<Record>, remove: Vec<&str>| HandleMsg::UpdateRecords {
+            alias: "Epstein didn't kill himself".to_string(),
+            set,
+            remove: remove.iter().map(|key| key.to_string()).collect(),
+            padding: None,
+        };
+        let records_of = |deps: &Extern<MockStorage, MockApi, MockQuerier>| -> Vec<Record> {
+            RecordsReadonlyStorage::from_storage(&deps.storage)
+                .get_records(b"epstein didn't kill himself")
+        };
+
+        // = when someone other than the owner updates the records
+        // = * it raises an error
+        let handle_result = handle(
+            &mut deps,
+            mock_env(mock_user_address(), &[]),
+            update_records(vec![record("url", "https://www.btn.group")], vec![]),
+        );
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, "Unauthorized");
+
+        // = when the owner sets records
+        // = * it stores them
+        handle(
+            &mut deps,
+            mock_env("frump", &[]),
+            update_records(
+                vec![
+                    record("url", "https://www.btn.group"),
+                    record("twitter", "@frump"),
+                ],
+                vec![],
+            ),
+        )
+        .unwrap();
+        assert_eq!(
+            records_of(&deps),
+            vec![
+                record("url", "https://www.btn.group"),
+                record("twitter", "@frump")
+            ]
+        );
+
+        // = when one of the changes is invalid
+        // = * it raises an error and leaves every record as it was
+        let handle_result = handle(
+            &mut deps,
+            mock_env("frump", &[]),
+            update_records(
+                vec![record("url", "https://btn.group"), record("addr.eth", "")],
+                vec!["twitter"],
+            ),
+        );
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, "Record value for addr.eth is invalid");
+        assert_eq!(
+            records_of(&deps),
+            vec![
+                record("url", "https://www.btn.group"),
+                record("twitter", "@frump")
+            ]
+        );
+
+        // = when a key is both set and removed
+        // = * it raises an error
+        let handle_result = handle(
+            &mut deps,
+            mock_env("frump", &[]),
+            update_records(vec![record("url", "https://btn.group")], vec!["URL"]),
+        );
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, "Record url is both set and removed");
+
+        // = when the changes would go over the record limit
+        // = * it raises an error
+        let too_many: Vec<Record> = (0..MAX_RECORDS_PER_ALIAS - 1)
+            .map(|i| record(&format!("key{}", i), "value"))
+            .collect();
+        let handle_result = handle(
+            &mut deps,
+            mock_env("frump", &[]),
+            update_records(too_many, vec![]),
+        );
+        let error = extract_error_msg(handle_result);
+        assert_eq!(
+            error,
+            format!(
+                "An alias can have at most {} records",
+                MAX_RECORDS_PER_ALIAS
+            )
+        );
+
+        // = when records are set and removed together
+        // = * it replaces the set records and removes the others
+        handle(
+            &mut deps,
+            mock_env("frump", &[]),
+            update_records(vec![record("url", "https://btn.group")], vec!["twitter"]),
+        )
+        .unwrap();
+        assert_eq!(records_of(&deps), vec![record("url", "https://btn.group")]);
+    }
+
+    #[test]
+    fn test_v1_alias_migration() {
+        let (_init_result, mut deps) = init_helper();
+        let search_by_address = QueryMsg::Search {
+            search_type: "address".to_string(),
+            search_value: "oldtimer".to_string(),
+            include_metadata: None,
+            viewing_key: None,
+        };
+
+        // = when an alias was stored by the previous version
+        PrefixedStorage::new(V1_ALIASES_PREFIX, &mut deps.storage).set(
+            b"oldie",
+            &Bincode2::serialize(&V1Alias {
+                human_address: HumanAddr::from("oldtimer"),
+                avatar_url: Some("https://www.btn.group".to_string()),
+            })
+            .unwrap(),
+        );
+        PrefixedStorage::new(V1_ADDRESSES_ALIASES_PREFIX, &mut deps.storage).set(
+            b"oldtimer",
+            &Bincode2::serialize(&"oldie".to_string()).unwrap(),
+        );
+
+        // = * it can be found by alias
+        let search_response = query(
+            &deps,
+            QueryMsg::Search {
+                search_type: "alias".to_string(),
+                search_value: "oldie".to_string(),
+                include_metadata: None,
+                viewing_key: None,
+            },
+        )
+        .unwrap();
+        let val: SearchResponse = from_binary(&search_response).unwrap();
+        assert_eq!(val.attributes.address, HumanAddr::from("oldtimer"));
+        assert_eq!(
+            val.attributes.avatar_url,
+            Some("https://www.btn.group".to_string())
+        );
+        // = * it can be found by address
+        let search_response = query(&deps, search_by_address.clone()).unwrap();
+        let val: SearchResponse = from_binary(&search_response).unwrap();
+        assert_eq!(val.attributes.alias, "oldie");
+
+        // = when the owner updates the alias
+        let handle_msg = HandleMsg::SetWildcard {
+            alias: "oldie".to_string(),
+            wildcard: true,
+            padding: None,
+        };
+        handle(&mut deps, mock_env("oldtimer", &[]), handle_msg).unwrap();
+        // = * it moves the alias out of the previous namespace
+        assert!(
+            ReadonlyPrefixedStorage::new(V1_ALIASES_PREFIX, &deps.storage)
+                .get(b"oldie")
+                .is_none()
+        );
+        assert!(
+            ReadonlyPrefixedStorage::new(V1_ADDRESSES_ALIASES_PREFIX, &deps.storage)
+                .get(b"oldtimer")
+                .is_none()
+        );
+        assert!(ReadonlyPrefixedStorage::new(ALIASES_PREFIX, &deps.storage)
+            .get(&sha_256(b"oldie"))
+            .is_some());
+        // = * it can still be found by address
+        let search_response = query(&deps, search_by_address).unwrap();
+        let val: SearchResponse = from_binary(&search_response).unwrap();
+        assert_eq!(val.attributes.alias, "oldie");
+    }
+
+    #[test]
+    fn test_legacy_alias_layout() {
+        let (_init_result, mut deps) = init_helper();
+        let owner: CanonicalAddr = deps
+            .api
+            .canonical_address(&HumanAddr::from("oldtimer"))
+            .unwrap();
+        let search_by_alias = QueryMsg::Search {
+            search_type: "alias".to_string(),
+            search_value: "oldie".to_string(),
+            include_metadata: None,
+            viewing_key: None,
+        };
+
+        // = when an alias was stored before display names were added
+        PrefixedStorage::new(ALIASES_PREFIX, &mut deps.storage).set(
+            &sha_256(b"oldie"),
+            &Bincode2::serialize(&LegacyAlias {
+                alias: "oldie".to_string(),
+                owner: owner.clone(),
+                avatar_url: None,
+                avatar_metadata: None,
+                created_at: 5,
+                updated_at: 5,
+                target: None,
+                wildcard: false,
+                private_reverse_lookup: false,
+                banner_url: None,
+                accent_color: None,
+            })
+            .unwrap(),
+        );
+        PrefixedStorage::new(ADDRESSES_ALIASES_PREFIX, &mut deps.storage).set(
+            owner.as_slice(),
+            &Bincode2::serialize(&sha_256(b"oldie").to_vec()).unwrap(),
+        );
+
+        // = * it can be found, with the alias as its display name
+        let search_response = query(&deps, search_by_alias.clone()).unwrap();
+        let val: SearchResponse = from_binary(&search_response).unwrap();
+        assert_eq!(val.attributes.address, HumanAddr::from("oldtimer"));
+        assert_eq!(val.attributes.display_name, Some("oldie".to_string()));
+
+        // = when the owner updates the alias
+        let handle_msg = HandleMsg::SetWildcard {
+            alias: "oldie".to_string(),
+            wildcard: true,
+            padding: None,
+        };
+        handle(&mut deps, mock_env("oldtimer", &[]), handle_msg).unwrap();
+        // = * it is saved in the current layout
+        let stored_alias: Alias = Bincode2::deserialize(
+            &ReadonlyPrefixedStorage::new(ALIASES_PREFIX, &deps.storage)
+                .get(&sha_256(b"oldie"))
+                .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(stored_alias.display_name, "oldie");
+        assert_eq!(stored_alias.created_at, 5);
+        assert!(stored_alias.wildcard);
+        // = * it can still be found
+        let search_response = query(&deps, search_by_alias).unwrap();
+        let val: SearchResponse = from_binary(&search_response).unwrap();
+        assert_eq!(val.attributes.address, HumanAddr::from("oldtimer"));
+    }
+
+    // === QUERY TESTS ===
+
+    #[test]
+    fn test_query_search() {
+        let (_init_result, deps) = init_helper();
+
+        // = when metadata is not requested
+        // = * it leaves metadata out of the response
+        let search_response = query(
+            &deps,
+            QueryMsg::Search {
+                search_type: "address".to_string(),
+                search_value: "frump".to_string(),
+                include_metadata: None,
+                viewing_key: None,
+            },
+        )
+        .unwrap();
+        let val: SearchResponse = from_binary(&search_response).unwrap();
+        assert_eq!(val.metadata, None);
+
+        // = when metadata is requested
+        // = * it returns the block heights the alias was created and updated at
+        let search_response = query(
+            &deps,
+            QueryMsg::Search {
+                search_type: "alias".to_string(),
+                search_value: "Epstein didn't kill himself".to_string(),
+                include_metadata: Some(true),
+                viewing_key: None,
+            },
+        )
+        .unwrap();
+        let val: SearchResponse = from_binary(&search_response).unwrap();
+        let env = mock_env(mock_user_address(), &[]);
+        assert_eq!(
+            val.metadata,
+            Some(AliasMetadata {
+                created_at: env.block.height,
+                updated_at: env.block.height,
+                expires_at: None,
+                status: AliasStatus::Active,
+                verified: false,
+                attestations: vec![],
+                endorsements: 0,
+                target: None,
+                wildcard: false,
+                frozen: false,
+                locked_until: None,
+                suspension: None,
+                lease: None,
+                images: vec![],
+                banner_url: None,
+                accent_color: None,
+                members: vec![],
+                co_owners: vec![],
+                approval_threshold: 1,
+                records: vec![],
+                encryption_key: None,
+                payment_preference: None,
+                linked_addresses: vec![],
+            })
+        );
+
+        // = when search type is invalid
+        // = * it returns an invalid parameter error for search_type
+        let query_response = query(
+            &deps,
+            QueryMsg::Search {
+                search_type: "avatar".to_string(),
+                search_value: "frump".to_string(),
+                include_metadata: None,
+                viewing_key: None,
+            },
+        );
+        let error = extract_query_error(query_response);
+        assert_eq!(error.code, QueryErrorCode::InvalidParameter);
+        assert_eq!(error.details.target, Some("search_type".to_string()));
+    }
+
+    #[test]
+    fn test_query_alias_at_version() {
+        let (_init_result, mut deps) = init_helper();
+        let alias: &str = "epstein didn't kill himself";
+        let alias_at_version = |deps: &Extern<MockStorage, MockApi, MockQuerier>, version: u64| {
+            query(
+                deps,
+                QueryMsg::AliasAtVersion {
+                    alias: alias.to_string(),
+                    version,
+                },
+            )
+        };
+        let unwrap_alias_version =
+            |query_result: QueryResult| match from_binary(&query_result.unwrap()).unwrap() {
+                QueryAnswer::AliasAtVersion {
+                    alias_version,
+                    latest_version,
+                } => (alias_version, latest_version),
+                _ => panic!("Unexpected query answer"),
+            };
+        let height: u64 = mock_env(mock_user_address(), &[]).block.height;
+
+        // = when the alias has only been registered
+        // = * its registration is version 1
+        let (alias_version, latest_version) = unwrap_alias_version(alias_at_version(&deps, 1));
+        assert_eq!(
+            alias_version,
+            AliasVersion {
+                version: 1,
+                owner: Some(HumanAddr::from("frump")),
+                target: None,
+                block_height: height,
+            }
+        );
+        assert_eq!(latest_version, 1);
+
+        // = when the alias is updated without changing what it resolves to
+        // = * it doesn't add a version
+        handle(
+            &mut deps,
+            mock_env("frump", &[]),
+            HandleMsg::SetDisplayName {
+                alias: alias.to_string(),
+                display_name: "Epstein Didn't Kill Himself".to_string(),
+                padding: None,
+            },
+        )
+        .unwrap();
+        let (_alias_version, latest_version) = unwrap_alias_version(alias_at_version(&deps, 1));
+        assert_eq!(latest_version, 1);
+
+        // = when the alias is destroyed
+        // = * it adds a version without an owner and keeps the earlier ones
+        handle(
+            &mut deps,
+            mock_env("frump", &[]),
+            HandleMsg::Destroy {
+                alias: alias.to_string(),
+                padding: None,
+            },
+        )
+        .unwrap();
+        let (alias_version, latest_version) = unwrap_alias_version(alias_at_version(&deps, 2));
+        assert_eq!(alias_version.owner, None);
+        assert_eq!(latest_version, 2);
+        let (alias_version, _latest_version) = unwrap_alias_version(alias_at_version(&deps, 1));
+        assert_eq!(alias_version.owner, Some(HumanAddr::from("frump")));
+
+        // = when the version doesn't exist
+        // = * it returns a not found error
+        let error = extract_query_error(alias_at_version(&deps, 3));
+        assert_eq!(error.code, QueryErrorCode::NotFound);
+
+        // = when fewer versions are kept
+        // = * it drops the oldest versions as the alias changes
+        let handle_result = handle(
+            &mut deps,
+            mock_env(mock_user_address(), &[]),
+            HandleMsg::SetAliasVersionsKept {
+                count: 0,
+                padding: None,
+            },
+        );
+        let error = extract_error_msg(handle_result);
+        assert_eq!(
+            error,
+            format!(
+                "Alias versions kept must be between 1 and {}",
+                MAX_ALIAS_VERSIONS_KEPT
+            )
+        );
+        handle(
+            &mut deps,
+            mock_env(mock_user_address(), &[]),
+            HandleMsg::SetAliasVersionsKept {
+                count: 2,
+                padding: None,
+            },
+        )
+        .unwrap();
+        let mut env = mock_env(mock_buttcoin().address, &[]);
+        env.block.height = height + TOMBSTONE_BLOCKS;
+        handle(
+            &mut deps,
+            env,
+            HandleMsg::Receive {
+                sender: HumanAddr::from("newowner"),
+                from: HumanAddr::from("newowner"),
+                amount: Uint128(AMOUNT_FOR_TRANSACTION),
+                msg: to_binary(&ReceiveMsg::Create {
+                    alias: alias.to_string(),
+                    avatar_url: None,
+                    avatar_metadata: None,
+                    records: None,
+                    fallback: None,
+                })
+                .unwrap(),
+                padding: None,
+            },
+        )
+        .unwrap();
+        let (alias_version, latest_version) = unwrap_alias_version(alias_at_version(&deps, 3));
+        assert_eq!(alias_version.owner, Some(HumanAddr::from("newowner")));
+        assert_eq!(latest_version, 3);
+        let error = extract_query_error(alias_at_version(&deps, 1));
+        assert_eq!(error.code, QueryErrorCode::NotFound);
+    }
+
+    #[test]
+    fn test_query_analytics() {
+        let (_init_result, mut deps) = init_helper();
+        let create_alias_message = ReceiveMsg::Create {
+            alias: "nailbiter".to_string(),
+            avatar_url: None,
+            avatar_metadata: None,
+            records: None,
+            fallback: None,
+        };
+        let receive_msg = HandleMsg::Receive {
+            sender: mock_user_address(),
+            from: mock_user_address(),
+            amount: Uint128(AMOUNT_FOR_TRANSACTION),
+            msg: to_binary(&create_alias_message).unwrap(),
+            padding: None,
+        };
+        handle(
+            &mut deps,
+            mock_env(mock_buttcoin().address, &[]),
+            receive_msg,
+        )
+        .unwrap();
+        let destroy_alias_message = HandleMsg::Destroy {
+            alias: "nailbiter".to_string(),
+            padding: None,
+        };
+        handle(
+            &mut deps,
+            mock_env(mock_user_address(), &[]),
+            destroy_alias_message,
+        )
+        .unwrap();
+        let day: u64 = mock_env(mock_user_address(), &[]).block.time / SECONDS_PER_DAY;
+
+        // = when analytics are requested for a range of days
+        // = * it returns the counts for the days with activity
+        let query_result = query(
+            &deps,
+            QueryMsg::Analytics {
+                start_day: day - 1,
+                end_day: day + 1,
+            },
+        )
+        .unwrap();
+        let query_answer: QueryAnswer = from_binary(&query_result).unwrap();
+        match query_answer {
+            QueryAnswer::Analytics { daily_stats } => {
+                assert_eq!(
+                    daily_stats,
+                    vec![DailyStats {
+                        day,
+                        registrations: 2,
+                        renewals: 0,
+                        destroys: 1,
+                    }]
+                );
+            }
+            _ => panic!("Unexpected query answer"),
+        }
+
+        // = when end day is before start day
+        // = * it raises an error
+        let query_result = query(
+            &deps,
+            QueryMsg::Analytics {
+                start_day: day,
+                end_day: day - 1,
+            },
+        );
+        let error = extract_query_error(query_result);
+        assert_eq!(error.code, QueryErrorCode::InvalidParameter);
+    }
+
+    #[test]
+    fn test_query_changes() {
+        let (_init_result, mut deps) = init_helper();
+        let set_change_log_size = |size: Option<u64>| HandleMsg::SetChangeLogSize {
+            size,
+            padding: None,
+        };
+
+        // = when someone other than the admin sets the change log size
+        // = * it raises an error
+        let handle_result = handle(
+            &mut deps,
+            mock_env("frump", &[]),
+            set_change_log_size(Some(2)),
+        );
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, "Unauthorized");
+        // = when the size is 0
+        // = * it raises an error
+        let handle_result = handle(
+            &mut deps,
+            mock_env(mock_user_address(), &[]),
+            set_change_log_size(Some(0)),
+        );
+        let error = extract_error_msg(handle_result);
+        assert_eq!(error, "Change log size must be greater than 0");
+
+        // = when the change log is bounded
+        handle(
+            &mut deps,
+            mock_env(mock_user_address(), &[]),
+            set_change_log_size(Some(2)),
+        )
+        .unwrap();
+        let receive_msg = HandleMsg::Receive {
+            sender: mock_user_address(),
+            from: mock_user_address(),
+            amount: Uint128(AMOUNT_FOR_TRANSACTION),
+            msg: to_binary(&ReceiveMsg::Create {
+                alias: "nailbiter".to_string(),
+                avatar_url: None,
+                avatar_metadata: None,
+                records: None,
+                fallback: None,
+            })
+            .unwrap(),
+            padding: None,
+        };
+        handle(
+            &mut deps,
+            mock_env(mock_buttcoin().address, &[]),
+            receive_msg,
+        )
+        .unwrap();
+        handle(
+            &mut deps,
+            mock_env(mock_user_address(), &[]),
+            HandleMsg::Destroy {
+                alias: "nailbiter".to_string(),
+                padding: None,
+            },
+        )
+        .unwrap();
+        // = * it prunes the oldest changes and returns the rest of the range
+        let query_result = query(
+            &deps,
+            QueryMsg::Changes {
+                start_seq: 0,
+                end_seq: 10,
+            },
+        )
+        .unwrap();
+        match from_binary(&query_result).unwrap() {
+            QueryAnswer::Changes {
+                changes,
+                earliest_seq,
+                latest_seq,
+            } => {
+                assert_eq!(
+                    changes
+                        .iter()
+                        .map(|change| (change.seq, change.action))
+                        .collect::<Vec<(u64, ChangeAction)>>(),
+                    vec![(2, ChangeAction::Create), (3, ChangeAction::Destroy)]
+                );
+                assert_eq!(earliest_seq, 2);
+                assert_eq!(latest_seq, 3);
+            }
+            _ => panic!("Unexpected query answer"),
+        }
+        // = * changes since a pruned change start at the earliest change kept
+        let query_result = query(&deps, QueryMsg::ChangesSince { seq: 0, limit: 1 }).unwrap();
+        match from_binary(&query_result).unwrap() {
+            QueryAnswer::ChangesSince { changes, .. } => assert_eq!(changes[0].seq, 2),
+            _ => panic!("Unexpected query answer"),
+        }
+
+        // = when the range is too large
+        // = * it raises an error
+        let query_result = query(
+            &deps,
+            QueryMsg::Changes {
+                start_seq: 0,
+                end_seq: u64::from(MAX_CHANGES_PER_QUERY),
+            },
+        );
+        let error = extract_query_error(query_result);
+        assert_eq!(error.code, QueryErrorCode::InvalidRequest);
+        // = when the range ends before it starts
+        // = * it raises an error
+        let query_result = query(
+            &deps,
+            QueryMsg::Changes {
+                start_seq: 3,
+                end_seq: 2,
+            },
+        );
+        let error = extract_query_error(query_result);
+        assert_eq!(error.code, QueryErrorCode::InvalidParameter);
+    }
+
+    #[test]
+    fn test_query_changes_since() {
+        let (_init_result, mut deps) = init_helper();
+        let create_alias_message = ReceiveMsg::Create {
+            alias: "nailbiter".to_string(),
+            avatar_url: None,
+            avatar_metadata: None,
+            records: None,
+            fallback: None,
+        };
+        let receive_msg = HandleMsg::Receive {
+            sender: mock_user_address(),
+            from: mock_user_address(),
             amount: Uint128(AMOUNT_FOR_TRANSACTION),
             msg: to_binary(&create_alias_message).unwrap(),
+            padding: None,
         };
-        let handle_result = handle(
+        handle(
             &mut deps,
             mock_env(mock_buttcoin().address, &[]),
-            receive_msg.clone(),
+            receive_msg,
+        )
+        .unwrap();
+        let destroy_alias_message = HandleMsg::Destroy {
+            alias: "nailbiter".to_string(),
+            padding: None,
+        };
+        handle(
+            &mut deps,
+            mock_env(mock_user_address(), &[]),
+            destroy_alias_message,
+        )
+        .unwrap();
+
+        // = when changes are requested after a sequence number
+        // = * it returns the later changes in order with the latest sequence number
+        let query_result = query(&deps, QueryMsg::ChangesSince { seq: 1, limit: 10 }).unwrap();
+        let query_answer: QueryAnswer = from_binary(&query_result).unwrap();
+        match query_answer {
+            QueryAnswer::ChangesSince {
+                changes,
+                latest_seq,
+            } => {
+                assert_eq!(latest_seq, 3);
+                assert_eq!(
+                    changes
+                        .iter()
+                        .map(|change| (change.seq, change.action))
+                        .collect::<Vec<(u64, ChangeAction)>>(),
+                    vec![(2, ChangeAction::Create), (3, ChangeAction::Destroy)]
+                );
+                assert_eq!(changes[1].alias, "nailbiter");
+                assert_eq!(changes[1].address, mock_user_address());
+            }
+            _ => panic!("Unexpected query answer"),
+        }
+
+        // = when a limit is given
+        // = * it returns at most that many changes
+        let query_result = query(&deps, QueryMsg::ChangesSince { seq: 0, limit: 1 }).unwrap();
+        let query_answer: QueryAnswer = from_binary(&query_result).unwrap();
+        match query_answer {
+            QueryAnswer::ChangesSince { changes, .. } => {
+                assert_eq!(changes.len(), 1);
+                assert_eq!(changes[0].seq, 1);
+            }
+            _ => panic!("Unexpected query answer"),
+        }
+    }
+
+    #[test]
+    fn test_query_changed_since() {
+        let (_init_result, mut deps) = init_helper();
+        let mut env = mock_env(mock_buttcoin().address, &[]);
+        env.block.height += 1;
+        let receive_msg = HandleMsg::Receive {
+            sender: mock_user_address(),
+            from: mock_user_address(),
+            amount: Uint128(AMOUNT_FOR_TRANSACTION),
+            msg: to_binary(&ReceiveMsg::Create {
+                alias: "nailbiter".to_string(),
+                avatar_url: None,
+                avatar_metadata: None,
+                records: None,
+                fallback: None,
+            })
+            .unwrap(),
+            padding: None,
+        };
+        handle(&mut deps, env, receive_msg).unwrap();
+        let changed_since = |deps: &Extern<MockStorage, MockApi, MockQuerier>,
+                             height: u64|
+         -> (Vec<ChangedAlias>, Option<u32>) {
+            let query_result = query(deps, QueryMsg::ChangedSince { height, page: 0 }).unwrap();
+            match from_binary(&query_result).unwrap() {
+                QueryAnswer::ChangedSince { aliases, next_page } => (aliases, next_page),
+                _ => panic!("Unexpected query answer"),
+            }
+        };
+        let init_height: u64 = mock_env(mock_user_address(), &[]).block.height;
+
+        // = when aliases were changed after the height
+        // = * it returns them with their owners
+        let (aliases, next_page) = changed_since(&deps, 0);
+        assert_eq!(
+            aliases,
+            vec![
+                ChangedAlias {
+                    alias: "epstein didn't kill himself".to_string(),
+                    address: Some(HumanAddr::from("frump")),
+                },
+                ChangedAlias {
+                    alias: "nailbiter".to_string(),
+                    address: Some(mock_user_address()),
+                },
+            ]
+        );
+        assert_eq!(next_page, None);
+        // = * it leaves out aliases changed at or before the height
+        let (aliases, _) = changed_since(&deps, init_height);
+        assert_eq!(
+            aliases
+                .iter()
+                .map(|alias| alias.alias.as_str())
+                .collect::<Vec<&str>>(),
+            vec!["nailbiter"]
+        );
+
+        // = when an alias was changed more than once
+        // = * it is listed once, without an address once it is destroyed
+        let mut env = mock_env(mock_user_address(), &[]);
+        env.block.height += 2;
+        handle(
+            &mut deps,
+            env,
+            HandleMsg::Destroy {
+                alias: "nailbiter".to_string(),
+                padding: None,
+            },
+        )
+        .unwrap();
+        let (aliases, _) = changed_since(&deps, init_height);
+        assert_eq!(
+            aliases,
+            vec![ChangedAlias {
+                alias: "nailbiter".to_string(),
+                address: None,
+            }]
+        );
+
+        // = when nothing changed after the height
+        // = * it returns no aliases
+        let (aliases, next_page) = changed_since(&deps, init_height + 2);
+        assert!(aliases.is_empty());
+        assert_eq!(next_page, None);
+    }
+
+    #[test]
+    fn test_query_checksum() {
+        let (_init_result, mut deps) = init_helper();
+        let checksum_of = |deps: &Extern<MockStorage, MockApi, MockQuerier>| -> (Binary, u64) {
+            let query_response = query(deps, QueryMsg::Checksum {}).unwrap();
+            match from_binary(&query_response).unwrap() {
+                QueryAnswer::Checksum {
+                    checksum,
+                    latest_seq,
+                } => (checksum, latest_seq),
+                _ => panic!("Unexpected query answer"),
+            }
+        };
+        let (initial_checksum, initial_seq) = checksum_of(&deps);
+
+        // = when contracts are initialized with the same aliases
+        // = * the checksums match
+        let (_init_result, other_deps) = init_helper();
+        assert_eq!(checksum_of(&other_deps).0, initial_checksum);
+
+        // = when an alias is created
+        // = * the checksum changes
+        let create_alias_message = ReceiveMsg::Create {
+            alias: "nailbiter".to_string(),
+            avatar_url: None,
+            avatar_metadata: None,
+            records: None,
+            fallback: None,
+        };
+        let receive_msg = HandleMsg::Receive {
+            sender: mock_user_address(),
+            from: mock_user_address(),
+            amount: Uint128(AMOUNT_FOR_TRANSACTION),
+            msg: to_binary(&create_alias_message).unwrap(),
+            padding: None,
+        };
+        handle(
+            &mut deps,
+            mock_env(mock_buttcoin().address, &[]),
+            receive_msg,
+        )
+        .unwrap();
+        let (checksum, latest_seq) = checksum_of(&deps);
+        assert_ne!(checksum, initial_checksum);
+        assert_eq!(latest_seq, initial_seq + 1);
+
+        // = when the alias is destroyed again
+        // = * the checksum goes back to what it was
+        let handle_msg = HandleMsg::Destroy {
+            alias: "nailbiter".to_string(),
+            padding: None,
+        };
+        handle(&mut deps, mock_env(mock_user_address(), &[]), handle_msg).unwrap();
+        assert_eq!(checksum_of(&deps).0, initial_checksum);
+    }
+
+    #[test]
+    fn test_query_config() {
+        let (_init_result, deps) = init_helper();
+        let config: Config = TypedStore::attach(&deps.storage).load(CONFIG_KEY).unwrap();
+        let query_result = query(&deps, QueryMsg::Config {}).unwrap();
+        // = * the response is padded to the response block size
+        assert_eq!(
+            query_result.as_slice().len() % DEFAULT_RESPONSE_BLOCK_SIZE as usize,
+            0
+        );
+        let query_answer: QueryAnswer = from_binary(&query_result).unwrap();
+        match query_answer {
+            QueryAnswer::Config {
+                admin,
+                airdrop_merkle_root,
+                alias_versions_kept,
+                avatar_domains,
+                batch_limits,
+                bootstrap_ends_at,
+                buttcoin,
+                butt_lode,
+                case_folding,
+                chain_id,
+                change_log_size,
+                cross_chain_snapshot,
+                discounts,
+                fee_splits,
+                governance,
+                launch,
+                lease_terms,
+                lottery_ends_at,
+                mint_phases,
+                oracles,
+                price_oracle,
+                promotions,
+                reclaim_terms,
+                require_avatar,
+                response_block_size,
+                routable_tokens,
+                timelock_blocks,
+            } => {
+                assert_eq!(admin, mock_user_address());
+                assert_eq!(airdrop_merkle_root, None);
+                assert_eq!(alias_versions_kept, DEFAULT_ALIAS_VERSIONS_KEPT);
+                assert!(avatar_domains.is_empty());
+                assert_eq!(
+                    batch_limits,
+                    BatchLimits {
+                        resolve_addresses: DEFAULT_MAX_BATCH_SIZE,
+                        resolve_canonical_addresses: DEFAULT_MAX_BATCH_SIZE,
+                    }
+                );
+                assert_eq!(bootstrap_ends_at, None);
+                assert_eq!(buttcoin, config.buttcoin);
+                assert_eq!(butt_lode, config.butt_lode);
+                assert_eq!(case_folding, CaseFolding::Unicode);
+                assert_eq!(chain_id, mock_env(mock_user_address(), &[]).block.chain_id);
+                assert_eq!(change_log_size, None);
+                assert_eq!(cross_chain_snapshot, None);
+                assert!(discounts.is_empty());
+                assert!(fee_splits.is_empty());
+                assert_eq!(governance, None);
+                assert_eq!(launch, None);
+                assert_eq!(lease_terms, None);
+                assert_eq!(lottery_ends_at, None);
+                assert!(mint_phases.is_empty());
+                assert_eq!(oracles, Vec::<HumanAddr>::new());
+                assert!(config.oracles.is_empty());
+                assert_eq!(price_oracle, None);
+                assert!(promotions.is_empty());
+                assert_eq!(reclaim_terms, None);
+                assert!(!require_avatar);
+                assert_eq!(response_block_size, DEFAULT_RESPONSE_BLOCK_SIZE);
+                assert_eq!(routable_tokens, config.routable_tokens);
+                assert_eq!(timelock_blocks, 0);
+            }
+            _ => panic!("Unexpected query answer"),
+        }
+    }
+
+    #[test]
+    fn test_query_metrics() {
+        let (_init_result, mut deps) = init_helper();
+        let metrics = |deps: &Extern<MockStorage, MockApi, MockQuerier>, viewing_key: &str| {
+            query(
+                deps,
+                QueryMsg::Metrics {
+                    viewing_key: viewing_key.to_string(),
+                },
+            )
+        };
+        let handle_msg = HandleMsg::SetViewingKey {
+            key: "hunter2".to_string(),
+            padding: None,
+        };
+        handle(&mut deps, mock_env(mock_user_address(), &[]), handle_msg).unwrap();
+
+        // = when queried without the admin's viewing key
+        // = * it raises an Unauthorized error
+        let error = extract_query_error(metrics(&deps, "hunter3"));
+        assert_eq!(error.code, QueryErrorCode::Unauthorized);
+
+        // = when the admin reserves aliases and an owner destroys their alias
+        let handle_msg = HandleMsg::ReserveAliases {
+            aliases: vec!["scammer".to_string(), "phisher".to_string()],
+            padding: None,
+        };
+        handle(&mut deps, mock_env(mock_user_address(), &[]), handle_msg).unwrap();
+        let handle_msg = HandleMsg::Destroy {
+            alias: "Epstein didn't kill himself".to_string(),
+            padding: None,
+        };
+        handle(&mut deps, mock_env("frump", &[]), handle_msg).unwrap();
+        // = * it counts them along with the admin's role-gated actions, leaving out SetViewingKey
+        match from_binary(&metrics(&deps, "hunter2").unwrap()).unwrap() {
+            QueryAnswer::Metrics { metrics } => assert_eq!(
+                metrics,
+                Metrics {
+                    destroys: 1,
+                    transfers: 0,
+                    reservations: 2,
+                    admin_actions: 1,
+                }
+            ),
+            _ => panic!("Unexpected query answer"),
+        }
+    }
+
+    #[test]
+    fn test_query_did_document() {
+        let (_init_result, mut deps) = init_helper();
+        let alias: &str = "epstein didn't kill himself";
+
+        // = when the alias does not exist
+        // = * it raises a not found error
+        let query_result = query(
+            &deps,
+            QueryMsg::DidDocument {
+                alias: "nobody".to_string(),
+            },
+        );
+        assert_eq!(
+            extract_query_error(query_result).code,
+            QueryErrorCode::NotFound
+        );
+
+        // = when the alias has a url record and another record
+        handle(
+            &mut deps,
+            mock_env("frump", &[]),
+            HandleMsg::UpdateRecords {
+                alias: alias.to_string(),
+                set: vec![
+                    Record {
+                        key: "url".to_string(),
+                        value: "https://www.btn.group".to_string(),
+                    },
+                    Record {
+                        key: "twitter".to_string(),
+                        value: "@btn_group".to_string(),
+                    },
+                ],
+                remove: vec![],
+                padding: None,
+            },
+        )
+        .unwrap();
+        // = * it encodes the alias into the DID, lets the owner authenticate and lists the url as
+        // a service
+        let query_result = query(
+            &deps,
+            QueryMsg::DidDocument {
+                alias: alias.to_string(),
+            },
+        )
+        .unwrap();
+        let query_answer: QueryAnswer = from_binary(&query_result).unwrap();
+        match query_answer {
+            QueryAnswer::DidDocument { did_document } => {
+                let did: &str = "did:scrt:epstein%20didn%27t%20kill%20himself";
+                assert_eq!(did_document.context, vec![DID_CONTEXT.to_string()]);
+                assert_eq!(did_document.id, did);
+                assert_eq!(did_document.verification_method.len(), 1);
+                assert_eq!(
+                    did_document.verification_method[0].blockchain_account_id,
+                    HumanAddr::from("frump")
+                );
+                assert_eq!(
+                    did_document.authentication,
+                    vec![format!("{}#controller-0", did)]
+                );
+                assert_eq!(
+                    did_document.service,
+                    vec![DidService {
+                        id: format!("{}#url", did),
+                        r#type: "url".to_string(),
+                        service_endpoint: "https://www.btn.group".to_string(),
+                    }]
+                );
+            }
+            _ => panic!("Unexpected query answer"),
+        }
+    }
+
+    #[test]
+    fn test_query_expiring_within() {
+        let (_init_result, mut deps) = init_helper();
+        let height: u64 = mock_env(mock_user_address(), &[]).block.height;
+        let fee: u128 = AMOUNT_FOR_TRANSACTION / 10;
+        handle(
+            &mut deps,
+            mock_env(mock_user_address(), &[]),
+            HandleMsg::SetLeaseTerms {
+                lease_terms: Some(LeaseTerms {
+                    period_blocks: 100,
+                    fee: Uint128(fee),
+                }),
+                padding: None,
+            },
+        )
+        .unwrap();
+        let lease = |deps: &mut Extern<MockStorage, MockApi, MockQuerier>,
+                     from: &str,
+                     alias: &str,
+                     periods: u16| {
+            handle(
+                deps,
+                mock_env(mock_buttcoin().address, &[]),
+                HandleMsg::Receive {
+                    sender: HumanAddr::from(from),
+                    from: HumanAddr::from(from),
+                    amount: Uint128(fee * u128::from(periods)),
+                    msg: to_binary(&ReceiveMsg::Lease {
+                        alias: alias.to_string(),
+                        periods,
+                    })
+                    .unwrap(),
+                    padding: None,
+                },
+            )
+            .unwrap();
+        };
+        lease(&mut deps, "renter", "later", 3);
+        lease(&mut deps, "tenant", "sooner", 1);
+        let expiring_within = |height: u64, blocks: u64| -> (Vec<ExpiringAlias>, Option<u32>) {
+            let query_result = query(
+                &deps,
+                QueryMsg::ExpiringWithin {
+                    height,
+                    blocks,
+                    page: 0,
+                },
+            )
+            .unwrap();
+            match from_binary(&query_result).unwrap() {
+                QueryAnswer::ExpiringWithin { aliases, next_page } => (aliases, next_page),
+                _ => panic!("Unexpected query answer"),
+            }
+        };
+        let expiring_alias = |alias: &str, expires_at: u64| ExpiringAlias {
+            alias: alias.to_string(),
+            expires_at,
+        };
+
+        // = when only one lease runs out within the window
+        // = * it lists that alias alone
+        assert_eq!(
+            expiring_within(height, 100),
+            (vec![expiring_alias("sooner", height + 100)], None)
+        );
+
+        // = when the window covers both leases
+        // = * it lists them soonest first and leaves out aliases that aren't leased
+        assert_eq!(
+            expiring_within(height, 300),
+            (
+                vec![
+                    expiring_alias("sooner", height + 100),
+                    expiring_alias("later", height + 300),
+                ],
+                None
+            )
+        );
+
+        // = when a lease has already run out
+        // = * it is left out
+        assert_eq!(
+            expiring_within(height + 100, 300),
+            (vec![expiring_alias("later", height + 300)], None)
+        );
+    }
+
+    #[test]
+    fn test_query_payment_uri() {
+        let (_init_result, mut deps) = init_helper();
+        let chain_id: String = mock_env(mock_user_address(), &[]).block.chain_id;
+        let payment_uri =
+            |deps: &Extern<MockStorage, MockApi, MockQuerier>, amount: Option<Uint128>| -> String {
+                let query_result = query(
+                    deps,
+                    QueryMsg::PaymentUri {
+                        alias: "Epstein didn't kill himself".to_string(),
+                        amount,
+                    },
+                )
+                .unwrap();
+                match from_binary(&query_result).unwrap() {
+                    QueryAnswer::PaymentUri { uri, .. } => uri,
+                    _ => panic!("Unexpected query answer"),
+                }
+            };
+
+        // = when the alias has no payment preference
+        // = * it pays the owner
+        assert_eq!(
+            payment_uri(&deps, None),
+            format!(
+                "secret:frump@{}?alias=epstein%20didn%27t%20kill%20himself",
+                chain_id
+            )
+        );
+
+        // = when the alias has a payment preference and an amount is given
+        let handle_msg = HandleMsg::SetPaymentPreference {
+            alias: "Epstein didn't kill himself".to_string(),
+            payment_preference: Some(PaymentPreference {
+                asset: Some(Asset::Native {
+                    denom: "uscrt".to_string(),
+                }),
+                memo_required: true,
+                receiving_address: Some(HumanAddr::from("cold-wallet")),
+            }),
+            padding: None,
+        };
+        handle(&mut deps, mock_env("frump", &[]), handle_msg).unwrap();
+        // = * it pays the receiving address the amount in the preferred asset
+        assert_eq!(
+            payment_uri(&deps, Some(Uint128(5))),
+            format!(
+                "secret:cold-wallet@{}?alias=epstein%20didn%27t%20kill%20himself\
+                 &amount=5&denom=uscrt&memo_required=true",
+                chain_id
+            )
+        );
+    }
+
+    #[test]
+    fn test_query_status() {
+        let (_init_result, mut deps) = init_helper();
+        let status_of = |deps: &Extern<MockStorage, MockApi, MockQuerier>| -> (u64, Fees) {
+            let query_response = query(deps, QueryMsg::Status {}).unwrap();
+            match from_binary(&query_response).unwrap() {
+                QueryAnswer::Status {
+                    admin,
+                    alias_count,
+                    fees,
+                    version,
+                } => {
+                    assert_eq!(admin, mock_user_address());
+                    assert_eq!(version, env!("CARGO_PKG_VERSION"));
+                    (alias_count, fees)
+                }
+                _ => panic!("Unexpected query answer"),
+            }
+        };
+
+        // = when the contract has been initialized
+        // = * it counts the seeded aliases and returns the fees
+        let (alias_count, fees) = status_of(&deps);
+        assert_eq!(alias_count, 1);
+        assert_eq!(
+            fees,
+            Fees {
+                token: mock_buttcoin(),
+                create: Uint128(AMOUNT_FOR_TRANSACTION),
+                rename: Uint128(AMOUNT_FOR_TRANSACTION),
+                accepted_payments: vec![],
+            }
+        );
+
+        // = when an alias is destroyed
+        // = * the count goes down
+        let handle_msg = HandleMsg::Destroy {
+            alias: "Epstein didn't kill himself".to_string(),
+            padding: None,
+        };
+        handle(&mut deps, mock_env("frump", &[]), handle_msg).unwrap();
+        assert_eq!(status_of(&deps).0, 0);
+    }
+
+    #[test]
+    fn test_query_history() {
+        let (_init_result, mut deps) = init_helper();
+        let history_of = |deps: &Extern<MockStorage, MockApi, MockQuerier>,
+                          alias: &str|
+         -> (bool, Option<u64>, bool) {
+            let query_response = query(
+                deps,
+                QueryMsg::History {
+                    alias: alias.to_string(),
+                },
+            )
+            .unwrap();
+            match from_binary(&query_response).unwrap() {
+                QueryAnswer::History {
+                    registered,
+                    last_released_at,
+                    renounced,
+                    ..
+                } => (registered, last_released_at, renounced),
+                _ => panic!("Unexpected query answer"),
+            }
+        };
+
+        // = when the alias has never been registered
+        // = * it is not registered and has never been released
+        assert_eq!(history_of(&deps, "nailbiter"), (false, None, false));
+
+        // = when the alias is registered
+        // = * it is registered and has never been released
+        assert_eq!(
+            history_of(&deps, "Epstein didn't kill himself"),
+            (true, None, false)
+        );
+
+        // = when the alias is renamed away
+        // = * the old alias reports when it was released
+        let rename_alias_message = ReceiveMsg::Rename {
+            from: "Epstein didn't kill himself".to_string(),
+            to: "nailbiter".to_string(),
+        };
+        let receive_msg = HandleMsg::Receive {
+            sender: HumanAddr::from("frump"),
+            from: HumanAddr::from("frump"),
+            amount: Uint128(AMOUNT_FOR_TRANSACTION),
+            msg: to_binary(&rename_alias_message).unwrap(),
+            padding: None,
+        };
+        let env = mock_env(mock_buttcoin().address, &[]);
+        handle(&mut deps, env.clone(), receive_msg).unwrap();
+        assert_eq!(
+            history_of(&deps, "epstein didn't kill himself"),
+            (false, Some(env.block.height), false)
+        );
+
+        // = when the alias is renounced
+        // = * it reports when it was released and that it was renounced
+        let handle_msg = HandleMsg::Renounce {
+            alias: "nailbiter".to_string(),
+            padding: None,
+        };
+        handle(&mut deps, mock_env("frump", &[]), handle_msg).unwrap();
+        assert_eq!(
+            history_of(&deps, "nailbiter"),
+            (false, Some(env.block.height), true)
+        );
+    }
+
+    #[test]
+    fn test_query_quote() {
+        let (_init_result, mut deps) = init_helper();
+
+        // = when the alias is available
+        // = * it returns the normalized alias and what creating it costs
+        let query_result = query(
+            &deps,
+            QueryMsg::Quote {
+                alias: " Nail Biter ".to_string(),
+                height: None,
+                periods: None,
+            },
+        )
+        .unwrap();
+        match from_binary(&query_result).unwrap() {
+            QueryAnswer::Quote {
+                alias,
+                token,
+                amount,
+                promotion,
+                blocks,
+            } => {
+                assert_eq!(alias, "nail biter");
+                assert_eq!(token, mock_buttcoin());
+                assert_eq!(amount, Uint128(AMOUNT_FOR_TRANSACTION));
+                assert_eq!(promotion, None);
+                assert_eq!(blocks, None);
+            }
+            _ => panic!("Unexpected query answer"),
+        }
+
+        // = when periods are given
+        let quote_lease = |deps: &Extern<MockStorage, MockApi, MockQuerier>| {
+            query(
+                deps,
+                QueryMsg::Quote {
+                    alias: "nail biter".to_string(),
+                    height: None,
+                    periods: Some(3),
+                },
+            )
+        };
+        // == when aliases can't be leased
+        // == * it raises an error
+        let error = extract_query_error(quote_lease(&deps));
+        assert_eq!(error.details.message, "Aliases can't be leased");
+        // == when there are lease terms
+        // == * it returns the fee and blocks for that many periods
+        handle(
+            &mut deps,
+            mock_env(mock_user_address(), &[]),
+            HandleMsg::SetLeaseTerms {
+                lease_terms: Some(LeaseTerms {
+                    period_blocks: 100,
+                    fee: Uint128(5),
+                }),
+                padding: None,
+            },
+        )
+        .unwrap();
+        match from_binary(&quote_lease(&deps).unwrap()).unwrap() {
+            QueryAnswer::Quote { amount, blocks, .. } => {
+                assert_eq!(amount, Uint128(15));
+                assert_eq!(blocks, Some(300));
+            }
+            _ => panic!("Unexpected query answer"),
+        }
+
+        // = when the alias has been taken
+        // = * it raises an error
+        let query_result = query(
+            &deps,
+            QueryMsg::Quote {
+                alias: "Epstein didn't kill himself".to_string(),
+                height: None,
+                periods: None,
+            },
         );
-        handle_result.unwrap();
+        let error = extract_query_error(query_result);
+        assert_eq!(error.code, QueryErrorCode::InvalidRequest);
+        assert_eq!(error.details.message, "Alias has already been taken");
+    }
 
-        // = when user tries to delete an alias that does not exist
-        // = * it raises an error
-        let destroy_alias_message = HandleMsg::Destroy {
-            alias: "idonotexist".to_string(),
+    #[test]
+    fn test_query_validate() {
+        let (_init_result, deps) = init_helper();
+        let validate = |alias: &str| -> (String, Vec<String>) {
+            let query_result = query(
+                &deps,
+                QueryMsg::Validate {
+                    alias: alias.to_string(),
+                },
+            )
+            .unwrap();
+            match from_binary(&query_result).unwrap() {
+                QueryAnswer::Validate { alias, violations } => (alias, violations),
+                _ => panic!("Unexpected query answer"),
+            }
         };
-        let res = handle(
-            &mut deps,
-            mock_env(mock_user_address(), &[]),
-            destroy_alias_message,
+
+        // = when the alias would be accepted
+        // = * it returns the normalized alias without violations
+        assert_eq!(validate(" Nail Biter "), ("nail biter".to_string(), vec![]));
+
+        // = when the alias is too long
+        // = * it returns the violation
+        assert_eq!(
+            validate(&"a".repeat(256)).1,
+            vec!["Alias is too long".to_string()]
         );
-        let error = extract_error_msg(res);
-        assert_eq!(error, "Alias not found");
 
-        // = when user tries to delete an alias that belongs to someone else
-        // = * it raises an error
-        let destroy_alias_message = HandleMsg::Destroy {
-            alias: alias.to_string(),
-        };
-        let res = handle(
-            &mut deps,
-            mock_env(mock_buttcoin().address, &[]),
-            destroy_alias_message,
+        // = when the alias is an address
+        // = * it returns the violation
+        assert_eq!(
+            validate("Secret1qypqxpq9qcrsszg2pvxq6rs0zqg3yyc5a8chmq").1,
+            vec!["Alias can't be an address".to_string()]
         );
-        let error = extract_error_msg(res);
-        assert_eq!(error, "Unauthorized");
 
-        // = when user destroy their own alias
-        // = * alias is removed
+        // = when the alias has been taken
+        // = * it returns the same error Create would raise
+        assert_eq!(
+            validate("Epstein didn't kill himself").1,
+            vec!["Alias has already been taken".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_query_recent() {
+        let (_init_result, mut deps) = init_helper();
+        for (alias, address) in &[
+            ("nailbiter", mock_user_address()),
+            ("crumpet", HumanAddr::from("crump")),
+        ] {
+            let create_alias_message = ReceiveMsg::Create {
+                alias: alias.to_string(),
+                avatar_url: None,
+                avatar_metadata: None,
+                records: None,
+                fallback: None,
+            };
+            let receive_msg = HandleMsg::Receive {
+                sender: address.clone(),
+                from: address.clone(),
+                amount: Uint128(AMOUNT_FOR_TRANSACTION),
+                msg: to_binary(&create_alias_message).unwrap(),
+                padding: None,
+            };
+            handle(
+                &mut deps,
+                mock_env(mock_buttcoin().address, &[]),
+                receive_msg,
+            )
+            .unwrap();
+        }
         let destroy_alias_message = HandleMsg::Destroy {
-            alias: alias.to_string(),
+            alias: "nailbiter".to_string(),
+            padding: None,
         };
         handle(
             &mut deps,
@@ -355,215 +17246,252 @@ mod tests {
             destroy_alias_message,
         )
         .unwrap();
-        // Query destroyed alias via alias
-        let query_response = query(
-            &mut deps,
-            QueryMsg::Search {
-                search_type: "alias".to_string(),
-                search_value: alias.to_string(),
-            },
-        );
-        let error = extract_error_msg(query_response);
-        assert_eq!(error, "Alias not found");
-        // Query destroyed alias via address
-        let query_response = query(
-            &mut deps,
-            QueryMsg::Search {
-                search_type: "address".to_string(),
-                search_value: mock_user_address().to_string(),
-            },
-        );
-        let error = extract_error_msg(query_response);
-        assert_eq!(error, "Alias not found");
+
+        // = when recent aliases are requested
+        // = * it returns the aliases that still exist, newest first
+        let query_result = query(&deps, QueryMsg::Recent { limit: 10 }).unwrap();
+        let query_answer: QueryAnswer = from_binary(&query_result).unwrap();
+        match query_answer {
+            QueryAnswer::Recent { aliases } => {
+                assert_eq!(
+                    aliases,
+                    vec![
+                        AliasAttributes {
+                            alias: "crumpet".to_string(),
+                            display_name: Some("crumpet".to_string()),
+                            avatar_url: None,
+                            avatar_metadata: None,
+                            address: HumanAddr::from("crump"),
+                        },
+                        AliasAttributes {
+                            alias: "epstein didn't kill himself".to_string(),
+                            display_name: Some("epstein didn't kill himself".to_string()),
+                            avatar_url: None,
+                            avatar_metadata: None,
+                            address: HumanAddr::from("frump"),
+                        },
+                    ]
+                );
+            }
+            _ => panic!("Unexpected query answer"),
+        }
+
+        // = when a limit is given
+        // = * it returns at most that many aliases
+        let query_result = query(&deps, QueryMsg::Recent { limit: 1 }).unwrap();
+        let query_answer: QueryAnswer = from_binary(&query_result).unwrap();
+        match query_answer {
+            QueryAnswer::Recent { aliases } => {
+                assert_eq!(aliases.len(), 1);
+                assert_eq!(aliases[0].alias, "crumpet");
+            }
+            _ => panic!("Unexpected query answer"),
+        }
     }
 
     #[test]
-    fn test_try_create() {
-        let alias = "   nail biter    ";
-        let avatar_url = "https://www.btn.group";
-
-        // Initialize
+    fn test_query_resolve_addresses() {
         let (_init_result, mut deps) = init_helper();
 
-        // when creating an alias
-        let create_alias_message = ReceiveMsg::Create {
-            alias: alias.to_string(),
-            avatar_url: Some(avatar_url.to_string()),
-        };
-        let receive_msg = HandleMsg::Receive {
-            sender: mock_user_address(),
-            from: mock_user_address(),
-            amount: Uint128(AMOUNT_FOR_TRANSACTION),
-            msg: to_binary(&create_alias_message).unwrap(),
-        };
+        // = when addresses are resolved
+        // = * it returns a result for each address in order
+        let query_result = query(
+            &deps,
+            QueryMsg::ResolveAddresses {
+                addresses: vec![
+                    mock_user_address(),
+                    HumanAddr::from("frump"),
+                    HumanAddr::from("x"),
+                ],
+            },
+        )
+        .unwrap();
+        let query_answer: QueryAnswer = from_binary(&query_result).unwrap();
+        match query_answer {
+            QueryAnswer::ResolveAddresses { addresses } => {
+                assert_eq!(
+                    addresses,
+                    vec![
+                        ResolvedAddress {
+                            address: mock_user_address(),
+                            result: ResolveResult::NotFound,
+                        },
+                        ResolvedAddress {
+                            address: HumanAddr::from("frump"),
+                            result: ResolveResult::Found {
+                                alias: "epstein didn't kill himself".to_string(),
+                                display_name: "epstein didn't kill himself".to_string(),
+                            },
+                        },
+                        ResolvedAddress {
+                            address: HumanAddr::from("x"),
+                            result: ResolveResult::Invalid,
+                        },
+                    ]
+                );
+            }
+            _ => panic!("Unexpected query answer"),
+        }
 
-        // = when user sends in a token that is not buttcoin
+        // = when more addresses than the max batch size are given
         // = * it raises an error
+        let query_result = query(
+            &deps,
+            QueryMsg::ResolveAddresses {
+                addresses: vec![mock_user_address(); DEFAULT_MAX_BATCH_SIZE as usize + 1],
+            },
+        );
+        let error = extract_query_error(query_result);
+        assert_eq!(error.code, QueryErrorCode::InvalidRequest);
+        assert_eq!(error.details.message, "Too many addresses. Maximum is 50.");
+
+        // = when the admin lowers the batch limit for ResolveAddresses
+        // = * it applies the new limit to ResolveAddresses only
+        let set_batch_limits = |resolve_addresses: u16| HandleMsg::SetBatchLimits {
+            batch_limits: BatchLimits {
+                resolve_addresses,
+                resolve_canonical_addresses: DEFAULT_MAX_BATCH_SIZE,
+            },
+            padding: None,
+        };
         let handle_result = handle(
             &mut deps,
-            mock_env(mock_user_address(), &[]),
-            receive_msg.clone(),
+            mock_env(HumanAddr::from("frump"), &[]),
+            set_batch_limits(2),
         );
         assert_eq!(
             handle_result.unwrap_err(),
             StdError::Unauthorized { backtrace: None }
         );
-
-        // = when user sends in buttcoin
         let handle_result = handle(
             &mut deps,
-            mock_env(mock_buttcoin().address, &[]),
-            receive_msg.clone(),
+            mock_env(mock_user_address(), &[]),
+            set_batch_limits(0),
         );
-        let handle_result_unwrapped = handle_result.unwrap();
-
-        // = * it sends the BUTT to the BUTT lode
         assert_eq!(
-            handle_result_unwrapped.messages,
-            vec![snip20::transfer_msg(
-                mock_butt_lode().address,
-                Uint128(AMOUNT_FOR_TRANSACTION),
-                None,
-                BLOCK_SIZE,
-                mock_buttcoin().contract_hash,
-                mock_buttcoin().address,
-            )
-            .unwrap()],
+            extract_error_msg(handle_result),
+            "Batch limits must be greater than 0"
         );
-
-        // = * It creates alias without trailing and leading whitespaces
-        let search_response = query(
+        handle(
             &mut deps,
-            QueryMsg::Search {
-                search_type: "alias".to_string(),
-                search_value: "nail biter".to_string(),
-            },
+            mock_env(mock_user_address(), &[]),
+            set_batch_limits(2),
         )
         .unwrap();
-        let val: SearchResponse = from_binary(&search_response).unwrap();
-        assert_eq!(mock_user_address(), val.attributes.clone().address);
-        assert_eq!(
-            avatar_url,
-            val.attributes.clone().avatar_url.unwrap().to_string()
+        let query_result = query(
+            &deps,
+            QueryMsg::ResolveAddresses {
+                addresses: vec![mock_user_address(); 3],
+            },
         );
-        let search_response = query(
-            &mut deps,
-            QueryMsg::Search {
-                search_type: "address".to_string(),
-                search_value: mock_user_address().to_string(),
+        let error = extract_query_error(query_result);
+        assert_eq!(error.details.message, "Too many addresses. Maximum is 2.");
+        let frump: Binary = deps
+            .api
+            .canonical_address(&HumanAddr::from("frump"))
+            .unwrap()
+            .0;
+        query(
+            &deps,
+            QueryMsg::ResolveCanonicalAddresses {
+                addresses: vec![frump; 3],
             },
         )
         .unwrap();
-        let val: SearchResponse = from_binary(&search_response).unwrap();
-        assert_eq!("nail biter", val.attributes.clone().alias.to_string());
-        assert_eq!(mock_user_address(), val.attributes.clone().address);
-        assert_eq!(
-            avatar_url,
-            val.attributes.clone().avatar_url.unwrap().to_string()
-        );
-
-        // = when alias already exists
-        let create_alias_message_two = ReceiveMsg::Create {
-            alias: "Epstein didn't kill himself".to_string(),
-            avatar_url: Some(avatar_url.to_string()),
-        };
-        let receive_msg_two = HandleMsg::Receive {
-            sender: HumanAddr::from("crump"),
-            from: HumanAddr::from("crump"),
-            amount: Uint128(AMOUNT_FOR_TRANSACTION),
-            msg: to_binary(&create_alias_message_two).unwrap(),
-        };
-        let handle_result = handle(
-            &mut deps,
-            mock_env(mock_buttcoin().address, &[]),
-            receive_msg_two,
-        );
-
-        // = * it raises an error
-        let error = extract_error_msg(handle_result);
-        assert_eq!(error, "Alias has already been taken");
-
-        // = when alias exists but is a different case
-        let create_alias_message = ReceiveMsg::Create {
-            alias: alias.to_uppercase().to_string(),
-            avatar_url: None,
-        };
-        let receive_msg = HandleMsg::Receive {
-            sender: mock_user_address(),
-            from: mock_user_address(),
-            amount: Uint128(AMOUNT_FOR_TRANSACTION),
-            msg: to_binary(&create_alias_message).unwrap(),
-        };
-
-        // = * it raises an error
-        let response = handle(
-            &mut deps,
-            mock_env(mock_buttcoin().address, &[]),
-            receive_msg,
-        );
-        let error = extract_error_msg(response);
-        assert_eq!(error, "Alias has already been taken");
+    }
 
-        // = when alias is too long
-        let alias = "Epstein didn't kill himself".repeat(20);
-        let create_alias_message = ReceiveMsg::Create {
-            alias: alias.to_uppercase().to_string(),
-            avatar_url: None,
-        };
-        let receive_msg = HandleMsg::Receive {
-            sender: mock_user_address(),
-            from: mock_user_address(),
-            amount: Uint128(AMOUNT_FOR_TRANSACTION),
-            msg: to_binary(&create_alias_message).unwrap(),
-        };
+    #[test]
+    fn test_query_resolve_canonical_addresses() {
+        let (_init_result, deps) = init_helper();
+        let frump: Binary = deps
+            .api
+            .canonical_address(&HumanAddr::from("frump"))
+            .unwrap()
+            .0;
+        let user: Binary = deps.api.canonical_address(&mock_user_address()).unwrap().0;
 
-        // = * it raises an error
-        let response = handle(
-            &mut deps,
-            mock_env(mock_buttcoin().address, &[]),
-            receive_msg,
-        );
-        let error = extract_error_msg(response);
-        assert_eq!(error, "Alias is too long");
+        // = when canonical addresses are resolved
+        // = * it returns a result for each address in order
+        let query_result = query(
+            &deps,
+            QueryMsg::ResolveCanonicalAddresses {
+                addresses: vec![user.clone(), frump.clone(), Binary(vec![])],
+            },
+        )
+        .unwrap();
+        let query_answer: QueryAnswer = from_binary(&query_result).unwrap();
+        match query_answer {
+            QueryAnswer::ResolveCanonicalAddresses { addresses } => {
+                assert_eq!(
+                    addresses,
+                    vec![
+                        ResolvedCanonicalAddress {
+                            address: user,
+                            result: ResolveResult::NotFound,
+                        },
+                        ResolvedCanonicalAddress {
+                            address: frump.clone(),
+                            result: ResolveResult::Found {
+                                alias: "epstein didn't kill himself".to_string(),
+                                display_name: "epstein didn't kill himself".to_string(),
+                            },
+                        },
+                        ResolvedCanonicalAddress {
+                            address: Binary(vec![]),
+                            result: ResolveResult::Invalid,
+                        },
+                    ]
+                );
+            }
+            _ => panic!("Unexpected query answer"),
+        }
 
-        // = when user already has an alias
+        // = when more addresses than the max batch size are given
         // = * it raises an error
-        let alias = "Epstein didn't kill himself".repeat(5);
-        let create_alias_message = ReceiveMsg::Create {
-            alias: alias.to_string(),
-            avatar_url: None,
-        };
-        let receive_msg = HandleMsg::Receive {
-            sender: HumanAddr::from("frump"),
-            from: HumanAddr::from("frump"),
-            amount: Uint128(AMOUNT_FOR_TRANSACTION),
-            msg: to_binary(&create_alias_message).unwrap(),
-        };
-        let response = handle(
-            &mut deps,
-            mock_env(mock_buttcoin().address, &[]),
-            receive_msg,
+        let query_result = query(
+            &deps,
+            QueryMsg::ResolveCanonicalAddresses {
+                addresses: vec![frump; DEFAULT_MAX_BATCH_SIZE as usize + 1],
+            },
         );
-        let error = extract_error_msg(response);
-        assert_eq!(error, "Address already has an alias");
+        let error = extract_query_error(query_result);
+        assert_eq!(error.code, QueryErrorCode::InvalidRequest);
+        assert_eq!(error.details.message, "Too many addresses. Maximum is 50.");
     }
 
-    // === QUERY TESTS ===
-
     #[test]
-    fn test_query_config() {
-        let (_init_result, deps) = init_helper();
-        let config: Config = TypedStore::attach(&deps.storage).load(CONFIG_KEY).unwrap();
-        let query_result = query(&deps, QueryMsg::Config {}).unwrap();
-        let query_answer: QueryAnswer = from_binary(&query_result).unwrap();
-        match query_answer {
-            QueryAnswer::Config {
-                buttcoin,
-                butt_lode,
-            } => {
-                assert_eq!(buttcoin, config.buttcoin);
-                assert_eq!(butt_lode, config.butt_lode);
+    fn test_testing_helpers() {
+        let mut deps =
+            crate::testing::mock_deps_with_aliases(vec![alias_attributes("frumpy", "frump")])
+                .unwrap();
+
+        // = when an alias is seeded
+        // = * it resolves like one created at init
+        seed_alias(&mut deps, "geezer", "some-geezer").unwrap();
+        let search_response = query(&deps, search_address_msg("some-geezer")).unwrap();
+        let val: SearchResponse = from_binary(&search_response).unwrap();
+        assert_eq!(val.attributes.alias, "geezer");
+        let query_result = query(&deps, resolve_addresses_msg(&["frump", "some-geezer"])).unwrap();
+        match from_binary(&query_result).unwrap() {
+            QueryAnswer::ResolveAddresses { addresses } => {
+                assert_eq!(
+                    addresses
+                        .into_iter()
+                        .map(|address| address.result)
+                        .collect::<Vec<ResolveResult>>(),
+                    vec![
+                        ResolveResult::Found {
+                            alias: "frumpy".to_string(),
+                            display_name: "frumpy".to_string(),
+                        },
+                        ResolveResult::Found {
+                            alias: "geezer".to_string(),
+                            display_name: "geezer".to_string(),
+                        },
+                    ]
+                );
             }
+            _ => panic!("Unexpected query answer"),
         }
     }
 }
@@ -1,9 +1,15 @@
 mod authorize;
+mod bech32;
 pub mod contract;
 pub mod msg;
+mod payments;
+mod signatures;
 pub mod state;
+#[cfg(any(test, feature = "testing"))]
+pub mod testing;
+pub mod viewing_key;
 
-#[cfg(target_arch = "wasm32")]
+#[cfg(all(target_arch = "wasm32", not(feature = "library")))]
 mod wasm {
     use super::contract;
     use cosmwasm_std::{
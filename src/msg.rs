@@ -1,21 +1,189 @@
-use crate::state::SecretContract;
+use crate::contract::BLOCK_SIZE;
+use crate::state::{
+    AcceptedPayment, AdminGrant, AdminRole, AliasVersion, Asset, Attestation, BatchLimits,
+    CaseFolding, Change, CrossChainSnapshot, DailyStats, Discount, Drop, EncryptionKey, Escrow,
+    FeeSplit, Governance, Image, ImageMetadata, Launch, Lease, LeaseTerms, LinkedAddress, Member,
+    Metrics, MintPhase, Offer, PaymentPreference, PendingAction, PriceOracle, Promotion, Reclaim,
+    ReclaimTerms, Record, Role, SecretContract, Suspension, TipJar,
+};
+use crate::viewing_key::ViewingKey;
 use cosmwasm_std::{Binary, HumanAddr, Uint128};
 use schemars::JsonSchema;
+use secret_toolkit::utils::{HandleCallback, Query};
 use serde::{Deserialize, Serialize};
 
 // === STRUCTS ===
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct AliasAttributes {
     pub alias: String,
+    // The alias as its owner wants it shown. Defaults to the alias when registering at init.
+    pub display_name: Option<String>,
     pub avatar_url: Option<String>,
+    pub avatar_metadata: Option<ImageMetadata>,
     pub address: HumanAddr,
 }
 
+// Block heights at which the alias was created and last modified
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct AliasMetadata {
+    pub created_at: u64,
+    pub updated_at: u64,
+    // When a leased alias is released unless its lease is renewed. None for aliases owned outright.
+    pub expires_at: Option<u64>,
+    pub status: AliasStatus,
+    // Whether any oracle has attested a platform handle for the alias
+    pub verified: bool,
+    pub attestations: Vec<Attestation>,
+    // How many addresses endorse the alias
+    pub endorsements: u64,
+    pub target: Option<String>,
+    pub wildcard: bool,
+    // Frozen aliases can't be edited again
+    pub frozen: bool,
+    // Block height until which the owner has locked the alias against transfers and destructive
+    // changes. Kept after it has passed.
+    pub locked_until: Option<u64>,
+    // Set while a Moderator has suspended the alias
+    pub suspension: Option<Suspension>,
+    // Set for leased aliases, which are released once the lease runs out
+    pub lease: Option<Lease>,
+    pub images: Vec<Image>,
+    pub banner_url: Option<String>,
+    pub accent_color: Option<String>,
+    // Set for organization aliases
+    pub members: Vec<Member>,
+    // Set for co-owned aliases, along with how many owners must approve destructive actions
+    pub co_owners: Vec<HumanAddr>,
+    pub approval_threshold: u8,
+    pub records: Vec<Record>,
+    pub encryption_key: Option<EncryptionKey>,
+    pub payment_preference: Option<PaymentPreference>,
+    pub linked_addresses: Vec<LinkedAddress>,
+}
+
+// An alias changed after the requested height. The address is its owner, or None once the alias
+// has been destroyed or renamed.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ChangedAlias {
+    pub alias: String,
+    pub address: Option<HumanAddr>,
+}
+
+// A W3C DID document for did:scrt:<alias>. The owner and co-owners can authenticate as it and
+// each record holding a url is listed as a service.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct DidDocument {
+    #[serde(rename = "@context")]
+    pub context: Vec<String>,
+    pub id: String,
+    pub verification_method: Vec<DidVerificationMethod>,
+    pub authentication: Vec<String>,
+    pub service: Vec<DidService>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct DidService {
+    pub id: String,
+    pub r#type: String,
+    pub service_endpoint: String,
+}
+
+// An address that controls the alias, identified by its bech32 account rather than a key
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct DidVerificationMethod {
+    pub id: String,
+    pub r#type: String,
+    pub controller: String,
+    pub blockchain_account_id: HumanAddr,
+}
+
+// A leased alias and the block its lease runs out at
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ExpiringAlias {
+    pub alias: String,
+    pub expires_at: u64,
+}
+
+// What each paid operation costs, in the given token
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Fees {
+    pub token: SecretContract,
+    pub create: Uint128,
+    pub rename: Uint128,
+    // Other assets the fees can be paid in
+    pub accepted_payments: Vec<AcceptedPayment>,
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct InitMsg {
     pub buttcoin: SecretContract,
     pub butt_lode: SecretContract,
     pub aliases: Vec<AliasAttributes>,
+    // Most versions of each alias kept for AliasAtVersion. 10 when not set.
+    pub alias_versions_kept: Option<u16>,
+    // Most inputs each batched query accepts. 50 for every query when not set.
+    pub batch_limits: Option<BatchLimits>,
+    // Blocks after init during which the admin can import aliases. No bootstrap phase when not set.
+    pub bootstrap_blocks: Option<u64>,
+    // Can't be changed after init. Unicode when not set.
+    pub case_folding: Option<CaseFolding>,
+    // Most changes to keep in the change log. Keeps every change when not set.
+    pub change_log_size: Option<u64>,
+    // Heights at which auctions, then registration open. Open from init when not set.
+    pub launch: Option<Launch>,
+    // Fees are a flat 1 Buttcoin when not set
+    pub price_oracle: Option<PriceOracle>,
+    pub prng_seed: Binary,
+    // Makes an avatar url required to register an alias. Off when not set.
+    pub require_avatar: Option<bool>,
+    // Responses are space padded to a multiple of this many bytes
+    pub response_block_size: Option<u16>,
+}
+
+// An admin handle waiting out the timelock. Anyone can execute it from executable_at.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct QueuedAction {
+    pub id: u64,
+    pub msg: HandleMsg,
+    pub queued_by: HumanAddr,
+    pub queued_at: u64,
+    pub executable_at: u64,
+}
+
+// Answer to PriceOracleQueryMsg::Price. The rate is the USD price of one Buttcoin scaled by 10^18.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PriceResponse {
+    pub rate: Uint128,
+    // Block time in seconds of the rate's last update
+    pub last_updated: u64,
+}
+
+// Serialized into the message of every error returned by a query
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct QueryError {
+    pub code: QueryErrorCode,
+    pub details: QueryErrorDetails,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct QueryErrorDetails {
+    pub target: Option<String>,
+    pub message: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ResolvedAddress {
+    pub address: HumanAddr,
+    pub result: ResolveResult,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ResolvedCanonicalAddress {
+    pub address: Binary,
+    pub result: ResolveResult,
 }
 
 // We define a custom struct for each query response
@@ -23,61 +191,1161 @@ pub struct InitMsg {
 pub struct SearchResponse {
     pub r#type: String,
     pub attributes: AliasAttributes,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<AliasMetadata>,
+}
+
+// An operation signed by signer with ADR-36 for someone else to submit. nonce must be the signer's
+// next nonce, so the operation can't be submitted twice.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Sponsorship {
+    pub signer: HumanAddr,
+    pub nonce: u64,
+    pub public_key: Binary,
+    pub signature: Binary,
 }
 
 // === ENUMS ===
+// Suspended takes precedence over Frozen, and Frozen over Leased. Queries don't know the block
+// height, so a lapsed lease is still Leased until expires_at is compared with the current block.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum AliasStatus {
+    Active,
+    Frozen,
+    Leased,
+    Suspended,
+}
+
 #[derive(Serialize, Deserialize, JsonSchema, Debug)]
 #[serde(rename_all = "snake_case")]
 pub enum HandleAnswer {
+    AcceptOffer { status: ResponseStatus },
+    AddImage { status: ResponseStatus },
+    AddKnownContract { status: ResponseStatus },
+    AddMember { status: ResponseStatus },
+    AddNoteViewer { status: ResponseStatus },
+    AddOracle { status: ResponseStatus },
+    AddRoutableToken { status: ResponseStatus },
+    Approve { status: ResponseStatus },
+    Attest { status: ResponseStatus },
+    CancelDrop { status: ResponseStatus },
+    CancelEscrow { status: ResponseStatus },
+    CancelQueuedAction { status: ResponseStatus },
+    ClaimAirdrop { status: ResponseStatus },
+    ClaimCrossChain { status: ResponseStatus },
+    ClaimGift { status: ResponseStatus },
+    CreateAliasViewingKey { key: ViewingKey },
+    CreateViewingKey { key: ViewingKey },
     Destroy { status: ResponseStatus },
+    EndBootstrap { status: ResponseStatus },
+    Endorse { status: ResponseStatus },
+    // winner is None when no applicant could take the alias, in which case all are refunded
+    FinalizeLottery { winner: Option<HumanAddr> },
+    Follow { status: ResponseStatus },
+    Freeze { status: ResponseStatus },
+    FundEscrow { status: ResponseStatus },
+    GrantRole { status: ResponseStatus },
+    // The registry's totals after the import, to compare with the exporting contract's
+    ImportChunk { alias_count: u64, checksum: Binary },
+    LinkAddress { status: ResponseStatus },
+    Lock { status: ResponseStatus },
+    MakeOffer { status: ResponseStatus },
+    OpenEscrow { status: ResponseStatus },
+    OpenLottery { status: ResponseStatus },
+    PurgeExpired { purged: u32 },
+    // The handle was timelocked rather than applied
+    Queued { id: u64, executable_at: u64 },
+    RemoveImage { status: ResponseStatus },
+    RemoveKnownContract { status: ResponseStatus },
+    RemoveMember { status: ResponseStatus },
+    RemoveNoteViewer { status: ResponseStatus },
+    RemoveOracle { status: ResponseStatus },
+    Reject { status: ResponseStatus },
+    Renounce { status: ResponseStatus },
+    // How many aliases the chunk added and how many are reserved in total
+    ReserveAliases { added: u32, reserved_count: u64 },
+    Revert { status: ResponseStatus },
+    RevokeAliasViewingKey { status: ResponseStatus },
+    RevokeAttestation { status: ResponseStatus },
+    RevokeEndorsement { status: ResponseStatus },
+    RevokeRole { status: ResponseStatus },
+    ScheduleDrop { status: ResponseStatus },
+    SetAcceptedPayments { status: ResponseStatus },
+    SetAirdropMerkleRoot { status: ResponseStatus },
+    SetAliasVersionsKept { status: ResponseStatus },
+    SetAvatarDomains { status: ResponseStatus },
+    SetBatchLimits { status: ResponseStatus },
+    SetChangeLogSize { status: ResponseStatus },
+    SetCoOwners { status: ResponseStatus },
+    SetCrossChainSnapshot { status: ResponseStatus },
+    SetDiscounts { status: ResponseStatus },
+    SetDisplayName { status: ResponseStatus },
+    SetEncryptionKey { status: ResponseStatus },
+    SetFeeSplits { status: ResponseStatus },
+    SetFollowsPrivate { status: ResponseStatus },
+    SetGovernance { status: ResponseStatus },
+    SetLaunch { status: ResponseStatus },
+    SetLeaseTerms { status: ResponseStatus },
+    SetMintPhases { status: ResponseStatus },
+    SetNote { status: ResponseStatus },
+    SetPaymentPreference { status: ResponseStatus },
+    SetPriceOracle { status: ResponseStatus },
+    SetPrimaryImage { status: ResponseStatus },
+    SetPrivateReverseLookup { status: ResponseStatus },
+    SetPromotions { status: ResponseStatus },
+    SetReclaimTerms { status: ResponseStatus },
+    SetRequireAvatar { status: ResponseStatus },
+    SetSigningKey { status: ResponseStatus },
+    SetTarget { status: ResponseStatus },
+    SetTimelock { status: ResponseStatus },
+    SetViewingKey { status: ResponseStatus },
+    SetWildcard { status: ResponseStatus },
+    SettleReclaim { status: ResponseStatus },
+    SponsoredTransfer { status: ResponseStatus },
+    StartReclaim { status: ResponseStatus },
+    Suspend { status: ResponseStatus },
+    Tip { status: ResponseStatus },
+    Transfer { status: ResponseStatus },
+    Unfollow { status: ResponseStatus },
+    UnlinkAddress { status: ResponseStatus },
+    UnreserveAliases { removed: u32, reserved_count: u64 },
+    Unsuspend { status: ResponseStatus },
+    UpdateProfile { status: ResponseStatus },
+    UpdateRecords { status: ResponseStatus },
+    WithdrawOffer { status: ResponseStatus },
+    WithdrawTips { status: ResponseStatus },
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum HandleMsg {
+    // Transfers the alias to the bidder and pays the owner the offer
+    AcceptOffer {
+        alias: String,
+        bidder: HumanAddr,
+        padding: Option<String>,
+    },
+    AddImage {
+        alias: String,
+        url: String,
+        metadata: Option<ImageMetadata>,
+        padding: Option<String>,
+    },
+    // SuperAdmin only. Keeps the label of a well-known contract from being registered as an alias.
+    AddKnownContract {
+        label: String,
+        address: HumanAddr,
+        padding: Option<String>,
+    },
+    // Adds the address as a member of the alias, or changes the role of an existing member
+    AddMember {
+        alias: String,
+        address: HumanAddr,
+        role: Role,
+        padding: Option<String>,
+    },
+    AddNoteViewer {
+        alias: String,
+        address: HumanAddr,
+        padding: Option<String>,
+    },
+    AddOracle {
+        address: HumanAddr,
+        padding: Option<String>,
+    },
+    AddRoutableToken {
+        token: SecretContract,
+        padding: Option<String>,
+    },
+    // Runs the pending action on a co-owned alias once enough owners have approved it
+    Approve {
+        alias: String,
+        padding: Option<String>,
+    },
+    Attest {
+        alias: String,
+        platform: String,
+        handle: String,
+        padding: Option<String>,
+    },
+    // Treasurer only. The alias stays reserved.
+    CancelDrop {
+        alias: String,
+        padding: Option<String>,
+    },
+    // The seller or the buyer can call off an escrow until the buyer has paid
+    CancelEscrow {
+        alias: String,
+        padding: Option<String>,
+    },
+    // SuperAdmin only
+    CancelQueuedAction {
+        id: u64,
+        padding: Option<String>,
+    },
+    // Registers the alias to the sender for free if sha256(alias | 0 | address) is a leaf of the
+    // airdrop merkle tree. Each pair of siblings is hashed in sorted order.
+    ClaimAirdrop {
+        alias: String,
+        proof: Vec<Binary>,
+        avatar_url: Option<String>,
+        padding: Option<String>,
+    },
+    // Claims an alias for the sender while the cross-chain snapshot is open. The name's owner on
+    // Ethereum signs "Claim <alias> for <sender> on <contract address>" with personal_sign, and
+    // the proof shows the alias and their address are in the snapshot.
+    ClaimCrossChain {
+        alias: String,
+        signature: Binary,
+        proof: Vec<Binary>,
+        avatar_url: Option<String>,
+        padding: Option<String>,
+    },
+    // Registers the gifted alias to the sender if sha256 of code matches the gift's code hash
+    ClaimGift {
+        alias: String,
+        code: String,
+        avatar_url: Option<String>,
+        padding: Option<String>,
+    },
+    // Create paid in an accepted native denomination. Answered with ReceiveAnswer::Create, or
+    // ReceiveAnswer::CreateWithFallback when fallback is set.
+    Create {
+        alias: String,
+        avatar_url: Option<String>,
+        avatar_metadata: Option<ImageMetadata>,
+        records: Option<Vec<Record>>,
+        fallback: Option<bool>,
+        padding: Option<String>,
+    },
+    // CreateGift paid in an accepted native denomination. Answered with
+    // ReceiveAnswer::CreateGift.
+    CreateGift {
+        alias: String,
+        code_hash: Binary,
+        padding: Option<String>,
+    },
+    CreateAliasViewingKey {
+        alias: String,
+        label: String,
+        entropy: String,
+        padding: Option<String>,
+    },
+    CreateViewingKey {
+        entropy: String,
+        padding: Option<String>,
+    },
+    // Needs approval when the alias is co-owned
     Destroy {
         alias: String,
+        padding: Option<String>,
+    },
+    // SuperAdmin only. Ends the bootstrap phase early. ImportChunk can't be used again afterwards.
+    EndBootstrap {
+        padding: Option<String>,
+    },
+    // Anyone can execute a queued action once its timelock is over. The action's own answer is
+    // returned.
+    ExecuteQueuedAction {
+        id: u64,
+        padding: Option<String>,
+    },
+    // Vouch for an alias. Each address can endorse an alias once.
+    Endorse {
+        alias: String,
+        padding: Option<String>,
+    },
+    // After the lottery, draws the alias's owner at random from its applicants. The winner's fee
+    // goes to the Butt Lode and the others are refunded. Anyone can finalize.
+    FinalizeLottery {
+        alias: String,
+        padding: Option<String>,
+    },
+    // Follow the owner of an alias. The sender needs an alias of their own.
+    Follow {
+        alias: String,
+        padding: Option<String>,
+    },
+    // Locks the alias's profile, images, records and name for good. Transfers stay possible if
+    // allowed. Needs approval when the alias is co-owned.
+    Freeze {
+        alias: String,
+        allow_transfers: bool,
+        padding: Option<String>,
+    },
+    // Pay for an alias in escrow in a native denomination. The alias is transferred and the
+    // seller paid in the same transaction.
+    FundEscrow {
+        alias: String,
+        padding: Option<String>,
+    },
+    // SuperAdmin only
+    GrantRole {
+        address: HumanAddr,
+        role: AdminRole,
+        padding: Option<String>,
+    },
+    // SuperAdmin only, during the bootstrap phase. Registers aliases from another contract's
+    // ExportChunk. checksum must be the chunk's checksum. Aliases already imported to the same
+    // address are skipped.
+    ImportChunk {
+        aliases: Vec<AliasAttributes>,
+        checksum: Binary,
+        padding: Option<String>,
+    },
+    // Links another address to the alias as a verified record. The address's holder signs
+    // "Link to <alias> on <contract address>" as ADR-36 arbitrary data, e.g. with Keplr's
+    // signArbitrary, and the public key must be the one the address is derived from.
+    LinkAddress {
+        alias: String,
+        address: HumanAddr,
+        public_key: Binary,
+        signature: Binary,
+        padding: Option<String>,
+    },
+    // Owner only. Until until_height, the alias can't be transferred, renamed, destroyed,
+    // renounced, frozen, sold or given co-owners, so a compromised wallet session can't take it.
+    // A lock can be extended but not shortened, and not taken out while the alias is in escrow.
+    Lock {
+        alias: String,
+        until_height: u64,
+        padding: Option<String>,
+    },
+    // Bid the native coins sent along for an alias. Tokens are bid with a Receive instead.
+    MakeOffer {
+        alias: String,
+        padding: Option<String>,
+    },
+    // Puts an alias in escrow for a buyer at a price. The alias can't be renamed, transferred or
+    // destroyed until the escrow is settled or cancelled. A leased alias goes to the buyer with
+    // its lease, and the escrow can't be funded once the lease has run out.
+    OpenEscrow {
+        alias: String,
+        buyer: HumanAddr,
+        asset: Asset,
+        amount: Uint128,
+        padding: Option<String>,
+    },
+    // SuperAdmin only. Opens a lottery for the next blocks, during which aliases are applied for
+    // with ApplyForAlias instead of being registered.
+    OpenLottery {
+        blocks: u64,
+        padding: Option<String>,
+    },
+    // Anyone can release up to limit aliases whose lease has run out, as registering them again
+    // would. Suspended aliases are skipped.
+    PurgeExpired {
+        limit: u32,
+        padding: Option<String>,
     },
     Receive {
         sender: HumanAddr,
         from: HumanAddr,
         amount: Uint128,
         msg: Binary,
+        padding: Option<String>,
+    },
+    RemoveImage {
+        alias: String,
+        index: u32,
+        padding: Option<String>,
+    },
+    // SuperAdmin only
+    RemoveKnownContract {
+        label: String,
+        padding: Option<String>,
+    },
+    RemoveMember {
+        alias: String,
+        address: HumanAddr,
+        padding: Option<String>,
+    },
+    RemoveNoteViewer {
+        alias: String,
+        address: HumanAddr,
+        padding: Option<String>,
+    },
+    RemoveOracle {
+        address: HumanAddr,
+        padding: Option<String>,
+    },
+    // Cancels the pending action on a co-owned alias
+    Reject {
+        alias: String,
+        padding: Option<String>,
+    },
+    // Rename paid in an accepted native denomination. Answered with ReceiveAnswer::Rename.
+    Rename {
+        from: String,
+        to: String,
+        padding: Option<String>,
+    },
+    // Destroys the alias and keeps anyone from registering it again. Needs approval when the alias
+    // is co-owned.
+    Renounce {
+        alias: String,
+        padding: Option<String>,
+    },
+    // Moderator only. Keeps the aliases from being registered. Large lists are sent in chunks of at
+    // most RESERVATION_CHUNK_SIZE, and resending a chunk is harmless.
+    ReserveAliases {
+        aliases: Vec<String>,
+        padding: Option<String>,
+    },
+    // Owner only. Restores the display name, avatar and records from before the last change. Only
+    // one step back is kept.
+    Revert {
+        alias: String,
+        padding: Option<String>,
+    },
+    RevokeAliasViewingKey {
+        alias: String,
+        label: String,
+        padding: Option<String>,
+    },
+    RevokeAttestation {
+        alias: String,
+        platform: String,
+        padding: Option<String>,
+    },
+    RevokeEndorsement {
+        alias: String,
+        padding: Option<String>,
+    },
+    // SuperAdmin only. The admin that instantiated the contract can't be revoked.
+    RevokeRole {
+        address: HumanAddr,
+        role: AdminRole,
+        padding: Option<String>,
+    },
+    // Treasurer only. Puts a reserved alias on sale, replacing any drop already scheduled for it.
+    ScheduleDrop {
+        alias: String,
+        drop: Drop,
+        padding: Option<String>,
+    },
+    // Treasurer only. Replaces the assets besides Buttcoin that fees can be paid in.
+    SetAcceptedPayments {
+        accepted_payments: Vec<AcceptedPayment>,
+        padding: Option<String>,
+    },
+    // SuperAdmin only. None ends the airdrop.
+    SetAirdropMerkleRoot {
+        merkle_root: Option<Binary>,
+        padding: Option<String>,
+    },
+    // SuperAdmin only. Fewer versions than are kept now are dropped as each alias next changes.
+    SetAliasVersionsKept {
+        count: u16,
+        padding: Option<String>,
+    },
+    // Moderator only. Domains such as ipfs.io, matched with their subdomains. An empty list allows
+    // avatars from any host.
+    SetAvatarDomains {
+        domains: Vec<String>,
+        padding: Option<String>,
+    },
+    // SuperAdmin only. The most inputs ResolveAddresses and ResolveCanonicalAddresses each accept.
+    SetBatchLimits {
+        batch_limits: BatchLimits,
+        padding: Option<String>,
+    },
+    // SuperAdmin only. None keeps every change from now on.
+    SetChangeLogSize {
+        size: Option<u64>,
+        padding: Option<String>,
+    },
+    // Needs approval when the alias is co-owned. An empty list with a threshold of 1 makes the
+    // owner the sole owner again.
+    SetCoOwners {
+        alias: String,
+        co_owners: Vec<HumanAddr>,
+        threshold: u8,
+        padding: Option<String>,
+    },
+    // SuperAdmin only. None closes cross-chain claims. Aliases claimed under the previous snapshot
+    // stay claimed.
+    SetCrossChainSnapshot {
+        cross_chain_snapshot: Option<CrossChainSnapshot>,
+        padding: Option<String>,
+    },
+    // Treasurer only. Replaces the discount table. Claims made under a merkle root stay claimed.
+    SetDiscounts {
+        discounts: Vec<Discount>,
+        padding: Option<String>,
+    },
+    // The display name may only differ from the alias in capitalization
+    SetDisplayName {
+        alias: String,
+        display_name: String,
+        padding: Option<String>,
+    },
+    // Owner only. The public key dApps encrypt payloads to the alias with. None removes it.
+    SetEncryptionKey {
+        alias: String,
+        encryption_key: Option<EncryptionKey>,
+        padding: Option<String>,
+    },
+    // Treasurer only. Replaces the fee splits. An empty list sends every fee to the Butt Lode.
+    // Burn can only be used while fees are paid in Buttcoin alone.
+    SetFeeSplits {
+        fee_splits: Vec<FeeSplit>,
+        padding: Option<String>,
+    },
+    // Keep the sender's followers and following to holders of their viewing key
+    SetFollowsPrivate {
+        private: bool,
+        padding: Option<String>,
+    },
+    // SuperAdmin only, and only the governance contract itself once it's exclusive
+    SetGovernance {
+        governance: Option<Governance>,
+        padding: Option<String>,
+    },
+    // SuperAdmin only. None opens everything straight away.
+    SetLaunch {
+        launch: Option<Launch>,
+        padding: Option<String>,
+    },
+    // Treasurer only. None stops new leases. Existing leases keep their terms.
+    SetLeaseTerms {
+        lease_terms: Option<LeaseTerms>,
+        padding: Option<String>,
+    },
+    // Treasurer only. Replaces every mint phase. An empty list opens registration to everyone.
+    SetMintPhases {
+        phases: Vec<MintPhase>,
+        padding: Option<String>,
+    },
+    // Clearing the note also removes its viewers
+    SetNote {
+        alias: String,
+        note: Option<String>,
+        padding: Option<String>,
+    },
+    // Owner only. None removes the payment preference.
+    SetPaymentPreference {
+        alias: String,
+        payment_preference: Option<PaymentPreference>,
+        padding: Option<String>,
+    },
+    // Treasurer only. None goes back to a flat fee.
+    SetPriceOracle {
+        price_oracle: Option<PriceOracle>,
+        padding: Option<String>,
+    },
+    SetPrimaryImage {
+        alias: String,
+        index: u32,
+        padding: Option<String>,
+    },
+    SetPrivateReverseLookup {
+        alias: String,
+        enabled: bool,
+        padding: Option<String>,
+    },
+    // Treasurer only. Replaces every scheduled promotion.
+    SetPromotions {
+        promotions: Vec<Promotion>,
+        padding: Option<String>,
+    },
+    // Moderator only. None stops new reclaims. Reclaims already running keep their heights.
+    SetReclaimTerms {
+        reclaim_terms: Option<ReclaimTerms>,
+        padding: Option<String>,
+    },
+    // Moderator only
+    SetRequireAvatar {
+        required: bool,
+        padding: Option<String>,
+    },
+    // Owner only. The compressed secp256k1 public key that signs in as the alias. None removes it.
+    SetSigningKey {
+        alias: String,
+        public_key: Option<Binary>,
+        padding: Option<String>,
+    },
+    SetTarget {
+        alias: String,
+        target: Option<String>,
+        padding: Option<String>,
+    },
+    // SuperAdmin only. Changing it is timelocked too while a timelock is set.
+    SetTimelock {
+        blocks: u64,
+        padding: Option<String>,
+    },
+    SetViewingKey {
+        key: String,
+        padding: Option<String>,
+    },
+    SetWildcard {
+        alias: String,
+        wildcard: bool,
+        padding: Option<String>,
+    },
+    // Anyone can settle a reclaim once it's over. The highest bid is kept as a fee and the alias
+    // goes to the bidder, or the bid is refunded if the bidder can no longer take the alias.
+    SettleReclaim {
+        alias: String,
+        padding: Option<String>,
+    },
+    // Anyone can relay a Transfer that the owner signed. The signed data is
+    // "Transfer <alias> to <recipient> with nonce <nonce> on <contract address>".
+    SponsoredTransfer {
+        alias: String,
+        recipient: HumanAddr,
+        sponsorship: Sponsorship,
+        padding: Option<String>,
+    },
+    // Anyone can put an alias that has been idle for the reclaim terms' idle_blocks up for a
+    // reclaim auction. Bids are made in Buttcoin with a Receive.
+    StartReclaim {
+        alias: String,
+        padding: Option<String>,
+    },
+    // Moderator only. For aliases under dispute, and never timelocked. Stops every change to the
+    // alias by its owner, members or a sale until Unsuspend. With hide_resolution, the alias and
+    // its address don't resolve either, and the alias is left out of DidDocument and Recent.
+    Suspend {
+        alias: String,
+        hide_resolution: bool,
+        padding: Option<String>,
+    },
+    // Native coins sent along are held for the alias's owner to withdraw. Tokens are tipped
+    // with a Receive instead.
+    Tip {
+        alias: String,
+        message: Option<String>,
+        padding: Option<String>,
+    },
+    // Needs approval when the alias is co-owned. The recipient must not already have an alias.
+    // A contract recipient is sent msg in a ReceiveAlias once the transfer goes through.
+    Transfer {
+        alias: String,
+        recipient: HumanAddr,
+        recipient_code_hash: Option<String>,
+        msg: Option<Binary>,
+        padding: Option<String>,
+    },
+    Unfollow {
+        alias: String,
+        padding: Option<String>,
+    },
+    UnlinkAddress {
+        alias: String,
+        address: HumanAddr,
+        padding: Option<String>,
+    },
+    // Moderator only
+    UnreserveAliases {
+        aliases: Vec<String>,
+        padding: Option<String>,
+    },
+    // Moderator only
+    Unsuspend {
+        alias: String,
+        padding: Option<String>,
+    },
+    UpdateProfile {
+        alias: String,
+        banner_url: Option<String>,
+        accent_color: Option<String>,
+        padding: Option<String>,
+    },
+    // Sets and removes records in one go. Nothing is saved if any change is invalid.
+    UpdateRecords {
+        alias: String,
+        set: Vec<Record>,
+        remove: Vec<String>,
+        padding: Option<String>,
+    },
+    // Returns the sender's offer on the alias, even once it has expired or the alias is gone
+    WithdrawOffer {
+        alias: String,
+        padding: Option<String>,
+    },
+    // Sends the owner everything in the alias's tip jar
+    WithdrawTips {
+        alias: String,
+        padding: Option<String>,
     },
 }
 
 #[derive(Serialize, Deserialize, JsonSchema, Debug)]
 #[serde(rename_all = "snake_case")]
 pub enum QueryAnswer {
+    // latest_version is the alias's newest version, which may be newer than the one asked for
+    AliasAtVersion {
+        alias_version: AliasVersion,
+        latest_version: u64,
+    },
+    Analytics {
+        daily_stats: Vec<DailyStats>,
+    },
+    ChangedSince {
+        aliases: Vec<ChangedAlias>,
+        next_page: Option<u32>,
+    },
+    Changes {
+        changes: Vec<Change>,
+        earliest_seq: u64,
+        latest_seq: u64,
+    },
+    ChangesSince {
+        changes: Vec<Change>,
+        latest_seq: u64,
+    },
+    Checksum {
+        checksum: Binary,
+        latest_seq: u64,
+    },
     Config {
+        admin: HumanAddr,
+        airdrop_merkle_root: Option<Binary>,
+        alias_versions_kept: u16,
+        avatar_domains: Vec<String>,
+        batch_limits: BatchLimits,
+        bootstrap_ends_at: Option<u64>,
         buttcoin: SecretContract,
         butt_lode: SecretContract,
+        case_folding: CaseFolding,
+        chain_id: String,
+        change_log_size: Option<u64>,
+        cross_chain_snapshot: Option<CrossChainSnapshot>,
+        discounts: Vec<Discount>,
+        fee_splits: Vec<FeeSplit>,
+        governance: Option<Governance>,
+        launch: Option<Launch>,
+        lease_terms: Option<LeaseTerms>,
+        lottery_ends_at: Option<u64>,
+        mint_phases: Vec<MintPhase>,
+        oracles: Vec<HumanAddr>,
+        price_oracle: Option<PriceOracle>,
+        promotions: Vec<Promotion>,
+        reclaim_terms: Option<ReclaimTerms>,
+        require_avatar: bool,
+        response_block_size: u16,
+        routable_tokens: Vec<SecretContract>,
+        timelock_blocks: u64,
+    },
+    DidDocument {
+        did_document: DidDocument,
+    },
+    Drop {
+        drop: Option<Drop>,
+    },
+    Escrow {
+        escrow: Option<Escrow>,
+    },
+    ExpiringWithin {
+        aliases: Vec<ExpiringAlias>,
+        next_page: Option<u32>,
+    },
+    // checksum covers the aliases in this chunk. alias_count and registry_checksum are the
+    // registry's totals, which the importing contract should reach.
+    ExportChunk {
+        aliases: Vec<AliasAttributes>,
+        checksum: Binary,
+        next_seq: Option<u64>,
+        alias_count: u64,
+        registry_checksum: Binary,
+    },
+    Followers {
+        aliases: Vec<String>,
+        next_page: Option<u32>,
+    },
+    Following {
+        aliases: Vec<String>,
+        next_page: Option<u32>,
+    },
+    // last_released_at is the block height at which the alias was last destroyed or renamed away,
+    // so it is set for any alias that was registered before and has been released since
+    History {
+        alias: String,
+        registered: bool,
+        last_released_at: Option<u64>,
+        renounced: bool,
+    },
+    Metrics {
+        metrics: Metrics,
+    },
+    Nonce {
+        nonce: u64,
+    },
+    Offers {
+        offers: Vec<Offer>,
+    },
+    Note {
+        note: String,
+    },
+    // uri is secret:<address>@<chain_id>?alias=<alias>, followed by the amount and the preferred
+    // asset when they are known, for encoding in a QR code
+    PaymentUri {
+        uri: String,
+        address: HumanAddr,
+        chain_id: String,
+    },
+    PendingAction {
+        pending_action: Option<PendingAction>,
+    },
+    // amount has the promotion running at the requested height taken off. blocks is how long a
+    // quoted lease lasts.
+    Quote {
+        alias: String,
+        token: SecretContract,
+        amount: Uint128,
+        promotion: Option<Promotion>,
+        blocks: Option<u64>,
+    },
+    QueuedActions {
+        queued_actions: Vec<QueuedAction>,
+        timelock_blocks: u64,
+    },
+    Recent {
+        aliases: Vec<AliasAttributes>,
+    },
+    Reclaim {
+        reclaim: Option<Reclaim>,
+    },
+    ResolveAddresses {
+        addresses: Vec<ResolvedAddress>,
+    },
+    ResolveCanonicalAddresses {
+        addresses: Vec<ResolvedCanonicalAddress>,
+    },
+    // The admin that instantiated the contract comes first, as a SuperAdmin
+    Roles {
+        admin_grants: Vec<AdminGrant>,
+    },
+    Status {
+        admin: HumanAddr,
+        alias_count: u64,
+        fees: Fees,
+        version: String,
+    },
+    TipJar {
+        tip_jar: TipJar,
+    },
+    // alias is normalized the way the contract stores it. No violations means Create would accept
+    // it, registrant specific checks aside.
+    Validate {
+        alias: String,
+        violations: Vec<String>,
+    },
+    VerifySignIn {
+        valid: bool,
     },
 }
 
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum QueryErrorCode {
+    InternalError,
+    InvalidParameter,
+    InvalidRequest,
+    NotFound,
+    Unauthorized,
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum QueryMsg {
+    // One of the versions kept of what the alias resolved to. A version is added whenever the
+    // alias's owner or target changes, including when it is destroyed.
+    AliasAtVersion {
+        alias: String,
+        version: u64,
+    },
+    Analytics {
+        start_day: u64,
+        end_day: u64,
+    },
+    // Aliases changed after height, for refreshing a cache without re-reading every alias
+    ChangedSince {
+        height: u64,
+        page: u32,
+    },
+    // Changes from start_seq to end_seq inclusive. Changes before earliest_seq have been pruned.
+    Changes {
+        start_seq: u64,
+        end_seq: u64,
+    },
+    ChangesSince {
+        seq: u64,
+        limit: u32,
+    },
+    Checksum {},
     Config {},
+    DidDocument {
+        alias: String,
+    },
+    Drop {
+        alias: String,
+    },
+    Escrow {
+        alias: String,
+    },
+    // Leased aliases whose lease runs out after height and at most blocks later, soonest first.
+    // Queries don't know the block height, so pass the current one. Aliases that aren't leased
+    // don't expire.
+    ExpiringWithin {
+        height: u64,
+        blocks: u64,
+        page: u32,
+    },
+    // Readable with the admin's viewing key. Exports the aliases touched by the changes from
+    // start_seq, so an alias can appear in more than one chunk.
+    ExportChunk {
+        start_seq: u64,
+        viewing_key: String,
+    },
+    // The aliases of the addresses that follow the alias's owner. Needs the owner's viewing key
+    // if their follows are private.
+    Followers {
+        alias: String,
+        page: u32,
+        viewing_key: Option<String>,
+    },
+    // The aliases of the addresses that the alias's owner follows
+    Following {
+        alias: String,
+        page: u32,
+        viewing_key: Option<String>,
+    },
+    // Whether the alias is registered now and whether it has been released before
+    History {
+        alias: String,
+    },
+    // Readable with the admin's viewing key
+    Metrics {
+        viewing_key: String,
+    },
+    // The nonce the address's next sponsored Create or Transfer has to be signed with
+    Nonce {
+        address: HumanAddr,
+    },
+    Offers {
+        alias: String,
+    },
+    // Readable with the viewing key of the owner or a viewer given as address, or otherwise with
+    // one of the alias's viewing keys
+    Note {
+        alias: String,
+        address: Option<HumanAddr>,
+        viewing_key: String,
+    },
+    // A "pay this alias" uri that follows the alias's target and payment preference
+    PaymentUri {
+        alias: String,
+        amount: Option<Uint128>,
+    },
+    PendingAction {
+        alias: String,
+    },
+    // What creating the alias costs. Fails if the alias can't be created. Queries don't know the
+    // block height, so pass the current one to have a running promotion applied. Registrations
+    // don't expire, so the only thing bought for a duration is a lease: give periods to quote a
+    // Lease of that many lease periods instead.
+    Quote {
+        alias: String,
+        height: Option<u64>,
+        periods: Option<u16>,
+    },
+    // Admin handles waiting out the timelock, so users can see changes coming
+    QueuedActions {},
+    Recent {
+        limit: u32,
+    },
+    // The reclaim auction running for the alias, if any
+    Reclaim {
+        alias: String,
+    },
+    ResolveAddresses {
+        addresses: Vec<HumanAddr>,
+    },
+    // Same as ResolveAddresses for addresses that are already canonical address bytes
+    ResolveCanonicalAddresses {
+        addresses: Vec<Binary>,
+    },
+    Roles {},
     Search {
         search_type: String,
         search_value: String,
+        include_metadata: Option<bool>,
+        // Needed to search by the address of an alias with private reverse lookup
+        viewing_key: Option<String>,
     },
+    Status {},
+    // Readable with the owner's viewing key
+    TipJar {
+        alias: String,
+        viewing_key: String,
+    },
+    Validate {
+        alias: String,
+    },
+    // Whether signature is the alias's signing key signing the challenge, for signing in as the
+    // alias off chain. The challenge should be fresh and name the service so it can't be replayed.
+    VerifySignIn {
+        alias: String,
+        challenge: Binary,
+        signature: Binary,
+    },
+}
+
+// Query sent to the price oracle
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum PriceOracleQueryMsg {
+    Price { symbol: String },
+}
+impl Query for PriceOracleQueryMsg {
+    const BLOCK_SIZE: usize = BLOCK_SIZE;
 }
 
 #[derive(Serialize, Deserialize, JsonSchema, Debug)]
 #[serde(rename_all = "snake_case")]
 pub enum ReceiveAnswer {
+    ApplyForAlias { status: ResponseStatus },
+    BidReclaim { status: ResponseStatus },
+    BuyDrop { status: ResponseStatus },
     Create { status: ResponseStatus },
+    CreateGift { status: ResponseStatus },
+    // The alias that was registered, which is a numbered variant when the one asked for was taken
+    CreateWithFallback { alias: String },
+    DefendReclaim { status: ResponseStatus },
+    FundEscrow { status: ResponseStatus },
+    Lease { status: ResponseStatus },
+    MakeOffer { status: ResponseStatus },
+    Rename { status: ResponseStatus },
+    RenewLease { status: ResponseStatus },
+    SendToAlias { status: ResponseStatus },
+    SponsoredCreate { status: ResponseStatus },
+    Tip { status: ResponseStatus },
 }
 
 #[derive(Serialize, Deserialize, JsonSchema, Debug)]
 #[serde(rename_all = "snake_case")]
 pub enum ReceiveMsg {
+    // Apply for an alias in the lottery with the fee, which is held until FinalizeLottery
+    ApplyForAlias {
+        alias: String,
+    },
+    // Bid Buttcoin in the alias's reclaim auction. The bid must beat the highest bid, which is
+    // refunded. Bidders can't already have an alias.
+    BidReclaim {
+        alias: String,
+    },
+    // Buy a dropped alias in Buttcoin at its current price. Anything sent over the price is
+    // refunded.
+    BuyDrop {
+        alias: String,
+    },
+    // Also accepted as "register" so wallets can pay and register in one transfer
+    #[serde(alias = "register")]
     Create {
         alias: String,
         avatar_url: Option<String>,
+        avatar_metadata: Option<ImageMetadata>,
+        // Saved along with the alias so a new profile can be filled in with one transaction
+        records: Option<Vec<Record>>,
+        // Registers the first available of alias2, alias3 and so on when the alias is taken
+        fallback: Option<bool>,
+    },
+    // Pays for an alias that is held for whoever has the code hashed to code_hash (sha256)
+    CreateGift {
+        alias: String,
+        code_hash: Binary,
+    },
+    // Create at the fee less the discount at index discount in the discount table. proof shows
+    // that sha256 of the from address is in the discount's snapshot. Answered with
+    // ReceiveAnswer::Create.
+    CreateWithDiscount {
+        alias: String,
+        avatar_url: Option<String>,
+        avatar_metadata: Option<ImageMetadata>,
+        records: Option<Vec<Record>>,
+        discount: u8,
+        proof: Vec<Binary>,
+    },
+    // Create during a mint phase. proof shows that sha256 of the from address is in the phase's
+    // allowlist. Answered with ReceiveAnswer::Create.
+    CreateWithProof {
+        alias: String,
+        avatar_url: Option<String>,
+        avatar_metadata: Option<ImageMetadata>,
+        records: Option<Vec<Record>>,
+        proof: Vec<Binary>,
+    },
+    // The owner keeps an alias being reclaimed by paying the highest bid in Buttcoin after bidding
+    // has ended. The bid is refunded and the payment is kept as a fee.
+    DefendReclaim {
+        alias: String,
+    },
+    // Moves the alias to a new name with its profile, history and lease
+    Rename {
+        from: String,
+        to: String,
+    },
+    // Pay for an alias in escrow with Buttcoin or a routable token
+    FundEscrow {
+        alias: String,
+    },
+    // Lease an alias for periods periods at the lease terms, paying fee * periods in Buttcoin
+    // Once the lease runs out anyone can register the alias, and its tips go to the holder
+    Lease {
+        alias: String,
+        periods: u16,
+    },
+    // Bid the received tokens for an alias
+    MakeOffer {
+        alias: String,
     },
+    // Extend a lease that hasn't run out by periods periods, paying its fee * periods in
+    // Buttcoin. Anyone can pay.
+    RenewLease {
+        alias: String,
+        periods: u16,
+    },
+    // Forward the received tokens to the address the alias resolves to
+    SendToAlias {
+        alias: String,
+    },
+    // Pay for an alias for the signer, who signed "Create <alias> with nonce <nonce> on
+    // <contract address>"
+    SponsoredCreate {
+        alias: String,
+        sponsorship: Sponsorship,
+    },
+    // Hold the received tokens in the alias's tip jar for its owner to withdraw
+    Tip {
+        alias: String,
+        message: Option<String>,
+    },
+}
+
+// Handle msg sent to a contract that is transferred an alias with a msg
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ReceiverHandleMsg {
+    ReceiveAlias {
+        alias: String,
+        from: HumanAddr,
+        msg: Binary,
+    },
+}
+impl HandleCallback for ReceiverHandleMsg {
+    const BLOCK_SIZE: usize = BLOCK_SIZE;
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ResolveResult {
+    Found { alias: String, display_name: String },
+    Invalid,
+    NotFound,
 }
 
 #[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
@@ -0,0 +1,259 @@
+use crate::contract::{AMOUNT_FOR_TRANSACTION, BLOCK_SIZE, BUTTCOIN_DECIMALS};
+use crate::msg::{PriceOracleQueryMsg, PriceResponse};
+use crate::state::{Asset, Config, FeeRecipient, PriceOracle, Promotion, SecretContract};
+use cosmwasm_std::{
+    BankMsg, Coin, CosmosMsg, Env, HandleResponse, HumanAddr, Querier, StdError, StdResult, Uint128,
+};
+use secret_toolkit::snip20;
+use secret_toolkit::utils::Query;
+
+// A fee that has been checked against what it should be and can be passed on
+pub struct Payment {
+    pub asset: Asset,
+    pub amount: Uint128,
+}
+
+// The fee in Buttcoin is AMOUNT_FOR_TRANSACTION unless it is priced in USD by a price oracle.
+// Handles pass the block time so a stale price is refused. Queries have no block time and quote
+// the latest price.
+pub fn fee_amount<Q: Querier>(
+    querier: &Q,
+    config: &Config,
+    time: Option<u64>,
+) -> StdResult<Uint128> {
+    let price_oracle: &PriceOracle = match &config.price_oracle {
+        Some(price_oracle) => price_oracle,
+        None => return Ok(Uint128(AMOUNT_FOR_TRANSACTION)),
+    };
+    let price: PriceResponse = PriceOracleQueryMsg::Price {
+        symbol: price_oracle.symbol.clone(),
+    }
+    .query(
+        querier,
+        price_oracle.contract.contract_hash.clone(),
+        price_oracle.contract.address.clone(),
+    )?;
+    if let Some(time) = time {
+        if time.saturating_sub(price.last_updated) > price_oracle.max_staleness {
+            return Err(StdError::generic_err("Price oracle rate is stale"));
+        }
+    }
+    let rate: u128 = price.rate.u128();
+    if rate == 0 {
+        return Err(StdError::generic_err("Price oracle rate is invalid"));
+    }
+
+    let usd_fee: u128 = price_oracle
+        .usd_fee
+        .u128()
+        .checked_mul(10u128.pow(BUTTCOIN_DECIMALS))
+        .ok_or_else(|| StdError::generic_err("USD fee is too large"))?;
+    // Round up so the fee never comes to less than the USD fee
+    let fee: u128 = usd_fee / rate + if usd_fee % rate == 0 { 0 } else { 1 };
+    Ok(Uint128(fee))
+}
+
+// A fee sent with a SNIP-20 Receive, in Buttcoin or one of the accepted tokens
+pub fn receive_payment<Q: Querier>(
+    querier: &Q,
+    config: &Config,
+    env: &Env,
+    amount: Uint128,
+) -> StdResult<Payment> {
+    discounted_receive_payment(querier, config, env, amount, 0)
+}
+
+// receive_payment with percent_off taken off the fee, on top of any running promotion, rounded in
+// the payer's favour
+pub fn discounted_receive_payment<Q: Querier>(
+    querier: &Q,
+    config: &Config,
+    env: &Env,
+    amount: Uint128,
+    percent_off: u8,
+) -> StdResult<Payment> {
+    let (token, fee): (SecretContract, Uint128) = if env.message.sender == config.buttcoin.address {
+        (
+            config.buttcoin.clone(),
+            fee_amount(querier, config, Some(env.block.time))?,
+        )
+    } else {
+        config
+            .accepted_payments
+            .iter()
+            .find_map(|accepted_payment| match &accepted_payment.asset {
+                Asset::Snip20 { token } if token.address == env.message.sender => {
+                    Some((token.clone(), accepted_payment.fee))
+                }
+                _ => None,
+            })
+            .ok_or(StdError::Unauthorized { backtrace: None })?
+    };
+    let fee: Uint128 = promotional_fee(config, fee, env.block.height)
+        .multiply_ratio(100 - u128::from(percent_off), 100u128);
+    validate_amount(amount, fee)?;
+
+    Ok(Payment {
+        asset: Asset::Snip20 { token },
+        amount,
+    })
+}
+
+// A fee sent as native coins along with the handle msg
+pub fn native_payment(config: &Config, env: &Env) -> StdResult<Payment> {
+    if env.message.sent_funds.len() != 1 {
+        return Err(StdError::generic_err(
+            "Send the fee in exactly one accepted denomination",
+        ));
+    }
+    let coin: &Coin = &env.message.sent_funds[0];
+    let fee: Uint128 = config
+        .accepted_payments
+        .iter()
+        .find_map(|accepted_payment| match &accepted_payment.asset {
+            Asset::Native { denom } if *denom == coin.denom => Some(accepted_payment.fee),
+            _ => None,
+        })
+        .ok_or_else(|| StdError::generic_err(format!("{} is not accepted", coin.denom)))?;
+    validate_amount(coin.amount, promotional_fee(config, fee, env.block.height))?;
+
+    Ok(Payment {
+        asset: Asset::Native {
+            denom: coin.denom.clone(),
+        },
+        amount: coin.amount,
+    })
+}
+
+// The promotion running at height, if any
+pub fn active_promotion(config: &Config, height: u64) -> Option<&Promotion> {
+    config
+        .promotions
+        .iter()
+        .find(|promotion| promotion.start_height <= height && height < promotion.end_height)
+}
+
+// fee with the promotion running at height taken off, rounded in the payer's favour
+pub fn promotional_fee(config: &Config, fee: Uint128, height: u64) -> Uint128 {
+    match active_promotion(config, height) {
+        Some(promotion) => fee.multiply_ratio(100 - u128::from(promotion.percent_off), 100u128),
+        None => fee,
+    }
+}
+
+// A fee that is only accepted in Buttcoin, such as a mint phase's fee or a lease payment
+pub fn buttcoin_payment(
+    config: &Config,
+    env: &Env,
+    amount: Uint128,
+    fee: Uint128,
+) -> StdResult<Payment> {
+    if env.message.sender != config.buttcoin.address {
+        return Err(StdError::generic_err("This fee is paid in Buttcoin"));
+    }
+    validate_amount(amount, fee)?;
+
+    Ok(Payment {
+        asset: Asset::Snip20 {
+            token: config.buttcoin.clone(),
+        },
+        amount,
+    })
+}
+
+fn validate_amount(amount: Uint128, fee: Uint128) -> StdResult<()> {
+    if amount != fee {
+        return Err(StdError::generic_err(format!(
+            "Amount sent in: {}. Amount required {}.",
+            amount, fee
+        )));
+    }
+
+    Ok(())
+}
+
+// Fees are passed on to the Butt Lode, or shared out by the fee splits. What rounding leaves over
+// goes to the first split.
+pub fn forward_payment(
+    mut response: HandleResponse,
+    config: Config,
+    contract_address: HumanAddr,
+    payment: Payment,
+) -> StdResult<HandleResponse> {
+    if config.fee_splits.is_empty() {
+        response.messages.push(send_asset_msg(
+            contract_address,
+            config.butt_lode.address,
+            payment.asset,
+            payment.amount,
+        )?);
+        return Ok(response);
+    }
+
+    let total_weight: u128 = config
+        .fee_splits
+        .iter()
+        .map(|fee_split| u128::from(fee_split.weight))
+        .sum();
+    let mut shares: Vec<Uint128> = config
+        .fee_splits
+        .iter()
+        .map(|fee_split| {
+            payment
+                .amount
+                .multiply_ratio(fee_split.weight, total_weight)
+        })
+        .collect();
+    let shared: u128 = shares.iter().map(|share| share.u128()).sum();
+    shares[0] = Uint128(shares[0].u128() + payment.amount.u128() - shared);
+    for (fee_split, share) in config.fee_splits.into_iter().zip(shares) {
+        if share.is_zero() {
+            continue;
+        }
+        let msg: CosmosMsg = match (fee_split.recipient, &payment.asset) {
+            (FeeRecipient::Address { address }, _) => send_asset_msg(
+                contract_address.clone(),
+                address,
+                payment.asset.clone(),
+                share,
+            )?,
+            (FeeRecipient::Burn, Asset::Snip20 { token }) => snip20::burn_msg(
+                share,
+                None,
+                BLOCK_SIZE,
+                token.contract_hash.clone(),
+                token.address.clone(),
+            )?,
+            // try_set_fee_splits keeps Burn to fees paid in Buttcoin
+            (FeeRecipient::Burn, Asset::Native { .. }) => {
+                return Err(StdError::generic_err("Native coins can't be burnt"))
+            }
+        };
+        response.messages.push(msg);
+    }
+    Ok(response)
+}
+
+// Sends an amount of an asset held by this contract
+pub fn send_asset_msg(
+    contract_address: HumanAddr,
+    recipient: HumanAddr,
+    asset: Asset,
+    amount: Uint128,
+) -> StdResult<CosmosMsg> {
+    match asset {
+        Asset::Native { denom } => Ok(CosmosMsg::Bank(BankMsg::Send {
+            from_address: contract_address,
+            to_address: recipient,
+            amount: vec![Coin { denom, amount }],
+        })),
+        Asset::Snip20 { token } => snip20::transfer_msg(
+            recipient,
+            amount,
+            None,
+            BLOCK_SIZE,
+            token.contract_hash,
+            token.address,
+        ),
+    }
+}
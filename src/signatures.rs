@@ -0,0 +1,92 @@
+use cosmwasm_std::Binary;
+use k256::ecdsa::signature::Verifier;
+use k256::ecdsa::{recoverable, Signature, VerifyingKey};
+use k256::elliptic_curve::sec1::ToEncodedPoint;
+use ripemd160::{Digest, Ripemd160};
+use secret_toolkit::crypto::sha_256;
+use sha3::Keccak256;
+use std::convert::TryFrom;
+
+pub const ETHEREUM_SIGNATURE_LENGTH: usize = 65;
+pub const PUBLIC_KEY_LENGTH: usize = 33;
+
+// A compressed secp256k1 public key, as wallets export them
+pub fn is_valid_public_key(public_key: &[u8]) -> bool {
+    public_key.len() == PUBLIC_KEY_LENGTH && VerifyingKey::from_sec1_bytes(public_key).is_ok()
+}
+
+// The address bytes cosmos chains derive from a public key, ripemd160(sha256(public key))
+pub fn address_bytes(public_key: &[u8]) -> Vec<u8> {
+    Ripemd160::digest(&sha_256(public_key)).to_vec()
+}
+
+// The amino JSON sign doc ADR-36 wraps arbitrary data in, which is what wallets sign for
+// signArbitrary. The signer is a bech32 address so it never needs escaping.
+pub fn adr36_sign_doc(signer: &str, data: &[u8]) -> Vec<u8> {
+    format!(
+        concat!(
+            r#"{{"account_number":"0","chain_id":"","fee":{{"amount":[],"gas":"0"}},"memo":"","#,
+            r#""msgs":[{{"type":"sign/MsgSignData","value":{{"data":"{}","signer":"{}"}}}}],"#,
+            r#""sequence":"0"}}"#
+        ),
+        Binary(data.to_vec()).to_base64(),
+        signer
+    )
+    .into_bytes()
+}
+
+// The lowercase 0x prefixed hex address Ethereum derives from a public key
+pub fn ethereum_address(verifying_key: &VerifyingKey) -> String {
+    let public_key = verifying_key.to_encoded_point(false);
+    let hash = Keccak256::digest(&public_key.as_bytes()[1..]);
+    let hex: String = hash[12..]
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect();
+    format!("0x{}", hex)
+}
+
+// What Ethereum wallets hash and sign for personal_sign
+pub fn ethereum_signed_message(message: &[u8]) -> Vec<u8> {
+    [
+        format!("\x19Ethereum Signed Message:\n{}", message.len()).as_bytes(),
+        message,
+    ]
+    .concat()
+}
+
+// The address of whoever signed the message with personal_sign. The signature is r | s | v with
+// v either the recovery id or, as most wallets give it, the recovery id plus 27.
+pub fn recover_ethereum_address(message: &[u8], signature: &[u8]) -> Option<String> {
+    if signature.len() != ETHEREUM_SIGNATURE_LENGTH {
+        return None;
+    }
+    let recovery_id: u8 = match signature[64] {
+        0 | 27 => 0,
+        1 | 28 => 1,
+        _ => return None,
+    };
+    let signature = recoverable::Signature::new(
+        &Signature::try_from(&signature[..64]).ok()?,
+        recoverable::Id::new(recovery_id).ok()?,
+    )
+    .ok()?;
+    let digest = Keccak256::digest(&ethereum_signed_message(message));
+    let verifying_key: VerifyingKey = signature
+        .recover_verifying_key_from_digest_bytes(&digest)
+        .ok()?;
+    Some(ethereum_address(&verifying_key))
+}
+
+// The signature is the 64 byte r | s over the sha256 of the message, with a low s
+pub fn verify_signature(public_key: &[u8], message: &[u8], signature: &[u8]) -> bool {
+    let verifying_key: VerifyingKey = match VerifyingKey::from_sec1_bytes(public_key) {
+        Ok(verifying_key) => verifying_key,
+        Err(_) => return false,
+    };
+    let signature: Signature = match Signature::try_from(signature) {
+        Ok(signature) => signature,
+        Err(_) => return false,
+    };
+    verifying_key.verify(message, &signature).is_ok()
+}
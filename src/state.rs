@@ -1,25 +1,486 @@
-use cosmwasm_std::{HumanAddr, ReadonlyStorage, StdResult, Storage};
+use cosmwasm_std::{
+    Api, Binary, CanonicalAddr, HumanAddr, ReadonlyStorage, StdResult, Storage, Uint128,
+};
 use cosmwasm_storage::{PrefixedStorage, ReadonlyPrefixedStorage};
 use schemars::JsonSchema;
+use secret_toolkit::crypto::sha_256;
 use secret_toolkit::serialization::{Bincode2, Serde};
 use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 
 // === CONSTANTS ===
-pub const ADDRESSES_ALIASES_PREFIX: &[u8] = b"addresses_aliases";
-pub const ALIASES_PREFIX: &[u8] = b"aliases";
+// Prefixes carry the version of their layout. When a layout changes, the prefix is bumped and
+// entries are moved over from the previous one as they're used.
+pub const ADDRESSES_ALIASES_PREFIX: &[u8] = b"addresses_aliases_v2";
+pub const AIRDROP_CLAIMS_PREFIX: &[u8] = b"airdrop_claims_v1";
+pub const ALIASES_PREFIX: &[u8] = b"aliases_v2";
+pub const ALIAS_VERSIONS_PREFIX: &[u8] = b"alias_versions_v1";
+pub const ALIAS_VIEWING_KEYS_PREFIX: &[u8] = b"alias_viewing_keys_v1";
+pub const ATTESTATIONS_PREFIX: &[u8] = b"attestations_v1";
+pub const CHANGES_PREFIX: &[u8] = b"changes_v1";
+pub const CO_OWNERSHIPS_PREFIX: &[u8] = b"co_ownerships_v1";
+pub const CROSS_CHAIN_CLAIMS_PREFIX: &[u8] = b"cross_chain_claims_v1";
+pub const DAILY_STATS_PREFIX: &[u8] = b"daily_stats_v1";
+pub const DISCOUNT_CLAIMS_PREFIX: &[u8] = b"discount_claims_v1";
+pub const DROPS_PREFIX: &[u8] = b"drops_v1";
+pub const ENCRYPTION_KEYS_PREFIX: &[u8] = b"encryption_keys_v1";
+pub const ENDORSEMENTS_PREFIX: &[u8] = b"endorsements_v1";
+pub const ESCROWS_PREFIX: &[u8] = b"escrows_v1";
+pub const FOLLOWS_PREFIX: &[u8] = b"follows_v1";
+pub const FROZEN_ALIASES_PREFIX: &[u8] = b"frozen_aliases_v1";
+pub const GIFTS_PREFIX: &[u8] = b"gifts_v1";
+pub const IMAGES_PREFIX: &[u8] = b"images_v1";
+pub const KNOWN_CONTRACTS_PREFIX: &[u8] = b"known_contracts_v1";
+pub const LOTTERY_APPLICATIONS_PREFIX: &[u8] = b"lottery_applications_v1";
+pub const LEASES_PREFIX: &[u8] = b"leases_v1";
+pub const LINKED_ADDRESSES_PREFIX: &[u8] = b"linked_addresses_v1";
+pub const LOCKS_PREFIX: &[u8] = b"locks_v1";
+pub const MEMBERS_PREFIX: &[u8] = b"members_v1";
+pub const NONCES_PREFIX: &[u8] = b"nonces_v1";
+pub const NOTES_PREFIX: &[u8] = b"notes_v1";
+pub const OFFERS_PREFIX: &[u8] = b"offers_v1";
+pub const PAYMENT_PREFERENCES_PREFIX: &[u8] = b"payment_preferences_v1";
+pub const PENDING_ACTIONS_PREFIX: &[u8] = b"pending_actions_v1";
+pub const PREVIOUS_PROFILES_PREFIX: &[u8] = b"previous_profiles_v1";
+pub const RECLAIMS_PREFIX: &[u8] = b"reclaims_v1";
+pub const RECORDS_PREFIX: &[u8] = b"records_v1";
+pub const RELEASES_PREFIX: &[u8] = b"releases_v1";
+pub const RENOUNCED_ALIASES_PREFIX: &[u8] = b"renounced_aliases_v1";
+pub const RESERVED_ALIASES_PREFIX: &[u8] = b"reserved_aliases_v1";
+pub const SIGNING_KEYS_PREFIX: &[u8] = b"signing_keys_v1";
+pub const SUSPENSIONS_PREFIX: &[u8] = b"suspensions_v1";
+pub const TIP_JARS_PREFIX: &[u8] = b"tip_jars_v1";
+pub const TOMBSTONES_PREFIX: &[u8] = b"tombstones_v1";
+pub const V1_ADDRESSES_ALIASES_PREFIX: &[u8] = b"addresses_aliases";
+pub const V1_ALIASES_PREFIX: &[u8] = b"aliases";
+pub const VIEWING_KEYS_PREFIX: &[u8] = b"viewing_keys_v1";
 
 // === STRUCTS ===
+// An asset other than Buttcoin that fees can be paid in, with the fee in that asset
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct AcceptedPayment {
+    pub asset: Asset,
+    pub fee: Uint128,
+}
+
+// An address granted an admin role, on top of the admin that instantiated the contract
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct AdminGrant {
+    pub address: HumanAddr,
+    pub role: AdminRole,
+}
+
+// AdminGrant as kept in config, with the canonical address
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct StoredAdminGrant {
+    pub address: CanonicalAddr,
+    pub role: AdminRole,
+}
+impl StoredAdminGrant {
+    pub fn into_humanized<A: Api>(self, api: &A) -> StdResult<AdminGrant> {
+        Ok(AdminGrant {
+            address: api.human_address(&self.address)?,
+            role: self.role,
+        })
+    }
+}
+
+// Bincode2 isn't self-describing, so new fields go at the end and the previous layout is kept as
+// a legacy struct that ReadonlyAliasesStorageImpl falls back to.
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
 pub struct Alias {
-    pub human_address: HumanAddr,
+    // Aliases are stored under a hash, so keep the normalized alias to look the alias up by
+    pub alias: String,
+    // The alias as its owner wants it shown, e.g. with capitals
+    pub display_name: String,
+    pub owner: CanonicalAddr,
     pub avatar_url: Option<String>,
+    pub avatar_metadata: Option<ImageMetadata>,
+    pub created_at: u64,
+    pub updated_at: u64,
+    // Another alias that this alias resolves through, CNAME style
+    pub target: Option<String>,
+    // Resolve unregistered subaliases such as "x.alice" to this alias
+    pub wildcard: bool,
+    // Only reveal this alias from its address to callers holding that address's viewing key or
+    // one of the alias's viewing keys
+    pub private_reverse_lookup: bool,
+    pub banner_url: Option<String>,
+    // Hex color such as #ff8800
+    pub accent_color: Option<String>,
+}
+
+// What an alias resolved to from block_height on. owner is None while the alias isn't registered.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct AliasVersion {
+    pub version: u64,
+    pub owner: Option<HumanAddr>,
+    pub target: Option<String>,
+    pub block_height: u64,
+}
+
+// A viewing key the owner minted for one alias, so it can be shared without exposing the
+// owner's own viewing key
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct AliasViewingKey {
+    pub label: String,
+    pub hashed_key: Vec<u8>,
+}
+
+// An external identity claim (e.g. a Twitter handle) vouched for by an oracle
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Attestation {
+    pub platform: String,
+    pub handle: String,
+    pub oracle: HumanAddr,
+    pub block_height: u64,
+}
+
+// The most inputs each batched query accepts, so one oversized query can't run out of gas
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, JsonSchema)]
+pub struct BatchLimits {
+    pub resolve_addresses: u16,
+    pub resolve_canonical_addresses: u16,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Change {
+    pub seq: u64,
+    pub action: ChangeAction,
+    pub alias: String,
+    pub address: HumanAddr,
+    pub block_height: u64,
+}
+
+// Addresses that share control of an alias with its owner. Destroy, Transfer and changing the
+// co-owners need approvals from threshold of the owner and co-owners.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct CoOwnership {
+    pub co_owners: Vec<CanonicalAddr>,
+    pub threshold: u8,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
 pub struct Config {
+    pub accepted_payments: Vec<AcceptedPayment>,
+    // Holds every role and can't be revoked, so the contract can't be locked out of its settings
+    pub admin: CanonicalAddr,
+    pub admin_grants: Vec<StoredAdminGrant>,
+    // Root of the merkle tree of (address, alias) pairs that can be claimed for free
+    pub airdrop_merkle_root: Option<Binary>,
+    // Most versions of each alias to keep, oldest dropped first
+    pub alias_versions_kept: u16,
+    // Hosts that avatars can be served from, subdomains included. Empty allows any host.
+    pub avatar_domains: Vec<String>,
+    pub batch_limits: BatchLimits,
+    // Block height until which the admin can assign aliases to any address with ImportChunk. None
+    // once the bootstrap phase has ended, which can't be undone.
+    pub bootstrap_ends_at: Option<u64>,
     pub buttcoin: SecretContract,
     pub butt_lode: SecretContract,
+    pub case_folding: CaseFolding,
+    // The chain the contract was instantiated on, for payment uris
+    pub chain_id: String,
+    // Most changes kept in the change log, with older ones pruned. None keeps every change.
+    pub change_log_size: Option<u64>,
+    // Aliases that owners of the same name on Ethereum, such as ENS names, can claim for free
+    pub cross_chain_snapshot: Option<CrossChainSnapshot>,
+    // Fee discounts for addresses in a snapshot, such as BUTT stakers. Each address can use a
+    // discount once.
+    pub discounts: Vec<Discount>,
+    // How fees are shared out. Empty sends every fee to the Butt Lode.
+    pub fee_splits: Vec<FeeSplit>,
+    pub governance: Option<StoredGovernance>,
+    // Heights at which drops and offers open, then registration. None is open from the start.
+    pub launch: Option<Launch>,
+    // Terms that new aliases can be leased on instead of being bought. None offers no leases.
+    pub lease_terms: Option<LeaseTerms>,
+    // Block height until which contested aliases can be applied for. Registration is closed until
+    // then, and aliases with applications wait for FinalizeLottery.
+    pub lottery_ends_at: Option<u64>,
+    // Allowlisted launch phases, in order. While one is running, aliases can only be registered
+    // with a proof that the sender is on its allowlist.
+    pub mint_phases: Vec<MintPhase>,
+    pub oracles: Vec<CanonicalAddr>,
+    pub price_oracle: Option<PriceOracle>,
+    // Scheduled fee discounts, in order. Registration fees are cut while one is running.
+    pub promotions: Vec<Promotion>,
+    // When and how squatted aliases can be reclaimed. None turns reclaims off.
+    pub reclaim_terms: Option<ReclaimTerms>,
+    // New aliases must have an avatar url
+    pub require_avatar: bool,
+    pub response_block_size: u16,
+    // Tokens that can be sent to an alias through this contract, besides Buttcoin
+    pub routable_tokens: Vec<SecretContract>,
+    // Blocks that fee changes, bans and config updates wait in the queue before they can be
+    // executed. 0 applies them straight away.
+    pub timelock_blocks: u64,
+}
+
+// Root of the merkle tree of (alias, Ethereum address) pairs taken from another chain's registry,
+// and the block height at which claims close
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct CrossChainSnapshot {
+    pub merkle_root: Binary,
+    pub ends_at: u64,
+}
+
+// Activity counts for one day, where day is the block time in days since the unix epoch
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct DailyStats {
+    pub day: u64,
+    pub registrations: u64,
+    pub renewals: u64,
+    pub destroys: u64,
+}
+
+// Registration at percent_off the fee for addresses whose sha256 is a leaf of merkle_root
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Discount {
+    pub merkle_root: Binary,
+    pub percent_off: u8,
+}
+
+// A reserved alias put on sale from start_height. The price falls in a straight line from
+// start_price to end_price over decay_blocks, Dutch auction style. 0 decay_blocks is a fixed price.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Drop {
+    pub start_height: u64,
+    pub start_price: Uint128,
+    pub end_price: Uint128,
+    pub decay_blocks: u64,
+}
+
+// A public key that dApps can encrypt payloads for the alias's owner to
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct EncryptionKey {
+    pub algorithm: EncryptionKeyAlgorithm,
+    pub public_key: Binary,
+}
+
+// A sale of an alias to a set buyer, settled as soon as the buyer pays the price
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Escrow {
+    pub buyer: HumanAddr,
+    pub asset: Asset,
+    pub amount: Uint128,
+    pub opened_at: u64,
+}
+
+// A share of each fee collected, in proportion to weight out of the total of every split's weight
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct FeeSplit {
+    pub recipient: FeeRecipient,
+    pub weight: u16,
+}
+
+// Who an address follows and who follows it. Follows are kept between the owners of aliases, so
+// they carry over when an alias is renamed and are only shown while the address holds an alias.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct Follows {
+    pub following: Vec<CanonicalAddr>,
+    pub followers: Vec<CanonicalAddr>,
+    // Only list these follows for holders of the address's viewing key, and leave the address
+    // out of other addresses' lists
+    pub private: bool,
+}
+
+// The profile and records of a frozen alias can never be changed again
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct FrozenAlias {
+    pub allow_transfers: bool,
+    pub frozen_at: u64,
+}
+
+// An alias paid for and held until someone claims it with the code hashed to code_hash
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Gift {
+    pub giver: CanonicalAddr,
+    pub code_hash: Binary,
+    pub display_name: String,
+    pub created_at: u64,
+}
+
+// A DAO contract that can send the admin handles, so fees and policy can follow token holder
+// votes. An exclusive governance contract takes them over from the admins entirely.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Governance {
+    pub contract: HumanAddr,
+    pub exclusive: bool,
+}
+
+// Governance as kept in config, with the contract's canonical address
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct StoredGovernance {
+    pub contract: CanonicalAddr,
+    pub exclusive: bool,
+}
+impl StoredGovernance {
+    pub fn into_humanized<A: Api>(self, api: &A) -> StdResult<Governance> {
+        Ok(Governance {
+            contract: api.human_address(&self.contract)?,
+            exclusive: self.exclusive,
+        })
+    }
+}
+
+// A gallery image for an alias's profile page
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Image {
+    pub url: String,
+    pub metadata: Option<ImageMetadata>,
+    pub primary: bool,
+}
+
+// Lets frontends reserve layout space and skip unsupported formats without fetching the image
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ImageMetadata {
+    pub mime_type: Option<String>,
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+}
+
+// A launch goes from Paused to AuctionOnly at auctions_open_at, then to Open at
+// registration_opens_at
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Launch {
+    pub auctions_open_at: u64,
+    pub registration_opens_at: u64,
+}
+
+// A leased alias is held for fee per period_blocks and is released once paid_until passes
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Lease {
+    pub period_blocks: u64,
+    pub fee: Uint128,
+    pub paid_until: u64,
+}
+
+// Lease terms offered to new leases. Existing leases keep the terms they started on.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct LeaseTerms {
+    pub period_blocks: u64,
+    pub fee: Uint128,
+}
+
+// A secondary address whose holder proved control of it by signing an ADR-36 message
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct LinkedAddress {
+    pub address: HumanAddr,
+    pub linked_at: u64,
+}
+
+// An application for an alias in the launch lottery, with the fee held until the draw
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct LotteryApplication {
+    pub applicant: CanonicalAddr,
+    pub asset: Asset,
+    pub amount: Uint128,
+    pub applied_at: u64,
+}
+
+// Running totals for monitoring the registry. reservations counts aliases the admin has banned
+// from registration and admin_actions counts successful handles that needed an admin role.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, JsonSchema)]
+pub struct Metrics {
+    pub destroys: u64,
+    pub transfers: u64,
+    pub reservations: u64,
+    pub admin_actions: u64,
+}
+
+// A launch phase between start_height and end_height (exclusive) where registering needs a
+// merkle proof of the sender's address, at a fee in Buttcoin set for the phase
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct MintPhase {
+    // Root of the merkle tree of sha256 hashes of allowlisted addresses
+    pub merkle_root: Binary,
+    pub start_height: u64,
+    pub end_height: u64,
+    pub fee: Uint128,
+}
+
+// The mutable fields of an alias as they were before its last change, so the owner can undo it
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct PreviousProfile {
+    pub display_name: String,
+    pub avatar_url: Option<String>,
+    pub avatar_metadata: Option<ImageMetadata>,
+    pub records: Vec<Record>,
+}
+
+// Prices fees in USD, converted to Buttcoin at the oracle's rate when a fee is paid
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PriceOracle {
+    pub contract: SecretContract,
+    // Symbol of Buttcoin in the oracle
+    pub symbol: String,
+    // USD scaled by 10^18, the same scale as the oracle's rates
+    pub usd_fee: Uint128,
+    // Seconds after which an oracle rate is too old to pay a fee with
+    pub max_staleness: u64,
+}
+
+// percent_off the registration fees for everyone between start_height and end_height (exclusive)
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Promotion {
+    pub start_height: u64,
+    pub end_height: u64,
+    pub percent_off: u8,
+}
+
+// An auction for an idle alias. Bids are taken until bidding_ends_at, then the owner can keep the
+// alias by matching the highest bid until defense_ends_at. Otherwise it goes to the highest bidder.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Reclaim {
+    pub started_by: HumanAddr,
+    pub started_at: u64,
+    pub bidding_ends_at: u64,
+    pub defense_ends_at: u64,
+    pub bidder: Option<HumanAddr>,
+    pub bid: Uint128,
+}
+
+// Reclaim as kept in storage, with canonical addresses
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct StoredReclaim {
+    pub started_by: CanonicalAddr,
+    pub started_at: u64,
+    pub bidding_ends_at: u64,
+    pub defense_ends_at: u64,
+    pub bidder: Option<CanonicalAddr>,
+    pub bid: Uint128,
+}
+impl StoredReclaim {
+    pub fn into_humanized<A: Api>(self, api: &A) -> StdResult<Reclaim> {
+        Ok(Reclaim {
+            started_by: api.human_address(&self.started_by)?,
+            started_at: self.started_at,
+            bidding_ends_at: self.bidding_ends_at,
+            defense_ends_at: self.defense_ends_at,
+            bidder: self
+                .bidder
+                .map(|bidder| api.human_address(&bidder))
+                .transpose()?,
+            bid: self.bid,
+        })
+    }
+}
+
+// An alias without records or an avatar that hasn't been updated for idle_blocks can be put up
+// for a reclaim auction, with bidding_blocks of bidding and defense_blocks for the owner to match
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ReclaimTerms {
+    pub idle_blocks: u64,
+    pub bidding_blocks: u64,
+    pub defense_blocks: u64,
+}
+
+// A text or address record on an alias's profile, such as url or addr.eth
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Record {
+    pub key: String,
+    pub value: String,
 }
 
 #[derive(Serialize, Deserialize, Eq, PartialEq, Debug, Clone, JsonSchema)]
@@ -28,6 +489,468 @@ pub struct SecretContract {
     pub contract_hash: String,
 }
 
+// An alias held still by a Moderator while it is under dispute. Nobody can change it and, with
+// hide_resolution, it doesn't resolve either.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Suspension {
+    pub hide_resolution: bool,
+    pub suspended_at: u64,
+}
+
+// A tip sent to an alias, kept so the owner can see who was thanking them for what
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Tip {
+    pub asset: Asset,
+    pub amount: Uint128,
+    pub message: Option<String>,
+    pub block_height: u64,
+}
+
+// Tips held by the contract for an alias until its owner withdraws them
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, JsonSchema)]
+pub struct TipJar {
+    pub balances: Vec<TipBalance>,
+    // The most recent tips, oldest first
+    pub tips: Vec<Tip>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct TipBalance {
+    pub asset: Asset,
+    pub amount: Uint128,
+}
+
+// Left when an alias is destroyed so that only its previous owner can register it again until
+// the block height expires_at
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Tombstone {
+    pub previous_owner: CanonicalAddr,
+    pub expires_at: u64,
+}
+
+// AliasVersion as kept in storage, with the owner's canonical address
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct StoredAliasVersion {
+    pub version: u64,
+    pub owner: Option<CanonicalAddr>,
+    pub target: Option<String>,
+    pub block_height: u64,
+}
+impl StoredAliasVersion {
+    pub fn into_humanized<A: Api>(self, api: &A) -> StdResult<AliasVersion> {
+        Ok(AliasVersion {
+            version: self.version,
+            owner: self
+                .owner
+                .map(|owner| api.human_address(&owner))
+                .transpose()?,
+            target: self.target,
+            block_height: self.block_height,
+        })
+    }
+}
+
+// Attestation as kept in storage, with the oracle's canonical address
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct StoredAttestation {
+    pub platform: String,
+    pub handle: String,
+    pub oracle: CanonicalAddr,
+    pub block_height: u64,
+}
+impl StoredAttestation {
+    pub fn into_humanized<A: Api>(self, api: &A) -> StdResult<Attestation> {
+        Ok(Attestation {
+            platform: self.platform,
+            handle: self.handle,
+            oracle: api.human_address(&self.oracle)?,
+            block_height: self.block_height,
+        })
+    }
+}
+
+// Change as kept in storage, with the owner's canonical address
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct StoredChange {
+    pub seq: u64,
+    pub action: ChangeAction,
+    pub alias: String,
+    pub address: CanonicalAddr,
+    pub block_height: u64,
+}
+impl StoredChange {
+    pub fn into_humanized<A: Api>(self, api: &A) -> StdResult<Change> {
+        Ok(Change {
+            seq: self.seq,
+            action: self.action,
+            alias: self.alias,
+            address: api.human_address(&self.address)?,
+            block_height: self.block_height,
+        })
+    }
+}
+
+// Someone other than the owner who may update an organization alias's records
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Member {
+    pub address: HumanAddr,
+    pub role: Role,
+}
+
+// Escrow as kept in storage, with the buyer's canonical address
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct StoredEscrow {
+    pub buyer: CanonicalAddr,
+    pub asset: Asset,
+    pub amount: Uint128,
+    pub opened_at: u64,
+}
+impl StoredEscrow {
+    pub fn into_humanized<A: Api>(self, api: &A) -> StdResult<Escrow> {
+        Ok(Escrow {
+            buyer: api.human_address(&self.buyer)?,
+            asset: self.asset,
+            amount: self.amount,
+            opened_at: self.opened_at,
+        })
+    }
+}
+
+// An admin handle waiting out the timelock, with the HandleMsg as JSON. It's executed as if sent
+// by queued_by, so it fails if they no longer hold the role it needs.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct StoredQueuedAction {
+    pub id: u64,
+    pub msg: Binary,
+    pub queued_by: CanonicalAddr,
+    pub queued_at: u64,
+    pub executable_at: u64,
+}
+
+// Member as kept in storage, with the member's canonical address
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct StoredMember {
+    pub address: CanonicalAddr,
+    pub role: Role,
+}
+impl StoredMember {
+    pub fn into_humanized<A: Api>(self, api: &A) -> StdResult<Member> {
+        Ok(Member {
+            address: api.human_address(&self.address)?,
+            role: self.role,
+        })
+    }
+}
+
+// Private text attached to an alias, e.g. a shipping address, readable by the owner and viewers
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Note {
+    pub text: String,
+    pub viewers: Vec<CanonicalAddr>,
+}
+
+// Funds held by the contract as a bid for an alias, until the owner accepts or the bidder withdraws
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Offer {
+    pub bidder: HumanAddr,
+    pub asset: Asset,
+    pub amount: Uint128,
+    pub made_at: u64,
+    // Block time after which the offer can't be accepted
+    pub expires_at: u64,
+}
+
+// Offer as kept in storage, with the bidder's canonical address
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct StoredOffer {
+    pub bidder: CanonicalAddr,
+    pub asset: Asset,
+    pub amount: Uint128,
+    pub made_at: u64,
+    pub expires_at: u64,
+}
+impl StoredOffer {
+    pub fn into_humanized<A: Api>(self, api: &A) -> StdResult<Offer> {
+        Ok(Offer {
+            bidder: api.human_address(&self.bidder)?,
+            asset: self.asset,
+            amount: self.amount,
+            made_at: self.made_at,
+            expires_at: self.expires_at,
+        })
+    }
+}
+
+// How the owner wants to be paid, so payment UIs can prefill the token, memo and destination.
+// receiving_address is where payments to the alias should go instead of the owner.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PaymentPreference {
+    pub asset: Option<Asset>,
+    pub memo_required: bool,
+    pub receiving_address: Option<HumanAddr>,
+}
+
+// PaymentPreference as kept in storage, with the receiving address canonicalized
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct StoredPaymentPreference {
+    pub asset: Option<Asset>,
+    pub memo_required: bool,
+    pub receiving_address: Option<CanonicalAddr>,
+}
+impl StoredPaymentPreference {
+    pub fn into_humanized<A: Api>(self, api: &A) -> StdResult<PaymentPreference> {
+        Ok(PaymentPreference {
+            asset: self.asset,
+            memo_required: self.memo_required,
+            receiving_address: self
+                .receiving_address
+                .map(|address| api.human_address(&address))
+                .transpose()?,
+        })
+    }
+}
+
+// An action on a co-owned alias waiting for enough approvals
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PendingAction {
+    pub action: ApprovalAction,
+    pub approvals: Vec<HumanAddr>,
+    pub proposed_at: u64,
+}
+
+// PendingAction as kept in storage, with canonical addresses
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct StoredPendingAction {
+    pub action: StoredApprovalAction,
+    pub approvals: Vec<CanonicalAddr>,
+    pub proposed_at: u64,
+}
+impl StoredPendingAction {
+    pub fn into_humanized<A: Api>(self, api: &A) -> StdResult<PendingAction> {
+        Ok(PendingAction {
+            action: self.action.into_humanized(api)?,
+            approvals: self
+                .approvals
+                .iter()
+                .map(|approval| api.human_address(approval))
+                .collect::<StdResult<Vec<HumanAddr>>>()?,
+            proposed_at: self.proposed_at,
+        })
+    }
+}
+
+// Forwarded as ReceiveAlias to a contract that is transferred an alias
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct TransferCallback {
+    pub code_hash: String,
+    pub msg: Binary,
+}
+
+// Alias as stored under ALIASES_PREFIX before display names were added. These entries are read
+// through and saved in the current layout the next time the alias is written.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct LegacyAlias {
+    pub alias: String,
+    pub owner: CanonicalAddr,
+    pub avatar_url: Option<String>,
+    pub avatar_metadata: Option<ImageMetadata>,
+    pub created_at: u64,
+    pub updated_at: u64,
+    pub target: Option<String>,
+    pub wildcard: bool,
+    pub private_reverse_lookup: bool,
+    pub banner_url: Option<String>,
+    pub accent_color: Option<String>,
+}
+impl LegacyAlias {
+    pub fn into_alias(self) -> Alias {
+        Alias {
+            display_name: self.alias.clone(),
+            alias: self.alias,
+            owner: self.owner,
+            avatar_url: self.avatar_url,
+            avatar_metadata: self.avatar_metadata,
+            created_at: self.created_at,
+            updated_at: self.updated_at,
+            target: self.target,
+            wildcard: self.wildcard,
+            private_reverse_lookup: self.private_reverse_lookup,
+            banner_url: self.banner_url,
+            accent_color: self.accent_color,
+        }
+    }
+}
+
+// Alias as stored under V1_ALIASES_PREFIX, keyed by the raw alias
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct V1Alias {
+    pub human_address: HumanAddr,
+    pub avatar_url: Option<String>,
+}
+impl V1Alias {
+    pub fn into_alias<A: Api>(self, api: &A, alias: String) -> StdResult<Alias> {
+        Ok(Alias {
+            display_name: alias.clone(),
+            alias,
+            owner: api.canonical_address(&self.human_address)?,
+            avatar_url: self.avatar_url,
+            avatar_metadata: None,
+            created_at: 0,
+            updated_at: 0,
+            target: None,
+            wildcard: false,
+            private_reverse_lookup: false,
+            banner_url: None,
+            accent_color: None,
+        })
+    }
+}
+
+// === ENUMS ===
+// A SuperAdmin can do anything and grants roles. Moderators police which aliases can be registered
+// and Treasurers set prices and the assets fees are paid in.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum AdminRole {
+    SuperAdmin,
+    Moderator,
+    Treasurer,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ApprovalAction {
+    Destroy,
+    Freeze {
+        allow_transfers: bool,
+    },
+    Renounce,
+    SetCoOwners {
+        co_owners: Vec<HumanAddr>,
+        threshold: u8,
+    },
+    Transfer {
+        recipient: HumanAddr,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Asset {
+    Native { denom: String },
+    Snip20 { token: SecretContract },
+}
+
+// How aliases are folded to one case before they're stored or looked up
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum CaseFolding {
+    // Only A to Z are lowercased
+    Ascii,
+    // Aliases are case sensitive
+    None,
+    Unicode,
+}
+impl CaseFolding {
+    pub fn fold(self, string: &str) -> String {
+        match self {
+            CaseFolding::Ascii => string.to_ascii_lowercase(),
+            CaseFolding::None => string.to_string(),
+            CaseFolding::Unicode => string.to_lowercase(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ChangeAction {
+    Create,
+    Destroy,
+    RenameFrom,
+    RenameTo,
+    TransferFrom,
+    TransferTo,
+    Update,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum EncryptionKeyAlgorithm {
+    // A compressed 33 byte public key
+    Secp256k1,
+    // A 32 byte Montgomery u coordinate
+    X25519,
+}
+
+// Burn is only allowed while every fee is paid in Buttcoin, the one asset known to be burnable
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum FeeRecipient {
+    Address { address: HumanAddr },
+    Burn,
+}
+
+// Where a launch is at. Paused takes nothing. AuctionOnly sells reserved aliases, such as short
+// premium ones, through drops and takes offers. Open also lets anyone register.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, PartialOrd, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum LaunchStage {
+    Paused,
+    AuctionOnly,
+    Open,
+}
+
+// Both roles can update an alias's records. Admins can also manage editors. Only the owners can
+// transfer, rename or destroy the alias.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Role {
+    Admin,
+    Editor,
+}
+
+// ApprovalAction as kept in storage, with canonical addresses
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub enum StoredApprovalAction {
+    Destroy,
+    Freeze {
+        allow_transfers: bool,
+    },
+    Renounce,
+    SetCoOwners {
+        co_owners: Vec<CanonicalAddr>,
+        threshold: u8,
+    },
+    Transfer {
+        recipient: CanonicalAddr,
+        callback: Option<TransferCallback>,
+    },
+}
+impl StoredApprovalAction {
+    pub fn into_humanized<A: Api>(self, api: &A) -> StdResult<ApprovalAction> {
+        Ok(match self {
+            StoredApprovalAction::Destroy => ApprovalAction::Destroy,
+            StoredApprovalAction::Freeze { allow_transfers } => {
+                ApprovalAction::Freeze { allow_transfers }
+            }
+            StoredApprovalAction::Renounce => ApprovalAction::Renounce,
+            StoredApprovalAction::SetCoOwners {
+                co_owners,
+                threshold,
+            } => ApprovalAction::SetCoOwners {
+                co_owners: co_owners
+                    .iter()
+                    .map(|co_owner| api.human_address(co_owner))
+                    .collect::<StdResult<Vec<HumanAddr>>>()?,
+                threshold,
+            },
+            StoredApprovalAction::Transfer { recipient, .. } => ApprovalAction::Transfer {
+                recipient: api.human_address(&recipient)?,
+            },
+        })
+    }
+}
+
 // === Aliases Storage ===
 pub struct AliasesReadonlyStorage<'a, S: Storage> {
     storage: ReadonlyPrefixedStorage<'a, S>,
@@ -40,7 +963,7 @@ impl<'a, S: Storage> AliasesReadonlyStorage<'a, S> {
     }
 
     pub fn get_alias(&self, key: &[u8]) -> Option<Alias> {
-        self.as_readonly().get(key)
+        self.as_readonly().get(&alias_key(key))
     }
 
     // private
@@ -50,7 +973,8 @@ impl<'a, S: Storage> AliasesReadonlyStorage<'a, S> {
     }
 }
 
-pub struct AliasesStorage<'a, S: Storage> {
+// Writes go through RegistryStorage so the address side is kept in step
+struct AliasesStorage<'a, S: Storage> {
     storage: PrefixedStorage<'a, S>,
 }
 impl<'a, S: Storage> AliasesStorage<'a, S> {
@@ -61,15 +985,15 @@ impl<'a, S: Storage> AliasesStorage<'a, S> {
     }
 
     pub fn get_alias(&mut self, key: &[u8]) -> Option<Alias> {
-        self.as_readonly().get(key)
+        self.as_readonly().get(&alias_key(key))
     }
 
     pub fn remove_alias(&mut self, key: &[u8]) {
-        remove(&mut self.storage, &key);
+        remove(&mut self.storage, &alias_key(key));
     }
 
     pub fn set_alias(&mut self, key: &[u8], value: Alias) {
-        save(&mut self.storage, &key, &value).ok();
+        save(&mut self.storage, &alias_key(key), &value).ok();
     }
 
     // private
@@ -82,8 +1006,13 @@ impl<'a, S: Storage> AliasesStorage<'a, S> {
 struct ReadonlyAliasesStorageImpl<'a, S: ReadonlyStorage>(&'a S);
 impl<'a, S: ReadonlyStorage> ReadonlyAliasesStorageImpl<'a, S> {
     pub fn get(&self, key: &[u8]) -> Option<Alias> {
-        let alias: Option<Alias> = may_load(self.0, &key).ok().unwrap();
-        alias
+        match may_load::<Alias, S>(self.0, &key) {
+            Ok(alias) => alias,
+            Err(_) => {
+                let legacy_alias: Option<LegacyAlias> = may_load(self.0, &key).ok().unwrap();
+                legacy_alias.map(LegacyAlias::into_alias)
+            }
+        }
     }
 }
 
@@ -99,7 +1028,7 @@ impl<'a, S: Storage> AddressesAliasesReadonlyStorage<'a, S> {
         }
     }
 
-    pub fn get_alias(&self, key: &String) -> Option<Vec<u8>> {
+    pub fn get_alias(&self, key: &[u8]) -> Option<Vec<u8>> {
         self.as_readonly().get(key)
     }
 
@@ -110,7 +1039,8 @@ impl<'a, S: Storage> AddressesAliasesReadonlyStorage<'a, S> {
     }
 }
 
-pub struct AddressesAliasesStorage<'a, S: Storage> {
+// Writes go through RegistryStorage so the alias side is kept in step
+struct AddressesAliasesStorage<'a, S: Storage> {
     storage: PrefixedStorage<'a, S>,
 }
 impl<'a, S: Storage> AddressesAliasesStorage<'a, S> {
@@ -120,7 +1050,7 @@ impl<'a, S: Storage> AddressesAliasesStorage<'a, S> {
         }
     }
 
-    pub fn get_alias(&mut self, key: &String) -> Option<Vec<u8>> {
+    pub fn get_alias(&mut self, key: &[u8]) -> Option<Vec<u8>> {
         self.as_readonly().get(key)
     }
 
@@ -128,8 +1058,8 @@ impl<'a, S: Storage> AddressesAliasesStorage<'a, S> {
         remove(&mut self.storage, &key);
     }
 
-    pub fn set_alias(&mut self, key: &[u8], value: &String) {
-        save(&mut self.storage, key, value).ok();
+    pub fn set_alias(&mut self, key: &[u8], value: &[u8]) {
+        save(&mut self.storage, key, &value.to_vec()).ok();
     }
 
     // private
@@ -141,13 +1071,1748 @@ impl<'a, S: Storage> AddressesAliasesStorage<'a, S> {
 
 struct ReadonlyAddressesAliasesStorageImpl<'a, S: ReadonlyStorage>(&'a S);
 impl<'a, S: ReadonlyStorage> ReadonlyAddressesAliasesStorageImpl<'a, S> {
-    pub fn get(&self, key: &String) -> Option<Vec<u8>> {
-        let alias: Option<Vec<u8>> = may_load(self.0, &key.as_bytes()).ok().unwrap();
+    pub fn get(&self, key: &[u8]) -> Option<Vec<u8>> {
+        let alias: Option<Vec<u8>> = may_load(self.0, key).ok().unwrap();
         alias
     }
 }
 
-// === FUNCTIONS ===
+// === Members Storage ===
+
+pub struct MembersReadonlyStorage<'a, S: Storage> {
+    storage: ReadonlyPrefixedStorage<'a, S>,
+}
+impl<'a, S: Storage> MembersReadonlyStorage<'a, S> {
+    pub fn from_storage(storage: &'a S) -> Self {
+        Self {
+            storage: ReadonlyPrefixedStorage::new(MEMBERS_PREFIX, storage),
+        }
+    }
+
+    pub fn get_members(&self, key: &[u8]) -> Vec<StoredMember> {
+        may_load(&self.storage, &alias_key(key))
+            .ok()
+            .unwrap()
+            .unwrap_or_default()
+    }
+}
+
+pub struct MembersStorage<'a, S: Storage> {
+    storage: PrefixedStorage<'a, S>,
+}
+impl<'a, S: Storage> MembersStorage<'a, S> {
+    pub fn from_storage(storage: &'a mut S) -> Self {
+        Self {
+            storage: PrefixedStorage::new(MEMBERS_PREFIX, storage),
+        }
+    }
+
+    pub fn get_members(&self, key: &[u8]) -> Vec<StoredMember> {
+        may_load(&self.storage, &alias_key(key))
+            .ok()
+            .unwrap()
+            .unwrap_or_default()
+    }
+
+    pub fn remove_members(&mut self, key: &[u8]) {
+        remove(&mut self.storage, &alias_key(key));
+    }
+
+    pub fn set_members(&mut self, key: &[u8], value: &[StoredMember]) {
+        save(&mut self.storage, &alias_key(key), &value).ok();
+    }
+}
+
+// === Notes Storage ===
+
+pub struct NotesReadonlyStorage<'a, S: Storage> {
+    storage: ReadonlyPrefixedStorage<'a, S>,
+}
+impl<'a, S: Storage> NotesReadonlyStorage<'a, S> {
+    pub fn from_storage(storage: &'a S) -> Self {
+        Self {
+            storage: ReadonlyPrefixedStorage::new(NOTES_PREFIX, storage),
+        }
+    }
+
+    pub fn get_note(&self, key: &[u8]) -> Option<Note> {
+        may_load(&self.storage, &alias_key(key)).ok().unwrap()
+    }
+}
+
+pub struct NotesStorage<'a, S: Storage> {
+    storage: PrefixedStorage<'a, S>,
+}
+impl<'a, S: Storage> NotesStorage<'a, S> {
+    pub fn from_storage(storage: &'a mut S) -> Self {
+        Self {
+            storage: PrefixedStorage::new(NOTES_PREFIX, storage),
+        }
+    }
+
+    pub fn get_note(&self, key: &[u8]) -> Option<Note> {
+        may_load(&self.storage, &alias_key(key)).ok().unwrap()
+    }
+
+    pub fn remove_note(&mut self, key: &[u8]) {
+        remove(&mut self.storage, &alias_key(key));
+    }
+
+    pub fn set_note(&mut self, key: &[u8], value: &Note) {
+        save(&mut self.storage, &alias_key(key), value).ok();
+    }
+}
+
+// === Registry Storage ===
+// The alias -> Alias and address -> alias key maps, where the owner of each Alias decides the
+// address entry that points back at it. Entries still in the v1 namespaces are read through and
+// moved across the first time they're loaded for a write.
+
+pub struct RegistryReadonlyStorage<'a, S: Storage, A: Api> {
+    storage: &'a S,
+    api: &'a A,
+}
+impl<'a, S: Storage, A: Api> RegistryReadonlyStorage<'a, S, A> {
+    pub fn from_storage(storage: &'a S, api: &'a A) -> Self {
+        Self { storage, api }
+    }
+
+    pub fn get_alias(&self, key: &[u8]) -> Option<Alias> {
+        AliasesReadonlyStorage::from_storage(self.storage)
+            .get_alias(key)
+            .or_else(|| self.get_v1_alias(key))
+    }
+
+    pub fn get_alias_for_address(&self, address: &CanonicalAddr) -> Option<Alias> {
+        match AddressesAliasesReadonlyStorage::from_storage(self.storage)
+            .get_alias(address.as_slice())
+        {
+            Some(key) => ReadonlyAliasesStorageImpl(&ReadonlyPrefixedStorage::new(
+                ALIASES_PREFIX,
+                self.storage,
+            ))
+            .get(&key),
+            None => {
+                let human_address: HumanAddr = self.api.human_address(address).ok()?;
+                let key: Vec<u8> = may_load(
+                    &ReadonlyPrefixedStorage::new(V1_ADDRESSES_ALIASES_PREFIX, self.storage),
+                    human_address.0.as_bytes(),
+                )
+                .ok()??;
+                self.get_alias(&key)
+            }
+        }
+    }
+
+    // private
+
+    fn get_v1_alias(&self, key: &[u8]) -> Option<Alias> {
+        let v1_alias: V1Alias = may_load(
+            &ReadonlyPrefixedStorage::new(V1_ALIASES_PREFIX, self.storage),
+            key,
+        )
+        .ok()??;
+        v1_alias
+            .into_alias(self.api, String::from_utf8(key.to_vec()).ok()?)
+            .ok()
+    }
+}
+
+pub struct RegistryStorage<'a, S: Storage, A: Api> {
+    storage: &'a mut S,
+    api: &'a A,
+}
+impl<'a, S: Storage, A: Api> RegistryStorage<'a, S, A> {
+    pub fn from_storage(storage: &'a mut S, api: &'a A) -> Self {
+        Self { storage, api }
+    }
+
+    pub fn get_alias(&mut self, key: &[u8]) -> Option<Alias> {
+        let alias: Option<Alias> = AliasesStorage::from_storage(self.storage).get_alias(key);
+        if alias.is_some() {
+            return alias;
+        }
+
+        let alias: Alias = self.as_readonly().get_v1_alias(key)?;
+        self.set_alias(key, alias.clone());
+        Some(alias)
+    }
+
+    pub fn get_alias_for_address(&mut self, address: &CanonicalAddr) -> Option<Alias> {
+        self.as_readonly().get_alias_for_address(address)
+    }
+
+    pub fn remove_alias(&mut self, key: &[u8]) {
+        if let Some(alias) = self.get_alias(key) {
+            self.remove_address_entry(&alias.owner, key);
+        }
+        AliasesStorage::from_storage(self.storage).remove_alias(key);
+    }
+
+    pub fn set_alias(&mut self, key: &[u8], value: Alias) {
+        if let Some(alias) = AliasesStorage::from_storage(self.storage).get_alias(key) {
+            if alias.owner != value.owner {
+                self.remove_address_entry(&alias.owner, key);
+            }
+        }
+        self.remove_v1_alias(key);
+        AddressesAliasesStorage::from_storage(self.storage)
+            .set_alias(value.owner.as_slice(), &alias_key(key));
+        AliasesStorage::from_storage(self.storage).set_alias(key, value);
+    }
+
+    // private
+
+    fn as_readonly(&self) -> RegistryReadonlyStorage<S, A> {
+        RegistryReadonlyStorage::from_storage(self.storage, self.api)
+    }
+
+    // Only drop the address entry if it still points at this alias
+    fn remove_address_entry(&mut self, owner: &CanonicalAddr, key: &[u8]) {
+        let mut addresses_aliases_storage = AddressesAliasesStorage::from_storage(self.storage);
+        if addresses_aliases_storage.get_alias(owner.as_slice()) == Some(alias_key(key).to_vec()) {
+            addresses_aliases_storage.remove_alias(owner.as_slice());
+        }
+    }
+
+    fn remove_v1_alias(&mut self, key: &[u8]) {
+        let mut v1_aliases_storage = PrefixedStorage::new(V1_ALIASES_PREFIX, self.storage);
+        let v1_alias: Option<V1Alias> = may_load(&v1_aliases_storage, key).ok().unwrap();
+        if v1_alias.is_none() {
+            return;
+        }
+        remove(&mut v1_aliases_storage, key);
+
+        let human_address: HumanAddr = v1_alias.unwrap().human_address;
+        let mut v1_addresses_aliases_storage =
+            PrefixedStorage::new(V1_ADDRESSES_ALIASES_PREFIX, self.storage);
+        let v1_key: Option<Vec<u8>> =
+            may_load(&v1_addresses_aliases_storage, human_address.0.as_bytes())
+                .ok()
+                .unwrap();
+        if v1_key == Some(key.to_vec()) {
+            remove(
+                &mut v1_addresses_aliases_storage,
+                human_address.0.as_bytes(),
+            );
+        }
+    }
+}
+
+// === AirdropClaims Storage ===
+
+pub struct AirdropClaimsReadonlyStorage<'a, S: Storage> {
+    storage: ReadonlyPrefixedStorage<'a, S>,
+}
+impl<'a, S: Storage> AirdropClaimsReadonlyStorage<'a, S> {
+    pub fn from_storage(storage: &'a S) -> Self {
+        Self {
+            storage: ReadonlyPrefixedStorage::new(AIRDROP_CLAIMS_PREFIX, storage),
+        }
+    }
+
+    pub fn is_claimed(&self, leaf: &[u8]) -> bool {
+        self.storage.get(leaf).is_some()
+    }
+}
+
+pub struct AirdropClaimsStorage<'a, S: Storage> {
+    storage: PrefixedStorage<'a, S>,
+}
+impl<'a, S: Storage> AirdropClaimsStorage<'a, S> {
+    pub fn from_storage(storage: &'a mut S) -> Self {
+        Self {
+            storage: PrefixedStorage::new(AIRDROP_CLAIMS_PREFIX, storage),
+        }
+    }
+
+    pub fn set_claimed(&mut self, leaf: &[u8]) {
+        self.storage.set(leaf, &[1]);
+    }
+}
+
+// === AliasVersions Storage ===
+// Kept after an alias is destroyed, so its earlier owners can still be looked up, and moved with
+// it when it's renamed
+
+pub struct AliasVersionsReadonlyStorage<'a, S: Storage> {
+    storage: ReadonlyPrefixedStorage<'a, S>,
+}
+impl<'a, S: Storage> AliasVersionsReadonlyStorage<'a, S> {
+    pub fn from_storage(storage: &'a S) -> Self {
+        Self {
+            storage: ReadonlyPrefixedStorage::new(ALIAS_VERSIONS_PREFIX, storage),
+        }
+    }
+
+    pub fn get_versions(&self, key: &[u8]) -> Vec<StoredAliasVersion> {
+        may_load(&self.storage, &alias_key(key))
+            .ok()
+            .unwrap()
+            .unwrap_or_default()
+    }
+}
+
+pub struct AliasVersionsStorage<'a, S: Storage> {
+    storage: PrefixedStorage<'a, S>,
+}
+impl<'a, S: Storage> AliasVersionsStorage<'a, S> {
+    pub fn from_storage(storage: &'a mut S) -> Self {
+        Self {
+            storage: PrefixedStorage::new(ALIAS_VERSIONS_PREFIX, storage),
+        }
+    }
+
+    pub fn get_versions(&self, key: &[u8]) -> Vec<StoredAliasVersion> {
+        may_load(&self.storage, &alias_key(key))
+            .ok()
+            .unwrap()
+            .unwrap_or_default()
+    }
+
+    pub fn remove_versions(&mut self, key: &[u8]) {
+        remove(&mut self.storage, &alias_key(key));
+    }
+
+    pub fn set_versions(&mut self, key: &[u8], value: &[StoredAliasVersion]) {
+        save(&mut self.storage, &alias_key(key), &value).ok();
+    }
+}
+
+// === AliasViewingKeys Storage ===
+
+pub struct AliasViewingKeysReadonlyStorage<'a, S: Storage> {
+    storage: ReadonlyPrefixedStorage<'a, S>,
+}
+impl<'a, S: Storage> AliasViewingKeysReadonlyStorage<'a, S> {
+    pub fn from_storage(storage: &'a S) -> Self {
+        Self {
+            storage: ReadonlyPrefixedStorage::new(ALIAS_VIEWING_KEYS_PREFIX, storage),
+        }
+    }
+
+    pub fn get_alias_viewing_keys(&self, key: &[u8]) -> Vec<AliasViewingKey> {
+        may_load(&self.storage, &alias_key(key))
+            .ok()
+            .unwrap()
+            .unwrap_or_default()
+    }
+}
+
+pub struct AliasViewingKeysStorage<'a, S: Storage> {
+    storage: PrefixedStorage<'a, S>,
+}
+impl<'a, S: Storage> AliasViewingKeysStorage<'a, S> {
+    pub fn from_storage(storage: &'a mut S) -> Self {
+        Self {
+            storage: PrefixedStorage::new(ALIAS_VIEWING_KEYS_PREFIX, storage),
+        }
+    }
+
+    pub fn get_alias_viewing_keys(&self, key: &[u8]) -> Vec<AliasViewingKey> {
+        may_load(&self.storage, &alias_key(key))
+            .ok()
+            .unwrap()
+            .unwrap_or_default()
+    }
+
+    pub fn remove_alias_viewing_keys(&mut self, key: &[u8]) {
+        remove(&mut self.storage, &alias_key(key));
+    }
+
+    pub fn set_alias_viewing_keys(&mut self, key: &[u8], value: &[AliasViewingKey]) {
+        save(&mut self.storage, &alias_key(key), &value).ok();
+    }
+}
+
+// === Attestations Storage ===
+
+pub struct AttestationsReadonlyStorage<'a, S: Storage> {
+    storage: ReadonlyPrefixedStorage<'a, S>,
+}
+impl<'a, S: Storage> AttestationsReadonlyStorage<'a, S> {
+    pub fn from_storage(storage: &'a S) -> Self {
+        Self {
+            storage: ReadonlyPrefixedStorage::new(ATTESTATIONS_PREFIX, storage),
+        }
+    }
+
+    pub fn get_attestations(&self, key: &[u8]) -> Vec<StoredAttestation> {
+        may_load(&self.storage, &alias_key(key))
+            .ok()
+            .unwrap()
+            .unwrap_or_default()
+    }
+}
+
+pub struct AttestationsStorage<'a, S: Storage> {
+    storage: PrefixedStorage<'a, S>,
+}
+impl<'a, S: Storage> AttestationsStorage<'a, S> {
+    pub fn from_storage(storage: &'a mut S) -> Self {
+        Self {
+            storage: PrefixedStorage::new(ATTESTATIONS_PREFIX, storage),
+        }
+    }
+
+    pub fn get_attestations(&self, key: &[u8]) -> Vec<StoredAttestation> {
+        may_load(&self.storage, &alias_key(key))
+            .ok()
+            .unwrap()
+            .unwrap_or_default()
+    }
+
+    pub fn remove_attestations(&mut self, key: &[u8]) {
+        remove(&mut self.storage, &alias_key(key));
+    }
+
+    pub fn set_attestations(&mut self, key: &[u8], value: &[StoredAttestation]) {
+        save(&mut self.storage, &alias_key(key), &value).ok();
+    }
+}
+
+// === Changes Storage ===
+
+pub struct ChangesReadonlyStorage<'a, S: Storage> {
+    storage: ReadonlyPrefixedStorage<'a, S>,
+}
+impl<'a, S: Storage> ChangesReadonlyStorage<'a, S> {
+    pub fn from_storage(storage: &'a S) -> Self {
+        Self {
+            storage: ReadonlyPrefixedStorage::new(CHANGES_PREFIX, storage),
+        }
+    }
+
+    pub fn get_change(&self, seq: u64) -> Option<StoredChange> {
+        may_load(&self.storage, &seq.to_be_bytes()).ok().unwrap()
+    }
+}
+
+pub struct ChangesStorage<'a, S: Storage> {
+    storage: PrefixedStorage<'a, S>,
+}
+impl<'a, S: Storage> ChangesStorage<'a, S> {
+    pub fn from_storage(storage: &'a mut S) -> Self {
+        Self {
+            storage: PrefixedStorage::new(CHANGES_PREFIX, storage),
+        }
+    }
+
+    pub fn remove_change(&mut self, seq: u64) {
+        remove(&mut self.storage, &seq.to_be_bytes());
+    }
+
+    pub fn set_change(&mut self, value: &StoredChange) {
+        save(&mut self.storage, &value.seq.to_be_bytes(), value).ok();
+    }
+}
+
+// === CoOwnerships Storage ===
+
+pub struct CoOwnershipsReadonlyStorage<'a, S: Storage> {
+    storage: ReadonlyPrefixedStorage<'a, S>,
+}
+impl<'a, S: Storage> CoOwnershipsReadonlyStorage<'a, S> {
+    pub fn from_storage(storage: &'a S) -> Self {
+        Self {
+            storage: ReadonlyPrefixedStorage::new(CO_OWNERSHIPS_PREFIX, storage),
+        }
+    }
+
+    pub fn get_co_ownership(&self, key: &[u8]) -> Option<CoOwnership> {
+        may_load(&self.storage, &alias_key(key)).ok().unwrap()
+    }
+}
+
+pub struct CoOwnershipsStorage<'a, S: Storage> {
+    storage: PrefixedStorage<'a, S>,
+}
+impl<'a, S: Storage> CoOwnershipsStorage<'a, S> {
+    pub fn from_storage(storage: &'a mut S) -> Self {
+        Self {
+            storage: PrefixedStorage::new(CO_OWNERSHIPS_PREFIX, storage),
+        }
+    }
+
+    pub fn get_co_ownership(&self, key: &[u8]) -> Option<CoOwnership> {
+        may_load(&self.storage, &alias_key(key)).ok().unwrap()
+    }
+
+    pub fn remove_co_ownership(&mut self, key: &[u8]) {
+        remove(&mut self.storage, &alias_key(key));
+    }
+
+    pub fn set_co_ownership(&mut self, key: &[u8], value: &CoOwnership) {
+        save(&mut self.storage, &alias_key(key), value).ok();
+    }
+}
+
+// === CrossChainClaims Storage ===
+
+pub struct CrossChainClaimsReadonlyStorage<'a, S: Storage> {
+    storage: ReadonlyPrefixedStorage<'a, S>,
+}
+impl<'a, S: Storage> CrossChainClaimsReadonlyStorage<'a, S> {
+    pub fn from_storage(storage: &'a S) -> Self {
+        Self {
+            storage: ReadonlyPrefixedStorage::new(CROSS_CHAIN_CLAIMS_PREFIX, storage),
+        }
+    }
+
+    pub fn is_claimed(&self, leaf: &[u8]) -> bool {
+        self.storage.get(leaf).is_some()
+    }
+}
+
+pub struct CrossChainClaimsStorage<'a, S: Storage> {
+    storage: PrefixedStorage<'a, S>,
+}
+impl<'a, S: Storage> CrossChainClaimsStorage<'a, S> {
+    pub fn from_storage(storage: &'a mut S) -> Self {
+        Self {
+            storage: PrefixedStorage::new(CROSS_CHAIN_CLAIMS_PREFIX, storage),
+        }
+    }
+
+    pub fn set_claimed(&mut self, leaf: &[u8]) {
+        self.storage.set(leaf, &[1]);
+    }
+}
+
+// === DailyStats Storage ===
+
+pub struct DailyStatsReadonlyStorage<'a, S: Storage> {
+    storage: ReadonlyPrefixedStorage<'a, S>,
+}
+impl<'a, S: Storage> DailyStatsReadonlyStorage<'a, S> {
+    pub fn from_storage(storage: &'a S) -> Self {
+        Self {
+            storage: ReadonlyPrefixedStorage::new(DAILY_STATS_PREFIX, storage),
+        }
+    }
+
+    pub fn get_daily_stats(&self, day: u64) -> Option<DailyStats> {
+        may_load(&self.storage, &day.to_be_bytes()).ok().unwrap()
+    }
+}
+
+pub struct DailyStatsStorage<'a, S: Storage> {
+    storage: PrefixedStorage<'a, S>,
+}
+impl<'a, S: Storage> DailyStatsStorage<'a, S> {
+    pub fn from_storage(storage: &'a mut S) -> Self {
+        Self {
+            storage: PrefixedStorage::new(DAILY_STATS_PREFIX, storage),
+        }
+    }
+
+    pub fn get_daily_stats(&self, day: u64) -> Option<DailyStats> {
+        may_load(&self.storage, &day.to_be_bytes()).ok().unwrap()
+    }
+
+    pub fn set_daily_stats(&mut self, value: &DailyStats) {
+        save(&mut self.storage, &value.day.to_be_bytes(), value).ok();
+    }
+}
+
+// === DiscountClaims Storage ===
+// Keyed by sha256 of the discount's merkle root and the claimant's leaf, so a new snapshot can be
+// claimed again
+pub struct DiscountClaimsReadonlyStorage<'a, S: Storage> {
+    storage: ReadonlyPrefixedStorage<'a, S>,
+}
+impl<'a, S: Storage> DiscountClaimsReadonlyStorage<'a, S> {
+    pub fn from_storage(storage: &'a S) -> Self {
+        Self {
+            storage: ReadonlyPrefixedStorage::new(DISCOUNT_CLAIMS_PREFIX, storage),
+        }
+    }
+
+    pub fn is_claimed(&self, merkle_root: &[u8], leaf: &[u8]) -> bool {
+        self.storage
+            .get(&sha_256(&[merkle_root, leaf].concat()))
+            .is_some()
+    }
+}
+
+pub struct DiscountClaimsStorage<'a, S: Storage> {
+    storage: PrefixedStorage<'a, S>,
+}
+impl<'a, S: Storage> DiscountClaimsStorage<'a, S> {
+    pub fn from_storage(storage: &'a mut S) -> Self {
+        Self {
+            storage: PrefixedStorage::new(DISCOUNT_CLAIMS_PREFIX, storage),
+        }
+    }
+
+    pub fn set_claimed(&mut self, merkle_root: &[u8], leaf: &[u8]) {
+        self.storage
+            .set(&sha_256(&[merkle_root, leaf].concat()), &[1]);
+    }
+}
+
+// === Drops Storage ===
+
+pub struct DropsReadonlyStorage<'a, S: Storage> {
+    storage: ReadonlyPrefixedStorage<'a, S>,
+}
+impl<'a, S: Storage> DropsReadonlyStorage<'a, S> {
+    pub fn from_storage(storage: &'a S) -> Self {
+        Self {
+            storage: ReadonlyPrefixedStorage::new(DROPS_PREFIX, storage),
+        }
+    }
+
+    pub fn get_drop(&self, key: &[u8]) -> Option<Drop> {
+        may_load(&self.storage, &alias_key(key)).ok().unwrap()
+    }
+}
+
+pub struct DropsStorage<'a, S: Storage> {
+    storage: PrefixedStorage<'a, S>,
+}
+impl<'a, S: Storage> DropsStorage<'a, S> {
+    pub fn from_storage(storage: &'a mut S) -> Self {
+        Self {
+            storage: PrefixedStorage::new(DROPS_PREFIX, storage),
+        }
+    }
+
+    pub fn get_drop(&self, key: &[u8]) -> Option<Drop> {
+        may_load(&self.storage, &alias_key(key)).ok().unwrap()
+    }
+
+    pub fn remove_drop(&mut self, key: &[u8]) {
+        remove(&mut self.storage, &alias_key(key));
+    }
+
+    pub fn set_drop(&mut self, key: &[u8], value: &Drop) {
+        save(&mut self.storage, &alias_key(key), value).ok();
+    }
+}
+
+// === EncryptionKeys Storage ===
+
+pub struct EncryptionKeysReadonlyStorage<'a, S: Storage> {
+    storage: ReadonlyPrefixedStorage<'a, S>,
+}
+impl<'a, S: Storage> EncryptionKeysReadonlyStorage<'a, S> {
+    pub fn from_storage(storage: &'a S) -> Self {
+        Self {
+            storage: ReadonlyPrefixedStorage::new(ENCRYPTION_KEYS_PREFIX, storage),
+        }
+    }
+
+    pub fn get_encryption_key(&self, key: &[u8]) -> Option<EncryptionKey> {
+        may_load(&self.storage, &alias_key(key)).ok().unwrap()
+    }
+}
+
+pub struct EncryptionKeysStorage<'a, S: Storage> {
+    storage: PrefixedStorage<'a, S>,
+}
+impl<'a, S: Storage> EncryptionKeysStorage<'a, S> {
+    pub fn from_storage(storage: &'a mut S) -> Self {
+        Self {
+            storage: PrefixedStorage::new(ENCRYPTION_KEYS_PREFIX, storage),
+        }
+    }
+
+    pub fn get_encryption_key(&self, key: &[u8]) -> Option<EncryptionKey> {
+        may_load(&self.storage, &alias_key(key)).ok().unwrap()
+    }
+
+    pub fn remove_encryption_key(&mut self, key: &[u8]) {
+        remove(&mut self.storage, &alias_key(key));
+    }
+
+    pub fn set_encryption_key(&mut self, key: &[u8], value: &EncryptionKey) {
+        save(&mut self.storage, &alias_key(key), value).ok();
+    }
+}
+
+// === Endorsements Storage ===
+// The addresses that endorse each alias
+
+pub struct EndorsementsReadonlyStorage<'a, S: Storage> {
+    storage: ReadonlyPrefixedStorage<'a, S>,
+}
+impl<'a, S: Storage> EndorsementsReadonlyStorage<'a, S> {
+    pub fn from_storage(storage: &'a S) -> Self {
+        Self {
+            storage: ReadonlyPrefixedStorage::new(ENDORSEMENTS_PREFIX, storage),
+        }
+    }
+
+    pub fn get_endorsers(&self, key: &[u8]) -> Vec<CanonicalAddr> {
+        may_load(&self.storage, &alias_key(key))
+            .ok()
+            .unwrap()
+            .unwrap_or_default()
+    }
+}
+
+pub struct EndorsementsStorage<'a, S: Storage> {
+    storage: PrefixedStorage<'a, S>,
+}
+impl<'a, S: Storage> EndorsementsStorage<'a, S> {
+    pub fn from_storage(storage: &'a mut S) -> Self {
+        Self {
+            storage: PrefixedStorage::new(ENDORSEMENTS_PREFIX, storage),
+        }
+    }
+
+    pub fn get_endorsers(&self, key: &[u8]) -> Vec<CanonicalAddr> {
+        may_load(&self.storage, &alias_key(key))
+            .ok()
+            .unwrap()
+            .unwrap_or_default()
+    }
+
+    pub fn remove_endorsers(&mut self, key: &[u8]) {
+        remove(&mut self.storage, &alias_key(key));
+    }
+
+    pub fn set_endorsers(&mut self, key: &[u8], value: &[CanonicalAddr]) {
+        save(&mut self.storage, &alias_key(key), &value).ok();
+    }
+}
+
+// === Escrows Storage ===
+
+pub struct EscrowsReadonlyStorage<'a, S: Storage> {
+    storage: ReadonlyPrefixedStorage<'a, S>,
+}
+impl<'a, S: Storage> EscrowsReadonlyStorage<'a, S> {
+    pub fn from_storage(storage: &'a S) -> Self {
+        Self {
+            storage: ReadonlyPrefixedStorage::new(ESCROWS_PREFIX, storage),
+        }
+    }
+
+    pub fn get_escrow(&self, key: &[u8]) -> Option<StoredEscrow> {
+        may_load(&self.storage, &alias_key(key)).ok().unwrap()
+    }
+}
+
+pub struct EscrowsStorage<'a, S: Storage> {
+    storage: PrefixedStorage<'a, S>,
+}
+impl<'a, S: Storage> EscrowsStorage<'a, S> {
+    pub fn from_storage(storage: &'a mut S) -> Self {
+        Self {
+            storage: PrefixedStorage::new(ESCROWS_PREFIX, storage),
+        }
+    }
+
+    pub fn get_escrow(&self, key: &[u8]) -> Option<StoredEscrow> {
+        may_load(&self.storage, &alias_key(key)).ok().unwrap()
+    }
+
+    pub fn remove_escrow(&mut self, key: &[u8]) {
+        remove(&mut self.storage, &alias_key(key));
+    }
+
+    pub fn set_escrow(&mut self, key: &[u8], value: &StoredEscrow) {
+        save(&mut self.storage, &alias_key(key), value).ok();
+    }
+}
+
+// === Follows Storage ===
+
+pub struct FollowsReadonlyStorage<'a, S: Storage> {
+    storage: ReadonlyPrefixedStorage<'a, S>,
+}
+impl<'a, S: Storage> FollowsReadonlyStorage<'a, S> {
+    pub fn from_storage(storage: &'a S) -> Self {
+        Self {
+            storage: ReadonlyPrefixedStorage::new(FOLLOWS_PREFIX, storage),
+        }
+    }
+
+    pub fn get_follows(&self, address: &CanonicalAddr) -> Follows {
+        may_load(&self.storage, address.as_slice())
+            .ok()
+            .unwrap()
+            .unwrap_or_default()
+    }
+}
+
+pub struct FollowsStorage<'a, S: Storage> {
+    storage: PrefixedStorage<'a, S>,
+}
+impl<'a, S: Storage> FollowsStorage<'a, S> {
+    pub fn from_storage(storage: &'a mut S) -> Self {
+        Self {
+            storage: PrefixedStorage::new(FOLLOWS_PREFIX, storage),
+        }
+    }
+
+    pub fn get_follows(&self, address: &CanonicalAddr) -> Follows {
+        may_load(&self.storage, address.as_slice())
+            .ok()
+            .unwrap()
+            .unwrap_or_default()
+    }
+
+    pub fn set_follows(&mut self, address: &CanonicalAddr, value: &Follows) {
+        save(&mut self.storage, address.as_slice(), value).ok();
+    }
+}
+
+// === FrozenAliases Storage ===
+
+pub struct FrozenAliasesReadonlyStorage<'a, S: Storage> {
+    storage: ReadonlyPrefixedStorage<'a, S>,
+}
+impl<'a, S: Storage> FrozenAliasesReadonlyStorage<'a, S> {
+    pub fn from_storage(storage: &'a S) -> Self {
+        Self {
+            storage: ReadonlyPrefixedStorage::new(FROZEN_ALIASES_PREFIX, storage),
+        }
+    }
+
+    pub fn get_frozen(&self, key: &[u8]) -> Option<FrozenAlias> {
+        may_load(&self.storage, &alias_key(key)).ok().unwrap()
+    }
+}
+
+pub struct FrozenAliasesStorage<'a, S: Storage> {
+    storage: PrefixedStorage<'a, S>,
+}
+impl<'a, S: Storage> FrozenAliasesStorage<'a, S> {
+    pub fn from_storage(storage: &'a mut S) -> Self {
+        Self {
+            storage: PrefixedStorage::new(FROZEN_ALIASES_PREFIX, storage),
+        }
+    }
+
+    pub fn remove_frozen(&mut self, key: &[u8]) {
+        remove(&mut self.storage, &alias_key(key));
+    }
+
+    pub fn set_frozen(&mut self, key: &[u8], value: &FrozenAlias) {
+        save(&mut self.storage, &alias_key(key), value).ok();
+    }
+}
+
+// === Gifts Storage ===
+
+pub struct GiftsReadonlyStorage<'a, S: Storage> {
+    storage: ReadonlyPrefixedStorage<'a, S>,
+}
+impl<'a, S: Storage> GiftsReadonlyStorage<'a, S> {
+    pub fn from_storage(storage: &'a S) -> Self {
+        Self {
+            storage: ReadonlyPrefixedStorage::new(GIFTS_PREFIX, storage),
+        }
+    }
+
+    pub fn get_gift(&self, key: &[u8]) -> Option<Gift> {
+        may_load(&self.storage, &alias_key(key)).ok().unwrap()
+    }
+}
+
+pub struct GiftsStorage<'a, S: Storage> {
+    storage: PrefixedStorage<'a, S>,
+}
+impl<'a, S: Storage> GiftsStorage<'a, S> {
+    pub fn from_storage(storage: &'a mut S) -> Self {
+        Self {
+            storage: PrefixedStorage::new(GIFTS_PREFIX, storage),
+        }
+    }
+
+    pub fn get_gift(&self, key: &[u8]) -> Option<Gift> {
+        may_load(&self.storage, &alias_key(key)).ok().unwrap()
+    }
+
+    pub fn remove_gift(&mut self, key: &[u8]) {
+        remove(&mut self.storage, &alias_key(key));
+    }
+
+    pub fn set_gift(&mut self, key: &[u8], value: &Gift) {
+        save(&mut self.storage, &alias_key(key), value).ok();
+    }
+}
+
+// === Images Storage ===
+
+pub struct ImagesReadonlyStorage<'a, S: Storage> {
+    storage: ReadonlyPrefixedStorage<'a, S>,
+}
+impl<'a, S: Storage> ImagesReadonlyStorage<'a, S> {
+    pub fn from_storage(storage: &'a S) -> Self {
+        Self {
+            storage: ReadonlyPrefixedStorage::new(IMAGES_PREFIX, storage),
+        }
+    }
+
+    pub fn get_images(&self, key: &[u8]) -> Vec<Image> {
+        may_load(&self.storage, &alias_key(key))
+            .ok()
+            .unwrap()
+            .unwrap_or_default()
+    }
+}
+
+pub struct ImagesStorage<'a, S: Storage> {
+    storage: PrefixedStorage<'a, S>,
+}
+impl<'a, S: Storage> ImagesStorage<'a, S> {
+    pub fn from_storage(storage: &'a mut S) -> Self {
+        Self {
+            storage: PrefixedStorage::new(IMAGES_PREFIX, storage),
+        }
+    }
+
+    pub fn get_images(&self, key: &[u8]) -> Vec<Image> {
+        may_load(&self.storage, &alias_key(key))
+            .ok()
+            .unwrap()
+            .unwrap_or_default()
+    }
+
+    pub fn remove_images(&mut self, key: &[u8]) {
+        remove(&mut self.storage, &alias_key(key));
+    }
+
+    pub fn set_images(&mut self, key: &[u8], value: &[Image]) {
+        save(&mut self.storage, &alias_key(key), &value).ok();
+    }
+}
+
+// === KnownContracts Storage ===
+
+// Labels of well-known contracts, such as sscrt, with the address of the contract
+pub struct KnownContractsReadonlyStorage<'a, S: Storage> {
+    storage: ReadonlyPrefixedStorage<'a, S>,
+}
+impl<'a, S: Storage> KnownContractsReadonlyStorage<'a, S> {
+    pub fn from_storage(storage: &'a S) -> Self {
+        Self {
+            storage: ReadonlyPrefixedStorage::new(KNOWN_CONTRACTS_PREFIX, storage),
+        }
+    }
+
+    pub fn get_known_contract(&self, key: &[u8]) -> Option<HumanAddr> {
+        may_load(&self.storage, &alias_key(key)).ok().unwrap()
+    }
+}
+
+pub struct KnownContractsStorage<'a, S: Storage> {
+    storage: PrefixedStorage<'a, S>,
+}
+impl<'a, S: Storage> KnownContractsStorage<'a, S> {
+    pub fn from_storage(storage: &'a mut S) -> Self {
+        Self {
+            storage: PrefixedStorage::new(KNOWN_CONTRACTS_PREFIX, storage),
+        }
+    }
+
+    pub fn remove_known_contract(&mut self, key: &[u8]) {
+        remove(&mut self.storage, &alias_key(key));
+    }
+
+    pub fn set_known_contract(&mut self, key: &[u8], value: &HumanAddr) {
+        save(&mut self.storage, &alias_key(key), value).ok();
+    }
+}
+
+// === Leases Storage ===
+// The leased aliases are also listed, so leases can be looked through by when they run out
+
+const LEASED_ALIASES_KEY: &[u8] = b"leased_aliases";
+
+pub struct LeasesReadonlyStorage<'a, S: Storage> {
+    storage: ReadonlyPrefixedStorage<'a, S>,
+}
+impl<'a, S: Storage> LeasesReadonlyStorage<'a, S> {
+    pub fn from_storage(storage: &'a S) -> Self {
+        Self {
+            storage: ReadonlyPrefixedStorage::new(LEASES_PREFIX, storage),
+        }
+    }
+
+    pub fn get_lease(&self, key: &[u8]) -> Option<Lease> {
+        may_load(&self.storage, &alias_key(key)).ok().unwrap()
+    }
+
+    pub fn get_leased_aliases(&self) -> Vec<String> {
+        may_load(&self.storage, LEASED_ALIASES_KEY)
+            .ok()
+            .unwrap()
+            .unwrap_or_default()
+    }
+}
+
+pub struct LeasesStorage<'a, S: Storage> {
+    storage: PrefixedStorage<'a, S>,
+}
+impl<'a, S: Storage> LeasesStorage<'a, S> {
+    pub fn from_storage(storage: &'a mut S) -> Self {
+        Self {
+            storage: PrefixedStorage::new(LEASES_PREFIX, storage),
+        }
+    }
+
+    pub fn get_lease(&self, key: &[u8]) -> Option<Lease> {
+        may_load(&self.storage, &alias_key(key)).ok().unwrap()
+    }
+
+    pub fn get_leased_aliases(&self) -> Vec<String> {
+        may_load(&self.storage, LEASED_ALIASES_KEY)
+            .ok()
+            .unwrap()
+            .unwrap_or_default()
+    }
+
+    pub fn remove_lease(&mut self, key: &[u8]) {
+        remove(&mut self.storage, &alias_key(key));
+        let mut leased_aliases: Vec<String> = self.get_leased_aliases();
+        leased_aliases.retain(|alias| alias.as_bytes() != key);
+        save(&mut self.storage, LEASED_ALIASES_KEY, &leased_aliases).ok();
+    }
+
+    pub fn set_lease(&mut self, key: &[u8], value: &Lease) {
+        save(&mut self.storage, &alias_key(key), value).ok();
+        let mut leased_aliases: Vec<String> = self.get_leased_aliases();
+        if !leased_aliases.iter().any(|alias| alias.as_bytes() == key) {
+            leased_aliases.push(String::from_utf8_lossy(key).to_string());
+            save(&mut self.storage, LEASED_ALIASES_KEY, &leased_aliases).ok();
+        }
+    }
+}
+
+// === LinkedAddresses Storage ===
+
+pub struct LinkedAddressesReadonlyStorage<'a, S: Storage> {
+    storage: ReadonlyPrefixedStorage<'a, S>,
+}
+impl<'a, S: Storage> LinkedAddressesReadonlyStorage<'a, S> {
+    pub fn from_storage(storage: &'a S) -> Self {
+        Self {
+            storage: ReadonlyPrefixedStorage::new(LINKED_ADDRESSES_PREFIX, storage),
+        }
+    }
+
+    pub fn get_linked_addresses(&self, key: &[u8]) -> Vec<LinkedAddress> {
+        may_load(&self.storage, &alias_key(key))
+            .ok()
+            .unwrap()
+            .unwrap_or_default()
+    }
+}
+
+pub struct LinkedAddressesStorage<'a, S: Storage> {
+    storage: PrefixedStorage<'a, S>,
+}
+impl<'a, S: Storage> LinkedAddressesStorage<'a, S> {
+    pub fn from_storage(storage: &'a mut S) -> Self {
+        Self {
+            storage: PrefixedStorage::new(LINKED_ADDRESSES_PREFIX, storage),
+        }
+    }
+
+    pub fn get_linked_addresses(&self, key: &[u8]) -> Vec<LinkedAddress> {
+        may_load(&self.storage, &alias_key(key))
+            .ok()
+            .unwrap()
+            .unwrap_or_default()
+    }
+
+    pub fn remove_linked_addresses(&mut self, key: &[u8]) {
+        remove(&mut self.storage, &alias_key(key));
+    }
+
+    pub fn set_linked_addresses(&mut self, key: &[u8], value: &[LinkedAddress]) {
+        save(&mut self.storage, &alias_key(key), &value).ok();
+    }
+}
+
+// === Locks Storage ===
+// The block height until which the owner has locked each alias against transfers and destructive
+// changes
+
+pub struct LocksReadonlyStorage<'a, S: Storage> {
+    storage: ReadonlyPrefixedStorage<'a, S>,
+}
+impl<'a, S: Storage> LocksReadonlyStorage<'a, S> {
+    pub fn from_storage(storage: &'a S) -> Self {
+        Self {
+            storage: ReadonlyPrefixedStorage::new(LOCKS_PREFIX, storage),
+        }
+    }
+
+    pub fn get_locked_until(&self, key: &[u8]) -> Option<u64> {
+        may_load(&self.storage, &alias_key(key)).ok().unwrap()
+    }
+}
+
+pub struct LocksStorage<'a, S: Storage> {
+    storage: PrefixedStorage<'a, S>,
+}
+impl<'a, S: Storage> LocksStorage<'a, S> {
+    pub fn from_storage(storage: &'a mut S) -> Self {
+        Self {
+            storage: PrefixedStorage::new(LOCKS_PREFIX, storage),
+        }
+    }
+
+    pub fn remove_lock(&mut self, key: &[u8]) {
+        remove(&mut self.storage, &alias_key(key));
+    }
+
+    pub fn set_locked_until(&mut self, key: &[u8], height: u64) {
+        save(&mut self.storage, &alias_key(key), &height).ok();
+    }
+}
+
+// === LotteryApplications Storage ===
+
+pub struct LotteryApplicationsReadonlyStorage<'a, S: Storage> {
+    storage: ReadonlyPrefixedStorage<'a, S>,
+}
+impl<'a, S: Storage> LotteryApplicationsReadonlyStorage<'a, S> {
+    pub fn from_storage(storage: &'a S) -> Self {
+        Self {
+            storage: ReadonlyPrefixedStorage::new(LOTTERY_APPLICATIONS_PREFIX, storage),
+        }
+    }
+
+    pub fn get_applications(&self, key: &[u8]) -> Vec<LotteryApplication> {
+        may_load(&self.storage, &alias_key(key))
+            .ok()
+            .unwrap()
+            .unwrap_or_default()
+    }
+}
+
+pub struct LotteryApplicationsStorage<'a, S: Storage> {
+    storage: PrefixedStorage<'a, S>,
+}
+impl<'a, S: Storage> LotteryApplicationsStorage<'a, S> {
+    pub fn from_storage(storage: &'a mut S) -> Self {
+        Self {
+            storage: PrefixedStorage::new(LOTTERY_APPLICATIONS_PREFIX, storage),
+        }
+    }
+
+    pub fn get_applications(&self, key: &[u8]) -> Vec<LotteryApplication> {
+        may_load(&self.storage, &alias_key(key))
+            .ok()
+            .unwrap()
+            .unwrap_or_default()
+    }
+
+    pub fn remove_applications(&mut self, key: &[u8]) {
+        remove(&mut self.storage, &alias_key(key));
+    }
+
+    pub fn set_applications(&mut self, key: &[u8], value: &[LotteryApplication]) {
+        save(&mut self.storage, &alias_key(key), &value).ok();
+    }
+}
+
+// === Nonces Storage ===
+
+pub struct NoncesReadonlyStorage<'a, S: Storage> {
+    storage: ReadonlyPrefixedStorage<'a, S>,
+}
+impl<'a, S: Storage> NoncesReadonlyStorage<'a, S> {
+    pub fn from_storage(storage: &'a S) -> Self {
+        Self {
+            storage: ReadonlyPrefixedStorage::new(NONCES_PREFIX, storage),
+        }
+    }
+
+    pub fn get_nonce(&self, address: &CanonicalAddr) -> u64 {
+        may_load(&self.storage, address.as_slice())
+            .ok()
+            .unwrap()
+            .unwrap_or(0)
+    }
+}
+
+pub struct NoncesStorage<'a, S: Storage> {
+    storage: PrefixedStorage<'a, S>,
+}
+impl<'a, S: Storage> NoncesStorage<'a, S> {
+    pub fn from_storage(storage: &'a mut S) -> Self {
+        Self {
+            storage: PrefixedStorage::new(NONCES_PREFIX, storage),
+        }
+    }
+
+    pub fn get_nonce(&self, address: &CanonicalAddr) -> u64 {
+        may_load(&self.storage, address.as_slice())
+            .ok()
+            .unwrap()
+            .unwrap_or(0)
+    }
+
+    pub fn set_nonce(&mut self, address: &CanonicalAddr, value: u64) {
+        save(&mut self.storage, address.as_slice(), &value).ok();
+    }
+}
+
+// === Offers Storage ===
+
+pub struct OffersReadonlyStorage<'a, S: Storage> {
+    storage: ReadonlyPrefixedStorage<'a, S>,
+}
+impl<'a, S: Storage> OffersReadonlyStorage<'a, S> {
+    pub fn from_storage(storage: &'a S) -> Self {
+        Self {
+            storage: ReadonlyPrefixedStorage::new(OFFERS_PREFIX, storage),
+        }
+    }
+
+    pub fn get_offers(&self, key: &[u8]) -> Vec<StoredOffer> {
+        may_load(&self.storage, &alias_key(key))
+            .ok()
+            .unwrap()
+            .unwrap_or_default()
+    }
+}
+
+pub struct OffersStorage<'a, S: Storage> {
+    storage: PrefixedStorage<'a, S>,
+}
+impl<'a, S: Storage> OffersStorage<'a, S> {
+    pub fn from_storage(storage: &'a mut S) -> Self {
+        Self {
+            storage: PrefixedStorage::new(OFFERS_PREFIX, storage),
+        }
+    }
+
+    pub fn get_offers(&self, key: &[u8]) -> Vec<StoredOffer> {
+        may_load(&self.storage, &alias_key(key))
+            .ok()
+            .unwrap()
+            .unwrap_or_default()
+    }
+
+    pub fn remove_offers(&mut self, key: &[u8]) {
+        remove(&mut self.storage, &alias_key(key));
+    }
+
+    pub fn set_offers(&mut self, key: &[u8], value: &[StoredOffer]) {
+        save(&mut self.storage, &alias_key(key), &value).ok();
+    }
+}
+
+// === PaymentPreferences Storage ===
+
+pub struct PaymentPreferencesReadonlyStorage<'a, S: Storage> {
+    storage: ReadonlyPrefixedStorage<'a, S>,
+}
+impl<'a, S: Storage> PaymentPreferencesReadonlyStorage<'a, S> {
+    pub fn from_storage(storage: &'a S) -> Self {
+        Self {
+            storage: ReadonlyPrefixedStorage::new(PAYMENT_PREFERENCES_PREFIX, storage),
+        }
+    }
+
+    pub fn get_payment_preference(&self, key: &[u8]) -> Option<StoredPaymentPreference> {
+        may_load(&self.storage, &alias_key(key)).ok().unwrap()
+    }
+}
+
+pub struct PaymentPreferencesStorage<'a, S: Storage> {
+    storage: PrefixedStorage<'a, S>,
+}
+impl<'a, S: Storage> PaymentPreferencesStorage<'a, S> {
+    pub fn from_storage(storage: &'a mut S) -> Self {
+        Self {
+            storage: PrefixedStorage::new(PAYMENT_PREFERENCES_PREFIX, storage),
+        }
+    }
+
+    pub fn get_payment_preference(&self, key: &[u8]) -> Option<StoredPaymentPreference> {
+        may_load(&self.storage, &alias_key(key)).ok().unwrap()
+    }
+
+    pub fn remove_payment_preference(&mut self, key: &[u8]) {
+        remove(&mut self.storage, &alias_key(key));
+    }
+
+    pub fn set_payment_preference(&mut self, key: &[u8], value: &StoredPaymentPreference) {
+        save(&mut self.storage, &alias_key(key), value).ok();
+    }
+}
+
+// === PendingActions Storage ===
+
+pub struct PendingActionsReadonlyStorage<'a, S: Storage> {
+    storage: ReadonlyPrefixedStorage<'a, S>,
+}
+impl<'a, S: Storage> PendingActionsReadonlyStorage<'a, S> {
+    pub fn from_storage(storage: &'a S) -> Self {
+        Self {
+            storage: ReadonlyPrefixedStorage::new(PENDING_ACTIONS_PREFIX, storage),
+        }
+    }
+
+    pub fn get_pending_action(&self, key: &[u8]) -> Option<StoredPendingAction> {
+        may_load(&self.storage, &alias_key(key)).ok().unwrap()
+    }
+}
+
+pub struct PendingActionsStorage<'a, S: Storage> {
+    storage: PrefixedStorage<'a, S>,
+}
+impl<'a, S: Storage> PendingActionsStorage<'a, S> {
+    pub fn from_storage(storage: &'a mut S) -> Self {
+        Self {
+            storage: PrefixedStorage::new(PENDING_ACTIONS_PREFIX, storage),
+        }
+    }
+
+    pub fn get_pending_action(&self, key: &[u8]) -> Option<StoredPendingAction> {
+        may_load(&self.storage, &alias_key(key)).ok().unwrap()
+    }
+
+    pub fn remove_pending_action(&mut self, key: &[u8]) {
+        remove(&mut self.storage, &alias_key(key));
+    }
+
+    pub fn set_pending_action(&mut self, key: &[u8], value: &StoredPendingAction) {
+        save(&mut self.storage, &alias_key(key), value).ok();
+    }
+}
+
+// === PreviousProfiles Storage ===
+
+pub struct PreviousProfilesReadonlyStorage<'a, S: Storage> {
+    storage: ReadonlyPrefixedStorage<'a, S>,
+}
+impl<'a, S: Storage> PreviousProfilesReadonlyStorage<'a, S> {
+    pub fn from_storage(storage: &'a S) -> Self {
+        Self {
+            storage: ReadonlyPrefixedStorage::new(PREVIOUS_PROFILES_PREFIX, storage),
+        }
+    }
+
+    pub fn get_previous_profile(&self, key: &[u8]) -> Option<PreviousProfile> {
+        may_load(&self.storage, &alias_key(key)).ok().unwrap()
+    }
+}
+
+pub struct PreviousProfilesStorage<'a, S: Storage> {
+    storage: PrefixedStorage<'a, S>,
+}
+impl<'a, S: Storage> PreviousProfilesStorage<'a, S> {
+    pub fn from_storage(storage: &'a mut S) -> Self {
+        Self {
+            storage: PrefixedStorage::new(PREVIOUS_PROFILES_PREFIX, storage),
+        }
+    }
+
+    pub fn get_previous_profile(&self, key: &[u8]) -> Option<PreviousProfile> {
+        may_load(&self.storage, &alias_key(key)).ok().unwrap()
+    }
+
+    pub fn remove_previous_profile(&mut self, key: &[u8]) {
+        remove(&mut self.storage, &alias_key(key));
+    }
+
+    pub fn set_previous_profile(&mut self, key: &[u8], value: &PreviousProfile) {
+        save(&mut self.storage, &alias_key(key), value).ok();
+    }
+}
+
+// === Reclaims Storage ===
+
+pub struct ReclaimsReadonlyStorage<'a, S: Storage> {
+    storage: ReadonlyPrefixedStorage<'a, S>,
+}
+impl<'a, S: Storage> ReclaimsReadonlyStorage<'a, S> {
+    pub fn from_storage(storage: &'a S) -> Self {
+        Self {
+            storage: ReadonlyPrefixedStorage::new(RECLAIMS_PREFIX, storage),
+        }
+    }
+
+    pub fn get_reclaim(&self, key: &[u8]) -> Option<StoredReclaim> {
+        may_load(&self.storage, &alias_key(key)).ok().unwrap()
+    }
+}
+
+pub struct ReclaimsStorage<'a, S: Storage> {
+    storage: PrefixedStorage<'a, S>,
+}
+impl<'a, S: Storage> ReclaimsStorage<'a, S> {
+    pub fn from_storage(storage: &'a mut S) -> Self {
+        Self {
+            storage: PrefixedStorage::new(RECLAIMS_PREFIX, storage),
+        }
+    }
+
+    pub fn get_reclaim(&self, key: &[u8]) -> Option<StoredReclaim> {
+        may_load(&self.storage, &alias_key(key)).ok().unwrap()
+    }
+
+    pub fn remove_reclaim(&mut self, key: &[u8]) {
+        remove(&mut self.storage, &alias_key(key));
+    }
+
+    pub fn set_reclaim(&mut self, key: &[u8], value: &StoredReclaim) {
+        save(&mut self.storage, &alias_key(key), value).ok();
+    }
+}
+
+// === Records Storage ===
+
+pub struct RecordsReadonlyStorage<'a, S: Storage> {
+    storage: ReadonlyPrefixedStorage<'a, S>,
+}
+impl<'a, S: Storage> RecordsReadonlyStorage<'a, S> {
+    pub fn from_storage(storage: &'a S) -> Self {
+        Self {
+            storage: ReadonlyPrefixedStorage::new(RECORDS_PREFIX, storage),
+        }
+    }
+
+    pub fn get_records(&self, key: &[u8]) -> Vec<Record> {
+        may_load(&self.storage, &alias_key(key))
+            .ok()
+            .unwrap()
+            .unwrap_or_default()
+    }
+}
+
+pub struct RecordsStorage<'a, S: Storage> {
+    storage: PrefixedStorage<'a, S>,
+}
+impl<'a, S: Storage> RecordsStorage<'a, S> {
+    pub fn from_storage(storage: &'a mut S) -> Self {
+        Self {
+            storage: PrefixedStorage::new(RECORDS_PREFIX, storage),
+        }
+    }
+
+    pub fn get_records(&self, key: &[u8]) -> Vec<Record> {
+        may_load(&self.storage, &alias_key(key))
+            .ok()
+            .unwrap()
+            .unwrap_or_default()
+    }
+
+    pub fn remove_records(&mut self, key: &[u8]) {
+        remove(&mut self.storage, &alias_key(key));
+    }
+
+    pub fn set_records(&mut self, key: &[u8], value: &[Record]) {
+        save(&mut self.storage, &alias_key(key), &value).ok();
+    }
+}
+
+// === Releases Storage ===
+// The block height at which each alias was last destroyed or renamed away
+
+pub struct ReleasesReadonlyStorage<'a, S: Storage> {
+    storage: ReadonlyPrefixedStorage<'a, S>,
+}
+impl<'a, S: Storage> ReleasesReadonlyStorage<'a, S> {
+    pub fn from_storage(storage: &'a S) -> Self {
+        Self {
+            storage: ReadonlyPrefixedStorage::new(RELEASES_PREFIX, storage),
+        }
+    }
+
+    pub fn get_released_at(&self, key: &[u8]) -> Option<u64> {
+        may_load(&self.storage, &alias_key(key)).ok().unwrap()
+    }
+}
+
+pub struct ReleasesStorage<'a, S: Storage> {
+    storage: PrefixedStorage<'a, S>,
+}
+impl<'a, S: Storage> ReleasesStorage<'a, S> {
+    pub fn from_storage(storage: &'a mut S) -> Self {
+        Self {
+            storage: PrefixedStorage::new(RELEASES_PREFIX, storage),
+        }
+    }
+
+    pub fn set_released_at(&mut self, key: &[u8], height: u64) {
+        save(&mut self.storage, &alias_key(key), &height).ok();
+    }
+}
+
+// === RenouncedAliases Storage ===
+
+pub struct RenouncedAliasesReadonlyStorage<'a, S: Storage> {
+    storage: ReadonlyPrefixedStorage<'a, S>,
+}
+impl<'a, S: Storage> RenouncedAliasesReadonlyStorage<'a, S> {
+    pub fn from_storage(storage: &'a S) -> Self {
+        Self {
+            storage: ReadonlyPrefixedStorage::new(RENOUNCED_ALIASES_PREFIX, storage),
+        }
+    }
+
+    pub fn is_renounced(&self, key: &[u8]) -> bool {
+        self.storage.get(&alias_key(key)).is_some()
+    }
+}
+
+pub struct RenouncedAliasesStorage<'a, S: Storage> {
+    storage: PrefixedStorage<'a, S>,
+}
+impl<'a, S: Storage> RenouncedAliasesStorage<'a, S> {
+    pub fn from_storage(storage: &'a mut S) -> Self {
+        Self {
+            storage: PrefixedStorage::new(RENOUNCED_ALIASES_PREFIX, storage),
+        }
+    }
+
+    pub fn set_renounced(&mut self, key: &[u8]) {
+        self.storage.set(&alias_key(key), &[1]);
+    }
+}
+
+// === ReservedAliases Storage ===
+
+pub struct ReservedAliasesReadonlyStorage<'a, S: Storage> {
+    storage: ReadonlyPrefixedStorage<'a, S>,
+}
+impl<'a, S: Storage> ReservedAliasesReadonlyStorage<'a, S> {
+    pub fn from_storage(storage: &'a S) -> Self {
+        Self {
+            storage: ReadonlyPrefixedStorage::new(RESERVED_ALIASES_PREFIX, storage),
+        }
+    }
+
+    pub fn is_reserved(&self, key: &[u8]) -> bool {
+        self.storage.get(&alias_key(key)).is_some()
+    }
+}
+
+pub struct ReservedAliasesStorage<'a, S: Storage> {
+    storage: PrefixedStorage<'a, S>,
+}
+impl<'a, S: Storage> ReservedAliasesStorage<'a, S> {
+    pub fn from_storage(storage: &'a mut S) -> Self {
+        Self {
+            storage: PrefixedStorage::new(RESERVED_ALIASES_PREFIX, storage),
+        }
+    }
+
+    pub fn is_reserved(&self, key: &[u8]) -> bool {
+        self.storage.get(&alias_key(key)).is_some()
+    }
+
+    pub fn remove_reserved(&mut self, key: &[u8]) {
+        self.storage.remove(&alias_key(key));
+    }
+
+    pub fn set_reserved(&mut self, key: &[u8]) {
+        self.storage.set(&alias_key(key), &[1]);
+    }
+}
+
+// === SigningKeys Storage ===
+
+pub struct SigningKeysReadonlyStorage<'a, S: Storage> {
+    storage: ReadonlyPrefixedStorage<'a, S>,
+}
+impl<'a, S: Storage> SigningKeysReadonlyStorage<'a, S> {
+    pub fn from_storage(storage: &'a S) -> Self {
+        Self {
+            storage: ReadonlyPrefixedStorage::new(SIGNING_KEYS_PREFIX, storage),
+        }
+    }
+
+    pub fn get_signing_key(&self, key: &[u8]) -> Option<Binary> {
+        may_load(&self.storage, &alias_key(key)).ok().unwrap()
+    }
+}
+
+pub struct SigningKeysStorage<'a, S: Storage> {
+    storage: PrefixedStorage<'a, S>,
+}
+impl<'a, S: Storage> SigningKeysStorage<'a, S> {
+    pub fn from_storage(storage: &'a mut S) -> Self {
+        Self {
+            storage: PrefixedStorage::new(SIGNING_KEYS_PREFIX, storage),
+        }
+    }
+
+    pub fn get_signing_key(&self, key: &[u8]) -> Option<Binary> {
+        may_load(&self.storage, &alias_key(key)).ok().unwrap()
+    }
+
+    pub fn remove_signing_key(&mut self, key: &[u8]) {
+        remove(&mut self.storage, &alias_key(key));
+    }
+
+    pub fn set_signing_key(&mut self, key: &[u8], value: &Binary) {
+        save(&mut self.storage, &alias_key(key), value).ok();
+    }
+}
+
+// === Suspensions Storage ===
+
+pub struct SuspensionsReadonlyStorage<'a, S: Storage> {
+    storage: ReadonlyPrefixedStorage<'a, S>,
+}
+impl<'a, S: Storage> SuspensionsReadonlyStorage<'a, S> {
+    pub fn from_storage(storage: &'a S) -> Self {
+        Self {
+            storage: ReadonlyPrefixedStorage::new(SUSPENSIONS_PREFIX, storage),
+        }
+    }
+
+    pub fn get_suspension(&self, key: &[u8]) -> Option<Suspension> {
+        may_load(&self.storage, &alias_key(key)).ok().unwrap()
+    }
+}
+
+pub struct SuspensionsStorage<'a, S: Storage> {
+    storage: PrefixedStorage<'a, S>,
+}
+impl<'a, S: Storage> SuspensionsStorage<'a, S> {
+    pub fn from_storage(storage: &'a mut S) -> Self {
+        Self {
+            storage: PrefixedStorage::new(SUSPENSIONS_PREFIX, storage),
+        }
+    }
+
+    pub fn get_suspension(&self, key: &[u8]) -> Option<Suspension> {
+        may_load(&self.storage, &alias_key(key)).ok().unwrap()
+    }
+
+    pub fn remove_suspension(&mut self, key: &[u8]) {
+        remove(&mut self.storage, &alias_key(key));
+    }
+
+    pub fn set_suspension(&mut self, key: &[u8], value: &Suspension) {
+        save(&mut self.storage, &alias_key(key), value).ok();
+    }
+}
+
+// === TipJars Storage ===
+
+pub struct TipJarsReadonlyStorage<'a, S: Storage> {
+    storage: ReadonlyPrefixedStorage<'a, S>,
+}
+impl<'a, S: Storage> TipJarsReadonlyStorage<'a, S> {
+    pub fn from_storage(storage: &'a S) -> Self {
+        Self {
+            storage: ReadonlyPrefixedStorage::new(TIP_JARS_PREFIX, storage),
+        }
+    }
+
+    pub fn get_tip_jar(&self, key: &[u8]) -> TipJar {
+        may_load(&self.storage, &alias_key(key))
+            .ok()
+            .unwrap()
+            .unwrap_or_default()
+    }
+}
+
+pub struct TipJarsStorage<'a, S: Storage> {
+    storage: PrefixedStorage<'a, S>,
+}
+impl<'a, S: Storage> TipJarsStorage<'a, S> {
+    pub fn from_storage(storage: &'a mut S) -> Self {
+        Self {
+            storage: PrefixedStorage::new(TIP_JARS_PREFIX, storage),
+        }
+    }
+
+    pub fn get_tip_jar(&self, key: &[u8]) -> TipJar {
+        may_load(&self.storage, &alias_key(key))
+            .ok()
+            .unwrap()
+            .unwrap_or_default()
+    }
+
+    pub fn remove_tip_jar(&mut self, key: &[u8]) {
+        remove(&mut self.storage, &alias_key(key));
+    }
+
+    pub fn set_tip_jar(&mut self, key: &[u8], value: &TipJar) {
+        save(&mut self.storage, &alias_key(key), &value).ok();
+    }
+}
+
+// === Tombstones Storage ===
+
+pub struct TombstonesReadonlyStorage<'a, S: Storage> {
+    storage: ReadonlyPrefixedStorage<'a, S>,
+}
+impl<'a, S: Storage> TombstonesReadonlyStorage<'a, S> {
+    pub fn from_storage(storage: &'a S) -> Self {
+        Self {
+            storage: ReadonlyPrefixedStorage::new(TOMBSTONES_PREFIX, storage),
+        }
+    }
+
+    pub fn get_tombstone(&self, key: &[u8]) -> Option<Tombstone> {
+        may_load(&self.storage, &alias_key(key)).ok().unwrap()
+    }
+}
+
+pub struct TombstonesStorage<'a, S: Storage> {
+    storage: PrefixedStorage<'a, S>,
+}
+impl<'a, S: Storage> TombstonesStorage<'a, S> {
+    pub fn from_storage(storage: &'a mut S) -> Self {
+        Self {
+            storage: PrefixedStorage::new(TOMBSTONES_PREFIX, storage),
+        }
+    }
+
+    pub fn remove_tombstone(&mut self, key: &[u8]) {
+        remove(&mut self.storage, &alias_key(key));
+    }
+
+    pub fn set_tombstone(&mut self, key: &[u8], value: &Tombstone) {
+        save(&mut self.storage, &alias_key(key), value).ok();
+    }
+}
+
+// === ViewingKeys Storage ===
+
+pub struct ViewingKeysReadonlyStorage<'a, S: Storage> {
+    storage: ReadonlyPrefixedStorage<'a, S>,
+}
+impl<'a, S: Storage> ViewingKeysReadonlyStorage<'a, S> {
+    pub fn from_storage(storage: &'a S) -> Self {
+        Self {
+            storage: ReadonlyPrefixedStorage::new(VIEWING_KEYS_PREFIX, storage),
+        }
+    }
+
+    pub fn get_viewing_key(&self, key: &[u8]) -> Option<Vec<u8>> {
+        self.storage.get(key)
+    }
+}
+
+pub struct ViewingKeysStorage<'a, S: Storage> {
+    storage: PrefixedStorage<'a, S>,
+}
+impl<'a, S: Storage> ViewingKeysStorage<'a, S> {
+    pub fn from_storage(storage: &'a mut S) -> Self {
+        Self {
+            storage: PrefixedStorage::new(VIEWING_KEYS_PREFIX, storage),
+        }
+    }
+
+    pub fn set_viewing_key(&mut self, key: &[u8], hashed_viewing_key: &[u8]) {
+        self.storage.set(key, hashed_viewing_key);
+    }
+}
+
+// === FUNCTIONS ===
+// Alias keyed maps are stored under the hash of the normalized alias so keys have a fixed length
+fn alias_key(alias: &[u8]) -> [u8; 32] {
+    sha_256(alias)
+}
+
 fn may_load<T: DeserializeOwned, S: ReadonlyStorage>(
     storage: &S,
     key: &[u8],
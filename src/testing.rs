@@ -0,0 +1,126 @@
+// Helpers for contracts that resolve aliases to test against this one with mock dependencies.
+// Enable with the testing feature.
+use crate::contract::{handle, init, AMOUNT_FOR_TRANSACTION};
+use crate::msg::{AliasAttributes, HandleMsg, InitMsg, QueryMsg, ReceiveMsg};
+use crate::state::SecretContract;
+use cosmwasm_std::testing::{mock_dependencies, mock_env, MockApi, MockQuerier, MockStorage};
+use cosmwasm_std::{to_binary, Binary, Extern, HandleResponse, HumanAddr, StdResult, Uint128};
+
+pub fn mock_admin() -> HumanAddr {
+    HumanAddr::from("some-geezer")
+}
+
+pub fn mock_buttcoin() -> SecretContract {
+    SecretContract {
+        address: HumanAddr("buttcoin-address".to_string()),
+        contract_hash: "buttcoin-contract-hash".to_string(),
+    }
+}
+
+pub fn mock_butt_lode() -> SecretContract {
+    SecretContract {
+        address: HumanAddr("profit-sharing-contract-address".to_string()),
+        contract_hash: "profit-sharing-contract-hash".to_string(),
+    }
+}
+
+pub fn alias_attributes(alias: &str, address: &str) -> AliasAttributes {
+    AliasAttributes {
+        alias: alias.to_string(),
+        display_name: None,
+        address: HumanAddr::from(address),
+        avatar_url: None,
+        avatar_metadata: None,
+    }
+}
+
+// Defaults for everything, with registration open and the aliases given created at init
+pub fn mock_init_msg(aliases: Vec<AliasAttributes>) -> InitMsg {
+    InitMsg {
+        aliases,
+        alias_versions_kept: None,
+        batch_limits: None,
+        bootstrap_blocks: None,
+        buttcoin: mock_buttcoin(),
+        case_folding: None,
+        butt_lode: mock_butt_lode(),
+        change_log_size: None,
+        launch: None,
+        price_oracle: None,
+        prng_seed: Binary::from("testing".as_bytes()),
+        require_avatar: None,
+        response_block_size: None,
+    }
+}
+
+// Mock dependencies with the contract initialized by mock_admin and the aliases in place
+pub fn mock_deps_with_aliases(
+    aliases: Vec<AliasAttributes>,
+) -> StdResult<Extern<MockStorage, MockApi, MockQuerier>> {
+    let mut deps = mock_dependencies(20, &[]);
+    init(
+        &mut deps,
+        mock_env(mock_admin(), &[]),
+        mock_init_msg(aliases),
+    )?;
+
+    Ok(deps)
+}
+
+// Creates the alias for address the way a wallet would, by sending the default fee in Buttcoin
+pub fn seed_alias(
+    deps: &mut Extern<MockStorage, MockApi, MockQuerier>,
+    alias: &str,
+    address: &str,
+) -> StdResult<HandleResponse> {
+    handle(
+        deps,
+        mock_env(mock_buttcoin().address, &[]),
+        create_msg(alias, address, AMOUNT_FOR_TRANSACTION),
+    )
+}
+
+// The Buttcoin transfer that creates an alias. Send it as the Buttcoin contract.
+pub fn create_msg(alias: &str, from: &str, amount: u128) -> HandleMsg {
+    HandleMsg::Receive {
+        sender: HumanAddr::from(from),
+        from: HumanAddr::from(from),
+        amount: Uint128(amount),
+        msg: to_binary(&ReceiveMsg::Create {
+            alias: alias.to_string(),
+            avatar_url: None,
+            avatar_metadata: None,
+            records: None,
+            fallback: None,
+        })
+        .unwrap(),
+        padding: None,
+    }
+}
+
+pub fn search_alias_msg(alias: &str) -> QueryMsg {
+    QueryMsg::Search {
+        search_type: "alias".to_string(),
+        search_value: alias.to_string(),
+        include_metadata: None,
+        viewing_key: None,
+    }
+}
+
+pub fn search_address_msg(address: &str) -> QueryMsg {
+    QueryMsg::Search {
+        search_type: "address".to_string(),
+        search_value: address.to_string(),
+        include_metadata: None,
+        viewing_key: None,
+    }
+}
+
+pub fn resolve_addresses_msg(addresses: &[&str]) -> QueryMsg {
+    QueryMsg::ResolveAddresses {
+        addresses: addresses
+            .iter()
+            .map(|address| HumanAddr::from(*address))
+            .collect(),
+    }
+}
@@ -0,0 +1,47 @@
+use cosmwasm_std::{Binary, Env};
+use schemars::JsonSchema;
+use secret_toolkit::crypto::{sha_256, Prng};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+pub const VIEWING_KEY_PREFIX: &str = "api_key_";
+pub const VIEWING_KEY_SIZE: usize = 32;
+
+#[derive(Serialize, Deserialize, JsonSchema, Clone, Debug, PartialEq)]
+pub struct ViewingKey(pub String);
+
+impl ViewingKey {
+    pub fn check_viewing_key(&self, hashed_key: &[u8]) -> bool {
+        let mine_hashed: [u8; VIEWING_KEY_SIZE] = self.to_hashed();
+        ct_slice_compare(&mine_hashed, hashed_key)
+    }
+
+    pub fn new(env: &Env, seed: &[u8], entropy: &[u8]) -> Self {
+        let rng_entropy: Vec<u8> = [
+            &env.block.height.to_be_bytes(),
+            &env.block.time.to_be_bytes(),
+            env.message.sender.0.as_bytes(),
+            entropy,
+        ]
+        .concat();
+        let mut rng = Prng::new(seed, &rng_entropy);
+        let key: [u8; 32] = sha_256(&rng.rand_bytes());
+
+        Self(VIEWING_KEY_PREFIX.to_string() + &Binary::from(key.to_vec()).to_base64())
+    }
+
+    pub fn to_hashed(&self) -> [u8; VIEWING_KEY_SIZE] {
+        sha_256(self.0.as_bytes())
+    }
+}
+
+impl fmt::Display for ViewingKey {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+// Compare without returning early so the time taken doesn't leak how much of the key matched
+fn ct_slice_compare(s1: &[u8], s2: &[u8]) -> bool {
+    s1.len() == s2.len() && s1.iter().zip(s2).fold(0, |acc, (a, b)| acc | (a ^ b)) == 0
+}